@@ -0,0 +1,65 @@
+-- RustCommerce Fulfillment Schema
+
+-- ============================================================================
+-- Order fulfillment status
+-- ============================================================================
+ALTER TABLE rc_orders
+    ADD COLUMN IF NOT EXISTS fulfillment_status VARCHAR(50) NOT NULL DEFAULT 'unfulfilled'; -- unfulfilled, partially_fulfilled, fulfilled
+
+CREATE INDEX IF NOT EXISTS idx_rc_orders_fulfillment_status ON rc_orders(fulfillment_status);
+
+-- ============================================================================
+-- Shipping Carriers (custom carriers; built-in carriers live in code)
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS rc_shipping_carriers (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    site_id UUID REFERENCES sites(id) ON DELETE CASCADE,
+    slug VARCHAR(100) NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    tracking_url_template TEXT NOT NULL DEFAULT '', -- contains {tracking_number}
+    is_enabled BOOLEAN DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (site_id, slug)
+);
+
+-- ============================================================================
+-- Shipments
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS rc_shipments (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    order_id UUID NOT NULL REFERENCES rc_orders(id) ON DELETE CASCADE,
+    shipment_number VARCHAR(100) NOT NULL,
+    status VARCHAR(50) NOT NULL DEFAULT 'pending', -- pending, shipped, in_transit, out_for_delivery, delivered, exception, returned, cancelled
+
+    -- Tracking
+    carrier VARCHAR(100) NOT NULL,
+    carrier_name VARCHAR(255) NOT NULL,
+    tracking_number VARCHAR(255),
+    tracking_url TEXT,
+
+    -- Notes
+    note TEXT,
+    notify_customer BOOLEAN DEFAULT TRUE,
+
+    -- Dates
+    shipped_at TIMESTAMPTZ,
+    delivered_at TIMESTAMPTZ,
+
+    meta JSONB DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_rc_shipments_order ON rc_shipments(order_id);
+CREATE INDEX IF NOT EXISTS idx_rc_shipments_tracking ON rc_shipments(tracking_number);
+
+-- Items covered by a shipment
+CREATE TABLE IF NOT EXISTS rc_shipment_items (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    shipment_id UUID NOT NULL REFERENCES rc_shipments(id) ON DELETE CASCADE,
+    order_item_id UUID NOT NULL REFERENCES rc_order_items(id) ON DELETE CASCADE,
+    quantity INTEGER NOT NULL CHECK (quantity > 0)
+);
+
+CREATE INDEX IF NOT EXISTS idx_rc_shipment_items_shipment ON rc_shipment_items(shipment_id);
+CREATE INDEX IF NOT EXISTS idx_rc_shipment_items_order_item ON rc_shipment_items(order_item_id);
@@ -0,0 +1,18 @@
+-- RustCommerce Shipping Labels Schema
+
+-- ============================================================================
+-- Shipment Labels (one current label per shipment)
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS rc_shipment_labels (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    shipment_id UUID NOT NULL REFERENCES rc_shipments(id) ON DELETE CASCADE,
+    provider VARCHAR(100) NOT NULL,
+    format VARCHAR(20) NOT NULL DEFAULT 'pdf', -- pdf, zpl
+    file_name VARCHAR(255) NOT NULL,
+    content_type VARCHAR(100) NOT NULL,
+    document BYTEA NOT NULL,
+    tracking_number VARCHAR(255),
+    cost DECIMAL(19, 4),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (shipment_id)
+);
@@ -0,0 +1,64 @@
+-- RustCommerce Local Pickup Schema
+
+-- ============================================================================
+-- Ready-for-pickup order status
+-- ============================================================================
+ALTER TYPE order_status ADD VALUE IF NOT EXISTS 'ready_for_pickup' AFTER 'on_hold';
+
+-- ============================================================================
+-- Pickup Locations
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS rc_pickup_locations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    site_id UUID REFERENCES sites(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    address JSONB NOT NULL DEFAULT '{}',
+    instructions TEXT,
+    phone VARCHAR(50),
+    email VARCHAR(255),
+
+    -- Schedule
+    opening_hours JSONB NOT NULL DEFAULT '[]', -- [{weekday, opens, closes}]
+    timezone VARCHAR(64) NOT NULL DEFAULT 'UTC', -- IANA name, e.g. Europe/Berlin
+
+    -- Slots
+    slot_duration_minutes INTEGER NOT NULL DEFAULT 60 CHECK (slot_duration_minutes > 0),
+    slot_capacity INTEGER NOT NULL DEFAULT 0, -- 0 = unlimited
+    lead_time_minutes INTEGER NOT NULL DEFAULT 120,
+    max_days_ahead INTEGER NOT NULL DEFAULT 14,
+
+    cost DECIMAL(19, 4),
+    is_enabled BOOLEAN DEFAULT TRUE,
+    sort_order INTEGER DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_rc_pickup_locations_site ON rc_pickup_locations(site_id);
+
+-- Holiday and other closures
+CREATE TABLE IF NOT EXISTS rc_pickup_closures (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    location_id UUID NOT NULL REFERENCES rc_pickup_locations(id) ON DELETE CASCADE,
+    start_date DATE NOT NULL,
+    end_date DATE NOT NULL,
+    reason VARCHAR(255),
+    CHECK (end_date >= start_date)
+);
+
+CREATE INDEX IF NOT EXISTS idx_rc_pickup_closures_location ON rc_pickup_closures(location_id, start_date);
+
+-- ============================================================================
+-- Order Pickups (one per order; counts against slot capacity)
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS rc_order_pickups (
+    order_id UUID PRIMARY KEY REFERENCES rc_orders(id) ON DELETE CASCADE,
+    location_id UUID NOT NULL REFERENCES rc_pickup_locations(id),
+    location_name VARCHAR(255) NOT NULL,
+    slot_start TIMESTAMPTZ NOT NULL,
+    slot_end TIMESTAMPTZ NOT NULL,
+    ready_at TIMESTAMPTZ,
+    picked_up_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_rc_order_pickups_slot ON rc_order_pickups(location_id, slot_start);
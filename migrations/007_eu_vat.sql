@@ -0,0 +1,35 @@
+-- RustCommerce EU VAT Schema
+
+-- ============================================================================
+-- Order VAT treatment
+-- ============================================================================
+ALTER TABLE rc_orders
+    ADD COLUMN IF NOT EXISTS vat_number VARCHAR(20),
+    ADD COLUMN IF NOT EXISTS vat_treatment VARCHAR(20), -- domestic, origin, destination, reverse_charge, export
+    ADD COLUMN IF NOT EXISTS vat_country CHAR(2),
+    ADD COLUMN IF NOT EXISTS vat_note TEXT,
+    ADD COLUMN IF NOT EXISTS vat_validation JSONB;
+
+CREATE INDEX IF NOT EXISTS idx_rc_orders_vat ON rc_orders(vat_treatment, vat_country);
+
+-- Rate charged on tax items, for the OSS report
+ALTER TABLE rc_order_items
+    ADD COLUMN IF NOT EXISTS tax_rate_percent DECIMAL(8, 4);
+
+-- ============================================================================
+-- VAT Number Validations (lookup log kept as proof of B2B status)
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS rc_vat_number_validations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    site_id UUID REFERENCES sites(id) ON DELETE CASCADE,
+    vat_number VARCHAR(20) NOT NULL,
+    country_code CHAR(2) NOT NULL,
+    valid BOOLEAN NOT NULL,
+    name VARCHAR(255),
+    address TEXT,
+    request_identifier VARCHAR(100),
+    provider VARCHAR(50) NOT NULL,
+    checked_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_rc_vat_validations_number ON rc_vat_number_validations(vat_number, checked_at DESC);
@@ -0,0 +1,10 @@
+-- RustCommerce Tax Rate Import Schema
+
+-- ============================================================================
+-- Tax rate locations: ";"-separated postcode and city lists from CSV imports
+-- ============================================================================
+ALTER TABLE rc_tax_rates
+    ALTER COLUMN postcode TYPE TEXT,
+    ALTER COLUMN city TYPE TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_rc_tax_rates_class_order ON rc_tax_rates(tax_class, tax_order);
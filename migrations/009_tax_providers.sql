@@ -0,0 +1,26 @@
+-- RustCommerce Tax Provider Schema
+
+-- ============================================================================
+-- Product tax codes (provider-specific, e.g. "P0000000")
+-- ============================================================================
+ALTER TABLE rc_products
+    ADD COLUMN IF NOT EXISTS tax_code VARCHAR(50);
+
+-- ============================================================================
+-- Tax Transactions (orders committed to an external provider)
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS rc_tax_transactions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    site_id UUID REFERENCES sites(id) ON DELETE CASCADE,
+    order_id UUID NOT NULL REFERENCES rc_orders(id) ON DELETE CASCADE,
+    provider VARCHAR(50) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'committed', -- committed, voided, failed
+    total_tax DECIMAL(19, 4) NOT NULL DEFAULT 0,
+    used_fallback BOOLEAN NOT NULL DEFAULT FALSE,
+    error TEXT,
+    committed_at TIMESTAMPTZ,
+    voided_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_rc_tax_transactions_order ON rc_tax_transactions(order_id, provider);
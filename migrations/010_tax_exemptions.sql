@@ -0,0 +1,33 @@
+-- RustCommerce Tax Exemption Schema
+
+-- ============================================================================
+-- Tax Exemptions (customer exemption certificates)
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS rc_tax_exemptions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    site_id UUID REFERENCES sites(id) ON DELETE CASCADE,
+    customer_id UUID NOT NULL REFERENCES rc_customers(id) ON DELETE CASCADE,
+    certificate_id VARCHAR(100) NOT NULL,
+    exemption_type VARCHAR(20) NOT NULL DEFAULT 'resale', -- resale, nonprofit, government, education, other
+    jurisdictions JSONB NOT NULL DEFAULT '[]', -- [{"country": "US", "state": "TX"}]
+    status VARCHAR(20) NOT NULL DEFAULT 'pending', -- pending, approved, rejected
+    document_url TEXT,
+    expires_at DATE,
+    notes TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_rc_tax_exemptions_customer ON rc_tax_exemptions(customer_id, status);
+
+-- ============================================================================
+-- Applied exemptions on orders and tax items
+-- ============================================================================
+ALTER TABLE rc_orders
+    ADD COLUMN IF NOT EXISTS tax_exemption JSONB;
+
+ALTER TABLE rc_order_items
+    ADD COLUMN IF NOT EXISTS tax_exemption_id UUID REFERENCES rc_tax_exemptions(id) ON DELETE SET NULL,
+    ADD COLUMN IF NOT EXISTS tax_exempt_amount DECIMAL(19, 4) NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_rc_order_items_tax_exemption ON rc_order_items(tax_exemption_id);
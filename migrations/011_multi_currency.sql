@@ -0,0 +1,69 @@
+-- RustCommerce Multi-currency Schema
+
+-- ============================================================================
+-- Currencies
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS rc_currencies (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    site_id UUID REFERENCES sites(id) ON DELETE CASCADE,
+    code CHAR(3) NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    symbol VARCHAR(10) NOT NULL,
+    symbol_position VARCHAR(20) NOT NULL DEFAULT 'left', -- left, left_space, right, right_space
+    decimal_separator VARCHAR(5) NOT NULL DEFAULT '.',
+    thousand_separator VARCHAR(5) NOT NULL DEFAULT ',',
+    decimals INTEGER NOT NULL DEFAULT 2,
+    is_default BOOLEAN NOT NULL DEFAULT FALSE,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    exchange_rate DECIMAL(19, 8) NOT NULL DEFAULT 1,
+    auto_update_rate BOOLEAN NOT NULL DEFAULT TRUE,
+    rate_markup DECIMAL(8, 4) NOT NULL DEFAULT 0,
+    rounding VARCHAR(20) NOT NULL DEFAULT 'nearest', -- none, up, down, nearest, nearest_half, nearest_quarter
+    min_amount DECIMAL(19, 4),
+    max_amount DECIMAL(19, 4),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ,
+    UNIQUE(site_id, code)
+);
+
+-- ============================================================================
+-- Currency Zones (geolocation-based currency)
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS rc_currency_zones (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    site_id UUID REFERENCES sites(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    currency_code CHAR(3) NOT NULL,
+    countries JSONB NOT NULL DEFAULT '[]',
+    auto_switch BOOLEAN NOT NULL DEFAULT TRUE,
+    priority INTEGER NOT NULL DEFAULT 0,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- ============================================================================
+-- Fixed per-currency product prices
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS rc_currency_prices (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    product_id UUID NOT NULL REFERENCES rc_products(id) ON DELETE CASCADE,
+    variation_id UUID REFERENCES rc_products(id) ON DELETE CASCADE,
+    currency_code CHAR(3) NOT NULL,
+    regular_price DECIMAL(19, 4) NOT NULL,
+    sale_price DECIMAL(19, 4),
+    price_type VARCHAR(20) NOT NULL DEFAULT 'manual', -- manual, converted
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_rc_currency_prices_product
+    ON rc_currency_prices(product_id, COALESCE(variation_id, '00000000-0000-0000-0000-000000000000'), currency_code);
+
+-- ============================================================================
+-- Cart and order currency
+-- ============================================================================
+ALTER TABLE rc_cart
+    ADD COLUMN IF NOT EXISTS currency CHAR(3);
+
+ALTER TABLE rc_orders
+    ADD COLUMN IF NOT EXISTS base_currency_rate DECIMAL(19, 8);
@@ -0,0 +1,35 @@
+-- RustCommerce Exchange Rates Schema
+
+-- ============================================================================
+-- Exchange Rates (latest rate per currency pair)
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS rc_exchange_rates (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    from_currency CHAR(3) NOT NULL,
+    to_currency CHAR(3) NOT NULL,
+    rate DECIMAL(19, 8) NOT NULL,
+    source VARCHAR(20) NOT NULL DEFAULT 'manual', -- manual, api, calculated
+    fetched_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    valid_until TIMESTAMPTZ,
+    UNIQUE(from_currency, to_currency)
+);
+
+-- ============================================================================
+-- Exchange Rate History
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS rc_exchange_rate_history (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    from_currency CHAR(3) NOT NULL,
+    to_currency CHAR(3) NOT NULL,
+    rate DECIMAL(19, 8) NOT NULL,
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_rc_exchange_rate_history_pair
+    ON rc_exchange_rate_history(from_currency, to_currency, recorded_at);
+
+-- ============================================================================
+-- Cart currency rate
+-- ============================================================================
+ALTER TABLE rc_cart
+    ADD COLUMN IF NOT EXISTS currency_rate DECIMAL(19, 8);
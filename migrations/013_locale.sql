@@ -0,0 +1,7 @@
+-- RustCommerce Locale Schema
+
+-- ============================================================================
+-- Order locale (price formatting in emails and invoices)
+-- ============================================================================
+ALTER TABLE rc_orders
+    ADD COLUMN IF NOT EXISTS locale VARCHAR(20);
@@ -0,0 +1,102 @@
+-- RustCommerce Subscriptions Schema
+
+-- ============================================================================
+-- Subscriptions
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS rc_subscriptions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    site_id UUID REFERENCES sites(id) ON DELETE CASCADE,
+    customer_id UUID NOT NULL REFERENCES rc_customers(id) ON DELETE CASCADE,
+    parent_order_id UUID NOT NULL REFERENCES rc_orders(id) ON DELETE RESTRICT,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending', -- pending, active, on_hold, cancelled, expired, pending_cancel, switched
+
+    -- Billing schedule
+    billing_period VARCHAR(10) NOT NULL, -- day, week, month, year
+    billing_interval INTEGER NOT NULL DEFAULT 1,
+    trial_period VARCHAR(10),
+    trial_length INTEGER,
+
+    -- Amounts
+    total DECIMAL(19, 4) NOT NULL DEFAULT 0,
+    subtotal DECIMAL(19, 4) NOT NULL DEFAULT 0,
+    tax_total DECIMAL(19, 4) NOT NULL DEFAULT 0,
+    shipping_total DECIMAL(19, 4) NOT NULL DEFAULT 0,
+    discount_total DECIMAL(19, 4) NOT NULL DEFAULT 0,
+    currency CHAR(3) NOT NULL,
+
+    -- Payment
+    payment_method VARCHAR(100) NOT NULL,
+    payment_token_id UUID,
+    requires_manual_renewal BOOLEAN NOT NULL DEFAULT FALSE,
+
+    -- Dates
+    start_date TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    trial_end_date TIMESTAMPTZ,
+    next_payment_date TIMESTAMPTZ,
+    end_date TIMESTAMPTZ,
+    cancelled_date TIMESTAMPTZ,
+
+    -- Addresses
+    billing_address JSONB NOT NULL DEFAULT '{}',
+    shipping_address JSONB NOT NULL DEFAULT '{}',
+
+    meta JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_rc_subscriptions_customer ON rc_subscriptions(customer_id);
+CREATE INDEX IF NOT EXISTS idx_rc_subscriptions_due
+    ON rc_subscriptions(next_payment_date) WHERE status = 'active';
+
+-- ============================================================================
+-- Subscription Items
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS rc_subscription_items (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    subscription_id UUID NOT NULL REFERENCES rc_subscriptions(id) ON DELETE CASCADE,
+    product_id UUID NOT NULL REFERENCES rc_products(id) ON DELETE RESTRICT,
+    variation_id UUID,
+    name VARCHAR(500) NOT NULL,
+    quantity INTEGER NOT NULL DEFAULT 1,
+    subtotal DECIMAL(19, 4) NOT NULL DEFAULT 0,
+    total DECIMAL(19, 4) NOT NULL DEFAULT 0,
+    tax DECIMAL(19, 4) NOT NULL DEFAULT 0,
+    meta JSONB NOT NULL DEFAULT '{}'
+);
+
+CREATE INDEX IF NOT EXISTS idx_rc_subscription_items_subscription ON rc_subscription_items(subscription_id);
+
+-- ============================================================================
+-- Renewal Orders
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS rc_subscription_renewals (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    subscription_id UUID NOT NULL REFERENCES rc_subscriptions(id) ON DELETE CASCADE,
+    order_id UUID NOT NULL REFERENCES rc_orders(id) ON DELETE CASCADE,
+    renewal_date TIMESTAMPTZ NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending', -- pending, processing, completed, failed, cancelled
+    amount DECIMAL(19, 4) NOT NULL,
+    payment_attempts INTEGER NOT NULL DEFAULT 0,
+    last_attempt TIMESTAMPTZ,
+    next_retry TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_rc_subscription_renewals_subscription
+    ON rc_subscription_renewals(subscription_id, renewal_date);
+
+-- ============================================================================
+-- Schedule Changes
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS rc_subscription_schedule_changes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    subscription_id UUID NOT NULL REFERENCES rc_subscriptions(id) ON DELETE CASCADE,
+    change_type VARCHAR(20) NOT NULL, -- plan_change, quantity_change, price_change, date_change, pause, resume
+    effective_date TIMESTAMPTZ NOT NULL,
+    old_value JSONB,
+    new_value JSONB,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_rc_subscription_schedule_changes_subscription
+    ON rc_subscription_schedule_changes(subscription_id);
@@ -0,0 +1,7 @@
+-- RustCommerce Subscription Dunning Schema
+
+-- ============================================================================
+-- Failed renewals waiting for a payment retry
+-- ============================================================================
+CREATE INDEX IF NOT EXISTS idx_rc_subscription_renewals_retry
+    ON rc_subscription_renewals(next_retry) WHERE status = 'failed';
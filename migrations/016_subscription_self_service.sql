@@ -0,0 +1,11 @@
+-- RustCommerce Subscription Self-Service Schema
+
+-- ============================================================================
+-- Schedule Changes
+-- ============================================================================
+ALTER TABLE rc_subscription_schedule_changes
+    ALTER COLUMN change_type TYPE VARCHAR(32); -- adds skip, payment_method_change, cancellation
+
+-- Paused subscriptions waiting to resume
+CREATE INDEX IF NOT EXISTS idx_rc_subscriptions_paused
+    ON rc_subscriptions(next_payment_date) WHERE status = 'on_hold';
@@ -0,0 +1,104 @@
+-- RustCommerce Bookings Schema
+
+-- ============================================================================
+-- Bookable Products
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS rc_bookable_products (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    product_id UUID NOT NULL UNIQUE REFERENCES rc_products(id) ON DELETE CASCADE,
+    booking_type VARCHAR(20) NOT NULL, -- fixed_time, fixed_duration, customer_defined
+
+    -- Blocks
+    duration INTEGER NOT NULL DEFAULT 1,
+    duration_unit VARCHAR(10) NOT NULL, -- minute, hour, day, month
+    min_duration INTEGER,
+    max_duration INTEGER,
+    buffer_before INTEGER, -- In duration units
+    buffer_after INTEGER,
+    block_capacity INTEGER NOT NULL DEFAULT 1,
+    timezone VARCHAR(64) NOT NULL DEFAULT 'UTC', -- IANA name, e.g. Europe/Berlin
+    calendar_display VARCHAR(20) NOT NULL DEFAULT 'always',
+
+    -- Confirmation and cancellation
+    requires_confirmation BOOLEAN NOT NULL DEFAULT FALSE,
+    can_be_cancelled BOOLEAN NOT NULL DEFAULT TRUE,
+    cancel_limit INTEGER, -- Hours before start
+
+    -- Persons and resources
+    has_persons BOOLEAN NOT NULL DEFAULT FALSE,
+    min_persons INTEGER,
+    max_persons INTEGER,
+    has_resources BOOLEAN NOT NULL DEFAULT FALSE,
+    resources_assignment VARCHAR(20) NOT NULL DEFAULT 'auto_assign', -- customer_select, auto_assign
+
+    -- Rules
+    default_date_availability VARCHAR(20) NOT NULL DEFAULT 'available', -- available, not_available
+    check_availability BOOLEAN NOT NULL DEFAULT TRUE,
+    availability_rules JSONB NOT NULL DEFAULT '[]',
+    pricing_rules JSONB NOT NULL DEFAULT '[]',
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ
+);
+
+-- ============================================================================
+-- Booking Resources (staff, rooms, equipment)
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS rc_booking_resources (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    site_id UUID REFERENCES sites(id) ON DELETE CASCADE,
+    name VARCHAR(200) NOT NULL,
+    quantity INTEGER NOT NULL DEFAULT 1,
+    base_cost DECIMAL(19, 4),
+    block_cost DECIMAL(19, 4),
+    availability_rules JSONB NOT NULL DEFAULT '[]',
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS rc_bookable_product_resources (
+    bookable_product_id UUID NOT NULL REFERENCES rc_bookable_products(id) ON DELETE CASCADE,
+    resource_id UUID NOT NULL REFERENCES rc_booking_resources(id) ON DELETE CASCADE,
+    PRIMARY KEY (bookable_product_id, resource_id)
+);
+
+-- ============================================================================
+-- Bookings
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS rc_bookings (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    site_id UUID REFERENCES sites(id) ON DELETE CASCADE,
+    product_id UUID NOT NULL REFERENCES rc_products(id) ON DELETE RESTRICT,
+    order_id UUID REFERENCES rc_orders(id) ON DELETE SET NULL,
+    order_item_id UUID,
+    customer_id UUID REFERENCES rc_customers(id) ON DELETE SET NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'unpaid', -- unpaid, pending, confirmed, paid, complete, in_cart, cancelled, was_p_in_cart
+
+    -- Timing
+    start_date TIMESTAMPTZ NOT NULL,
+    end_date TIMESTAMPTZ NOT NULL,
+    all_day BOOLEAN NOT NULL DEFAULT FALSE,
+
+    -- Details
+    persons INTEGER NOT NULL DEFAULT 1,
+    resource_id UUID REFERENCES rc_booking_resources(id) ON DELETE SET NULL,
+    cost DECIMAL(19, 4) NOT NULL DEFAULT 0,
+
+    -- Customer info
+    customer_name VARCHAR(200),
+    customer_email VARCHAR(200),
+    customer_phone VARCHAR(50),
+    customer_note TEXT,
+
+    meta JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ
+);
+
+-- Overlap lookups for availability checks
+CREATE INDEX IF NOT EXISTS idx_rc_bookings_product_time
+    ON rc_bookings(product_id, start_date, end_date) WHERE status NOT IN ('cancelled', 'was_p_in_cart');
+CREATE INDEX IF NOT EXISTS idx_rc_bookings_resource_time
+    ON rc_bookings(resource_id, start_date, end_date) WHERE status NOT IN ('cancelled', 'was_p_in_cart');
+CREATE INDEX IF NOT EXISTS idx_rc_bookings_customer ON rc_bookings(customer_id);
+CREATE INDEX IF NOT EXISTS idx_rc_bookings_order ON rc_bookings(order_id);
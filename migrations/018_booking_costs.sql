@@ -0,0 +1,8 @@
+-- RustCommerce Booking Costs Schema
+
+-- ============================================================================
+-- Bookable product costs (pricing rules adjust the block cost)
+-- ============================================================================
+ALTER TABLE rc_bookable_products
+    ADD COLUMN IF NOT EXISTS base_cost DECIMAL(19, 4) NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS block_cost DECIMAL(19, 4) NOT NULL DEFAULT 0;
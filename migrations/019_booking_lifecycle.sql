@@ -0,0 +1,11 @@
+-- RustCommerce Booking Lifecycle Schema
+
+-- ============================================================================
+-- Hold release, confirmation queue and reminder lookups
+-- ============================================================================
+CREATE INDEX IF NOT EXISTS idx_rc_bookings_in_cart
+    ON rc_bookings(((meta->>'hold_expires_at')::TIMESTAMPTZ)) WHERE status = 'in_cart';
+CREATE INDEX IF NOT EXISTS idx_rc_bookings_pending
+    ON rc_bookings(start_date) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_rc_bookings_upcoming
+    ON rc_bookings(start_date) WHERE status IN ('paid', 'confirmed');
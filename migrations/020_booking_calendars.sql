@@ -0,0 +1,35 @@
+-- RustCommerce Booking Calendars Schema
+
+-- ============================================================================
+-- iCalendar feeds (secret-token URLs per product or resource)
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS rc_booking_calendar_feeds (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    site_id UUID REFERENCES sites(id) ON DELETE CASCADE,
+    product_id UUID REFERENCES rc_products(id) ON DELETE CASCADE,
+    resource_id UUID REFERENCES rc_booking_resources(id) ON DELETE CASCADE,
+    token VARCHAR(64) NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK ((product_id IS NULL) <> (resource_id IS NULL))
+);
+
+-- ============================================================================
+-- External calendars imported as bookings
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS rc_booking_external_calendars (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    site_id UUID REFERENCES sites(id) ON DELETE CASCADE,
+    name VARCHAR(200) NOT NULL,
+    url TEXT NOT NULL,
+    product_id UUID NOT NULL REFERENCES rc_products(id) ON DELETE CASCADE,
+    resource_id UUID REFERENCES rc_booking_resources(id) ON DELETE CASCADE,
+    last_synced_at TIMESTAMPTZ,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ
+);
+
+-- Imported bookings are matched to their events by calendar and UID
+CREATE UNIQUE INDEX IF NOT EXISTS idx_rc_bookings_external_uid
+    ON rc_bookings((meta->>'external_calendar_id'), (meta->>'external_uid'))
+    WHERE meta ? 'external_uid' AND status <> 'cancelled';
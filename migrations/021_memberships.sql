@@ -0,0 +1,69 @@
+-- RustCommerce Memberships Schema
+
+-- ============================================================================
+-- Membership plans
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS rc_membership_plans (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    site_id UUID REFERENCES sites(id) ON DELETE CASCADE,
+    name VARCHAR(200) NOT NULL,
+    slug VARCHAR(200) NOT NULL,
+    description TEXT,
+    status VARCHAR(20) NOT NULL DEFAULT 'active', -- active, draft, archived
+
+    -- Access
+    access_method VARCHAR(20) NOT NULL DEFAULT 'unlimited', -- unlimited, fixed_length, fixed_dates, subscription
+    access_length INTEGER,
+    access_length_period VARCHAR(10), -- day, week, month, year
+    access_start_date TIMESTAMPTZ,
+    access_end_date TIMESTAMPTZ,
+
+    -- Products that grant the plan, and what it restricts
+    product_ids UUID[] NOT NULL DEFAULT '{}',
+    restricted_content JSONB NOT NULL DEFAULT '[]',
+    member_discounts JSONB NOT NULL DEFAULT '[]',
+
+    -- Trial
+    trial_enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    trial_length INTEGER,
+    trial_period VARCHAR(10),
+
+    meta JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ,
+    UNIQUE (site_id, slug)
+);
+
+CREATE INDEX IF NOT EXISTS idx_rc_membership_plans_product_ids ON rc_membership_plans USING GIN(product_ids);
+
+-- ============================================================================
+-- User memberships
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS rc_user_memberships (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    site_id UUID REFERENCES sites(id) ON DELETE CASCADE,
+    plan_id UUID NOT NULL REFERENCES rc_membership_plans(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    customer_id UUID REFERENCES rc_customers(id) ON DELETE SET NULL,
+    order_id UUID REFERENCES rc_orders(id) ON DELETE SET NULL,
+    subscription_id UUID REFERENCES rc_subscriptions(id) ON DELETE SET NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'active', -- active, free_trial, delayed_start, complimentary, pending, paused, expired, cancelled
+
+    start_date TIMESTAMPTZ NOT NULL,
+    end_date TIMESTAMPTZ,
+    paused_date TIMESTAMPTZ,
+    cancelled_date TIMESTAMPTZ,
+    in_trial BOOLEAN NOT NULL DEFAULT FALSE,
+    trial_end_date TIMESTAMPTZ,
+
+    meta JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_rc_user_memberships_user ON rc_user_memberships(user_id, status);
+CREATE INDEX IF NOT EXISTS idx_rc_user_memberships_plan ON rc_user_memberships(plan_id);
+
+-- Status refresh finds memberships whose dates have passed
+CREATE INDEX IF NOT EXISTS idx_rc_user_memberships_dates ON rc_user_memberships(end_date, start_date, trial_end_date)
+    WHERE status IN ('active', 'free_trial', 'delayed_start', 'complimentary');
@@ -0,0 +1,193 @@
+//! Local Address Verifier
+//!
+//! Offline verification against the bundled geo data: country and state
+//! codes, postcode formats and whitespace. It cannot confirm that a street
+//! exists, only that the address is well formed.
+
+use async_trait::async_trait;
+
+use super::provider::{
+    AddressIssue, AddressVerification, AddressVerificationError, AddressVerificationProvider,
+    VerificationStatus,
+};
+use crate::geo;
+use crate::models::customer::Address;
+
+/// Offline address verifier
+#[derive(Debug, Clone, Default)]
+pub struct LocalAddressVerifier;
+
+impl LocalAddressVerifier {
+    /// Create a new local verifier
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Collapse runs of whitespace and trim
+    fn clean(value: &str) -> String {
+        value.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    /// Verify and normalise an address without any network calls
+    pub fn check(&self, address: &Address) -> AddressVerification {
+        let mut issues = Vec::new();
+        let mut normalized = address.clone();
+
+        for value in [
+            &mut normalized.company,
+            &mut normalized.address_1,
+            &mut normalized.address_2,
+            &mut normalized.city,
+        ] {
+            *value = Self::clean(value);
+        }
+
+        // Country: accept alpha-3 and lower case, store alpha-2
+        match geo::normalize_country(&address.country) {
+            Some(code) => normalized.country = code.to_string(),
+            None => issues.push(AddressIssue {
+                field: "country".to_string(),
+                message: format!("Unknown country: {}", address.country.trim()),
+            }),
+        }
+        let country = normalized.country.clone();
+
+        // State: accept names and full ISO codes, store the local code
+        let state = Self::clean(&address.state);
+        if state.is_empty() {
+            normalized.state = state;
+        } else if let Some(code) = geo::normalize_subdivision(&country, &state) {
+            normalized.state = code.to_string();
+        } else if geo::country(&country).is_some_and(|c| c.has_subdivisions()) {
+            normalized.state = state.clone();
+            issues.push(AddressIssue {
+                field: "state".to_string(),
+                message: format!("Unknown state / county: {}", state),
+            });
+        } else {
+            normalized.state = state;
+        }
+
+        // Postcode
+        let postcode = address.postcode.trim();
+        if postcode.is_empty() {
+            normalized.postcode = String::new();
+            if geo::requires_postcode(&country) {
+                issues.push(AddressIssue {
+                    field: "postcode".to_string(),
+                    message: "Postcode is required".to_string(),
+                });
+            }
+        } else {
+            match geo::normalize_postcode(&country, postcode) {
+                Some(formatted) => normalized.postcode = formatted,
+                None => {
+                    normalized.postcode = postcode.to_string();
+                    issues.push(AddressIssue {
+                        field: "postcode".to_string(),
+                        message: match geo::postcode_example(&country) {
+                            Some(example) => format!("Postcode should look like {}", example),
+                            None => "Postcode is not valid".to_string(),
+                        },
+                    });
+                }
+            }
+        }
+
+        let status = if !issues.is_empty() {
+            VerificationStatus::Invalid
+        } else if Self::differs(address, &normalized) {
+            VerificationStatus::Corrected
+        } else {
+            VerificationStatus::Verified
+        };
+
+        AddressVerification {
+            provider: self.id().to_string(),
+            status,
+            suggestion: if status == VerificationStatus::Corrected { Some(normalized) } else { None },
+            issues,
+        }
+    }
+
+    fn differs(a: &Address, b: &Address) -> bool {
+        a.company != b.company
+            || a.address_1 != b.address_1
+            || a.address_2 != b.address_2
+            || a.city != b.city
+            || a.state != b.state
+            || a.postcode != b.postcode
+            || a.country != b.country
+    }
+}
+
+#[async_trait]
+impl AddressVerificationProvider for LocalAddressVerifier {
+    fn id(&self) -> &str {
+        "local"
+    }
+
+    fn title(&self) -> &str {
+        "Format check (offline)"
+    }
+
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    async fn verify(&self, address: &Address) -> Result<AddressVerification, AddressVerificationError> {
+        Ok(self.check(address))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(country: &str, state: &str, postcode: &str) -> Address {
+        Address {
+            address_1: "1  Main  St".to_string(),
+            city: "Springfield".to_string(),
+            state: state.to_string(),
+            postcode: postcode.to_string(),
+            country: country.to_string(),
+            ..Address::default()
+        }
+    }
+
+    #[test]
+    fn test_suggests_normalised_address() {
+        let verifier = LocalAddressVerifier::new();
+        let original = address("usa", "California", "902101234");
+        let result = verifier.check(&original);
+
+        assert_eq!(result.status, VerificationStatus::Corrected);
+        let suggestion = result.suggestion.as_ref().unwrap();
+        assert_eq!(suggestion.country, "US");
+        assert_eq!(suggestion.state, "CA");
+        assert_eq!(suggestion.postcode, "90210-1234");
+        assert_eq!(suggestion.address_1, "1 Main St");
+
+        let changed: Vec<String> = result.changes(&original).into_iter().map(|c| c.field).collect();
+        assert_eq!(changed, vec!["address_1", "state", "postcode", "country"]);
+    }
+
+    #[test]
+    fn test_reports_invalid_fields() {
+        let verifier = LocalAddressVerifier::new();
+
+        let result = verifier.check(&address("CA", "ON", "12345"));
+        assert_eq!(result.status, VerificationStatus::Invalid);
+        assert_eq!(result.issues[0].field, "postcode");
+        assert!(result.suggestion.is_none());
+
+        let result = verifier.check(&address("US", "Atlantis", ""));
+        let fields: Vec<&str> = result.issues.iter().map(|i| i.field.as_str()).collect();
+        assert_eq!(fields, vec!["state", "postcode"]);
+
+        // Countries without postcodes
+        let mut hk = address("HK", "", "");
+        hk.address_1 = "1 Queen's Road".to_string();
+        assert_eq!(verifier.check(&hk).status, VerificationStatus::Verified);
+    }
+}
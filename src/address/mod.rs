@@ -0,0 +1,9 @@
+//! RustCommerce Address Verification
+//!
+//! Address verification providers that check customer addresses and suggest
+//! corrections before an order is placed.
+
+pub mod provider;
+pub mod local;
+
+pub use provider::{AddressVerificationProvider, AddressVerificationRegistry};
@@ -0,0 +1,178 @@
+//! Address Verification Provider Base
+//!
+//! Defines the address verification provider trait and registry.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::models::customer::Address;
+
+/// Address verification error
+#[derive(Debug, Clone)]
+pub enum AddressVerificationError {
+    NotConfigured,
+    UnsupportedCountry(String),
+    NetworkError(String),
+    ProviderError(String),
+}
+
+impl std::fmt::Display for AddressVerificationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotConfigured => write!(f, "Address verification provider not configured"),
+            Self::UnsupportedCountry(country) => write!(f, "Address verification is not available for {}", country),
+            Self::NetworkError(msg) => write!(f, "Network error: {}", msg),
+            Self::ProviderError(msg) => write!(f, "Address verification error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for AddressVerificationError {}
+
+/// Verification outcome
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationStatus {
+    /// Address is valid as entered
+    Verified,
+    /// Address is valid once the suggested corrections are applied
+    Corrected,
+    /// Address could not be confirmed either way
+    Unverified,
+    /// Address is invalid
+    Invalid,
+}
+
+/// Problem found with one address field
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressIssue {
+    pub field: String,
+    pub message: String,
+}
+
+/// Suggested change to one address field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressChange {
+    pub field: String,
+    pub original: String,
+    pub suggested: String,
+}
+
+/// Verification result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressVerification {
+    pub provider: String,
+    pub status: VerificationStatus,
+    /// Corrected address, when the provider has one
+    pub suggestion: Option<Address>,
+    pub issues: Vec<AddressIssue>,
+}
+
+impl AddressVerification {
+    /// Field-by-field differences between an address and the suggestion
+    pub fn changes(&self, original: &Address) -> Vec<AddressChange> {
+        let Some(ref suggested) = self.suggestion else {
+            return vec![];
+        };
+
+        let fields: [(&str, &String, &String); 7] = [
+            ("company", &original.company, &suggested.company),
+            ("address_1", &original.address_1, &suggested.address_1),
+            ("address_2", &original.address_2, &suggested.address_2),
+            ("city", &original.city, &suggested.city),
+            ("state", &original.state, &suggested.state),
+            ("postcode", &original.postcode, &suggested.postcode),
+            ("country", &original.country, &suggested.country),
+        ];
+
+        fields.iter()
+            .filter(|(_, a, b)| a != b)
+            .map(|(field, a, b)| AddressChange {
+                field: field.to_string(),
+                original: a.to_string(),
+                suggested: b.to_string(),
+            })
+            .collect()
+    }
+}
+
+/// Address verification provider trait
+#[async_trait]
+pub trait AddressVerificationProvider: Send + Sync {
+    /// Get provider ID
+    fn id(&self) -> &str;
+
+    /// Get provider title
+    fn title(&self) -> &str;
+
+    /// Check if provider is available
+    fn is_available(&self) -> bool;
+
+    /// Check if the provider can verify addresses in a country
+    fn supports_country(&self, _country: &str) -> bool {
+        true
+    }
+
+    /// Verify an address
+    async fn verify(&self, address: &Address) -> Result<AddressVerification, AddressVerificationError>;
+}
+
+/// Address verification provider registry
+pub struct AddressVerificationRegistry {
+    providers: HashMap<String, Arc<dyn AddressVerificationProvider>>,
+}
+
+impl AddressVerificationRegistry {
+    /// Create a new provider registry
+    pub fn new() -> Self {
+        Self {
+            providers: HashMap::new(),
+        }
+    }
+
+    /// Register a verification provider
+    pub fn register(&mut self, provider: Arc<dyn AddressVerificationProvider>) {
+        self.providers.insert(provider.id().to_string(), provider);
+    }
+
+    /// Get a provider by ID
+    pub fn get(&self, id: &str) -> Option<Arc<dyn AddressVerificationProvider>> {
+        self.providers.get(id).cloned()
+    }
+
+    /// Get available providers
+    pub fn get_available(&self) -> Vec<Arc<dyn AddressVerificationProvider>> {
+        self.providers.values()
+            .filter(|p| p.is_available())
+            .cloned()
+            .collect()
+    }
+
+    /// Verify an address using the specified provider
+    pub async fn verify(
+        &self,
+        provider_id: &str,
+        address: &Address,
+    ) -> Result<AddressVerification, AddressVerificationError> {
+        let provider = self.get(provider_id)
+            .ok_or(AddressVerificationError::NotConfigured)?;
+
+        if !provider.is_available() {
+            return Err(AddressVerificationError::NotConfigured);
+        }
+
+        if !provider.supports_country(&address.country) {
+            return Err(AddressVerificationError::UnsupportedCountry(address.country.clone()));
+        }
+
+        provider.verify(address).await
+    }
+}
+
+impl Default for AddressVerificationRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
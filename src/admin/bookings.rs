@@ -53,3 +53,12 @@ pub struct ResourceView {
     pub quantity: i32,
     pub bookings_count: i32,
 }
+
+/// Email templates available for bookings
+pub fn get_booking_email_templates() -> Vec<(&'static str, &'static str)> {
+    vec![
+        ("booking_confirmed", "Booking confirmed"),
+        ("booking_cancelled", "Booking cancelled"),
+        ("booking_reminder", "Booking reminder"),
+    ]
+}
@@ -46,6 +46,12 @@ pub fn get_order_columns() -> Vec<OrderListColumn> {
             sortable: false,
             width: None,
         },
+        OrderListColumn {
+            id: "fulfillment_status".to_string(),
+            title: "Fulfillment".to_string(),
+            sortable: true,
+            width: Some("120px".to_string()),
+        },
         OrderListColumn {
             id: "total".to_string(),
             title: "Total".to_string(),
@@ -66,6 +72,7 @@ pub fn get_order_bulk_actions() -> Vec<(&'static str, &'static str)> {
     vec![
         ("mark_processing", "Change status to processing"),
         ("mark_on-hold", "Change status to on-hold"),
+        ("mark_ready-for-pickup", "Change status to ready for pickup"),
         ("mark_completed", "Change status to completed"),
         ("mark_cancelled", "Change status to cancelled"),
         ("trash", "Move to Trash"),
@@ -78,6 +85,7 @@ pub fn get_status_color(status: &str) -> &'static str {
         "pending" => "#f39c12",
         "processing" => "#3498db",
         "on-hold" => "#e74c3c",
+        "ready-for-pickup" => "#16a085",
         "completed" => "#27ae60",
         "cancelled" => "#95a5a6",
         "refunded" => "#9b59b6",
@@ -92,6 +100,9 @@ pub fn get_order_actions() -> Vec<(&'static str, &'static str, &'static str)> {
     vec![
         ("view", "View", "eye"),
         ("edit", "Edit", "edit"),
+        ("ship", "Create shipment", "truck"),
+        ("print_label", "Print shipping label", "printer"),
+        ("ready_for_pickup", "Mark ready for pickup", "package"),
         ("email", "Email invoice", "mail"),
         ("resend", "Resend notifications", "refresh-cw"),
         ("refund", "Refund", "rotate-ccw"),
@@ -125,6 +136,10 @@ pub fn get_order_editor_sections() -> Vec<OrderEditorSection> {
             id: "items".to_string(),
             title: "Items".to_string(),
         },
+        OrderEditorSection {
+            id: "shipments".to_string(),
+            title: "Shipments".to_string(),
+        },
         OrderEditorSection {
             id: "totals".to_string(),
             title: "Totals".to_string(),
@@ -142,6 +157,7 @@ pub fn get_order_statuses() -> Vec<(&'static str, &'static str)> {
         ("pending", "Pending payment"),
         ("processing", "Processing"),
         ("on-hold", "On hold"),
+        ("ready-for-pickup", "Ready for pickup"),
         ("completed", "Completed"),
         ("cancelled", "Cancelled"),
         ("refunded", "Refunded"),
@@ -160,5 +176,7 @@ pub fn get_order_email_templates() -> Vec<(&'static str, &'static str)> {
         ("refunded_order", "Refunded order"),
         ("customer_on_hold_order", "Order on-hold"),
         ("customer_invoice", "Customer invoice"),
+        ("order_shipped", "Order shipped"),
+        ("order_ready_for_pickup", "Order ready for pickup"),
     ]
 }
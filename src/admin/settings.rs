@@ -4,6 +4,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::geo;
+
 /// Settings tab configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SettingsTab {
@@ -98,7 +100,7 @@ pub fn get_settings_tabs() -> Vec<SettingsTab> {
                             description: None,
                             field_type: SettingsFieldType::Select,
                             default: Some("US".to_string()),
-                            options: vec![],
+                            options: get_country_state_options(),
                             required: true,
                         },
                         SettingsField {
@@ -293,3 +295,41 @@ pub fn get_settings_tabs() -> Vec<SettingsTab> {
         },
     ]
 }
+
+/// Country and state options (`US` and `US:CA` style codes) for store location pickers
+pub fn get_country_state_options() -> Vec<(String, String)> {
+    let mut options = Vec::new();
+    for (code, name) in geo::country_options() {
+        let states = geo::subdivision_options(&code);
+        if states.is_empty() {
+            options.push((code, name));
+        } else {
+            for (state_code, state_name) in states {
+                options.push((format!("{}:{}", code, state_code), format!("{} - {}", name, state_name)));
+            }
+        }
+    }
+    options
+}
+
+/// Location options for shipping zone pickers: continents, countries and states
+pub fn get_shipping_zone_location_options() -> Vec<(String, String, String)> {
+    let mut options: Vec<(String, String, String)> = geo::continent_options()
+        .into_iter()
+        .map(|(code, name)| ("continent".to_string(), code, name))
+        .collect();
+
+    for (code, name) in geo::country_options() {
+        let states = geo::subdivision_options(&code);
+        options.push(("country".to_string(), code.clone(), name.clone()));
+        for (state_code, state_name) in states {
+            options.push((
+                "state".to_string(),
+                format!("{}:{}", code, state_code),
+                format!("{}, {}", state_name, name),
+            ));
+        }
+    }
+
+    options
+}
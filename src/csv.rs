@@ -0,0 +1,50 @@
+//! CSV output shared by exports and reports
+
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+/// Quote a CSV line (RFC 4180), terminated with CRLF. Cells a spreadsheet
+/// would run as a formula are neutralized.
+pub(crate) fn csv_line(fields: impl Iterator<Item = String>) -> String {
+    let mut line = fields
+        .map(neutralize_formula)
+        .map(|f| {
+            if f.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", f.replace('"', "\"\""))
+            } else {
+                f
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    line.push_str("\r\n");
+    line
+}
+
+/// Prefix cells starting with `=`, `+`, `-`, `@`, a tab or a carriage
+/// return with `'` so that spreadsheets show them as text. Numbers such as
+/// `-1.50` are left alone.
+fn neutralize_formula(field: String) -> String {
+    if field.starts_with(['=', '+', '-', '@', '\t', '\r']) && Decimal::from_str(&field).is_err() {
+        format!("'{}", field)
+    } else {
+        field
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_line() {
+        let line = csv_line(["a".to_string(), "b,c".to_string(), "say \"hi\"".to_string()].into_iter());
+        assert_eq!(line, "a,\"b,c\",\"say \"\"hi\"\"\"\r\n");
+    }
+
+    #[test]
+    fn test_formulas_are_neutralized() {
+        let line = csv_line(["=1+1".to_string(), "@SUM(A1)".to_string(), "-1.50".to_string(), "+cmd".to_string()].into_iter());
+        assert_eq!(line, "'=1+1,'@SUM(A1),-1.50,'+cmd\r\n");
+    }
+}
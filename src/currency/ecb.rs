@@ -0,0 +1,237 @@
+//! ECB Exchange Rate Provider
+//!
+//! Reads the European Central Bank's daily reference rate XML. Any feed in
+//! the same format works, so the URL can point at a mirror, a local server
+//! or a file on disk.
+
+use async_trait::async_trait;
+use chrono::{NaiveDate, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
+
+use super::provider::{ExchangeRateError, ExchangeRateProvider, ExchangeRates};
+
+/// ECB daily reference rates (quoted against EUR)
+pub const ECB_DAILY_URL: &str = "https://www.ecb.europa.eu/stats/eurofxref/eurofxref-daily.xml";
+
+/// ECB provider configuration
+#[derive(Debug, Clone)]
+pub struct EcbConfig {
+    /// `http(s)://` URL, `file://` URL or plain file path
+    pub url: String,
+    /// Currency the feed quotes against
+    pub base: String,
+    pub timeout_seconds: u64,
+}
+
+impl Default for EcbConfig {
+    fn default() -> Self {
+        Self {
+            url: ECB_DAILY_URL.to_string(),
+            base: "EUR".to_string(),
+            timeout_seconds: 10,
+        }
+    }
+}
+
+/// ECB exchange rate provider
+pub struct EcbRateProvider {
+    config: EcbConfig,
+    enabled: bool,
+}
+
+impl EcbRateProvider {
+    /// Create a new ECB provider
+    pub fn new(config: EcbConfig) -> Self {
+        Self {
+            config,
+            enabled: true,
+        }
+    }
+
+    /// Set enabled state
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Parse an ECB-style feed: `<Cube time="...">` holding
+    /// `<Cube currency="USD" rate="1.0876"/>` entries. History feeds list
+    /// the newest day first; only that day is read.
+    pub fn parse_rates(&self, body: &str) -> Result<ExchangeRates, ExchangeRateError> {
+        let mut rates = HashMap::new();
+        let mut published = None;
+
+        for tag in body.split('<').skip(1) {
+            let tag = tag.split('>').next().unwrap_or_default();
+            let name = tag.split_whitespace().next().unwrap_or_default();
+            if name.rsplit(':').next() != Some("Cube") {
+                continue;
+            }
+
+            if let Some(time) = attribute(tag, "time") {
+                if published.is_some() {
+                    break;
+                }
+                let date = NaiveDate::parse_from_str(time, "%Y-%m-%d")
+                    .map_err(|e| ExchangeRateError::ParseError(format!("time '{}': {}", time, e)))?;
+                published = Some(date);
+            }
+
+            if let (Some(currency), Some(rate)) = (attribute(tag, "currency"), attribute(tag, "rate")) {
+                let rate = Decimal::from_str(rate)
+                    .map_err(|e| ExchangeRateError::ParseError(format!("rate for {}: {}", currency, e)))?;
+                rates.insert(currency.to_uppercase(), rate);
+            }
+        }
+
+        if rates.is_empty() {
+            return Err(ExchangeRateError::ParseError("feed contains no rates".to_string()));
+        }
+
+        Ok(ExchangeRates {
+            provider: self.id().to_string(),
+            base: self.config.base.to_uppercase(),
+            rates,
+            published_at: published
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+                .map(|d| d.and_utc())
+                .unwrap_or_else(Utc::now),
+        })
+    }
+
+    async fn read_feed(&self) -> Result<String, ExchangeRateError> {
+        let url = self.config.url.trim();
+
+        if !url.contains("://") || url.starts_with("file://") {
+            let path = url.trim_start_matches("file://");
+            return tokio::fs::read_to_string(path)
+                .await
+                .map_err(|e| ExchangeRateError::NetworkError(format!("{}: {}", path, e)));
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(self.config.timeout_seconds))
+            .build()
+            .map_err(|e| ExchangeRateError::NetworkError(e.to_string()))?;
+
+        let response = client.get(url)
+            .send()
+            .await
+            .map_err(|e| ExchangeRateError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(ExchangeRateError::NetworkError(
+                format!("{} returned {}", url, response.status()),
+            ));
+        }
+
+        response.text()
+            .await
+            .map_err(|e| ExchangeRateError::NetworkError(e.to_string()))
+    }
+}
+
+/// Value of a single- or double-quoted XML attribute
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let mut offset = 0;
+    while let Some(pos) = tag[offset..].find(name) {
+        let start = offset + pos;
+        offset = start + name.len();
+        if !tag[..start].ends_with(char::is_whitespace) {
+            continue;
+        }
+        let Some(value) = tag[offset..].trim_start().strip_prefix('=') else { continue };
+        let value = value.trim_start();
+        let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+        return value[1..].split(quote).next();
+    }
+    None
+}
+
+#[async_trait]
+impl ExchangeRateProvider for EcbRateProvider {
+    fn id(&self) -> &str {
+        "ecb"
+    }
+
+    fn title(&self) -> &str {
+        "European Central Bank"
+    }
+
+    fn is_available(&self) -> bool {
+        self.enabled && !self.config.url.is_empty()
+    }
+
+    async fn fetch_rates(&self) -> Result<ExchangeRates, ExchangeRateError> {
+        if !self.is_available() {
+            return Err(ExchangeRateError::NotConfigured);
+        }
+        let body = self.read_feed().await?;
+        self.parse_rates(&body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    const FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gesmes:Envelope xmlns:gesmes="http://www.gesmes.org/xml/2002-08-01" xmlns="http://www.ecb.int/vocabulary/2002-08-01/eurofxref">
+    <gesmes:subject>Reference rates</gesmes:subject>
+    <Cube>
+        <Cube time='2024-03-15'>
+            <Cube currency='USD' rate='1.0887'/>
+            <Cube currency='JPY' rate='161.68'/>
+            <Cube currency="GBP" rate="0.85365"/>
+        </Cube>
+    </Cube>
+</gesmes:Envelope>"#;
+
+    #[test]
+    fn test_parse_rates() {
+        let provider = EcbRateProvider::new(EcbConfig::default());
+        let rates = provider.parse_rates(FEED).unwrap();
+
+        assert_eq!(rates.base, "EUR");
+        assert_eq!(rates.rates.len(), 3);
+        assert_eq!(rates.rates["GBP"], dec!(0.85365));
+        assert_eq!(rates.published_at.date_naive(), NaiveDate::from_ymd_opt(2024, 3, 15).unwrap());
+
+        assert_eq!(rates.cross_rate("EUR", "USD").unwrap(), dec!(1.0887));
+        assert_eq!(rates.cross_rate("USD", "GBP").unwrap(), dec!(0.78410030));
+        assert!(matches!(rates.cross_rate("USD", "CHF"), Err(ExchangeRateError::MissingRate(_))));
+
+        assert!(provider.parse_rates("<Cube></Cube>").is_err());
+    }
+
+    #[test]
+    fn test_parse_history_feed_reads_latest_day() {
+        let feed = r#"<Cube>
+            <Cube time="2024-03-15"><Cube currency="USD" rate="1.0887"/></Cube>
+            <Cube time="2024-03-14"><Cube currency="USD" rate="1.0925"/><Cube currency="CHF" rate="0.9631"/></Cube>
+        </Cube>"#;
+        let rates = EcbRateProvider::new(EcbConfig::default()).parse_rates(feed).unwrap();
+
+        assert_eq!(rates.rates.len(), 1);
+        assert_eq!(rates.rates["USD"], dec!(1.0887));
+        assert_eq!(rates.published_at.date_naive(), NaiveDate::from_ymd_opt(2024, 3, 15).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_from_file() {
+        let path = std::env::temp_dir().join(format!("rc-ecb-{}.xml", uuid::Uuid::now_v7()));
+        std::fs::write(&path, FEED).unwrap();
+
+        let provider = EcbRateProvider::new(EcbConfig {
+            url: format!("file://{}", path.display()),
+            ..Default::default()
+        });
+        let rates = provider.fetch_rates().await.unwrap();
+        assert_eq!(rates.rates["JPY"], dec!(161.68));
+
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,282 @@
+//! ISO 4217 currency table
+//!
+//! Active ISO 4217 currencies with their minor units (decimal places), as
+//! published by the ISO 4217 maintenance agency. Symbols are the common
+//! local symbols; currencies without one use their code.
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
+
+/// Minor units assumed for codes missing from the table
+pub const DEFAULT_MINOR_UNITS: u32 = 2;
+
+/// ISO 4217 currency
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IsoCurrency {
+    pub code: &'static str,
+    pub numeric: &'static str,
+    pub name: &'static str,
+    /// Decimal places of the minor unit (0 for JPY, 3 for KWD, 4 for CLF)
+    pub minor_units: u32,
+    pub symbol: &'static str,
+}
+
+impl IsoCurrency {
+    /// Round an amount to the currency's minor unit (half away from zero)
+    pub fn round(&self, amount: Decimal) -> Decimal {
+        amount.round_dp_with_strategy(self.minor_units, RoundingStrategy::MidpointAwayFromZero)
+    }
+
+    /// Amount as an integer count of minor units, e.g. 10.50 USD is 1050
+    pub fn to_minor_units(&self, amount: Decimal) -> Option<i64> {
+        (self.round(amount) * Decimal::from(10_i64.pow(self.minor_units))).to_i64()
+    }
+
+    /// Amount from an integer count of minor units
+    pub fn from_minor_units(&self, units: i64) -> Decimal {
+        Decimal::new(units, self.minor_units)
+    }
+}
+
+/// Look up a currency by alphabetic code (case-insensitive)
+pub fn iso_currency(code: &str) -> Option<&'static IsoCurrency> {
+    let code = code.trim().to_ascii_uppercase();
+    ISO_CURRENCIES.binary_search_by(|c| c.code.cmp(code.as_str()))
+        .ok()
+        .map(|i| &ISO_CURRENCIES[i])
+}
+
+/// Minor units for a currency code, defaulting to 2 for unknown codes
+pub fn minor_units(code: &str) -> u32 {
+    iso_currency(code).map_or(DEFAULT_MINOR_UNITS, |c| c.minor_units)
+}
+
+/// Symbol for a currency code, falling back to the code itself
+pub fn currency_symbol(code: &str) -> &str {
+    iso_currency(code).map_or(code, |c| c.symbol)
+}
+
+/// Convert an amount in `code` to euros at `eur_rate` (euros per unit of
+/// `code`), rounded to the cent. Euro amounts are only rounded.
+pub fn to_eur(amount: Decimal, code: &str, eur_rate: Decimal) -> Decimal {
+    let amount = if code.trim().eq_ignore_ascii_case("EUR") { amount } else { amount * eur_rate };
+    amount.round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero)
+}
+
+/// All active ISO 4217 currencies, sorted by code
+pub static ISO_CURRENCIES: &[IsoCurrency] = &[
+    IsoCurrency { code: "AED", numeric: "784", name: "UAE Dirham", minor_units: 2, symbol: "د.إ" },
+    IsoCurrency { code: "AFN", numeric: "971", name: "Afghani", minor_units: 2, symbol: "؋" },
+    IsoCurrency { code: "ALL", numeric: "008", name: "Lek", minor_units: 2, symbol: "L" },
+    IsoCurrency { code: "AMD", numeric: "051", name: "Armenian Dram", minor_units: 2, symbol: "֏" },
+    IsoCurrency { code: "ANG", numeric: "532", name: "Netherlands Antillean Guilder", minor_units: 2, symbol: "ƒ" },
+    IsoCurrency { code: "AOA", numeric: "973", name: "Kwanza", minor_units: 2, symbol: "Kz" },
+    IsoCurrency { code: "ARS", numeric: "032", name: "Argentine Peso", minor_units: 2, symbol: "ARS$" },
+    IsoCurrency { code: "AUD", numeric: "036", name: "Australian Dollar", minor_units: 2, symbol: "A$" },
+    IsoCurrency { code: "AWG", numeric: "533", name: "Aruban Florin", minor_units: 2, symbol: "ƒ" },
+    IsoCurrency { code: "AZN", numeric: "944", name: "Azerbaijan Manat", minor_units: 2, symbol: "₼" },
+    IsoCurrency { code: "BAM", numeric: "977", name: "Convertible Mark", minor_units: 2, symbol: "KM" },
+    IsoCurrency { code: "BBD", numeric: "052", name: "Barbados Dollar", minor_units: 2, symbol: "Bds$" },
+    IsoCurrency { code: "BDT", numeric: "050", name: "Taka", minor_units: 2, symbol: "৳" },
+    IsoCurrency { code: "BGN", numeric: "975", name: "Bulgarian Lev", minor_units: 2, symbol: "лв" },
+    IsoCurrency { code: "BHD", numeric: "048", name: "Bahraini Dinar", minor_units: 3, symbol: "BD" },
+    IsoCurrency { code: "BIF", numeric: "108", name: "Burundi Franc", minor_units: 0, symbol: "FBu" },
+    IsoCurrency { code: "BMD", numeric: "060", name: "Bermudian Dollar", minor_units: 2, symbol: "BD$" },
+    IsoCurrency { code: "BND", numeric: "096", name: "Brunei Dollar", minor_units: 2, symbol: "B$" },
+    IsoCurrency { code: "BOB", numeric: "068", name: "Boliviano", minor_units: 2, symbol: "Bs" },
+    IsoCurrency { code: "BOV", numeric: "984", name: "Mvdol", minor_units: 2, symbol: "BOV" },
+    IsoCurrency { code: "BRL", numeric: "986", name: "Brazilian Real", minor_units: 2, symbol: "R$" },
+    IsoCurrency { code: "BSD", numeric: "044", name: "Bahamian Dollar", minor_units: 2, symbol: "B$" },
+    IsoCurrency { code: "BTN", numeric: "064", name: "Ngultrum", minor_units: 2, symbol: "Nu." },
+    IsoCurrency { code: "BWP", numeric: "072", name: "Pula", minor_units: 2, symbol: "P" },
+    IsoCurrency { code: "BYN", numeric: "933", name: "Belarusian Ruble", minor_units: 2, symbol: "Br" },
+    IsoCurrency { code: "BZD", numeric: "084", name: "Belize Dollar", minor_units: 2, symbol: "BZ$" },
+    IsoCurrency { code: "CAD", numeric: "124", name: "Canadian Dollar", minor_units: 2, symbol: "C$" },
+    IsoCurrency { code: "CDF", numeric: "976", name: "Congolese Franc", minor_units: 2, symbol: "FC" },
+    IsoCurrency { code: "CHE", numeric: "947", name: "WIR Euro", minor_units: 2, symbol: "CHE" },
+    IsoCurrency { code: "CHF", numeric: "756", name: "Swiss Franc", minor_units: 2, symbol: "CHF" },
+    IsoCurrency { code: "CHW", numeric: "948", name: "WIR Franc", minor_units: 2, symbol: "CHW" },
+    IsoCurrency { code: "CLF", numeric: "990", name: "Unidad de Fomento", minor_units: 4, symbol: "UF" },
+    IsoCurrency { code: "CLP", numeric: "152", name: "Chilean Peso", minor_units: 0, symbol: "CLP$" },
+    IsoCurrency { code: "CNY", numeric: "156", name: "Yuan Renminbi", minor_units: 2, symbol: "¥" },
+    IsoCurrency { code: "COP", numeric: "170", name: "Colombian Peso", minor_units: 2, symbol: "COP$" },
+    IsoCurrency { code: "COU", numeric: "970", name: "Unidad de Valor Real", minor_units: 2, symbol: "COU" },
+    IsoCurrency { code: "CRC", numeric: "188", name: "Costa Rican Colon", minor_units: 2, symbol: "₡" },
+    IsoCurrency { code: "CUP", numeric: "192", name: "Cuban Peso", minor_units: 2, symbol: "$MN" },
+    IsoCurrency { code: "CVE", numeric: "132", name: "Cabo Verde Escudo", minor_units: 2, symbol: "Esc" },
+    IsoCurrency { code: "CZK", numeric: "203", name: "Czech Koruna", minor_units: 2, symbol: "Kč" },
+    IsoCurrency { code: "DJF", numeric: "262", name: "Djibouti Franc", minor_units: 0, symbol: "Fdj" },
+    IsoCurrency { code: "DKK", numeric: "208", name: "Danish Krone", minor_units: 2, symbol: "kr" },
+    IsoCurrency { code: "DOP", numeric: "214", name: "Dominican Peso", minor_units: 2, symbol: "RD$" },
+    IsoCurrency { code: "DZD", numeric: "012", name: "Algerian Dinar", minor_units: 2, symbol: "DA" },
+    IsoCurrency { code: "EGP", numeric: "818", name: "Egyptian Pound", minor_units: 2, symbol: "E£" },
+    IsoCurrency { code: "ERN", numeric: "232", name: "Nakfa", minor_units: 2, symbol: "Nfk" },
+    IsoCurrency { code: "ETB", numeric: "230", name: "Ethiopian Birr", minor_units: 2, symbol: "Br" },
+    IsoCurrency { code: "EUR", numeric: "978", name: "Euro", minor_units: 2, symbol: "€" },
+    IsoCurrency { code: "FJD", numeric: "242", name: "Fiji Dollar", minor_units: 2, symbol: "FJ$" },
+    IsoCurrency { code: "FKP", numeric: "238", name: "Falkland Islands Pound", minor_units: 2, symbol: "£" },
+    IsoCurrency { code: "GBP", numeric: "826", name: "Pound Sterling", minor_units: 2, symbol: "£" },
+    IsoCurrency { code: "GEL", numeric: "981", name: "Lari", minor_units: 2, symbol: "₾" },
+    IsoCurrency { code: "GHS", numeric: "936", name: "Ghana Cedi", minor_units: 2, symbol: "₵" },
+    IsoCurrency { code: "GIP", numeric: "292", name: "Gibraltar Pound", minor_units: 2, symbol: "£" },
+    IsoCurrency { code: "GMD", numeric: "270", name: "Dalasi", minor_units: 2, symbol: "D" },
+    IsoCurrency { code: "GNF", numeric: "324", name: "Guinean Franc", minor_units: 0, symbol: "FG" },
+    IsoCurrency { code: "GTQ", numeric: "320", name: "Quetzal", minor_units: 2, symbol: "Q" },
+    IsoCurrency { code: "GYD", numeric: "328", name: "Guyana Dollar", minor_units: 2, symbol: "G$" },
+    IsoCurrency { code: "HKD", numeric: "344", name: "Hong Kong Dollar", minor_units: 2, symbol: "HK$" },
+    IsoCurrency { code: "HNL", numeric: "340", name: "Lempira", minor_units: 2, symbol: "L" },
+    IsoCurrency { code: "HTG", numeric: "332", name: "Gourde", minor_units: 2, symbol: "G" },
+    IsoCurrency { code: "HUF", numeric: "348", name: "Forint", minor_units: 2, symbol: "Ft" },
+    IsoCurrency { code: "IDR", numeric: "360", name: "Rupiah", minor_units: 2, symbol: "Rp" },
+    IsoCurrency { code: "ILS", numeric: "376", name: "New Israeli Sheqel", minor_units: 2, symbol: "₪" },
+    IsoCurrency { code: "INR", numeric: "356", name: "Indian Rupee", minor_units: 2, symbol: "₹" },
+    IsoCurrency { code: "IQD", numeric: "368", name: "Iraqi Dinar", minor_units: 3, symbol: "ع.د" },
+    IsoCurrency { code: "IRR", numeric: "364", name: "Iranian Rial", minor_units: 2, symbol: "﷼" },
+    IsoCurrency { code: "ISK", numeric: "352", name: "Iceland Krona", minor_units: 0, symbol: "kr" },
+    IsoCurrency { code: "JMD", numeric: "388", name: "Jamaican Dollar", minor_units: 2, symbol: "J$" },
+    IsoCurrency { code: "JOD", numeric: "400", name: "Jordanian Dinar", minor_units: 3, symbol: "JD" },
+    IsoCurrency { code: "JPY", numeric: "392", name: "Yen", minor_units: 0, symbol: "¥" },
+    IsoCurrency { code: "KES", numeric: "404", name: "Kenyan Shilling", minor_units: 2, symbol: "KSh" },
+    IsoCurrency { code: "KGS", numeric: "417", name: "Som", minor_units: 2, symbol: "сом" },
+    IsoCurrency { code: "KHR", numeric: "116", name: "Riel", minor_units: 2, symbol: "៛" },
+    IsoCurrency { code: "KMF", numeric: "174", name: "Comorian Franc", minor_units: 0, symbol: "CF" },
+    IsoCurrency { code: "KPW", numeric: "408", name: "North Korean Won", minor_units: 2, symbol: "₩" },
+    IsoCurrency { code: "KRW", numeric: "410", name: "Won", minor_units: 0, symbol: "₩" },
+    IsoCurrency { code: "KWD", numeric: "414", name: "Kuwaiti Dinar", minor_units: 3, symbol: "KD" },
+    IsoCurrency { code: "KYD", numeric: "136", name: "Cayman Islands Dollar", minor_units: 2, symbol: "CI$" },
+    IsoCurrency { code: "KZT", numeric: "398", name: "Tenge", minor_units: 2, symbol: "₸" },
+    IsoCurrency { code: "LAK", numeric: "418", name: "Lao Kip", minor_units: 2, symbol: "₭" },
+    IsoCurrency { code: "LBP", numeric: "422", name: "Lebanese Pound", minor_units: 2, symbol: "L£" },
+    IsoCurrency { code: "LKR", numeric: "144", name: "Sri Lanka Rupee", minor_units: 2, symbol: "Rs" },
+    IsoCurrency { code: "LRD", numeric: "430", name: "Liberian Dollar", minor_units: 2, symbol: "L$" },
+    IsoCurrency { code: "LSL", numeric: "426", name: "Loti", minor_units: 2, symbol: "L" },
+    IsoCurrency { code: "LYD", numeric: "434", name: "Libyan Dinar", minor_units: 3, symbol: "LD" },
+    IsoCurrency { code: "MAD", numeric: "504", name: "Moroccan Dirham", minor_units: 2, symbol: "DH" },
+    IsoCurrency { code: "MDL", numeric: "498", name: "Moldovan Leu", minor_units: 2, symbol: "L" },
+    IsoCurrency { code: "MGA", numeric: "969", name: "Malagasy Ariary", minor_units: 2, symbol: "Ar" },
+    IsoCurrency { code: "MKD", numeric: "807", name: "Denar", minor_units: 2, symbol: "ден" },
+    IsoCurrency { code: "MMK", numeric: "104", name: "Kyat", minor_units: 2, symbol: "K" },
+    IsoCurrency { code: "MNT", numeric: "496", name: "Tugrik", minor_units: 2, symbol: "₮" },
+    IsoCurrency { code: "MOP", numeric: "446", name: "Pataca", minor_units: 2, symbol: "MOP$" },
+    IsoCurrency { code: "MRU", numeric: "929", name: "Ouguiya", minor_units: 2, symbol: "UM" },
+    IsoCurrency { code: "MUR", numeric: "480", name: "Mauritius Rupee", minor_units: 2, symbol: "Rs" },
+    IsoCurrency { code: "MVR", numeric: "462", name: "Rufiyaa", minor_units: 2, symbol: "Rf" },
+    IsoCurrency { code: "MWK", numeric: "454", name: "Malawi Kwacha", minor_units: 2, symbol: "MK" },
+    IsoCurrency { code: "MXN", numeric: "484", name: "Mexican Peso", minor_units: 2, symbol: "Mex$" },
+    IsoCurrency { code: "MXV", numeric: "979", name: "Mexican Unidad de Inversion (UDI)", minor_units: 2, symbol: "MXV" },
+    IsoCurrency { code: "MYR", numeric: "458", name: "Malaysian Ringgit", minor_units: 2, symbol: "RM" },
+    IsoCurrency { code: "MZN", numeric: "943", name: "Mozambique Metical", minor_units: 2, symbol: "MT" },
+    IsoCurrency { code: "NAD", numeric: "516", name: "Namibia Dollar", minor_units: 2, symbol: "N$" },
+    IsoCurrency { code: "NGN", numeric: "566", name: "Naira", minor_units: 2, symbol: "₦" },
+    IsoCurrency { code: "NIO", numeric: "558", name: "Cordoba Oro", minor_units: 2, symbol: "C$" },
+    IsoCurrency { code: "NOK", numeric: "578", name: "Norwegian Krone", minor_units: 2, symbol: "kr" },
+    IsoCurrency { code: "NPR", numeric: "524", name: "Nepalese Rupee", minor_units: 2, symbol: "Rs" },
+    IsoCurrency { code: "NZD", numeric: "554", name: "New Zealand Dollar", minor_units: 2, symbol: "NZ$" },
+    IsoCurrency { code: "OMR", numeric: "512", name: "Rial Omani", minor_units: 3, symbol: "OMR" },
+    IsoCurrency { code: "PAB", numeric: "590", name: "Balboa", minor_units: 2, symbol: "B/." },
+    IsoCurrency { code: "PEN", numeric: "604", name: "Sol", minor_units: 2, symbol: "S/" },
+    IsoCurrency { code: "PGK", numeric: "598", name: "Kina", minor_units: 2, symbol: "K" },
+    IsoCurrency { code: "PHP", numeric: "608", name: "Philippine Peso", minor_units: 2, symbol: "₱" },
+    IsoCurrency { code: "PKR", numeric: "586", name: "Pakistan Rupee", minor_units: 2, symbol: "₨" },
+    IsoCurrency { code: "PLN", numeric: "985", name: "Zloty", minor_units: 2, symbol: "zł" },
+    IsoCurrency { code: "PYG", numeric: "600", name: "Guarani", minor_units: 0, symbol: "₲" },
+    IsoCurrency { code: "QAR", numeric: "634", name: "Qatari Rial", minor_units: 2, symbol: "QR" },
+    IsoCurrency { code: "RON", numeric: "946", name: "Romanian Leu", minor_units: 2, symbol: "lei" },
+    IsoCurrency { code: "RSD", numeric: "941", name: "Serbian Dinar", minor_units: 2, symbol: "din" },
+    IsoCurrency { code: "RUB", numeric: "643", name: "Russian Ruble", minor_units: 2, symbol: "₽" },
+    IsoCurrency { code: "RWF", numeric: "646", name: "Rwanda Franc", minor_units: 0, symbol: "FRw" },
+    IsoCurrency { code: "SAR", numeric: "682", name: "Saudi Riyal", minor_units: 2, symbol: "﷼" },
+    IsoCurrency { code: "SBD", numeric: "090", name: "Solomon Islands Dollar", minor_units: 2, symbol: "SI$" },
+    IsoCurrency { code: "SCR", numeric: "690", name: "Seychelles Rupee", minor_units: 2, symbol: "SR" },
+    IsoCurrency { code: "SDG", numeric: "938", name: "Sudanese Pound", minor_units: 2, symbol: "SDG" },
+    IsoCurrency { code: "SEK", numeric: "752", name: "Swedish Krona", minor_units: 2, symbol: "kr" },
+    IsoCurrency { code: "SGD", numeric: "702", name: "Singapore Dollar", minor_units: 2, symbol: "S$" },
+    IsoCurrency { code: "SHP", numeric: "654", name: "Saint Helena Pound", minor_units: 2, symbol: "£" },
+    IsoCurrency { code: "SLE", numeric: "925", name: "Leone", minor_units: 2, symbol: "Le" },
+    IsoCurrency { code: "SOS", numeric: "706", name: "Somali Shilling", minor_units: 2, symbol: "Sh" },
+    IsoCurrency { code: "SRD", numeric: "968", name: "Surinam Dollar", minor_units: 2, symbol: "SR$" },
+    IsoCurrency { code: "SSP", numeric: "728", name: "South Sudanese Pound", minor_units: 2, symbol: "SSP" },
+    IsoCurrency { code: "STN", numeric: "930", name: "Dobra", minor_units: 2, symbol: "Db" },
+    IsoCurrency { code: "SVC", numeric: "222", name: "El Salvador Colon", minor_units: 2, symbol: "₡" },
+    IsoCurrency { code: "SYP", numeric: "760", name: "Syrian Pound", minor_units: 2, symbol: "SYP" },
+    IsoCurrency { code: "SZL", numeric: "748", name: "Lilangeni", minor_units: 2, symbol: "E" },
+    IsoCurrency { code: "THB", numeric: "764", name: "Baht", minor_units: 2, symbol: "฿" },
+    IsoCurrency { code: "TJS", numeric: "972", name: "Somoni", minor_units: 2, symbol: "SM" },
+    IsoCurrency { code: "TMT", numeric: "934", name: "Turkmenistan New Manat", minor_units: 2, symbol: "m" },
+    IsoCurrency { code: "TND", numeric: "788", name: "Tunisian Dinar", minor_units: 3, symbol: "DT" },
+    IsoCurrency { code: "TOP", numeric: "776", name: "Pa'anga", minor_units: 2, symbol: "T$" },
+    IsoCurrency { code: "TRY", numeric: "949", name: "Turkish Lira", minor_units: 2, symbol: "₺" },
+    IsoCurrency { code: "TTD", numeric: "780", name: "Trinidad and Tobago Dollar", minor_units: 2, symbol: "TT$" },
+    IsoCurrency { code: "TWD", numeric: "901", name: "New Taiwan Dollar", minor_units: 2, symbol: "NT$" },
+    IsoCurrency { code: "TZS", numeric: "834", name: "Tanzanian Shilling", minor_units: 2, symbol: "TSh" },
+    IsoCurrency { code: "UAH", numeric: "980", name: "Hryvnia", minor_units: 2, symbol: "₴" },
+    IsoCurrency { code: "UGX", numeric: "800", name: "Uganda Shilling", minor_units: 0, symbol: "USh" },
+    IsoCurrency { code: "USD", numeric: "840", name: "US Dollar", minor_units: 2, symbol: "$" },
+    IsoCurrency { code: "USN", numeric: "997", name: "US Dollar (Next day)", minor_units: 2, symbol: "USN" },
+    IsoCurrency { code: "UYI", numeric: "940", name: "Uruguay Peso en Unidades Indexadas (UI)", minor_units: 0, symbol: "UYI" },
+    IsoCurrency { code: "UYU", numeric: "858", name: "Peso Uruguayo", minor_units: 2, symbol: "$U" },
+    IsoCurrency { code: "UYW", numeric: "927", name: "Unidad Previsional", minor_units: 4, symbol: "UYW" },
+    IsoCurrency { code: "UZS", numeric: "860", name: "Uzbekistan Sum", minor_units: 2, symbol: "soʻm" },
+    IsoCurrency { code: "VED", numeric: "926", name: "Bolívar Soberano", minor_units: 2, symbol: "Bs.D" },
+    IsoCurrency { code: "VES", numeric: "928", name: "Bolívar Soberano", minor_units: 2, symbol: "Bs.S" },
+    IsoCurrency { code: "VND", numeric: "704", name: "Dong", minor_units: 0, symbol: "₫" },
+    IsoCurrency { code: "VUV", numeric: "548", name: "Vatu", minor_units: 0, symbol: "VT" },
+    IsoCurrency { code: "WST", numeric: "882", name: "Tala", minor_units: 2, symbol: "WS$" },
+    IsoCurrency { code: "XAF", numeric: "950", name: "CFA Franc BEAC", minor_units: 0, symbol: "FCFA" },
+    IsoCurrency { code: "XCD", numeric: "951", name: "East Caribbean Dollar", minor_units: 2, symbol: "EC$" },
+    IsoCurrency { code: "XCG", numeric: "532", name: "Caribbean Guilder", minor_units: 2, symbol: "Cg" },
+    IsoCurrency { code: "XOF", numeric: "952", name: "CFA Franc BCEAO", minor_units: 0, symbol: "CFA" },
+    IsoCurrency { code: "XPF", numeric: "953", name: "CFP Franc", minor_units: 0, symbol: "XPF" },
+    IsoCurrency { code: "YER", numeric: "886", name: "Yemeni Rial", minor_units: 2, symbol: "﷼" },
+    IsoCurrency { code: "ZAR", numeric: "710", name: "Rand", minor_units: 2, symbol: "R" },
+    IsoCurrency { code: "ZMW", numeric: "967", name: "Zambian Kwacha", minor_units: 2, symbol: "ZK" },
+    IsoCurrency { code: "ZWG", numeric: "924", name: "Zimbabwe Gold", minor_units: 2, symbol: "ZiG" },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_table_is_sorted() {
+        assert!(ISO_CURRENCIES.windows(2).all(|w| w[0].code < w[1].code));
+        assert!(ISO_CURRENCIES.iter().all(|c| c.code.len() == 3 && c.numeric.len() == 3));
+    }
+
+    #[test]
+    fn test_minor_units() {
+        assert_eq!(minor_units("usd"), 2);
+        assert_eq!(minor_units("JPY"), 0);
+        assert_eq!(minor_units("KWD"), 3);
+        assert_eq!(minor_units("CLF"), 4);
+        assert_eq!(minor_units("XXX"), DEFAULT_MINOR_UNITS);
+        assert_eq!(currency_symbol("EUR"), "€");
+        assert_eq!(currency_symbol("XXX"), "XXX");
+    }
+
+    #[test]
+    fn test_to_eur() {
+        assert_eq!(to_eur(dec!(100.005), "eur", dec!(2)), dec!(100.01));
+        assert_eq!(to_eur(dec!(100), "GBP", dec!(1.1625)), dec!(116.25));
+        assert_eq!(to_eur(dec!(1000), "JPY", dec!(0.0061)), dec!(6.10));
+    }
+
+    #[test]
+    fn test_to_minor_units() {
+        let jpy = iso_currency("JPY").unwrap();
+        assert_eq!(jpy.round(dec!(1234.5)), dec!(1235));
+        assert_eq!(jpy.to_minor_units(dec!(1234.5)), Some(1235));
+
+        let kwd = iso_currency("KWD").unwrap();
+        assert_eq!(kwd.to_minor_units(dec!(12.3456)), Some(12346));
+        assert_eq!(kwd.from_minor_units(12346), dec!(12.346));
+
+        let clf = iso_currency("CLF").unwrap();
+        assert_eq!(clf.to_minor_units(dec!(1.23456)), Some(12346));
+
+        let usd = iso_currency("USD").unwrap();
+        assert_eq!(usd.to_minor_units(dec!(19.995)), Some(2000));
+        assert_eq!(usd.to_minor_units(dec!(-0.125)), Some(-13));
+    }
+}
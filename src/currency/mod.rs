@@ -0,0 +1,16 @@
+//! RustCommerce Currencies
+//!
+//! ISO 4217 currency data, plus the exchange rate providers used to keep
+//! multi-currency rates up to date.
+
+pub mod iso;
+#[cfg(feature = "multi_currency")]
+pub mod provider;
+#[cfg(feature = "multi_currency")]
+pub mod ecb;
+
+pub use iso::{currency_symbol, iso_currency, minor_units, to_eur, IsoCurrency, ISO_CURRENCIES};
+#[cfg(feature = "multi_currency")]
+pub use provider::{ExchangeRateError, ExchangeRateProvider, ExchangeRateProviderRegistry, ExchangeRates};
+#[cfg(feature = "multi_currency")]
+pub use ecb::{EcbConfig, EcbRateProvider};
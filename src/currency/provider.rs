@@ -0,0 +1,118 @@
+//! Exchange Rate Provider Base
+//!
+//! Defines the exchange rate provider trait and registry.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Decimal places kept for cross rates (matches the rate columns)
+pub const RATE_PRECISION: u32 = 8;
+
+/// Exchange rate provider error
+#[derive(Debug, Clone)]
+pub enum ExchangeRateError {
+    NotConfigured,
+    NetworkError(String),
+    ParseError(String),
+    MissingRate(String),
+}
+
+impl std::fmt::Display for ExchangeRateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotConfigured => write!(f, "Exchange rate provider not configured"),
+            Self::NetworkError(msg) => write!(f, "Network error: {}", msg),
+            Self::ParseError(msg) => write!(f, "Could not read exchange rates: {}", msg),
+            Self::MissingRate(code) => write!(f, "No exchange rate for {}", code),
+        }
+    }
+}
+
+impl std::error::Error for ExchangeRateError {}
+
+/// A set of rates published by a provider, each quoted as units of the
+/// currency per one unit of `base`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExchangeRates {
+    pub provider: String,
+    pub base: String,
+    pub rates: HashMap<String, Decimal>,
+    pub published_at: DateTime<Utc>,
+}
+
+impl ExchangeRates {
+    /// Rate of a currency against the feed's base
+    fn quote(&self, code: &str) -> Option<Decimal> {
+        if code.eq_ignore_ascii_case(&self.base) {
+            return Some(Decimal::ONE);
+        }
+        self.rates.get(&code.to_uppercase())
+            .copied()
+            .filter(|rate| *rate > Decimal::ZERO)
+    }
+
+    /// Units of `to` per one unit of `from`, e.g. USD to GBP from an EUR feed
+    pub fn cross_rate(&self, from: &str, to: &str) -> Result<Decimal, ExchangeRateError> {
+        let from_rate = self.quote(from).ok_or_else(|| ExchangeRateError::MissingRate(from.to_string()))?;
+        let to_rate = self.quote(to).ok_or_else(|| ExchangeRateError::MissingRate(to.to_string()))?;
+        Ok((to_rate / from_rate).round_dp(RATE_PRECISION))
+    }
+}
+
+/// Exchange rate provider trait
+#[async_trait]
+pub trait ExchangeRateProvider: Send + Sync {
+    /// Get provider ID
+    fn id(&self) -> &str;
+
+    /// Get provider title
+    fn title(&self) -> &str;
+
+    /// Check if provider is available
+    fn is_available(&self) -> bool;
+
+    /// Fetch the latest published rates
+    async fn fetch_rates(&self) -> Result<ExchangeRates, ExchangeRateError>;
+}
+
+/// Exchange rate provider registry
+pub struct ExchangeRateProviderRegistry {
+    providers: HashMap<String, Arc<dyn ExchangeRateProvider>>,
+}
+
+impl ExchangeRateProviderRegistry {
+    /// Create a new provider registry
+    pub fn new() -> Self {
+        Self {
+            providers: HashMap::new(),
+        }
+    }
+
+    /// Register an exchange rate provider
+    pub fn register(&mut self, provider: Arc<dyn ExchangeRateProvider>) {
+        self.providers.insert(provider.id().to_string(), provider);
+    }
+
+    /// Get a provider by ID
+    pub fn get(&self, id: &str) -> Option<Arc<dyn ExchangeRateProvider>> {
+        self.providers.get(id).cloned()
+    }
+
+    /// Get available providers
+    pub fn get_available(&self) -> Vec<Arc<dyn ExchangeRateProvider>> {
+        self.providers.values()
+            .filter(|p| p.is_available())
+            .cloned()
+            .collect()
+    }
+}
+
+impl Default for ExchangeRateProviderRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,259 @@
+//! ISO 3166-1 country table
+//!
+//! Generated from the Debian `iso-codes` package; continent assignments follow
+//! the conventional seven-continent split used for shipping zones.
+
+use super::{Continent, Country};
+
+/// All ISO 3166-1 countries, sorted by alpha-2 code
+pub static COUNTRIES: &[Country] = &[
+    Country { alpha2: "AD", alpha3: "AND", numeric: "020", name: "Andorra", continent: Continent::Europe },
+    Country { alpha2: "AE", alpha3: "ARE", numeric: "784", name: "United Arab Emirates", continent: Continent::Asia },
+    Country { alpha2: "AF", alpha3: "AFG", numeric: "004", name: "Afghanistan", continent: Continent::Asia },
+    Country { alpha2: "AG", alpha3: "ATG", numeric: "028", name: "Antigua and Barbuda", continent: Continent::NorthAmerica },
+    Country { alpha2: "AI", alpha3: "AIA", numeric: "660", name: "Anguilla", continent: Continent::NorthAmerica },
+    Country { alpha2: "AL", alpha3: "ALB", numeric: "008", name: "Albania", continent: Continent::Europe },
+    Country { alpha2: "AM", alpha3: "ARM", numeric: "051", name: "Armenia", continent: Continent::Asia },
+    Country { alpha2: "AO", alpha3: "AGO", numeric: "024", name: "Angola", continent: Continent::Africa },
+    Country { alpha2: "AQ", alpha3: "ATA", numeric: "010", name: "Antarctica", continent: Continent::Antarctica },
+    Country { alpha2: "AR", alpha3: "ARG", numeric: "032", name: "Argentina", continent: Continent::SouthAmerica },
+    Country { alpha2: "AS", alpha3: "ASM", numeric: "016", name: "American Samoa", continent: Continent::Oceania },
+    Country { alpha2: "AT", alpha3: "AUT", numeric: "040", name: "Austria", continent: Continent::Europe },
+    Country { alpha2: "AU", alpha3: "AUS", numeric: "036", name: "Australia", continent: Continent::Oceania },
+    Country { alpha2: "AW", alpha3: "ABW", numeric: "533", name: "Aruba", continent: Continent::NorthAmerica },
+    Country { alpha2: "AX", alpha3: "ALA", numeric: "248", name: "Åland Islands", continent: Continent::Europe },
+    Country { alpha2: "AZ", alpha3: "AZE", numeric: "031", name: "Azerbaijan", continent: Continent::Asia },
+    Country { alpha2: "BA", alpha3: "BIH", numeric: "070", name: "Bosnia and Herzegovina", continent: Continent::Europe },
+    Country { alpha2: "BB", alpha3: "BRB", numeric: "052", name: "Barbados", continent: Continent::NorthAmerica },
+    Country { alpha2: "BD", alpha3: "BGD", numeric: "050", name: "Bangladesh", continent: Continent::Asia },
+    Country { alpha2: "BE", alpha3: "BEL", numeric: "056", name: "Belgium", continent: Continent::Europe },
+    Country { alpha2: "BF", alpha3: "BFA", numeric: "854", name: "Burkina Faso", continent: Continent::Africa },
+    Country { alpha2: "BG", alpha3: "BGR", numeric: "100", name: "Bulgaria", continent: Continent::Europe },
+    Country { alpha2: "BH", alpha3: "BHR", numeric: "048", name: "Bahrain", continent: Continent::Asia },
+    Country { alpha2: "BI", alpha3: "BDI", numeric: "108", name: "Burundi", continent: Continent::Africa },
+    Country { alpha2: "BJ", alpha3: "BEN", numeric: "204", name: "Benin", continent: Continent::Africa },
+    Country { alpha2: "BL", alpha3: "BLM", numeric: "652", name: "Saint Barthélemy", continent: Continent::NorthAmerica },
+    Country { alpha2: "BM", alpha3: "BMU", numeric: "060", name: "Bermuda", continent: Continent::NorthAmerica },
+    Country { alpha2: "BN", alpha3: "BRN", numeric: "096", name: "Brunei Darussalam", continent: Continent::Asia },
+    Country { alpha2: "BO", alpha3: "BOL", numeric: "068", name: "Bolivia", continent: Continent::SouthAmerica },
+    Country { alpha2: "BQ", alpha3: "BES", numeric: "535", name: "Bonaire, Sint Eustatius and Saba", continent: Continent::NorthAmerica },
+    Country { alpha2: "BR", alpha3: "BRA", numeric: "076", name: "Brazil", continent: Continent::SouthAmerica },
+    Country { alpha2: "BS", alpha3: "BHS", numeric: "044", name: "Bahamas", continent: Continent::NorthAmerica },
+    Country { alpha2: "BT", alpha3: "BTN", numeric: "064", name: "Bhutan", continent: Continent::Asia },
+    Country { alpha2: "BV", alpha3: "BVT", numeric: "074", name: "Bouvet Island", continent: Continent::Antarctica },
+    Country { alpha2: "BW", alpha3: "BWA", numeric: "072", name: "Botswana", continent: Continent::Africa },
+    Country { alpha2: "BY", alpha3: "BLR", numeric: "112", name: "Belarus", continent: Continent::Europe },
+    Country { alpha2: "BZ", alpha3: "BLZ", numeric: "084", name: "Belize", continent: Continent::NorthAmerica },
+    Country { alpha2: "CA", alpha3: "CAN", numeric: "124", name: "Canada", continent: Continent::NorthAmerica },
+    Country { alpha2: "CC", alpha3: "CCK", numeric: "166", name: "Cocos (Keeling) Islands", continent: Continent::Asia },
+    Country { alpha2: "CD", alpha3: "COD", numeric: "180", name: "Congo, The Democratic Republic of the", continent: Continent::Africa },
+    Country { alpha2: "CF", alpha3: "CAF", numeric: "140", name: "Central African Republic", continent: Continent::Africa },
+    Country { alpha2: "CG", alpha3: "COG", numeric: "178", name: "Congo", continent: Continent::Africa },
+    Country { alpha2: "CH", alpha3: "CHE", numeric: "756", name: "Switzerland", continent: Continent::Europe },
+    Country { alpha2: "CI", alpha3: "CIV", numeric: "384", name: "Côte d'Ivoire", continent: Continent::Africa },
+    Country { alpha2: "CK", alpha3: "COK", numeric: "184", name: "Cook Islands", continent: Continent::Oceania },
+    Country { alpha2: "CL", alpha3: "CHL", numeric: "152", name: "Chile", continent: Continent::SouthAmerica },
+    Country { alpha2: "CM", alpha3: "CMR", numeric: "120", name: "Cameroon", continent: Continent::Africa },
+    Country { alpha2: "CN", alpha3: "CHN", numeric: "156", name: "China", continent: Continent::Asia },
+    Country { alpha2: "CO", alpha3: "COL", numeric: "170", name: "Colombia", continent: Continent::SouthAmerica },
+    Country { alpha2: "CR", alpha3: "CRI", numeric: "188", name: "Costa Rica", continent: Continent::NorthAmerica },
+    Country { alpha2: "CU", alpha3: "CUB", numeric: "192", name: "Cuba", continent: Continent::NorthAmerica },
+    Country { alpha2: "CV", alpha3: "CPV", numeric: "132", name: "Cabo Verde", continent: Continent::Africa },
+    Country { alpha2: "CW", alpha3: "CUW", numeric: "531", name: "Curaçao", continent: Continent::NorthAmerica },
+    Country { alpha2: "CX", alpha3: "CXR", numeric: "162", name: "Christmas Island", continent: Continent::Asia },
+    Country { alpha2: "CY", alpha3: "CYP", numeric: "196", name: "Cyprus", continent: Continent::Europe },
+    Country { alpha2: "CZ", alpha3: "CZE", numeric: "203", name: "Czechia", continent: Continent::Europe },
+    Country { alpha2: "DE", alpha3: "DEU", numeric: "276", name: "Germany", continent: Continent::Europe },
+    Country { alpha2: "DJ", alpha3: "DJI", numeric: "262", name: "Djibouti", continent: Continent::Africa },
+    Country { alpha2: "DK", alpha3: "DNK", numeric: "208", name: "Denmark", continent: Continent::Europe },
+    Country { alpha2: "DM", alpha3: "DMA", numeric: "212", name: "Dominica", continent: Continent::NorthAmerica },
+    Country { alpha2: "DO", alpha3: "DOM", numeric: "214", name: "Dominican Republic", continent: Continent::NorthAmerica },
+    Country { alpha2: "DZ", alpha3: "DZA", numeric: "012", name: "Algeria", continent: Continent::Africa },
+    Country { alpha2: "EC", alpha3: "ECU", numeric: "218", name: "Ecuador", continent: Continent::SouthAmerica },
+    Country { alpha2: "EE", alpha3: "EST", numeric: "233", name: "Estonia", continent: Continent::Europe },
+    Country { alpha2: "EG", alpha3: "EGY", numeric: "818", name: "Egypt", continent: Continent::Africa },
+    Country { alpha2: "EH", alpha3: "ESH", numeric: "732", name: "Western Sahara", continent: Continent::Africa },
+    Country { alpha2: "ER", alpha3: "ERI", numeric: "232", name: "Eritrea", continent: Continent::Africa },
+    Country { alpha2: "ES", alpha3: "ESP", numeric: "724", name: "Spain", continent: Continent::Europe },
+    Country { alpha2: "ET", alpha3: "ETH", numeric: "231", name: "Ethiopia", continent: Continent::Africa },
+    Country { alpha2: "FI", alpha3: "FIN", numeric: "246", name: "Finland", continent: Continent::Europe },
+    Country { alpha2: "FJ", alpha3: "FJI", numeric: "242", name: "Fiji", continent: Continent::Oceania },
+    Country { alpha2: "FK", alpha3: "FLK", numeric: "238", name: "Falkland Islands (Malvinas)", continent: Continent::SouthAmerica },
+    Country { alpha2: "FM", alpha3: "FSM", numeric: "583", name: "Micronesia, Federated States of", continent: Continent::Oceania },
+    Country { alpha2: "FO", alpha3: "FRO", numeric: "234", name: "Faroe Islands", continent: Continent::Europe },
+    Country { alpha2: "FR", alpha3: "FRA", numeric: "250", name: "France", continent: Continent::Europe },
+    Country { alpha2: "GA", alpha3: "GAB", numeric: "266", name: "Gabon", continent: Continent::Africa },
+    Country { alpha2: "GB", alpha3: "GBR", numeric: "826", name: "United Kingdom", continent: Continent::Europe },
+    Country { alpha2: "GD", alpha3: "GRD", numeric: "308", name: "Grenada", continent: Continent::NorthAmerica },
+    Country { alpha2: "GE", alpha3: "GEO", numeric: "268", name: "Georgia", continent: Continent::Asia },
+    Country { alpha2: "GF", alpha3: "GUF", numeric: "254", name: "French Guiana", continent: Continent::SouthAmerica },
+    Country { alpha2: "GG", alpha3: "GGY", numeric: "831", name: "Guernsey", continent: Continent::Europe },
+    Country { alpha2: "GH", alpha3: "GHA", numeric: "288", name: "Ghana", continent: Continent::Africa },
+    Country { alpha2: "GI", alpha3: "GIB", numeric: "292", name: "Gibraltar", continent: Continent::Europe },
+    Country { alpha2: "GL", alpha3: "GRL", numeric: "304", name: "Greenland", continent: Continent::NorthAmerica },
+    Country { alpha2: "GM", alpha3: "GMB", numeric: "270", name: "Gambia", continent: Continent::Africa },
+    Country { alpha2: "GN", alpha3: "GIN", numeric: "324", name: "Guinea", continent: Continent::Africa },
+    Country { alpha2: "GP", alpha3: "GLP", numeric: "312", name: "Guadeloupe", continent: Continent::NorthAmerica },
+    Country { alpha2: "GQ", alpha3: "GNQ", numeric: "226", name: "Equatorial Guinea", continent: Continent::Africa },
+    Country { alpha2: "GR", alpha3: "GRC", numeric: "300", name: "Greece", continent: Continent::Europe },
+    Country { alpha2: "GS", alpha3: "SGS", numeric: "239", name: "South Georgia and the South Sandwich Islands", continent: Continent::Antarctica },
+    Country { alpha2: "GT", alpha3: "GTM", numeric: "320", name: "Guatemala", continent: Continent::NorthAmerica },
+    Country { alpha2: "GU", alpha3: "GUM", numeric: "316", name: "Guam", continent: Continent::Oceania },
+    Country { alpha2: "GW", alpha3: "GNB", numeric: "624", name: "Guinea-Bissau", continent: Continent::Africa },
+    Country { alpha2: "GY", alpha3: "GUY", numeric: "328", name: "Guyana", continent: Continent::SouthAmerica },
+    Country { alpha2: "HK", alpha3: "HKG", numeric: "344", name: "Hong Kong", continent: Continent::Asia },
+    Country { alpha2: "HM", alpha3: "HMD", numeric: "334", name: "Heard Island and McDonald Islands", continent: Continent::Antarctica },
+    Country { alpha2: "HN", alpha3: "HND", numeric: "340", name: "Honduras", continent: Continent::NorthAmerica },
+    Country { alpha2: "HR", alpha3: "HRV", numeric: "191", name: "Croatia", continent: Continent::Europe },
+    Country { alpha2: "HT", alpha3: "HTI", numeric: "332", name: "Haiti", continent: Continent::NorthAmerica },
+    Country { alpha2: "HU", alpha3: "HUN", numeric: "348", name: "Hungary", continent: Continent::Europe },
+    Country { alpha2: "ID", alpha3: "IDN", numeric: "360", name: "Indonesia", continent: Continent::Asia },
+    Country { alpha2: "IE", alpha3: "IRL", numeric: "372", name: "Ireland", continent: Continent::Europe },
+    Country { alpha2: "IL", alpha3: "ISR", numeric: "376", name: "Israel", continent: Continent::Asia },
+    Country { alpha2: "IM", alpha3: "IMN", numeric: "833", name: "Isle of Man", continent: Continent::Europe },
+    Country { alpha2: "IN", alpha3: "IND", numeric: "356", name: "India", continent: Continent::Asia },
+    Country { alpha2: "IO", alpha3: "IOT", numeric: "086", name: "British Indian Ocean Territory", continent: Continent::Asia },
+    Country { alpha2: "IQ", alpha3: "IRQ", numeric: "368", name: "Iraq", continent: Continent::Asia },
+    Country { alpha2: "IR", alpha3: "IRN", numeric: "364", name: "Iran", continent: Continent::Asia },
+    Country { alpha2: "IS", alpha3: "ISL", numeric: "352", name: "Iceland", continent: Continent::Europe },
+    Country { alpha2: "IT", alpha3: "ITA", numeric: "380", name: "Italy", continent: Continent::Europe },
+    Country { alpha2: "JE", alpha3: "JEY", numeric: "832", name: "Jersey", continent: Continent::Europe },
+    Country { alpha2: "JM", alpha3: "JAM", numeric: "388", name: "Jamaica", continent: Continent::NorthAmerica },
+    Country { alpha2: "JO", alpha3: "JOR", numeric: "400", name: "Jordan", continent: Continent::Asia },
+    Country { alpha2: "JP", alpha3: "JPN", numeric: "392", name: "Japan", continent: Continent::Asia },
+    Country { alpha2: "KE", alpha3: "KEN", numeric: "404", name: "Kenya", continent: Continent::Africa },
+    Country { alpha2: "KG", alpha3: "KGZ", numeric: "417", name: "Kyrgyzstan", continent: Continent::Asia },
+    Country { alpha2: "KH", alpha3: "KHM", numeric: "116", name: "Cambodia", continent: Continent::Asia },
+    Country { alpha2: "KI", alpha3: "KIR", numeric: "296", name: "Kiribati", continent: Continent::Oceania },
+    Country { alpha2: "KM", alpha3: "COM", numeric: "174", name: "Comoros", continent: Continent::Africa },
+    Country { alpha2: "KN", alpha3: "KNA", numeric: "659", name: "Saint Kitts and Nevis", continent: Continent::NorthAmerica },
+    Country { alpha2: "KP", alpha3: "PRK", numeric: "408", name: "North Korea", continent: Continent::Asia },
+    Country { alpha2: "KR", alpha3: "KOR", numeric: "410", name: "South Korea", continent: Continent::Asia },
+    Country { alpha2: "KW", alpha3: "KWT", numeric: "414", name: "Kuwait", continent: Continent::Asia },
+    Country { alpha2: "KY", alpha3: "CYM", numeric: "136", name: "Cayman Islands", continent: Continent::NorthAmerica },
+    Country { alpha2: "KZ", alpha3: "KAZ", numeric: "398", name: "Kazakhstan", continent: Continent::Asia },
+    Country { alpha2: "LA", alpha3: "LAO", numeric: "418", name: "Laos", continent: Continent::Asia },
+    Country { alpha2: "LB", alpha3: "LBN", numeric: "422", name: "Lebanon", continent: Continent::Asia },
+    Country { alpha2: "LC", alpha3: "LCA", numeric: "662", name: "Saint Lucia", continent: Continent::NorthAmerica },
+    Country { alpha2: "LI", alpha3: "LIE", numeric: "438", name: "Liechtenstein", continent: Continent::Europe },
+    Country { alpha2: "LK", alpha3: "LKA", numeric: "144", name: "Sri Lanka", continent: Continent::Asia },
+    Country { alpha2: "LR", alpha3: "LBR", numeric: "430", name: "Liberia", continent: Continent::Africa },
+    Country { alpha2: "LS", alpha3: "LSO", numeric: "426", name: "Lesotho", continent: Continent::Africa },
+    Country { alpha2: "LT", alpha3: "LTU", numeric: "440", name: "Lithuania", continent: Continent::Europe },
+    Country { alpha2: "LU", alpha3: "LUX", numeric: "442", name: "Luxembourg", continent: Continent::Europe },
+    Country { alpha2: "LV", alpha3: "LVA", numeric: "428", name: "Latvia", continent: Continent::Europe },
+    Country { alpha2: "LY", alpha3: "LBY", numeric: "434", name: "Libya", continent: Continent::Africa },
+    Country { alpha2: "MA", alpha3: "MAR", numeric: "504", name: "Morocco", continent: Continent::Africa },
+    Country { alpha2: "MC", alpha3: "MCO", numeric: "492", name: "Monaco", continent: Continent::Europe },
+    Country { alpha2: "MD", alpha3: "MDA", numeric: "498", name: "Moldova", continent: Continent::Europe },
+    Country { alpha2: "ME", alpha3: "MNE", numeric: "499", name: "Montenegro", continent: Continent::Europe },
+    Country { alpha2: "MF", alpha3: "MAF", numeric: "663", name: "Saint Martin (French part)", continent: Continent::NorthAmerica },
+    Country { alpha2: "MG", alpha3: "MDG", numeric: "450", name: "Madagascar", continent: Continent::Africa },
+    Country { alpha2: "MH", alpha3: "MHL", numeric: "584", name: "Marshall Islands", continent: Continent::Oceania },
+    Country { alpha2: "MK", alpha3: "MKD", numeric: "807", name: "North Macedonia", continent: Continent::Europe },
+    Country { alpha2: "ML", alpha3: "MLI", numeric: "466", name: "Mali", continent: Continent::Africa },
+    Country { alpha2: "MM", alpha3: "MMR", numeric: "104", name: "Myanmar", continent: Continent::Asia },
+    Country { alpha2: "MN", alpha3: "MNG", numeric: "496", name: "Mongolia", continent: Continent::Asia },
+    Country { alpha2: "MO", alpha3: "MAC", numeric: "446", name: "Macao", continent: Continent::Asia },
+    Country { alpha2: "MP", alpha3: "MNP", numeric: "580", name: "Northern Mariana Islands", continent: Continent::Oceania },
+    Country { alpha2: "MQ", alpha3: "MTQ", numeric: "474", name: "Martinique", continent: Continent::NorthAmerica },
+    Country { alpha2: "MR", alpha3: "MRT", numeric: "478", name: "Mauritania", continent: Continent::Africa },
+    Country { alpha2: "MS", alpha3: "MSR", numeric: "500", name: "Montserrat", continent: Continent::NorthAmerica },
+    Country { alpha2: "MT", alpha3: "MLT", numeric: "470", name: "Malta", continent: Continent::Europe },
+    Country { alpha2: "MU", alpha3: "MUS", numeric: "480", name: "Mauritius", continent: Continent::Africa },
+    Country { alpha2: "MV", alpha3: "MDV", numeric: "462", name: "Maldives", continent: Continent::Asia },
+    Country { alpha2: "MW", alpha3: "MWI", numeric: "454", name: "Malawi", continent: Continent::Africa },
+    Country { alpha2: "MX", alpha3: "MEX", numeric: "484", name: "Mexico", continent: Continent::NorthAmerica },
+    Country { alpha2: "MY", alpha3: "MYS", numeric: "458", name: "Malaysia", continent: Continent::Asia },
+    Country { alpha2: "MZ", alpha3: "MOZ", numeric: "508", name: "Mozambique", continent: Continent::Africa },
+    Country { alpha2: "NA", alpha3: "NAM", numeric: "516", name: "Namibia", continent: Continent::Africa },
+    Country { alpha2: "NC", alpha3: "NCL", numeric: "540", name: "New Caledonia", continent: Continent::Oceania },
+    Country { alpha2: "NE", alpha3: "NER", numeric: "562", name: "Niger", continent: Continent::Africa },
+    Country { alpha2: "NF", alpha3: "NFK", numeric: "574", name: "Norfolk Island", continent: Continent::Oceania },
+    Country { alpha2: "NG", alpha3: "NGA", numeric: "566", name: "Nigeria", continent: Continent::Africa },
+    Country { alpha2: "NI", alpha3: "NIC", numeric: "558", name: "Nicaragua", continent: Continent::NorthAmerica },
+    Country { alpha2: "NL", alpha3: "NLD", numeric: "528", name: "Netherlands", continent: Continent::Europe },
+    Country { alpha2: "NO", alpha3: "NOR", numeric: "578", name: "Norway", continent: Continent::Europe },
+    Country { alpha2: "NP", alpha3: "NPL", numeric: "524", name: "Nepal", continent: Continent::Asia },
+    Country { alpha2: "NR", alpha3: "NRU", numeric: "520", name: "Nauru", continent: Continent::Oceania },
+    Country { alpha2: "NU", alpha3: "NIU", numeric: "570", name: "Niue", continent: Continent::Oceania },
+    Country { alpha2: "NZ", alpha3: "NZL", numeric: "554", name: "New Zealand", continent: Continent::Oceania },
+    Country { alpha2: "OM", alpha3: "OMN", numeric: "512", name: "Oman", continent: Continent::Asia },
+    Country { alpha2: "PA", alpha3: "PAN", numeric: "591", name: "Panama", continent: Continent::NorthAmerica },
+    Country { alpha2: "PE", alpha3: "PER", numeric: "604", name: "Peru", continent: Continent::SouthAmerica },
+    Country { alpha2: "PF", alpha3: "PYF", numeric: "258", name: "French Polynesia", continent: Continent::Oceania },
+    Country { alpha2: "PG", alpha3: "PNG", numeric: "598", name: "Papua New Guinea", continent: Continent::Oceania },
+    Country { alpha2: "PH", alpha3: "PHL", numeric: "608", name: "Philippines", continent: Continent::Asia },
+    Country { alpha2: "PK", alpha3: "PAK", numeric: "586", name: "Pakistan", continent: Continent::Asia },
+    Country { alpha2: "PL", alpha3: "POL", numeric: "616", name: "Poland", continent: Continent::Europe },
+    Country { alpha2: "PM", alpha3: "SPM", numeric: "666", name: "Saint Pierre and Miquelon", continent: Continent::NorthAmerica },
+    Country { alpha2: "PN", alpha3: "PCN", numeric: "612", name: "Pitcairn", continent: Continent::Oceania },
+    Country { alpha2: "PR", alpha3: "PRI", numeric: "630", name: "Puerto Rico", continent: Continent::NorthAmerica },
+    Country { alpha2: "PS", alpha3: "PSE", numeric: "275", name: "Palestine, State of", continent: Continent::Asia },
+    Country { alpha2: "PT", alpha3: "PRT", numeric: "620", name: "Portugal", continent: Continent::Europe },
+    Country { alpha2: "PW", alpha3: "PLW", numeric: "585", name: "Palau", continent: Continent::Oceania },
+    Country { alpha2: "PY", alpha3: "PRY", numeric: "600", name: "Paraguay", continent: Continent::SouthAmerica },
+    Country { alpha2: "QA", alpha3: "QAT", numeric: "634", name: "Qatar", continent: Continent::Asia },
+    Country { alpha2: "RE", alpha3: "REU", numeric: "638", name: "Réunion", continent: Continent::Africa },
+    Country { alpha2: "RO", alpha3: "ROU", numeric: "642", name: "Romania", continent: Continent::Europe },
+    Country { alpha2: "RS", alpha3: "SRB", numeric: "688", name: "Serbia", continent: Continent::Europe },
+    Country { alpha2: "RU", alpha3: "RUS", numeric: "643", name: "Russian Federation", continent: Continent::Europe },
+    Country { alpha2: "RW", alpha3: "RWA", numeric: "646", name: "Rwanda", continent: Continent::Africa },
+    Country { alpha2: "SA", alpha3: "SAU", numeric: "682", name: "Saudi Arabia", continent: Continent::Asia },
+    Country { alpha2: "SB", alpha3: "SLB", numeric: "090", name: "Solomon Islands", continent: Continent::Oceania },
+    Country { alpha2: "SC", alpha3: "SYC", numeric: "690", name: "Seychelles", continent: Continent::Africa },
+    Country { alpha2: "SD", alpha3: "SDN", numeric: "729", name: "Sudan", continent: Continent::Africa },
+    Country { alpha2: "SE", alpha3: "SWE", numeric: "752", name: "Sweden", continent: Continent::Europe },
+    Country { alpha2: "SG", alpha3: "SGP", numeric: "702", name: "Singapore", continent: Continent::Asia },
+    Country { alpha2: "SH", alpha3: "SHN", numeric: "654", name: "Saint Helena, Ascension and Tristan da Cunha", continent: Continent::Africa },
+    Country { alpha2: "SI", alpha3: "SVN", numeric: "705", name: "Slovenia", continent: Continent::Europe },
+    Country { alpha2: "SJ", alpha3: "SJM", numeric: "744", name: "Svalbard and Jan Mayen", continent: Continent::Europe },
+    Country { alpha2: "SK", alpha3: "SVK", numeric: "703", name: "Slovakia", continent: Continent::Europe },
+    Country { alpha2: "SL", alpha3: "SLE", numeric: "694", name: "Sierra Leone", continent: Continent::Africa },
+    Country { alpha2: "SM", alpha3: "SMR", numeric: "674", name: "San Marino", continent: Continent::Europe },
+    Country { alpha2: "SN", alpha3: "SEN", numeric: "686", name: "Senegal", continent: Continent::Africa },
+    Country { alpha2: "SO", alpha3: "SOM", numeric: "706", name: "Somalia", continent: Continent::Africa },
+    Country { alpha2: "SR", alpha3: "SUR", numeric: "740", name: "Suriname", continent: Continent::SouthAmerica },
+    Country { alpha2: "SS", alpha3: "SSD", numeric: "728", name: "South Sudan", continent: Continent::Africa },
+    Country { alpha2: "ST", alpha3: "STP", numeric: "678", name: "Sao Tome and Principe", continent: Continent::Africa },
+    Country { alpha2: "SV", alpha3: "SLV", numeric: "222", name: "El Salvador", continent: Continent::NorthAmerica },
+    Country { alpha2: "SX", alpha3: "SXM", numeric: "534", name: "Sint Maarten (Dutch part)", continent: Continent::NorthAmerica },
+    Country { alpha2: "SY", alpha3: "SYR", numeric: "760", name: "Syria", continent: Continent::Asia },
+    Country { alpha2: "SZ", alpha3: "SWZ", numeric: "748", name: "Eswatini", continent: Continent::Africa },
+    Country { alpha2: "TC", alpha3: "TCA", numeric: "796", name: "Turks and Caicos Islands", continent: Continent::NorthAmerica },
+    Country { alpha2: "TD", alpha3: "TCD", numeric: "148", name: "Chad", continent: Continent::Africa },
+    Country { alpha2: "TF", alpha3: "ATF", numeric: "260", name: "French Southern Territories", continent: Continent::Antarctica },
+    Country { alpha2: "TG", alpha3: "TGO", numeric: "768", name: "Togo", continent: Continent::Africa },
+    Country { alpha2: "TH", alpha3: "THA", numeric: "764", name: "Thailand", continent: Continent::Asia },
+    Country { alpha2: "TJ", alpha3: "TJK", numeric: "762", name: "Tajikistan", continent: Continent::Asia },
+    Country { alpha2: "TK", alpha3: "TKL", numeric: "772", name: "Tokelau", continent: Continent::Oceania },
+    Country { alpha2: "TL", alpha3: "TLS", numeric: "626", name: "Timor-Leste", continent: Continent::Asia },
+    Country { alpha2: "TM", alpha3: "TKM", numeric: "795", name: "Turkmenistan", continent: Continent::Asia },
+    Country { alpha2: "TN", alpha3: "TUN", numeric: "788", name: "Tunisia", continent: Continent::Africa },
+    Country { alpha2: "TO", alpha3: "TON", numeric: "776", name: "Tonga", continent: Continent::Oceania },
+    Country { alpha2: "TR", alpha3: "TUR", numeric: "792", name: "Türkiye", continent: Continent::Asia },
+    Country { alpha2: "TT", alpha3: "TTO", numeric: "780", name: "Trinidad and Tobago", continent: Continent::NorthAmerica },
+    Country { alpha2: "TV", alpha3: "TUV", numeric: "798", name: "Tuvalu", continent: Continent::Oceania },
+    Country { alpha2: "TW", alpha3: "TWN", numeric: "158", name: "Taiwan", continent: Continent::Asia },
+    Country { alpha2: "TZ", alpha3: "TZA", numeric: "834", name: "Tanzania", continent: Continent::Africa },
+    Country { alpha2: "UA", alpha3: "UKR", numeric: "804", name: "Ukraine", continent: Continent::Europe },
+    Country { alpha2: "UG", alpha3: "UGA", numeric: "800", name: "Uganda", continent: Continent::Africa },
+    Country { alpha2: "UM", alpha3: "UMI", numeric: "581", name: "United States Minor Outlying Islands", continent: Continent::Oceania },
+    Country { alpha2: "US", alpha3: "USA", numeric: "840", name: "United States", continent: Continent::NorthAmerica },
+    Country { alpha2: "UY", alpha3: "URY", numeric: "858", name: "Uruguay", continent: Continent::SouthAmerica },
+    Country { alpha2: "UZ", alpha3: "UZB", numeric: "860", name: "Uzbekistan", continent: Continent::Asia },
+    Country { alpha2: "VA", alpha3: "VAT", numeric: "336", name: "Holy See (Vatican City State)", continent: Continent::Europe },
+    Country { alpha2: "VC", alpha3: "VCT", numeric: "670", name: "Saint Vincent and the Grenadines", continent: Continent::NorthAmerica },
+    Country { alpha2: "VE", alpha3: "VEN", numeric: "862", name: "Venezuela", continent: Continent::SouthAmerica },
+    Country { alpha2: "VG", alpha3: "VGB", numeric: "092", name: "Virgin Islands, British", continent: Continent::NorthAmerica },
+    Country { alpha2: "VI", alpha3: "VIR", numeric: "850", name: "Virgin Islands, U.S.", continent: Continent::NorthAmerica },
+    Country { alpha2: "VN", alpha3: "VNM", numeric: "704", name: "Vietnam", continent: Continent::Asia },
+    Country { alpha2: "VU", alpha3: "VUT", numeric: "548", name: "Vanuatu", continent: Continent::Oceania },
+    Country { alpha2: "WF", alpha3: "WLF", numeric: "876", name: "Wallis and Futuna", continent: Continent::Oceania },
+    Country { alpha2: "WS", alpha3: "WSM", numeric: "882", name: "Samoa", continent: Continent::Oceania },
+    Country { alpha2: "YE", alpha3: "YEM", numeric: "887", name: "Yemen", continent: Continent::Asia },
+    Country { alpha2: "YT", alpha3: "MYT", numeric: "175", name: "Mayotte", continent: Continent::Africa },
+    Country { alpha2: "ZA", alpha3: "ZAF", numeric: "710", name: "South Africa", continent: Continent::Africa },
+    Country { alpha2: "ZM", alpha3: "ZMB", numeric: "894", name: "Zambia", continent: Continent::Africa },
+    Country { alpha2: "ZW", alpha3: "ZWE", numeric: "716", name: "Zimbabwe", continent: Continent::Africa },
+];
@@ -0,0 +1,400 @@
+//! Geographic Data
+//!
+//! Bundled ISO 3166 country, subdivision and continent data, plus EU/EEA
+//! membership and postcode formats. Used for shipping zone matching, tax rate
+//! matching, checkout address validation and the admin country pickers.
+
+mod countries;
+mod subdivisions;
+pub mod postcodes;
+
+use serde::{Deserialize, Serialize};
+
+pub use countries::COUNTRIES;
+pub use subdivisions::SUBDIVISIONS;
+pub use postcodes::{normalize_postcode, is_valid_postcode, requires_postcode, postcode_example};
+
+/// Continent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Continent {
+    Africa,
+    Antarctica,
+    Asia,
+    Europe,
+    NorthAmerica,
+    Oceania,
+    SouthAmerica,
+}
+
+impl Continent {
+    /// All continents
+    pub const ALL: [Continent; 7] = [
+        Self::Africa,
+        Self::Antarctica,
+        Self::Asia,
+        Self::Europe,
+        Self::NorthAmerica,
+        Self::Oceania,
+        Self::SouthAmerica,
+    ];
+
+    /// Two-letter continent code as used in shipping zone locations
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Africa => "AF",
+            Self::Antarctica => "AN",
+            Self::Asia => "AS",
+            Self::Europe => "EU",
+            Self::NorthAmerica => "NA",
+            Self::Oceania => "OC",
+            Self::SouthAmerica => "SA",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Africa => "Africa",
+            Self::Antarctica => "Antarctica",
+            Self::Asia => "Asia",
+            Self::Europe => "Europe",
+            Self::NorthAmerica => "North America",
+            Self::Oceania => "Oceania",
+            Self::SouthAmerica => "South America",
+        }
+    }
+
+    /// Look up a continent by its two-letter code (case-insensitive)
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.iter()
+            .copied()
+            .find(|c| c.code().eq_ignore_ascii_case(code.trim()))
+    }
+
+    /// Countries on this continent
+    pub fn countries(&self) -> impl Iterator<Item = &'static Country> {
+        let continent = *self;
+        COUNTRIES.iter().filter(move |c| c.continent == continent)
+    }
+}
+
+/// ISO 3166-1 country
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Country {
+    pub alpha2: &'static str,
+    pub alpha3: &'static str,
+    pub numeric: &'static str,
+    pub name: &'static str,
+    pub continent: Continent,
+}
+
+impl Country {
+    /// Check if the country is an EU member state
+    pub fn is_eu_member(&self) -> bool {
+        EU_MEMBER_STATES.contains(&self.alpha2)
+    }
+
+    /// Check if the country is part of the European Economic Area
+    pub fn is_eea_member(&self) -> bool {
+        self.is_eu_member() || EEA_EFTA_STATES.contains(&self.alpha2)
+    }
+
+    /// Subdivisions of this country (all levels)
+    pub fn subdivisions(&self) -> &'static [Subdivision] {
+        subdivisions_of(self.alpha2)
+    }
+
+    /// Check if the country has any ISO 3166-2 subdivisions
+    pub fn has_subdivisions(&self) -> bool {
+        !self.subdivisions().is_empty()
+    }
+}
+
+/// ISO 3166-2 subdivision
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Subdivision {
+    /// Full code, e.g. `US-CA`
+    pub code: &'static str,
+    pub name: &'static str,
+    /// Subdivision category, e.g. `State`, `Province`, `Region`
+    pub kind: &'static str,
+    /// Full code of the parent subdivision, if nested
+    pub parent: Option<&'static str>,
+}
+
+impl Subdivision {
+    /// Country part of the code, e.g. `US`
+    pub fn country_code(&self) -> &'static str {
+        self.code.split_once('-').map_or(self.code, |(country, _)| country)
+    }
+
+    /// Subdivision part of the code, e.g. `CA` for `US-CA`
+    pub fn local_code(&self) -> &'static str {
+        self.code.split_once('-').map_or(self.code, |(_, local)| local)
+    }
+
+    /// Check if this is a top-level subdivision
+    pub fn is_top_level(&self) -> bool {
+        self.parent.is_none()
+    }
+}
+
+/// EU member states (alpha-2)
+pub const EU_MEMBER_STATES: &[&str] = &[
+    "AT", "BE", "BG", "CY", "CZ", "DE", "DK", "EE", "ES", "FI", "FR", "GR", "HR", "HU",
+    "IE", "IT", "LT", "LU", "LV", "MT", "NL", "PL", "PT", "RO", "SE", "SI", "SK",
+];
+
+/// EFTA states that are part of the EEA (alpha-2)
+pub const EEA_EFTA_STATES: &[&str] = &["IS", "LI", "NO"];
+
+/// Look up a country by alpha-2 or alpha-3 code (case-insensitive)
+pub fn country(code: &str) -> Option<&'static Country> {
+    let code = code.trim();
+    match code.len() {
+        2 => COUNTRIES.iter().find(|c| c.alpha2.eq_ignore_ascii_case(code)),
+        3 => COUNTRIES.iter().find(|c| c.alpha3.eq_ignore_ascii_case(code)),
+        _ => None,
+    }
+}
+
+/// Normalize a country code to its upper-case alpha-2 form
+pub fn normalize_country(code: &str) -> Option<&'static str> {
+    country(code).map(|c| c.alpha2)
+}
+
+/// Check if two country codes refer to the same country (alpha-2 or alpha-3).
+///
+/// Falls back to a case-insensitive comparison when either code is unknown.
+pub fn same_country(a: &str, b: &str) -> bool {
+    match (country(a), country(b)) {
+        (Some(x), Some(y)) => x.alpha2 == y.alpha2,
+        _ => a.trim().eq_ignore_ascii_case(b.trim()),
+    }
+}
+
+/// Check if a country code is a valid ISO 3166-1 code
+pub fn is_valid_country(code: &str) -> bool {
+    country(code).is_some()
+}
+
+/// All subdivisions of a country, sorted by code
+pub fn subdivisions_of(country_code: &str) -> &'static [Subdivision] {
+    let Some(country) = country(country_code) else {
+        return &[];
+    };
+
+    // Subdivisions are sorted by full code, so a country's entries are contiguous
+    let start = SUBDIVISIONS.partition_point(|s| s.country_code() < country.alpha2);
+    let end = start + SUBDIVISIONS[start..].partition_point(|s| s.country_code() == country.alpha2);
+    &SUBDIVISIONS[start..end]
+}
+
+/// Top-level subdivisions of a country (states, provinces, regions, ...)
+pub fn top_level_subdivisions(country_code: &str) -> impl Iterator<Item = &'static Subdivision> {
+    subdivisions_of(country_code).iter().filter(|s| s.is_top_level())
+}
+
+/// Look up a subdivision of a country.
+///
+/// Accepts the local code (`CA`), the full code (`US-CA`) or the
+/// subdivision name (`California`), all case-insensitive.
+pub fn subdivision(country_code: &str, value: &str) -> Option<&'static Subdivision> {
+    find_subdivision(subdivisions_of(country_code).iter(), value)
+}
+
+/// Look up a subdivision among those a country's state field takes,
+/// accepting the same values as [`subdivision`]
+pub fn state_subdivision(country_code: &str, value: &str) -> Option<&'static Subdivision> {
+    find_subdivision(state_subdivisions(country_code), value)
+}
+
+fn find_subdivision<'a>(
+    subdivisions: impl Iterator<Item = &'a Subdivision> + Clone,
+    value: &str,
+) -> Option<&'a Subdivision> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+
+    subdivisions.clone()
+        .find(|s| s.code.eq_ignore_ascii_case(value) || s.local_code().eq_ignore_ascii_case(value))
+        .or_else(|| {
+            let lower = value.to_lowercase();
+            subdivisions.into_iter().find(|s| s.name.to_lowercase() == lower)
+        })
+}
+
+/// Normalize a subdivision to its local code (`California` -> `CA`)
+pub fn normalize_subdivision(country_code: &str, value: &str) -> Option<&'static str> {
+    subdivision(country_code, value).map(|s| s.local_code())
+}
+
+/// Check if two subdivision values refer to the same subdivision of a country.
+///
+/// Falls back to a case-insensitive comparison when either value is unknown.
+pub fn same_subdivision(country_code: &str, a: &str, b: &str) -> bool {
+    match (subdivision(country_code, a), subdivision(country_code, b)) {
+        (Some(x), Some(y)) => x.code == y.code,
+        _ => a.trim().eq_ignore_ascii_case(b.trim()),
+    }
+}
+
+/// Countries whose address format needs a state, province or prefecture.
+/// Elsewhere the state is optional free text, such as a UK county.
+const COUNTRIES_REQUIRING_STATE: &[&str] = &[
+    "AE", "AR", "AU", "BR", "CA", "CN", "EG", "ES", "HK", "ID", "IN", "IT", "JP", "KN", "MX",
+    "MY", "NG", "PW", "SV", "TH", "US", "VE",
+];
+
+/// Countries whose addresses name a province rather than the region above it
+const COUNTRIES_USING_PROVINCES: &[&str] = &["ES", "IT"];
+
+/// Subdivisions a country's state field takes: provinces where addresses
+/// name one, top-level subdivisions elsewhere. Regions without provinces,
+/// such as Ceuta, stand in for their own province.
+pub fn state_subdivisions(country_code: &str) -> impl Iterator<Item = &'static Subdivision> + Clone {
+    let subdivisions = subdivisions_of(country_code);
+    let provinces = subdivisions.first()
+        .is_some_and(|s| COUNTRIES_USING_PROVINCES.contains(&s.country_code()));
+
+    subdivisions.iter().filter(move |s| {
+        if provinces {
+            !subdivisions.iter().any(|child| child.parent == Some(s.code))
+        } else {
+            s.is_top_level()
+        }
+    })
+}
+
+/// Whether addresses in a country need a state
+pub fn requires_state(country_code: &str) -> bool {
+    normalize_country(country_code).is_some_and(|code| COUNTRIES_REQUIRING_STATE.contains(&code))
+}
+
+/// Check if a country is on the given continent (by two-letter continent code)
+pub fn country_in_continent(country_code: &str, continent_code: &str) -> bool {
+    match (country(country_code), Continent::from_code(continent_code)) {
+        (Some(country), Some(continent)) => country.continent == continent,
+        _ => false,
+    }
+}
+
+/// Check if a country is an EU member state
+pub fn is_eu_member(country_code: &str) -> bool {
+    country(country_code).is_some_and(|c| c.is_eu_member())
+}
+
+/// Check if a country is part of the European Economic Area
+pub fn is_eea_member(country_code: &str) -> bool {
+    country(country_code).is_some_and(|c| c.is_eea_member())
+}
+
+/// Country options for select fields, sorted by name
+pub fn country_options() -> Vec<(String, String)> {
+    let mut options: Vec<(String, String)> = COUNTRIES.iter()
+        .map(|c| (c.alpha2.to_string(), c.name.to_string()))
+        .collect();
+    options.sort_by(|a, b| a.1.cmp(&b.1));
+    options
+}
+
+/// Options for a country's state select field, sorted by name
+pub fn subdivision_options(country_code: &str) -> Vec<(String, String)> {
+    let mut options: Vec<(String, String)> = state_subdivisions(country_code)
+        .map(|s| (s.local_code().to_string(), s.name.to_string()))
+        .collect();
+    options.sort_by(|a, b| a.1.cmp(&b.1));
+    options
+}
+
+/// Continent options for select fields
+pub fn continent_options() -> Vec<(String, String)> {
+    Continent::ALL.iter()
+        .map(|c| (c.code().to_string(), c.display_name().to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_country_lookup() {
+        assert_eq!(country("pt").map(|c| c.alpha3), Some("PRT"));
+        assert_eq!(country("IRL").map(|c| c.alpha2), Some("IE"));
+        assert_eq!(COUNTRIES.len(), 249);
+        assert!(country("XX").is_none());
+        assert!(country("").is_none());
+
+        assert!(same_country("GB", "gbr"));
+        assert!(!same_country("GB", "IE"));
+        assert!(!same_country("XX", "YY"));
+    }
+
+    #[test]
+    fn test_continents() {
+        assert!(country_in_continent("PT", "EU"));
+        assert!(country_in_continent("IE", "EU"));
+        assert!(country_in_continent("GR", "eu"));
+        assert!(country_in_continent("US", "NA"));
+        assert!(!country_in_continent("US", "EU"));
+        assert!(!country_in_continent("US", "XX"));
+        assert!(Continent::Antarctica.countries().any(|c| c.alpha2 == "AQ"));
+    }
+
+    #[test]
+    fn test_eu_membership() {
+        assert_eq!(EU_MEMBER_STATES.len(), 27);
+        assert!(is_eu_member("DE"));
+        assert!(!is_eu_member("GB"));
+        assert!(!is_eu_member("NO"));
+        assert!(is_eea_member("NO"));
+        assert!(!is_eea_member("CH"));
+    }
+
+    #[test]
+    fn test_subdivisions() {
+        let us = subdivisions_of("US");
+        assert_eq!(us.len(), 57);
+        assert!(us.iter().all(|s| s.country_code() == "US"));
+
+        assert_eq!(normalize_subdivision("US", "California"), Some("CA"));
+        assert_eq!(normalize_subdivision("US", "us-ca"), Some("CA"));
+        assert_eq!(normalize_subdivision("USA", "ca"), Some("CA"));
+        assert!(subdivision("US", "ZZ").is_none());
+
+        assert_eq!(subdivision("ES", "ES-B").map(|s| s.name), Some("Barcelona"));
+        assert!(SUBDIVISIONS.iter().all(|s| !s.name.contains(['[', '*', '†'])));
+
+        assert!(same_subdivision("US", "CA", "California"));
+        assert!(!same_subdivision("US", "CA", "NY"));
+
+        assert!(requires_state("US"));
+        assert!(requires_state("usa"));
+        assert!(!requires_state("GB"));
+        assert!(!requires_state("FR"));
+    }
+
+    #[test]
+    fn test_options() {
+        assert_eq!(country_options().len(), COUNTRIES.len());
+        assert!(subdivision_options("FR").iter().all(|(code, _)| !code.is_empty()));
+        assert!(subdivision_options("FR").len() < subdivisions_of("FR").len());
+
+        // Spanish and Italian addresses name the province, not the region
+        let es = subdivision_options("ES");
+        assert_eq!(es.len(), 52);
+        assert!(es.iter().any(|(code, name)| code == "B" && name == "Barcelona"));
+        assert!(es.iter().any(|(code, _)| code == "CE"));
+        assert!(!es.iter().any(|(code, _)| code == "CT"));
+        assert!(subdivision_options("IT").iter().any(|(code, _)| code == "MI"));
+        assert!(!subdivision_options("IT").iter().any(|(code, _)| code == "25"));
+
+        assert_eq!(state_subdivision("ES", "Barcelona").map(|s| s.code), Some("ES-B"));
+        assert!(state_subdivision("ES", "CT").is_none());
+        assert!(subdivision("ES", "CT").is_some());
+        assert_eq!(continent_options().len(), 7);
+    }
+}
@@ -0,0 +1,195 @@
+//! Postcode Formats
+//!
+//! Per-country postcode patterns. In a pattern `#` is a digit, `@` a letter
+//! and `?` a letter or digit; anything else is literal. Spaces and hyphens
+//! are optional on input and re-inserted when normalising.
+
+/// Countries that do not use postcodes
+pub const COUNTRIES_WITHOUT_POSTCODES: &[&str] = &[
+    "AE", "AG", "AO", "AW", "BF", "BI", "BJ", "BO", "BS", "BW", "BZ", "CD", "CF", "CG", "CI",
+    "CK", "CM", "DJ", "DM", "ER", "FJ", "GD", "GH", "GM", "GQ", "GY", "HK", "KI", "KM", "KN",
+    "KP", "LC", "ML", "MO", "MR", "MW", "NR", "NU", "QA", "RW", "SB", "SC", "SL", "SR", "ST",
+    "SY", "TF", "TG", "TK", "TL", "TO", "TV", "UG", "VU", "YE", "ZW",
+];
+
+/// Postcode patterns by country, sorted by country code
+static POSTCODE_FORMATS: &[(&str, &[&str])] = &[
+    ("AR", &["@####@@@", "####"]),
+    ("AT", &["####"]),
+    ("AU", &["####"]),
+    ("BA", &["#####"]),
+    ("BD", &["####"]),
+    ("BE", &["####"]),
+    ("BG", &["####"]),
+    ("BR", &["#####-###"]),
+    ("BY", &["######"]),
+    ("CA", &["@#@ #@#"]),
+    ("CH", &["####"]),
+    ("CL", &["###-####"]),
+    ("CN", &["######"]),
+    ("CO", &["######"]),
+    ("CY", &["####"]),
+    ("CZ", &["### ##"]),
+    ("DE", &["#####"]),
+    ("DK", &["####"]),
+    ("DZ", &["#####"]),
+    ("EE", &["#####"]),
+    ("EG", &["#####"]),
+    ("ES", &["#####"]),
+    ("FI", &["#####"]),
+    ("FR", &["#####"]),
+    ("GB", &["@# #@@", "@## #@@", "@@# #@@", "@@## #@@", "@#@ #@@", "@@#@ #@@", "GIR 0AA"]),
+    ("GR", &["### ##"]),
+    ("HR", &["#####"]),
+    ("HU", &["####"]),
+    ("ID", &["#####"]),
+    ("IE", &["@## ????", "@#@ ????"]),
+    ("IL", &["#######"]),
+    ("IN", &["### ###"]),
+    ("IS", &["###"]),
+    ("IT", &["#####"]),
+    ("JP", &["###-####"]),
+    ("KR", &["#####"]),
+    ("KZ", &["######"]),
+    ("LI", &["####"]),
+    ("LT", &["LT-#####", "#####"]),
+    ("LU", &["L-####", "####"]),
+    ("LV", &["LV-####", "####"]),
+    ("MA", &["#####"]),
+    ("MC", &["980##"]),
+    ("MT", &["@@@ ####"]),
+    ("MX", &["#####"]),
+    ("MY", &["#####"]),
+    ("NG", &["######"]),
+    ("NL", &["#### @@"]),
+    ("NO", &["####"]),
+    ("NZ", &["####"]),
+    ("PH", &["####"]),
+    ("PK", &["#####"]),
+    ("PL", &["##-###"]),
+    ("PR", &["#####", "#####-####"]),
+    ("PT", &["####-###"]),
+    ("RO", &["######"]),
+    ("RS", &["#####"]),
+    ("RU", &["######"]),
+    ("SA", &["#####", "#####-####"]),
+    ("SE", &["### ##"]),
+    ("SG", &["######"]),
+    ("SI", &["####"]),
+    ("SK", &["### ##"]),
+    ("TH", &["#####"]),
+    ("TN", &["####"]),
+    ("TR", &["#####"]),
+    ("TW", &["###", "###-##", "###-###"]),
+    ("UA", &["#####"]),
+    ("US", &["#####", "#####-####"]),
+    ("VN", &["######"]),
+    ("ZA", &["####"]),
+];
+
+/// Postcode patterns for a country, if known
+pub fn postcode_formats(country_code: &str) -> Option<&'static [&'static str]> {
+    let code = super::normalize_country(country_code)?;
+    POSTCODE_FORMATS
+        .binary_search_by(|(c, _)| (*c).cmp(code))
+        .ok()
+        .map(|i| POSTCODE_FORMATS[i].1)
+}
+
+/// Whether addresses in a country need a postcode
+pub fn requires_postcode(country_code: &str) -> bool {
+    match super::normalize_country(country_code) {
+        Some(code) => !COUNTRIES_WITHOUT_POSTCODES.contains(&code),
+        None => true,
+    }
+}
+
+/// Format a postcode against a single pattern, or None if it does not match
+fn apply_format(pattern: &str, postcode: &str) -> Option<String> {
+    let mut input = postcode.chars().filter(|c| !matches!(c, ' ' | '-'));
+    let mut out = String::with_capacity(pattern.len());
+
+    for p in pattern.chars() {
+        if matches!(p, ' ' | '-') {
+            out.push(p);
+            continue;
+        }
+        let c = input.next()?;
+        let ok = match p {
+            '#' => c.is_ascii_digit(),
+            '@' => c.is_ascii_alphabetic(),
+            '?' => c.is_ascii_alphanumeric(),
+            literal => c == literal,
+        };
+        if !ok {
+            return None;
+        }
+        out.push(c);
+    }
+
+    if input.next().is_some() {
+        return None;
+    }
+    Some(out)
+}
+
+/// Normalise a postcode to its country's canonical format.
+///
+/// Returns the trimmed, upper-cased input when the country has no known
+/// format, and None when it has one and the postcode doesn't match it.
+pub fn normalize_postcode(country_code: &str, postcode: &str) -> Option<String> {
+    let cleaned = postcode.split_whitespace().collect::<Vec<_>>().join(" ").to_uppercase();
+
+    match postcode_formats(country_code) {
+        Some(formats) => formats.iter().find_map(|f| apply_format(f, &cleaned)),
+        None => Some(cleaned),
+    }
+}
+
+/// Check a postcode against its country's format
+pub fn is_valid_postcode(country_code: &str, postcode: &str) -> bool {
+    normalize_postcode(country_code, postcode).is_some()
+}
+
+/// Human-readable example of the expected postcode format, e.g. "A9A 9A9"
+pub fn postcode_example(country_code: &str) -> Option<String> {
+    postcode_formats(country_code)?
+        .first()
+        .map(|f| f.replace('#', "9").replace('@', "A").replace('?', "X"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_formats_are_sorted() {
+        assert!(POSTCODE_FORMATS.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn test_normalize_postcode() {
+        assert_eq!(normalize_postcode("US", "90210").as_deref(), Some("90210"));
+        assert_eq!(normalize_postcode("US", "902101234").as_deref(), Some("90210-1234"));
+        assert_eq!(normalize_postcode("CA", "k1a0b1").as_deref(), Some("K1A 0B1"));
+        assert_eq!(normalize_postcode("GB", "sw1a1aa").as_deref(), Some("SW1A 1AA"));
+        assert_eq!(normalize_postcode("NL", "1012 ab").as_deref(), Some("1012 AB"));
+        assert_eq!(normalize_postcode("PL", "00950").as_deref(), Some("00-950"));
+        assert_eq!(normalize_postcode("USA", " 10001 ").as_deref(), Some("10001"));
+
+        assert!(normalize_postcode("US", "9021").is_none());
+        assert!(normalize_postcode("CA", "12345").is_none());
+        assert!(normalize_postcode("DE", "1011").is_none());
+
+        // Unknown formats pass through
+        assert_eq!(normalize_postcode("ZZ", "abc 1").as_deref(), Some("ABC 1"));
+    }
+
+    #[test]
+    fn test_requires_postcode() {
+        assert!(requires_postcode("US"));
+        assert!(!requires_postcode("HK"));
+        assert!(!requires_postcode("ARE"));
+        assert_eq!(postcode_example("CA").as_deref(), Some("A9A 9A9"));
+    }
+}
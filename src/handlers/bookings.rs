@@ -2,9 +2,30 @@
 //!
 //! HTTP request handlers for booking management.
 
+use axum::{
+    extract::Path,
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::models::booking::{BookingRequest, BookingStatus};
+
+#[cfg(feature = "bookings")]
+use axum::{extract::Query, http::header, Extension};
+#[cfg(feature = "bookings")]
+use chrono::{NaiveDate, Utc};
+#[cfg(feature = "bookings")]
+use std::sync::Arc;
+#[cfg(feature = "bookings")]
+use crate::models::booking::BookingCalendarFeed;
+#[cfg(feature = "bookings")]
+use crate::services::booking::BookingService;
+#[cfg(feature = "bookings")]
+use crate::services::booking_calendar::{BookingCalendarService, BookingEvent};
+
 #[derive(Debug, Deserialize)]
 pub struct BookingQuery {
     pub status: Option<String>,
@@ -33,7 +54,29 @@ pub struct AvailabilityQuery {
     pub product_id: Uuid,
     pub resource_id: Option<Uuid>,
     pub date: String,
+    /// Last date to check, inclusive; defaults to `date`
+    pub date_to: Option<String>,
     pub duration: Option<i32>,
+    pub persons: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RejectBookingRequest {
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateCalendarFeedRequest {
+    pub product_id: Option<Uuid>,
+    pub resource_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateExternalCalendarRequest {
+    pub name: String,
+    pub url: String,
+    pub product_id: Uuid,
+    pub resource_id: Option<Uuid>,
 }
 
 #[derive(Debug, Serialize)]
@@ -54,3 +97,278 @@ pub struct BookingResponse {
     pub end_datetime: String,
     pub total: String,
 }
+
+/// Available booking blocks for a product
+/// GET /rc/v1/bookings/availability?product_id=...&date=YYYY-MM-DD&date_to=YYYY-MM-DD
+#[cfg(feature = "bookings")]
+pub async fn get_availability(
+    Query(query): Query<AvailabilityQuery>,
+) -> impl IntoResponse {
+    let from = NaiveDate::parse_from_str(&query.date, "%Y-%m-%d");
+    let to = query.date_to.as_deref().map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d")).transpose();
+    let (from, to) = match (from, to) {
+        (Ok(from), Ok(to)) => (from, to.unwrap_or(from)),
+        _ => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "code": "invalid_date",
+                    "message": "Dates must be in YYYY-MM-DD format"
+                })),
+            );
+        }
+    };
+
+    if to < from || (to - from).num_days() >= BookingService::MAX_RANGE_DAYS {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "code": "invalid_date_range",
+                "message": format!("Availability can be checked for 1 to {} days", BookingService::MAX_RANGE_DAYS)
+            })),
+        );
+    }
+
+    // Would load the bookable product, its resources and overlapping bookings from database
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "product_id": query.product_id,
+            "resource_id": query.resource_id,
+            "date_from": from,
+            "date_to": to,
+            "available_slots": [],
+            "unavailable_dates": [],
+            "fully_booked_dates": []
+        })),
+    )
+}
+
+/// Price a booking, with the cost breakdown shown before adding it to the cart
+/// POST /rc/v1/bookings/cost
+pub async fn calculate_booking_cost(
+    Json(request): Json<BookingRequest>,
+) -> impl IntoResponse {
+    if request.persons.is_some_and(|p| p < 1) || request.duration.is_some_and(|d| d < 1) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "code": "invalid_booking",
+                "message": "Persons and duration must be at least 1"
+            })),
+        );
+    }
+
+    // Would load the bookable product and selected resource from database
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "product_id": request.product_id,
+            "start_date": request.start_date,
+            "resource_id": request.resource_id,
+            "cost": null
+        })),
+    )
+}
+
+/// Hold a booking's slot and add it to the cart
+/// POST /rc/v1/bookings/hold
+pub async fn hold_booking(
+    Json(request): Json<BookingRequest>,
+) -> impl IntoResponse {
+    if request.persons.is_some_and(|p| p < 1) || request.duration.is_some_and(|d| d < 1) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "code": "invalid_booking",
+                "message": "Persons and duration must be at least 1"
+            })),
+        );
+    }
+
+    // Would load the cart, bookable product, resources and overlapping bookings from database
+    (
+        StatusCode::CREATED,
+        Json(serde_json::json!({
+            "product_id": request.product_id,
+            "start_date": request.start_date,
+            "status": BookingStatus::InCart,
+            "message": "Booking held in cart"
+        })),
+    )
+}
+
+/// Bookings waiting for confirmation, soonest first
+/// GET /rc/v1/bookings/pending-confirmation
+pub async fn list_pending_confirmation() -> impl IntoResponse {
+    // Would load pending bookings from database
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "bookings": [],
+            "total": 0
+        })),
+    )
+}
+
+/// Confirm a booking that requires confirmation
+/// POST /rc/v1/bookings/:id/confirm
+pub async fn confirm_booking(
+    Path(booking_id): Path<Uuid>,
+) -> impl IntoResponse {
+    // Would load the booking and its product from database
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "id": booking_id,
+            "status": BookingStatus::Confirmed,
+            "message": "Booking confirmed"
+        })),
+    )
+}
+
+/// Decline a booking that requires confirmation, refunding it if paid
+/// POST /rc/v1/bookings/:id/reject
+pub async fn reject_booking(
+    Path(booking_id): Path<Uuid>,
+    Json(request): Json<RejectBookingRequest>,
+) -> impl IntoResponse {
+    // Would load the booking, its product and order from database
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "id": booking_id,
+            "status": BookingStatus::Cancelled,
+            "reason": request.reason,
+            "message": "Booking declined"
+        })),
+    )
+}
+
+/// Cancel a booking for the customer, refunding it if paid
+/// POST /rc/v1/bookings/:id/cancel
+pub async fn cancel_booking(
+    Path(booking_id): Path<Uuid>,
+) -> impl IntoResponse {
+    // Would load the booking, its product and order from database
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "id": booking_id,
+            "status": BookingStatus::Cancelled,
+            "message": "Booking cancelled"
+        })),
+    )
+}
+
+/// Create a secret iCalendar feed for a product or resource
+/// POST /rc/v1/bookings/calendar-feeds
+#[cfg(feature = "bookings")]
+pub async fn create_calendar_feed(
+    Extension(calendars): Extension<Arc<BookingCalendarService>>,
+    Json(request): Json<CreateCalendarFeedRequest>,
+) -> impl IntoResponse {
+    if request.product_id.is_some() == request.resource_id.is_some() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "code": "invalid_feed",
+                "message": "A feed is for either a product or a resource"
+            })),
+        );
+    }
+
+    let feed = BookingCalendarFeed {
+        id: Uuid::now_v7(),
+        site_id: None,
+        product_id: request.product_id,
+        resource_id: request.resource_id,
+        token: BookingCalendarFeed::generate_token(),
+        created_at: Utc::now(),
+    };
+    // Would save the feed to database
+    let url = calendars.feed_url(&feed);
+
+    (
+        StatusCode::CREATED,
+        Json(serde_json::json!({
+            "id": feed.id,
+            "product_id": feed.product_id,
+            "resource_id": feed.resource_id,
+            "url": url
+        })),
+    )
+}
+
+/// iCalendar feed of confirmed bookings
+/// GET /rc/v1/bookings/calendar/:token
+#[cfg(feature = "bookings")]
+pub async fn get_calendar_feed(
+    Path(token): Path<String>,
+) -> impl IntoResponse {
+    let feeds: Vec<BookingCalendarFeed> = Vec::new(); // Would load from database
+    let Some(feed) = BookingCalendarService::find_feed(&feeds, &token) else {
+        return (
+            StatusCode::NOT_FOUND,
+            [(header::CONTENT_TYPE, "text/plain; charset=utf-8".to_string())],
+            "Calendar not found".to_string(),
+        );
+    };
+
+    // Would load the feed's bookings with their products from database
+    let events: Vec<BookingEvent> = Vec::new();
+    let name = match feed.resource_id {
+        Some(_) => "Resource bookings",
+        None => "Product bookings",
+    };
+
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "text/calendar; charset=utf-8".to_string())],
+        BookingCalendarService::feed(name, &events),
+    )
+}
+
+/// Add an external iCalendar feed that blocks availability
+/// POST /rc/v1/bookings/external-calendars
+pub async fn create_external_calendar(
+    Json(request): Json<CreateExternalCalendarRequest>,
+) -> impl IntoResponse {
+    let url = request.url.trim();
+    if !["http://", "https://", "webcal://"].iter().any(|scheme| url.starts_with(scheme)) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "code": "invalid_calendar_url",
+                "message": "Calendar URL must start with http://, https:// or webcal://"
+            })),
+        );
+    }
+
+    // Would save the calendar and queue its first import
+    (
+        StatusCode::CREATED,
+        Json(serde_json::json!({
+            "id": Uuid::now_v7(),
+            "name": request.name,
+            "url": url,
+            "product_id": request.product_id,
+            "resource_id": request.resource_id
+        })),
+    )
+}
+
+/// Import an external calendar now
+/// POST /rc/v1/bookings/external-calendars/:id/sync
+pub async fn sync_external_calendar(
+    Path(calendar_id): Path<Uuid>,
+) -> impl IntoResponse {
+    // Would load the calendar, its product and imported bookings from database
+    (
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({
+            "id": calendar_id,
+            "message": "Calendar import started"
+        })),
+    )
+}
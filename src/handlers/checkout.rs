@@ -11,6 +11,14 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::address::local::LocalAddressVerifier;
+use crate::models::customer::Address;
+use crate::models::order::Order;
+use crate::models::pickup::PickupSelection;
+use crate::services::checkout::AddressSuggestion;
+use crate::vat::format::parse_vat_number;
+use crate::vat::local::LocalVatProvider;
+
 /// Process checkout
 /// POST /rc/v1/checkout
 pub async fn process_checkout(
@@ -21,10 +29,7 @@ pub async fn process_checkout(
         StatusCode::CREATED,
         Json(CheckoutResponse {
             order_id: Uuid::now_v7(),
-            order_number: format!("RC-{}-{:04}",
-                chrono::Utc::now().format("%Y%m%d"),
-                rand::random::<u16>() % 10000
-            ),
+            order_number: Order::generate_number(chrono::Utc::now()),
             redirect_url: None,
             payment_result: PaymentResultResponse {
                 result: "success".to_string(),
@@ -40,6 +45,14 @@ pub struct CheckoutRequest {
     pub billing: CheckoutAddressRequest,
     pub shipping: Option<CheckoutAddressRequest>,
     pub ship_to_different_address: Option<bool>,
+    /// Shopper has reviewed any suggested address corrections
+    pub address_confirmed: Option<bool>,
+    /// Address types ("billing", "shipping") whose suggestion was accepted
+    #[serde(default)]
+    pub accepted_address_suggestions: Vec<String>,
+    pub pickup: Option<PickupSelection>,
+    /// EU VAT number for reverse charge
+    pub vat_number: Option<String>,
     pub payment_method: String,
     pub payment_data: Option<serde_json::Value>,
     pub customer_note: Option<String>,
@@ -157,6 +170,7 @@ pub async fn validate_checkout(
         Json(ValidationResponse {
             valid: true,
             errors: vec![],
+            address_suggestions: vec![],
         }),
     )
 }
@@ -165,6 +179,8 @@ pub async fn validate_checkout(
 pub struct ValidationResponse {
     pub valid: bool,
     pub errors: Vec<ValidationError>,
+    /// Corrected addresses the shopper can accept before placing the order
+    pub address_suggestions: Vec<AddressSuggestion>,
 }
 
 #[derive(Debug, Serialize)]
@@ -173,6 +189,77 @@ pub struct ValidationError {
     pub message: String,
 }
 
+/// Verify an address and suggest corrections
+/// POST /rc/v1/checkout/verify-address
+pub async fn verify_address(
+    Json(request): Json<CheckoutAddressRequest>,
+) -> impl IntoResponse {
+    let address = Address {
+        first_name: request.first_name,
+        last_name: request.last_name,
+        company: request.company.unwrap_or_default(),
+        address_1: request.address_1,
+        address_2: request.address_2.unwrap_or_default(),
+        city: request.city,
+        state: request.state,
+        postcode: request.postcode,
+        country: request.country,
+        email: request.email.unwrap_or_default(),
+        phone: request.phone.unwrap_or_default(),
+    };
+
+    let verification = LocalAddressVerifier::new().check(&address);
+    let changes = verification.changes(&address);
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "status": verification.status,
+            "suggestion": verification.suggestion,
+            "changes": changes,
+            "issues": verification.issues,
+        })),
+    )
+}
+
+/// Validate an EU VAT number
+/// POST /rc/v1/checkout/validate-vat
+pub async fn validate_vat_number(
+    Json(request): Json<ValidateVatRequest>,
+) -> impl IntoResponse {
+    let Some(number) = parse_vat_number(&request.vat_number, request.country.as_deref()) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "code": "invalid_vat_number",
+                "message": format!("{} is not a valid VAT number", request.vat_number.trim())
+            })),
+        );
+    };
+
+    // Would use the configured provider from the VAT number registry
+    let validation = LocalVatProvider::new().check(&number);
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "vat_number": validation.vat_number,
+            "country": validation.country_code,
+            "valid": validation.valid,
+            "name": validation.name,
+            "address": validation.address,
+            "provider": validation.provider,
+        })),
+    )
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ValidateVatRequest {
+    pub vat_number: String,
+    /// Billing country, used when the number has no prefix
+    pub country: Option<String>,
+}
+
 /// Get order received/thank you page data
 /// GET /rc/v1/checkout/order-received/:order_id
 pub async fn get_order_received(
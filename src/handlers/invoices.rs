@@ -5,6 +5,8 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::models::invoice::Invoice;
+
 #[derive(Debug, Deserialize)]
 pub struct InvoiceQuery {
     pub status: Option<String>,
@@ -67,6 +69,29 @@ pub struct InvoiceResponse {
     pub pdf_url: Option<String>,
 }
 
+impl InvoiceResponse {
+    /// Build a response with amounts formatted for the invoice's locale
+    pub fn from_invoice(invoice: &Invoice) -> Self {
+        Self {
+            id: invoice.id,
+            invoice_number: invoice.invoice_number.clone(),
+            invoice_type: format!("{:?}", invoice.invoice_type).to_lowercase(),
+            status: format!("{:?}", invoice.status).to_lowercase(),
+            customer_id: invoice.customer_id,
+            customer_name: invoice.buyer.name.clone(),
+            subtotal: invoice.format_amount(invoice.subtotal),
+            tax_total: invoice.format_amount(invoice.tax_total),
+            total: invoice.format_amount(invoice.total),
+            amount_paid: invoice.format_amount(invoice.amount_paid),
+            amount_due: invoice.format_amount(invoice.amount_due),
+            invoice_date: invoice.invoice_date.to_rfc3339(),
+            due_date: invoice.due_date.map(|d| d.to_rfc3339()),
+            is_overdue: invoice.is_overdue(),
+            pdf_url: invoice.pdf_url.clone(),
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct InvoiceDetailResponse {
     pub invoice: InvoiceResponse,
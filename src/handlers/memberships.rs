@@ -2,9 +2,12 @@
 //!
 //! HTTP request handlers for membership management.
 
+use axum::{http::StatusCode, response::IntoResponse, Json};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::models::membership::RestrictedContentType;
+
 #[derive(Debug, Deserialize)]
 pub struct MembershipQuery {
     pub status: Option<String>,
@@ -44,3 +47,34 @@ pub struct MembershipResponse {
     pub end_date: Option<String>,
     pub benefits: Vec<String>,
 }
+
+/// Check whether the current customer can view content
+/// POST /rc/v1/memberships/access
+pub async fn check_content_access(
+    Json(request): Json<ContentAccessRequest>,
+) -> impl IntoResponse {
+    let content_type: RestrictedContentType = match serde_json::from_value(serde_json::json!(request.content_type)) {
+        Ok(content_type) => content_type,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "code": "invalid_content_type",
+                    "message": "Unknown content type"
+                })),
+            );
+        }
+    };
+
+    // Would load plans and the customer's memberships from database
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "content_id": request.content_id,
+            "content_type": content_type,
+            "has_access": true,
+            "required_plans": [],
+            "upgrade_url": null
+        })),
+    )
+}
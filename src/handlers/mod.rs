@@ -12,6 +12,8 @@ pub mod shipping;
 pub mod tax;
 pub mod reports;
 pub mod webhooks;
+pub mod shipments;
+pub mod pickup;
 pub mod admin;
 
 // Additional handlers for enhanced features
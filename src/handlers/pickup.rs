@@ -0,0 +1,146 @@
+//! Local Pickup API Handlers
+//!
+//! REST API endpoints for pickup locations, pickup slots and pickup orders.
+
+use axum::{
+    extract::{Path, Query},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use uuid::Uuid;
+
+use crate::models::order::OrderStatus;
+use crate::models::pickup::{CreatePickupLocationRequest, PickupSlotQuery};
+
+/// Default number of days returned by the slot endpoint
+const DEFAULT_SLOT_DAYS: i32 = 7;
+
+/// List pickup locations
+/// GET /rc/v1/pickup/locations
+pub async fn list_pickup_locations() -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({ "locations": [] })),
+    )
+}
+
+/// Get a pickup location
+/// GET /rc/v1/pickup/locations/:id
+pub async fn get_pickup_location(
+    Path(location_id): Path<Uuid>,
+) -> impl IntoResponse {
+    (
+        StatusCode::NOT_FOUND,
+        Json(serde_json::json!({
+            "code": "pickup_location_not_found",
+            "message": "Pickup location not found"
+        })),
+    )
+}
+
+/// Create a pickup location
+/// POST /rc/v1/pickup/locations
+pub async fn create_pickup_location(
+    Json(request): Json<CreatePickupLocationRequest>,
+) -> impl IntoResponse {
+    if let Some(hours) = request.opening_hours.iter().find(|h| h.opens >= h.closes) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "code": "invalid_opening_hours",
+                "message": format!("Opening hours on {} must close after they open", hours.weekday)
+            })),
+        );
+    }
+
+    if request.slot_duration_minutes.is_some_and(|d| d <= 0) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "code": "invalid_slot_duration",
+                "message": "Slot duration must be positive"
+            })),
+        );
+    }
+
+    (
+        StatusCode::CREATED,
+        Json(serde_json::json!({
+            "id": Uuid::now_v7(),
+            "name": request.name,
+            "message": "Pickup location created"
+        })),
+    )
+}
+
+/// Update a pickup location
+/// PUT /rc/v1/pickup/locations/:id
+pub async fn update_pickup_location(
+    Path(location_id): Path<Uuid>,
+    Json(request): Json<CreatePickupLocationRequest>,
+) -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "id": location_id,
+            "name": request.name,
+            "message": "Pickup location updated"
+        })),
+    )
+}
+
+/// Delete a pickup location
+/// DELETE /rc/v1/pickup/locations/:id
+pub async fn delete_pickup_location(
+    Path(location_id): Path<Uuid>,
+) -> impl IntoResponse {
+    StatusCode::NO_CONTENT
+}
+
+/// Available pickup slots for a location
+/// GET /rc/v1/pickup/locations/:id/slots?from=YYYY-MM-DD&days=7
+pub async fn get_pickup_slots(
+    Path(location_id): Path<Uuid>,
+    Query(query): Query<PickupSlotQuery>,
+) -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "location_id": location_id,
+            "from": query.from,
+            "days": query.days.unwrap_or(DEFAULT_SLOT_DAYS),
+            "slots": []
+        })),
+    )
+}
+
+/// Mark a pickup order as ready for collection and notify the customer
+/// POST /rc/v1/orders/:id/ready-for-pickup
+pub async fn mark_order_ready_for_pickup(
+    Path(order_id): Path<Uuid>,
+) -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "order_id": order_id,
+            "status": OrderStatus::ReadyForPickup,
+            "message": "Order marked as ready for pickup"
+        })),
+    )
+}
+
+/// Mark a pickup order as collected
+/// POST /rc/v1/orders/:id/picked-up
+pub async fn mark_order_picked_up(
+    Path(order_id): Path<Uuid>,
+) -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "order_id": order_id,
+            "status": OrderStatus::Completed,
+            "message": "Order collected"
+        })),
+    )
+}
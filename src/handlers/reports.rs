@@ -4,12 +4,16 @@
 
 use axum::{
     extract::Query,
-    http::StatusCode,
-    response::IntoResponse,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
 use serde::{Deserialize, Serialize};
 
+use crate::models::tax::TaxExemptionReportFilter;
+use crate::services::report::{DateRange, ReportService};
+use crate::settings::RustCommerceSettings;
+
 /// Report filter
 #[derive(Debug, Deserialize)]
 pub struct ReportFilter {
@@ -215,6 +219,132 @@ pub async fn get_revenue_report(
     )
 }
 
+/// Get the EU One-Stop-Shop VAT report for a quarter
+/// GET /rc/v1/reports/oss?year=2026&quarter=3
+pub async fn get_oss_report(
+    Query(filter): Query<OssReportFilter>,
+) -> impl IntoResponse {
+    if !(1..=4).contains(&filter.quarter) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "code": "invalid_quarter",
+                "message": "Quarter must be between 1 and 4"
+            })),
+        );
+    }
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "period": format!("{}-Q{}", filter.year, filter.quarter),
+            "lines": [],
+            "total_taxable_amount": "0.00",
+            "total_vat_amount": "0.00",
+            "order_count": 0
+        })),
+    )
+}
+
+/// Get collected tax per rate and jurisdiction, net of refunds
+/// GET /rc/v1/reports/taxes?period=last_month&format=csv
+pub async fn get_tax_report(
+    Query(filter): Query<TaxReportFilter>,
+) -> Response {
+    let Some(range) = DateRange::from_filter(
+        filter.period.as_deref(),
+        filter.date_min.as_deref(),
+        filter.date_max.as_deref(),
+    ) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "code": "invalid_period",
+                "message": "Unknown report period or invalid dates"
+            })),
+        ).into_response();
+    };
+
+    let service = ReportService::new(RustCommerceSettings::default());
+    let orders = Vec::new(); // Would load orders paid or refunded in the range from database
+    let rates = Vec::new(); // Would load tax rates from database
+    let report = service.generate_tax_report(&orders, &rates, range);
+
+    if filter.format.as_deref() == Some("csv") {
+        return (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (header::CONTENT_DISPOSITION, "attachment; filename=\"tax-report.csv\"".to_string()),
+            ],
+            service.tax_report_csv(&report),
+        ).into_response();
+    }
+
+    (StatusCode::OK, Json(report)).into_response()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TaxReportFilter {
+    pub period: Option<String>,
+    pub date_min: Option<String>,
+    pub date_max: Option<String>,
+    /// "json" (default) or "csv"
+    pub format: Option<String>,
+}
+
+/// Get exempted sales by exemption certificate
+/// GET /rc/v1/reports/tax-exemptions?date_min=2026-01-01&date_max=2026-12-31
+pub async fn get_tax_exemption_report(
+    Query(filter): Query<TaxExemptionReportFilter>,
+) -> impl IntoResponse {
+    let service = ReportService::new(RustCommerceSettings::default());
+    let orders = Vec::new(); // Would load paid orders with exemptions from database
+    let lines = service.generate_tax_exemption_report(&orders, &filter);
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "date_min": filter.date_min,
+            "date_max": filter.date_max,
+            "lines": lines
+        })),
+    )
+}
+
+/// Get failed subscription renewals recovered or lost by dunning
+/// GET /rc/v1/reports/dunning?period=last_month
+#[cfg(feature = "subscriptions")]
+pub async fn get_dunning_report(
+    Query(filter): Query<ReportFilter>,
+) -> Response {
+    let Some(range) = DateRange::from_filter(
+        filter.period.as_deref(),
+        filter.date_min.as_deref(),
+        filter.date_max.as_deref(),
+    ) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "code": "invalid_period",
+                "message": "Unknown report period or invalid dates"
+            })),
+        ).into_response();
+    };
+
+    let service = ReportService::new(RustCommerceSettings::default());
+    let renewals = Vec::new(); // Would load renewals due in the range from database
+    let report = service.generate_dunning_report(&renewals, range);
+
+    (StatusCode::OK, Json(report)).into_response()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OssReportFilter {
+    pub year: i32,
+    pub quarter: u32,
+}
+
 /// Export report
 /// GET /rc/v1/reports/export
 pub async fn export_report(
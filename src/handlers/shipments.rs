@@ -0,0 +1,202 @@
+//! Shipment API Handlers
+//!
+//! REST API endpoints for order fulfillment and customer tracking.
+
+use axum::{
+    extract::{Path, Query},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::models::fulfillment::{
+    CreateShipmentRequest, UpdateShipmentRequest, CreateCarrierRequest, TrackOrderRequest,
+    FulfillmentStatus, CreateLabelRequest,
+};
+use crate::labels::provider::LabelFormat;
+
+/// List shipments for an order
+/// GET /rc/v1/orders/:id/shipments
+pub async fn list_order_shipments(
+    Path(order_id): Path<Uuid>,
+) -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "order_id": order_id,
+            "fulfillment_status": FulfillmentStatus::Unfulfilled,
+            "shipments": []
+        })),
+    )
+}
+
+/// Create a shipment for an order
+/// POST /rc/v1/orders/:id/shipments
+pub async fn create_order_shipment(
+    Path(order_id): Path<Uuid>,
+    Json(request): Json<CreateShipmentRequest>,
+) -> impl IntoResponse {
+    if request.items.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "code": "shipment_no_items",
+                "message": "A shipment must contain at least one item"
+            })),
+        );
+    }
+
+    (
+        StatusCode::CREATED,
+        Json(serde_json::json!({
+            "id": Uuid::now_v7(),
+            "order_id": order_id,
+            "carrier": request.carrier,
+            "tracking_number": request.tracking_number,
+            "message": "Shipment created"
+        })),
+    )
+}
+
+/// Update a shipment
+/// PUT /rc/v1/orders/:id/shipments/:shipment_id
+pub async fn update_order_shipment(
+    Path((order_id, shipment_id)): Path<(Uuid, Uuid)>,
+    Json(request): Json<UpdateShipmentRequest>,
+) -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "id": shipment_id,
+            "order_id": order_id,
+            "status": request.status,
+            "message": "Shipment updated"
+        })),
+    )
+}
+
+/// Delete a shipment
+/// DELETE /rc/v1/orders/:id/shipments/:shipment_id
+pub async fn delete_order_shipment(
+    Path((order_id, shipment_id)): Path<(Uuid, Uuid)>,
+) -> impl IntoResponse {
+    StatusCode::NO_CONTENT
+}
+
+/// Generate a shipping label for a shipment
+/// POST /rc/v1/orders/:id/shipments/:shipment_id/label
+pub async fn create_shipment_label(
+    Path((order_id, shipment_id)): Path<(Uuid, Uuid)>,
+    Json(request): Json<CreateLabelRequest>,
+) -> impl IntoResponse {
+    let format = request.format.unwrap_or_default();
+
+    (
+        StatusCode::CREATED,
+        Json(serde_json::json!({
+            "shipment_id": shipment_id,
+            "order_id": order_id,
+            "provider": request.provider.unwrap_or_else(|| "builtin".to_string()),
+            "format": format,
+            "content_type": format.content_type(),
+            "tracking_number": null,
+            "message": "Label created"
+        })),
+    )
+}
+
+/// Download a shipment's label document
+/// GET /rc/v1/orders/:id/shipments/:shipment_id/label
+pub async fn download_shipment_label(
+    Path((order_id, shipment_id)): Path<(Uuid, Uuid)>,
+) -> impl IntoResponse {
+    (
+        StatusCode::NOT_FOUND,
+        Json(serde_json::json!({
+            "code": "label_not_found",
+            "message": "No label has been generated for this shipment"
+        })),
+    )
+}
+
+/// List label providers
+/// GET /rc/v1/shipping/label-providers
+pub async fn list_label_providers() -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "providers": [{
+                "id": "builtin",
+                "title": "Printable label and packing slip",
+                "formats": [LabelFormat::Pdf, LabelFormat::Zpl]
+            }]
+        })),
+    )
+}
+
+/// List shipping carriers
+/// GET /rc/v1/shipping/carriers
+pub async fn list_carriers() -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({ "carriers": [] })),
+    )
+}
+
+/// Create a custom shipping carrier
+/// POST /rc/v1/shipping/carriers
+pub async fn create_carrier(
+    Json(request): Json<CreateCarrierRequest>,
+) -> impl IntoResponse {
+    if !request.tracking_url_template.is_empty()
+        && !request.tracking_url_template.contains("{tracking_number}")
+    {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "code": "invalid_tracking_url_template",
+                "message": "Tracking URL template must contain {tracking_number}"
+            })),
+        );
+    }
+
+    (
+        StatusCode::CREATED,
+        Json(serde_json::json!({
+            "id": Uuid::now_v7(),
+            "name": request.name,
+            "message": "Carrier created"
+        })),
+    )
+}
+
+/// Customer-facing order tracking
+/// GET /rc/v1/track?order_number=...&email=...
+pub async fn track_order(
+    Query(request): Query<TrackOrderRequest>,
+) -> impl IntoResponse {
+    (
+        StatusCode::NOT_FOUND,
+        Json(serde_json::json!({
+            "code": "order_not_found",
+            "message": "No order matches this order number and email address"
+        })),
+    )
+}
+
+/// Tracking for the current customer's order
+/// GET /rc/v1/my-account/orders/:id/tracking
+pub async fn get_my_order_tracking(
+    Path(order_id): Path<Uuid>,
+) -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "order_id": order_id,
+            "fulfillment_status": FulfillmentStatus::Unfulfilled,
+            "shipments": []
+        })),
+    )
+}
@@ -2,9 +2,18 @@
 //!
 //! HTTP request handlers for subscription management.
 
+use axum::{
+    extract::Path,
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::models::subscription::SubscriptionStatus;
+
 #[derive(Debug, Deserialize)]
 pub struct SubscriptionQuery {
     pub status: Option<String>,
@@ -38,3 +47,182 @@ pub struct SubscriptionResponse {
     pub billing_period: String,
     pub next_payment_date: Option<String>,
 }
+
+#[derive(Debug, Deserialize)]
+pub struct PauseSubscriptionRequest {
+    pub cycles: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChangeRenewalDateRequest {
+    pub date: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChangeQuantityRequest {
+    pub quantity: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChangePaymentMethodRequest {
+    pub gateway_id: String,
+    pub payment_token_id: Uuid,
+}
+
+/// Pause a subscription for a number of billing cycles
+/// POST /rc/v1/subscriptions/:id/pause
+pub async fn pause_subscription(
+    Path(subscription_id): Path<Uuid>,
+    Json(request): Json<PauseSubscriptionRequest>,
+) -> impl IntoResponse {
+    if request.cycles < 1 {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "code": "invalid_pause_length",
+                "message": "Subscriptions must be paused for at least one billing cycle"
+            })),
+        );
+    }
+
+    // Would load the subscription and its product settings from database
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "id": subscription_id,
+            "status": SubscriptionStatus::OnHold,
+            "cycles": request.cycles,
+            "message": "Subscription paused"
+        })),
+    )
+}
+
+/// Resume a paused subscription
+/// POST /rc/v1/subscriptions/:id/resume
+pub async fn resume_subscription(
+    Path(subscription_id): Path<Uuid>,
+) -> impl IntoResponse {
+    // Would load the subscription from database
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "id": subscription_id,
+            "status": SubscriptionStatus::Active,
+            "message": "Subscription resumed"
+        })),
+    )
+}
+
+/// Skip the next renewal
+/// POST /rc/v1/subscriptions/:id/skip
+pub async fn skip_next_renewal(
+    Path(subscription_id): Path<Uuid>,
+) -> impl IntoResponse {
+    // Would load the subscription and its product settings from database
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "id": subscription_id,
+            "message": "Next renewal skipped"
+        })),
+    )
+}
+
+/// Move the next renewal date
+/// POST /rc/v1/subscriptions/:id/renewal-date
+pub async fn change_renewal_date(
+    Path(subscription_id): Path<Uuid>,
+    Json(request): Json<ChangeRenewalDateRequest>,
+) -> impl IntoResponse {
+    if request.date <= Utc::now() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "code": "invalid_renewal_date",
+                "message": "The renewal date must be in the future"
+            })),
+        );
+    }
+
+    // Would load the subscription and its product settings from database
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "id": subscription_id,
+            "next_payment_date": request.date,
+            "message": "Renewal date changed"
+        })),
+    )
+}
+
+/// Change the quantity of a subscription item
+/// POST /rc/v1/subscriptions/:id/items/:item_id/quantity
+pub async fn change_item_quantity(
+    Path((subscription_id, item_id)): Path<(Uuid, Uuid)>,
+    Json(request): Json<ChangeQuantityRequest>,
+) -> impl IntoResponse {
+    if request.quantity < 1 {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "code": "invalid_quantity",
+                "message": "Quantity must be at least 1"
+            })),
+        );
+    }
+
+    // Would load the subscription and its product settings from database
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "id": subscription_id,
+            "item_id": item_id,
+            "quantity": request.quantity,
+            "message": "Quantity changed"
+        })),
+    )
+}
+
+/// Charge renewals to a different saved payment method
+/// POST /rc/v1/subscriptions/:id/payment-method
+pub async fn change_payment_method(
+    Path(subscription_id): Path<Uuid>,
+    Json(request): Json<ChangePaymentMethodRequest>,
+) -> impl IntoResponse {
+    if request.gateway_id.trim().is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "code": "invalid_payment_method",
+                "message": "A payment method is required"
+            })),
+        );
+    }
+
+    // Would load the subscription and saved payment token from database
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "id": subscription_id,
+            "payment_method": request.gateway_id,
+            "payment_token_id": request.payment_token_id,
+            "message": "Payment method updated"
+        })),
+    )
+}
+
+/// Cancel a subscription at the end of its current term
+/// POST /rc/v1/subscriptions/:id/cancel
+pub async fn cancel_subscription(
+    Path(subscription_id): Path<Uuid>,
+) -> impl IntoResponse {
+    // Would load the subscription and its product settings from database
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "id": subscription_id,
+            "status": SubscriptionStatus::PendingCancel,
+            "message": "Subscription will be cancelled at the end of the current term"
+        })),
+    )
+}
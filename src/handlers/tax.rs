@@ -4,14 +4,19 @@
 
 use axum::{
     extract::{Path, Query},
-    http::StatusCode,
+    http::{header, StatusCode},
     response::IntoResponse,
     Json,
 };
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::models::tax::{TaxRate, TaxRateFilter, CreateTaxRateRequest, UpdateTaxRateRequest};
+use crate::models::tax::{
+    TaxRate, TaxRateFilter, CreateTaxRateRequest, UpdateTaxRateRequest, TaxRateExportQuery,
+    TaxRateImportRequest, TaxExemption, TaxExemptionRequest, TaxExemptionReviewRequest, TaxExemptionStatus,
+};
+use crate::services::tax_import::TaxImportService;
+use crate::settings::RustCommerceSettings;
 
 /// List tax rates
 /// GET /rc/v1/taxes
@@ -104,6 +109,113 @@ pub struct BatchTaxRateRequest {
     pub delete: Option<Vec<Uuid>>,
 }
 
+/// Export tax rates as CSV
+/// GET /rc/v1/taxes/export?tax_class=reduced-rate
+pub async fn export_tax_rates(
+    Query(query): Query<TaxRateExportQuery>,
+) -> impl IntoResponse {
+    let service = TaxImportService::new(RustCommerceSettings::default());
+    let rates: Vec<TaxRate> = Vec::new(); // Would load from database
+    let csv = service.export_csv(&rates, query.tax_class.as_deref());
+
+    let file_name = service.export_file_name(query.tax_class.as_deref());
+
+    (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", file_name)),
+        ],
+        csv,
+    )
+}
+
+/// Import tax rates from CSV (merge or replace, optionally as a dry run)
+/// POST /rc/v1/taxes/import
+pub async fn import_tax_rates(
+    Json(request): Json<TaxRateImportRequest>,
+) -> impl IntoResponse {
+    let service = TaxImportService::new(RustCommerceSettings::default());
+    let existing: Vec<TaxRate> = Vec::new(); // Would load from database
+    let plan = service.plan_import(&existing, &request, None);
+    let summary = plan.summary(request.dry_run);
+
+    if !plan.is_valid() {
+        return (StatusCode::UNPROCESSABLE_ENTITY, Json(summary));
+    }
+
+    if !request.dry_run {
+        // Would persist the created, updated and deleted rates
+        let _rates = service.apply_plan(&existing, &plan);
+    }
+
+    (StatusCode::OK, Json(summary))
+}
+
+/// List a customer's tax exemptions
+/// GET /rc/v1/customers/:id/tax-exemptions
+pub async fn list_tax_exemptions(
+    Path(customer_id): Path<Uuid>,
+) -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "customer_id": customer_id,
+            "exemptions": []
+        })),
+    )
+}
+
+/// Upload or assign a tax exemption certificate; it applies once approved
+/// POST /rc/v1/customers/:id/tax-exemptions
+pub async fn create_tax_exemption(
+    Path(customer_id): Path<Uuid>,
+    Json(request): Json<TaxExemptionRequest>,
+) -> impl IntoResponse {
+    if request.certificate_id.trim().is_empty() || request.jurisdictions.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "code": "invalid_tax_exemption",
+                "message": "A certificate ID and at least one jurisdiction are required"
+            })),
+        );
+    }
+
+    let exemption = TaxExemption {
+        id: Uuid::now_v7(),
+        site_id: None,
+        customer_id,
+        certificate_id: request.certificate_id.trim().to_string(),
+        exemption_type: request.exemption_type.unwrap_or_default(),
+        jurisdictions: request.jurisdictions,
+        status: TaxExemptionStatus::Pending,
+        document_url: request.document_url,
+        expires_at: request.expires_at,
+        notes: request.notes,
+        created_at: chrono::Utc::now(),
+        updated_at: None,
+    };
+
+    (StatusCode::CREATED, Json(serde_json::json!(exemption)))
+}
+
+/// Approve or reject a tax exemption
+/// PUT /rc/v1/tax-exemptions/:id
+pub async fn review_tax_exemption(
+    Path(id): Path<Uuid>,
+    Json(request): Json<TaxExemptionReviewRequest>,
+) -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "id": id,
+            "status": request.status,
+            "message": "Tax exemption updated"
+        })),
+    )
+}
+
 /// List tax classes
 /// GET /rc/v1/taxes/classes
 pub async fn list_tax_classes() -> impl IntoResponse {
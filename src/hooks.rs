@@ -106,6 +106,12 @@ pub fn get_hooks() -> Vec<Hook> {
             description: "Fires after order is created during checkout".to_string(),
             parameters: vec!["order_id".to_string(), "order".to_string()],
         },
+        Hook {
+            name: "rustcommerce_address_verification_result".to_string(),
+            hook_type: HookType::Filter,
+            description: "Filter the address verification result shown at checkout".to_string(),
+            parameters: vec!["verification".to_string(), "address".to_string(), "address_type".to_string()],
+        },
 
         // Order hooks
         Hook {
@@ -133,6 +139,64 @@ pub fn get_hooks() -> Vec<Hook> {
             parameters: vec!["order_id".to_string(), "refund_id".to_string()],
         },
 
+        // Fulfillment hooks
+        Hook {
+            name: "rustcommerce_shipment_created".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when a shipment is created for an order".to_string(),
+            parameters: vec!["order_id".to_string(), "shipment".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_shipment_status_changed".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when a shipment status changes".to_string(),
+            parameters: vec!["shipment_id".to_string(), "old_status".to_string(), "new_status".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_order_fulfillment_status_changed".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when an order's fulfillment status changes".to_string(),
+            parameters: vec!["order_id".to_string(), "fulfillment_status".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_shipment_label_created".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when a shipping label is generated for a shipment".to_string(),
+            parameters: vec!["shipment_id".to_string(), "provider".to_string(), "tracking_number".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_label_request".to_string(),
+            hook_type: HookType::Filter,
+            description: "Filter the label request before it is sent to the label provider".to_string(),
+            parameters: vec!["request".to_string(), "shipment".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_shipment_tracking_url".to_string(),
+            hook_type: HookType::Filter,
+            description: "Filter the tracking URL of a shipment".to_string(),
+            parameters: vec!["tracking_url".to_string(), "shipment".to_string()],
+        },
+
+        // Local pickup hooks
+        Hook {
+            name: "rustcommerce_order_ready_for_pickup".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when a pickup order is ready for collection".to_string(),
+            parameters: vec!["order_id".to_string(), "location_id".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_order_picked_up".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when a customer collects a pickup order".to_string(),
+            parameters: vec!["order_id".to_string(), "location_id".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_pickup_slots".to_string(),
+            hook_type: HookType::Filter,
+            description: "Filter the pickup slots offered for a location".to_string(),
+            parameters: vec!["slots".to_string(), "location".to_string()],
+        },
+
         // Customer hooks
         Hook {
             name: "rustcommerce_created_customer".to_string(),
@@ -167,6 +231,32 @@ pub fn get_hooks() -> Vec<Hook> {
             parameters: vec!["discount".to_string(), "coupon".to_string(), "cart".to_string()],
         },
 
+        // Currency hooks
+        Hook {
+            name: "rustcommerce_currency_resolved".to_string(),
+            hook_type: HookType::Filter,
+            description: "Filter the currency resolved for a visitor".to_string(),
+            parameters: vec!["currency".to_string(), "context".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_currency_switched".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when a customer switches the cart currency".to_string(),
+            parameters: vec!["cart_id".to_string(), "from".to_string(), "to".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_exchange_rates_updated".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires after exchange rates are refreshed from a provider".to_string(),
+            parameters: vec!["summary".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_exchange_rate_alert".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when a refreshed exchange rate moves more than the alert threshold".to_string(),
+            parameters: vec!["currency".to_string(), "previous_rate".to_string(), "rate".to_string()],
+        },
+
         // Shipping hooks
         Hook {
             name: "rustcommerce_shipping_method_chosen".to_string(),
@@ -194,6 +284,228 @@ pub fn get_hooks() -> Vec<Hook> {
             description: "Filter calculated taxes".to_string(),
             parameters: vec!["taxes".to_string(), "price".to_string(), "rates".to_string()],
         },
+        Hook {
+            name: "rustcommerce_tax_rates_imported".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires after tax rates are imported from CSV".to_string(),
+            parameters: vec!["summary".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_tax_provider_request".to_string(),
+            hook_type: HookType::Filter,
+            description: "Filter the request sent to an external tax provider".to_string(),
+            parameters: vec!["request".to_string(), "provider".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_tax_provider_fallback".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when a tax provider fails and the rate table is used instead".to_string(),
+            parameters: vec!["provider".to_string(), "error".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_tax_committed".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires after a paid order's taxes are committed with the tax provider".to_string(),
+            parameters: vec!["order_id".to_string(), "provider".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_tax_voided".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires after a refunded order's tax transaction is voided".to_string(),
+            parameters: vec!["order_id".to_string(), "provider".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_tax_exemption_submitted".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when a customer uploads a tax exemption certificate".to_string(),
+            parameters: vec!["exemption".to_string(), "customer_id".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_tax_exemption_reviewed".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when a tax exemption is approved or rejected".to_string(),
+            parameters: vec!["exemption".to_string(), "status".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_tax_exemption_applied".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when an order's taxes are waived under an exemption".to_string(),
+            parameters: vec!["order_id".to_string(), "exemption".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_vat_number_validated".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires after a customer VAT number is looked up".to_string(),
+            parameters: vec!["validation".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_vat_treatment".to_string(),
+            hook_type: HookType::Filter,
+            description: "Filter the EU VAT treatment (domestic, origin, destination, reverse charge, export)".to_string(),
+            parameters: vec!["treatment".to_string(), "customer_country".to_string(), "validation".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_reverse_charge_applied".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when an order is zero-rated under reverse charge".to_string(),
+            parameters: vec!["order_id".to_string(), "vat_number".to_string()],
+        },
+
+        // Subscription hooks
+        Hook {
+            name: "rustcommerce_subscription_created".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when a subscription is created from a checkout order".to_string(),
+            parameters: vec!["subscription".to_string(), "order_id".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_subscription_renewal_order".to_string(),
+            hook_type: HookType::Filter,
+            description: "Filter a renewal order before it is saved".to_string(),
+            parameters: vec!["order".to_string(), "subscription".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_subscription_renewal_payment_complete".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when a renewal payment succeeds".to_string(),
+            parameters: vec!["subscription_id".to_string(), "order_id".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_subscription_renewal_payment_failed".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when a renewal payment fails".to_string(),
+            parameters: vec!["subscription_id".to_string(), "order_id".to_string(), "error".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_subscription_payment_retry_scheduled".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when a failed renewal payment is scheduled for a retry".to_string(),
+            parameters: vec!["subscription_id".to_string(), "order_id".to_string(), "retry_date".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_subscription_cancelled_for_nonpayment".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when a subscription ends after its last renewal retry fails".to_string(),
+            parameters: vec!["subscription_id".to_string(), "order_id".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_subscription_switch_calculation".to_string(),
+            hook_type: HookType::Filter,
+            description: "Filter the proration calculated for a plan switch".to_string(),
+            parameters: vec!["calculation".to_string(), "subscription".to_string(), "request".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_subscription_switched".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when a subscription item is switched to a new plan".to_string(),
+            parameters: vec!["subscription_id".to_string(), "schedule_change".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_subscription_expired".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when a subscription reaches its end date".to_string(),
+            parameters: vec!["subscription_id".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_subscription_paused".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when a customer pauses a subscription".to_string(),
+            parameters: vec!["subscription_id".to_string(), "schedule_change".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_subscription_resumed".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when a paused subscription is resumed".to_string(),
+            parameters: vec!["subscription_id".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_subscription_schedule_changed".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when a customer changes a subscription's schedule, quantity or payment method".to_string(),
+            parameters: vec!["subscription_id".to_string(), "schedule_change".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_subscription_pending_cancel".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when a subscription is set to cancel at the end of its term".to_string(),
+            parameters: vec!["subscription_id".to_string(), "end_date".to_string()],
+        },
+
+        // Booking hooks
+        Hook {
+            name: "rustcommerce_booking_cost".to_string(),
+            hook_type: HookType::Filter,
+            description: "Filter a booking's calculated cost before it is added to the cart".to_string(),
+            parameters: vec!["cost".to_string(), "booking_request".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_booking_held".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when a booking's slot is held in a cart".to_string(),
+            parameters: vec!["booking_id".to_string(), "cart_id".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_booking_hold_released".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when an in-cart hold is removed or runs out".to_string(),
+            parameters: vec!["booking_id".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_booking_confirmed".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when an admin confirms a booking".to_string(),
+            parameters: vec!["booking_id".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_booking_cancelled".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when a booking is cancelled or declined".to_string(),
+            parameters: vec!["booking_id".to_string(), "refund".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_booking_reminder".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when a booking reminder email is sent".to_string(),
+            parameters: vec!["booking_id".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_booking_calendar_imported".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires after an external calendar is imported".to_string(),
+            parameters: vec!["calendar_id".to_string(), "sync".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_booking_calendar_import_failed".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when an external calendar can't be fetched or read".to_string(),
+            parameters: vec!["calendar_id".to_string(), "error".to_string()],
+        },
+
+        // Membership hooks
+        Hook {
+            name: "rustcommerce_membership_granted".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when a purchase grants or extends a membership".to_string(),
+            parameters: vec!["membership_id".to_string(), "order_id".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_membership_status_changed".to_string(),
+            hook_type: HookType::Action,
+            description: "Fires when a membership starts, leaves its trial or expires".to_string(),
+            parameters: vec!["membership_id".to_string(), "old_status".to_string(), "new_status".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_membership_access".to_string(),
+            hook_type: HookType::Filter,
+            description: "Filter the result of a member content or purchase access check".to_string(),
+            parameters: vec!["access".to_string(), "user_id".to_string(), "content_id".to_string()],
+        },
+        Hook {
+            name: "rustcommerce_member_discount".to_string(),
+            hook_type: HookType::Filter,
+            description: "Filter a member's discount on a cart line before it is applied".to_string(),
+            parameters: vec!["discount".to_string(), "cart_item".to_string(), "plan_id".to_string()],
+        },
 
         // Payment hooks
         Hook {
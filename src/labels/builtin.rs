@@ -0,0 +1,344 @@
+//! Built-in Label Provider
+//!
+//! Renders a printable address label per package plus a packing slip,
+//! entirely offline. No carrier is involved, so no tracking number is issued.
+
+use async_trait::async_trait;
+
+use super::pdf::{PdfDocument, PdfPage, A4, LABEL_4X6};
+use super::provider::{LabelProvider, LabelError, LabelFormat, LabelRequest, LabelResult, LabelPackage};
+use crate::models::customer::Address;
+
+/// Packing slip rows per page
+const SLIP_ROWS_PER_PAGE: usize = 32;
+
+/// Built-in label provider configuration
+#[derive(Debug, Clone)]
+pub struct BuiltinLabelConfig {
+    pub title: String,
+    pub include_packing_slip: bool,
+    pub weight_unit: String,
+}
+
+impl Default for BuiltinLabelConfig {
+    fn default() -> Self {
+        Self {
+            title: "Printable label and packing slip".to_string(),
+            include_packing_slip: true,
+            weight_unit: "kg".to_string(),
+        }
+    }
+}
+
+/// Built-in offline label provider
+pub struct BuiltinLabelProvider {
+    config: BuiltinLabelConfig,
+}
+
+impl BuiltinLabelProvider {
+    /// Create a new built-in label provider
+    pub fn new(config: BuiltinLabelConfig) -> Self {
+        Self { config }
+    }
+
+    /// Address lines for printing
+    fn address_lines(address: &Address) -> Vec<String> {
+        address.get_formatted()
+            .lines()
+            .map(|l| l.to_string())
+            .collect()
+    }
+
+    /// Render the PDF document: one address label per package, then the packing slip
+    fn render_pdf(&self, request: &LabelRequest) -> Vec<u8> {
+        let mut doc = PdfDocument::new();
+        let count = request.packages.len();
+
+        for (index, package) in request.packages.iter().enumerate() {
+            doc.add_page(self.render_address_label(request, package, index, count));
+        }
+
+        if self.config.include_packing_slip {
+            for page in self.render_packing_slip(request) {
+                doc.add_page(page);
+            }
+        }
+
+        doc.to_bytes()
+    }
+
+    fn render_address_label(
+        &self,
+        request: &LabelRequest,
+        package: &LabelPackage,
+        index: usize,
+        count: usize,
+    ) -> PdfPage {
+        let mut page = PdfPage::new(LABEL_4X6);
+        let (width, height) = (page.width, page.height);
+        page.rect(8.0, 8.0, width - 16.0, height - 16.0, 1.5);
+
+        // Sender
+        let mut y = height - 30.0;
+        page.text(18.0, y, 8.0, true, "FROM");
+        y -= 12.0;
+        page.text(18.0, y, 9.0, false, &request.store_name);
+        for line in Self::address_lines(&request.ship_from) {
+            y -= 11.0;
+            page.text(18.0, y, 9.0, false, &line);
+        }
+
+        y -= 14.0;
+        page.line(8.0, y, width - 8.0, y, 1.0);
+
+        // Recipient
+        y -= 24.0;
+        page.text(18.0, y, 10.0, true, "SHIP TO");
+        for (i, line) in Self::address_lines(&request.ship_to).iter().enumerate() {
+            y -= 20.0;
+            page.text(18.0, y, if i == 0 { 16.0 } else { 14.0 }, i == 0, line);
+        }
+
+        // Footer
+        page.line(8.0, 90.0, width - 8.0, 90.0, 1.0);
+        page.text(18.0, 70.0, 10.0, true, &format!("Order {}", request.order_number));
+        page.text(18.0, 55.0, 9.0, false, &format!("Shipment {}", request.shipment_number));
+        page.text(18.0, 40.0, 9.0, false, &format!("Package {} of {}", index + 1, count));
+        if let Some(weight) = package.weight {
+            page.text(150.0, 40.0, 9.0, false, &format!("Weight: {} {}", weight.normalize(), self.config.weight_unit));
+        }
+        if let Some(ref service) = request.service_code {
+            page.text(150.0, 55.0, 9.0, false, &format!("Service: {}", service));
+        }
+
+        page
+    }
+
+    fn render_packing_slip(&self, request: &LabelRequest) -> Vec<PdfPage> {
+        let rows: Vec<(String, String, i32)> = request.packages.iter()
+            .flat_map(|p| p.items.iter())
+            .map(|i| (i.name.clone(), i.sku.clone().unwrap_or_default(), i.quantity))
+            .collect();
+        let chunks: Vec<&[(String, String, i32)]> = if rows.is_empty() {
+            vec![&[]]
+        } else {
+            rows.chunks(SLIP_ROWS_PER_PAGE).collect()
+        };
+        let page_count = chunks.len();
+
+        chunks.into_iter().enumerate().map(|(page_index, chunk)| {
+            let mut page = PdfPage::new(A4);
+            let (width, height) = (page.width, page.height);
+
+            let mut y = height - 60.0;
+            page.text(50.0, y, 20.0, true, "Packing Slip");
+            page.text(width - 200.0, y, 10.0, false, &format!("Page {} of {}", page_index + 1, page_count));
+            y -= 24.0;
+            page.text(50.0, y, 11.0, true, &request.store_name);
+            y -= 16.0;
+            page.text(50.0, y, 10.0, false, &format!("Order: {}", request.order_number));
+            y -= 14.0;
+            page.text(50.0, y, 10.0, false, &format!("Shipment: {}", request.shipment_number));
+
+            // Ship-to block (first page only)
+            if page_index == 0 {
+                let mut address_y = height - 100.0;
+                page.text(320.0, address_y, 10.0, true, "Ship to");
+                for line in Self::address_lines(&request.ship_to) {
+                    address_y -= 14.0;
+                    page.text(320.0, address_y, 10.0, false, &line);
+                }
+                y = y.min(address_y);
+            }
+
+            // Item table
+            y -= 30.0;
+            page.text(50.0, y, 10.0, true, "Item");
+            page.text(380.0, y, 10.0, true, "SKU");
+            page.text(500.0, y, 10.0, true, "Qty");
+            y -= 6.0;
+            page.line(50.0, y, width - 50.0, y, 0.8);
+
+            for (name, sku, quantity) in chunk {
+                y -= 18.0;
+                page.text(50.0, y, 10.0, false, &truncate(name, 60));
+                page.text(380.0, y, 10.0, false, &truncate(sku, 18));
+                page.text(500.0, y, 10.0, false, &quantity.to_string());
+            }
+
+            if page_index + 1 == page_count {
+                if let Some(ref note) = request.note {
+                    y -= 36.0;
+                    page.text(50.0, y, 10.0, true, "Note");
+                    y -= 14.0;
+                    page.text(50.0, y, 10.0, false, &truncate(note, 90));
+                }
+            }
+
+            page
+        }).collect()
+    }
+
+    /// Render ZPL address labels, one per package
+    fn render_zpl(&self, request: &LabelRequest) -> Vec<u8> {
+        let count = request.packages.len();
+        let mut out = String::new();
+
+        for (index, package) in request.packages.iter().enumerate() {
+            out.push_str("^XA^CI28\n");
+            out.push_str(&format!("^FO30,30^A0N,22,22^FDFROM: {}^FS\n", zpl_escape(&request.store_name)));
+            let mut y = 60;
+            for line in Self::address_lines(&request.ship_from) {
+                out.push_str(&format!("^FO30,{}^A0N,20,20^FD{}^FS\n", y, zpl_escape(&line)));
+                y += 24;
+            }
+            y += 20;
+            out.push_str(&format!("^FO20,{}^GB772,3,3^FS\n", y));
+            y += 30;
+            out.push_str(&format!("^FO30,{}^A0N,28,28^FDSHIP TO^FS\n", y));
+            for line in Self::address_lines(&request.ship_to) {
+                y += 44;
+                out.push_str(&format!("^FO30,{}^A0N,40,40^FD{}^FS\n", y, zpl_escape(&line)));
+            }
+            out.push_str(&format!("^FO30,1050^A0N,28,28^FDOrder {}^FS\n", zpl_escape(&request.order_number)));
+            out.push_str(&format!(
+                "^FO30,1090^A0N,24,24^FDShipment {} - Package {} of {}^FS\n",
+                zpl_escape(&request.shipment_number), index + 1, count
+            ));
+            if let Some(weight) = package.weight {
+                out.push_str(&format!(
+                    "^FO30,1125^A0N,24,24^FDWeight: {} {}^FS\n",
+                    weight.normalize(), zpl_escape(&self.config.weight_unit)
+                ));
+            }
+            out.push_str(&format!("^FO450,1040^BCN,80,N,N,N^FD{}^FS\n", zpl_escape(&request.shipment_number)));
+            out.push_str("^XZ\n");
+        }
+
+        out.into_bytes()
+    }
+}
+
+impl Default for BuiltinLabelProvider {
+    fn default() -> Self {
+        Self::new(BuiltinLabelConfig::default())
+    }
+}
+
+#[async_trait]
+impl LabelProvider for BuiltinLabelProvider {
+    fn id(&self) -> &str {
+        "builtin"
+    }
+
+    fn title(&self) -> &str {
+        &self.config.title
+    }
+
+    fn formats(&self) -> Vec<LabelFormat> {
+        vec![LabelFormat::Pdf, LabelFormat::Zpl]
+    }
+
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    async fn create_label(&self, request: LabelRequest) -> Result<LabelResult, LabelError> {
+        if request.ship_to.address_1.trim().is_empty() || request.ship_to.country.trim().is_empty() {
+            return Err(LabelError::InvalidAddress("Recipient address is incomplete".to_string()));
+        }
+
+        let document = match request.format {
+            LabelFormat::Pdf => self.render_pdf(&request),
+            LabelFormat::Zpl => self.render_zpl(&request),
+        };
+
+        Ok(LabelResult {
+            format: request.format,
+            document,
+            tracking_number: None,
+            carrier: None,
+            cost: None,
+        })
+    }
+}
+
+/// Truncate text to a maximum number of characters
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        text.to_string()
+    } else {
+        let mut truncated: String = text.chars().take(max.saturating_sub(3)).collect();
+        truncated.push_str("...");
+        truncated
+    }
+}
+
+/// Strip ZPL control characters from field data
+fn zpl_escape(text: &str) -> String {
+    text.replace(['^', '~'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::labels::provider::LabelItem;
+
+    fn request(format: LabelFormat) -> LabelRequest {
+        let ship_to = Address {
+            first_name: "Ada".to_string(),
+            last_name: "Lovelace".to_string(),
+            address_1: "12 St James's Square".to_string(),
+            city: "London".to_string(),
+            postcode: "SW1Y 4JH".to_string(),
+            country: "GB".to_string(),
+            ..Address::default()
+        };
+
+        LabelRequest {
+            order_number: "RC-1001".to_string(),
+            shipment_number: "RC-1001-1".to_string(),
+            store_name: "My Store".to_string(),
+            ship_from: Address::default(),
+            ship_to,
+            packages: vec![LabelPackage {
+                items: vec![LabelItem { name: "Widget".to_string(), sku: Some("W-1".to_string()), quantity: 2 }],
+                ..LabelPackage::default()
+            }],
+            format,
+            service_code: None,
+            note: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pdf_label() {
+        let provider = BuiltinLabelProvider::default();
+        let result = provider.create_label(request(LabelFormat::Pdf)).await.unwrap();
+
+        assert!(result.document.starts_with(b"%PDF-"));
+        assert!(result.tracking_number.is_none());
+        // Address label + packing slip
+        assert!(String::from_utf8_lossy(&result.document).contains("/Count 2"));
+    }
+
+    #[tokio::test]
+    async fn test_zpl_label() {
+        let provider = BuiltinLabelProvider::default();
+        let result = provider.create_label(request(LabelFormat::Zpl)).await.unwrap();
+        let zpl = String::from_utf8(result.document).unwrap();
+
+        assert!(zpl.starts_with("^XA"));
+        assert!(zpl.trim_end().ends_with("^XZ"));
+        assert!(zpl.contains("Ada Lovelace"));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_incomplete_address() {
+        let provider = BuiltinLabelProvider::default();
+        let mut req = request(LabelFormat::Pdf);
+        req.ship_to.address_1.clear();
+
+        assert!(provider.create_label(req).await.is_err());
+    }
+}
@@ -0,0 +1,9 @@
+//! RustCommerce Shipping Labels
+//!
+//! Label provider integrations for printing shipping labels and packing slips.
+
+pub mod provider;
+pub mod pdf;
+pub mod builtin;
+
+pub use provider::{LabelProvider, LabelProviderRegistry};
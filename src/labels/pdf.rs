@@ -0,0 +1,185 @@
+//! Minimal PDF Writer
+//!
+//! Just enough of PDF 1.4 to draw text, lines and boxes with the standard
+//! Helvetica fonts, so labels and packing slips can be rendered offline.
+
+/// Points per inch
+pub const POINTS_PER_INCH: f32 = 72.0;
+
+/// A4 page size in points
+pub const A4: (f32, f32) = (595.0, 842.0);
+
+/// 4x6 inch thermal label size in points
+pub const LABEL_4X6: (f32, f32) = (4.0 * POINTS_PER_INCH, 6.0 * POINTS_PER_INCH);
+
+/// PDF page
+#[derive(Debug, Clone)]
+pub struct PdfPage {
+    pub width: f32,
+    pub height: f32,
+    content: Vec<u8>,
+}
+
+impl PdfPage {
+    /// Create an empty page of the given size (points)
+    pub fn new((width, height): (f32, f32)) -> Self {
+        Self {
+            width,
+            height,
+            content: Vec::new(),
+        }
+    }
+
+    /// Draw a single line of text with its baseline at (x, y)
+    pub fn text(&mut self, x: f32, y: f32, size: f32, bold: bool, text: &str) {
+        let font = if bold { "F2" } else { "F1" };
+        self.content.extend_from_slice(
+            format!("BT /{} {:.1} Tf {:.2} {:.2} Td (", font, size, x, y).as_bytes(),
+        );
+        self.content.extend(encode_text(text));
+        self.content.extend_from_slice(b") Tj ET\n");
+    }
+
+    /// Draw a straight line
+    pub fn line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, line_width: f32) {
+        self.content.extend_from_slice(
+            format!("{:.2} w {:.2} {:.2} m {:.2} {:.2} l S\n", line_width, x1, y1, x2, y2).as_bytes(),
+        );
+    }
+
+    /// Draw a rectangle outline
+    pub fn rect(&mut self, x: f32, y: f32, width: f32, height: f32, line_width: f32) {
+        self.content.extend_from_slice(
+            format!("{:.2} w {:.2} {:.2} {:.2} {:.2} re S\n", line_width, x, y, width, height).as_bytes(),
+        );
+    }
+}
+
+/// PDF document
+#[derive(Debug, Clone, Default)]
+pub struct PdfDocument {
+    pages: Vec<PdfPage>,
+}
+
+impl PdfDocument {
+    /// Create an empty document
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a page
+    pub fn add_page(&mut self, page: PdfPage) {
+        self.pages.push(page);
+    }
+
+    /// Number of pages
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Serialize the document
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // Object numbers: 1 catalog, 2 page tree, 3-4 fonts, then page + content per page
+        let page_ids: Vec<usize> = (0..self.pages.len()).map(|i| 5 + i * 2).collect();
+
+        let mut objects: Vec<Vec<u8>> = vec![
+            b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                page_ids.iter().map(|id| format!("{} 0 R", id)).collect::<Vec<_>>().join(" "),
+                self.pages.len()
+            ).into_bytes(),
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_vec(),
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>".to_vec(),
+        ];
+
+        for (page, id) in self.pages.iter().zip(&page_ids) {
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] \
+                 /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                page.width, page.height, id + 1
+            ).into_bytes());
+
+            let mut stream = format!("<< /Length {} >>\nstream\n", page.content.len()).into_bytes();
+            stream.extend_from_slice(&page.content);
+            stream.extend_from_slice(b"\nendstream");
+            objects.push(stream);
+        }
+
+        let mut out = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+            out.extend_from_slice(object);
+            out.extend_from_slice(b"\nendobj\n");
+        }
+
+        let xref_offset = out.len();
+        out.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes());
+        for offset in offsets {
+            out.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+        out.extend_from_slice(format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref_offset
+        ).as_bytes());
+
+        out
+    }
+}
+
+/// Encode text as a WinAnsi PDF string body, escaping delimiters.
+/// Characters outside Latin-1 are replaced with `?`.
+fn encode_text(text: &str) -> Vec<u8> {
+    let mut out = Vec::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                out.push(b'\\');
+                out.push(c as u8);
+            }
+            '\n' | '\r' | '\t' => out.push(b' '),
+            c if (c as u32) < 0x20 => {}
+            c if (c as u32) < 0x80 || ((c as u32) >= 0xA0 && (c as u32) <= 0xFF) => out.push(c as u32 as u8),
+            _ => out.push(b'?'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_document_structure() {
+        let mut page = PdfPage::new(LABEL_4X6);
+        page.text(10.0, 400.0, 12.0, true, "Ship to (John)");
+        page.rect(5.0, 5.0, 278.0, 422.0, 1.0);
+
+        let mut doc = PdfDocument::new();
+        doc.add_page(page);
+        doc.add_page(PdfPage::new(A4));
+        let bytes = doc.to_bytes();
+        let text = String::from_utf8_lossy(&bytes);
+
+        assert!(bytes.starts_with(b"%PDF-1.4"));
+        assert!(text.trim_end().ends_with("%%EOF"));
+        assert!(text.contains("/Count 2"));
+        assert!(text.contains("(Ship to \\(John\\)) Tj"));
+
+        // startxref must point at the xref table
+        let startxref: usize = text.rsplit("startxref\n").next().unwrap()
+            .lines().next().unwrap().parse().unwrap();
+        assert!(bytes[startxref..].starts_with(b"xref"));
+    }
+
+    #[test]
+    fn test_encode_text() {
+        assert_eq!(encode_text("Café"), b"Caf\xE9".to_vec());
+        assert_eq!(encode_text("東京"), b"??".to_vec());
+        assert_eq!(encode_text("a\\b"), b"a\\\\b".to_vec());
+    }
+}
@@ -0,0 +1,198 @@
+//! Label Provider Base
+//!
+//! Defines the shipping label provider trait and registry.
+
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::models::customer::Address;
+
+/// Label provider error
+#[derive(Debug, Clone)]
+pub enum LabelError {
+    NotConfigured,
+    UnsupportedFormat(LabelFormat),
+    InvalidAddress(String),
+    InvalidPackage(String),
+    NetworkError(String),
+    ProviderError(String),
+}
+
+impl std::fmt::Display for LabelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotConfigured => write!(f, "Label provider not configured"),
+            Self::UnsupportedFormat(format) => write!(f, "Label format {:?} is not supported", format),
+            Self::InvalidAddress(msg) => write!(f, "Invalid address: {}", msg),
+            Self::InvalidPackage(msg) => write!(f, "Invalid package: {}", msg),
+            Self::NetworkError(msg) => write!(f, "Network error: {}", msg),
+            Self::ProviderError(msg) => write!(f, "Label provider error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for LabelError {}
+
+/// Label document format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LabelFormat {
+    #[default]
+    Pdf,
+    Zpl,
+}
+
+impl LabelFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Pdf => "application/pdf",
+            Self::Zpl => "application/x-zpl",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Pdf => "pdf",
+            Self::Zpl => "zpl",
+        }
+    }
+}
+
+/// Label request for one shipment
+#[derive(Debug, Clone)]
+pub struct LabelRequest {
+    pub order_number: String,
+    pub shipment_number: String,
+    pub store_name: String,
+    pub ship_from: Address,
+    pub ship_to: Address,
+    pub packages: Vec<LabelPackage>,
+    pub format: LabelFormat,
+    /// Carrier service code, e.g. "ground" (provider specific)
+    pub service_code: Option<String>,
+    pub note: Option<String>,
+}
+
+/// Physical package in a shipment
+#[derive(Debug, Clone, Default)]
+pub struct LabelPackage {
+    pub weight: Option<Decimal>,
+    pub length: Option<Decimal>,
+    pub width: Option<Decimal>,
+    pub height: Option<Decimal>,
+    pub items: Vec<LabelItem>,
+}
+
+/// Item packed in a package
+#[derive(Debug, Clone)]
+pub struct LabelItem {
+    pub name: String,
+    pub sku: Option<String>,
+    pub quantity: i32,
+}
+
+/// Generated label document
+#[derive(Debug, Clone)]
+pub struct LabelResult {
+    pub format: LabelFormat,
+    pub document: Vec<u8>,
+    /// Tracking number assigned by the carrier, if any
+    pub tracking_number: Option<String>,
+    /// Carrier slug the label was purchased for, if any
+    pub carrier: Option<String>,
+    pub cost: Option<Decimal>,
+}
+
+/// Shipping label provider trait
+#[async_trait]
+pub trait LabelProvider: Send + Sync {
+    /// Get provider ID
+    fn id(&self) -> &str;
+
+    /// Get provider title
+    fn title(&self) -> &str;
+
+    /// Supported label formats
+    fn formats(&self) -> Vec<LabelFormat>;
+
+    /// Check if a format is supported
+    fn supports_format(&self, format: LabelFormat) -> bool {
+        self.formats().contains(&format)
+    }
+
+    /// Check if provider is available
+    fn is_available(&self) -> bool;
+
+    /// Create a label for a shipment
+    async fn create_label(&self, request: LabelRequest) -> Result<LabelResult, LabelError>;
+
+    /// Void a previously created label
+    async fn void_label(&self, _tracking_number: &str) -> Result<(), LabelError> {
+        Ok(())
+    }
+}
+
+/// Label provider registry
+pub struct LabelProviderRegistry {
+    providers: HashMap<String, Arc<dyn LabelProvider>>,
+}
+
+impl LabelProviderRegistry {
+    /// Create a new provider registry
+    pub fn new() -> Self {
+        Self {
+            providers: HashMap::new(),
+        }
+    }
+
+    /// Register a label provider
+    pub fn register(&mut self, provider: Arc<dyn LabelProvider>) {
+        self.providers.insert(provider.id().to_string(), provider);
+    }
+
+    /// Get a provider by ID
+    pub fn get(&self, id: &str) -> Option<Arc<dyn LabelProvider>> {
+        self.providers.get(id).cloned()
+    }
+
+    /// Get available providers
+    pub fn get_available(&self) -> Vec<Arc<dyn LabelProvider>> {
+        self.providers.values()
+            .filter(|p| p.is_available())
+            .cloned()
+            .collect()
+    }
+
+    /// Create a label using the specified provider
+    pub async fn create_label(
+        &self,
+        provider_id: &str,
+        request: LabelRequest,
+    ) -> Result<LabelResult, LabelError> {
+        let provider = self.get(provider_id)
+            .ok_or(LabelError::NotConfigured)?;
+
+        if !provider.is_available() {
+            return Err(LabelError::NotConfigured);
+        }
+
+        if !provider.supports_format(request.format) {
+            return Err(LabelError::UnsupportedFormat(request.format));
+        }
+
+        if request.packages.is_empty() {
+            return Err(LabelError::InvalidPackage("Shipment has no packages".to_string()));
+        }
+
+        provider.create_label(request).await
+    }
+}
+
+impl Default for LabelProviderRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -9,6 +9,10 @@
 //! - Shipping methods and zones
 //! - Tax calculations
 //! - Coupons and discounts
+//! - Multi-currency pricing and checkout (`multi_currency` feature)
+//! - Subscriptions with scheduled renewal billing (`subscriptions` feature)
+//! - Bookings with rule-based availability and calendar feeds (`bookings` feature)
+//! - Memberships with member-only content and products (`memberships` feature)
 //! - Reports and analytics
 //!
 //! # Architecture
@@ -18,15 +22,29 @@
 //! - **Handlers**: HTTP request handlers for REST API
 //! - **Services**: Business logic layer
 //! - **Payments**: Payment gateway integrations
+//! - **Labels**: Shipping label providers
+//! - **Address**: Address verification providers
+//! - **VAT**: EU VAT number validation providers
+//! - **Tax**: Tax calculation providers
+//! - **Currency**: ISO 4217 currency data and exchange rate providers
 //! - **Admin**: Admin interface functionality
+//! - **Locale**: CLDR-style price and number formatting
 
 pub mod models;
 pub mod handlers;
 pub mod services;
 pub mod payments;
+pub mod labels;
+pub mod address;
+pub mod vat;
+pub mod tax;
+pub mod currency;
 pub mod admin;
+pub mod geo;
+pub mod locale;
 mod plugin;
 mod settings;
+mod csv;
 mod hooks;
 mod shortcodes;
 mod widgets;
@@ -61,6 +79,22 @@ pub use services::checkout::CheckoutService;
 pub use services::inventory::InventoryService;
 pub use services::shipping::ShippingService;
 pub use services::tax::TaxService;
+#[cfg(feature = "multi_currency")]
+pub use services::currency::CurrencyService;
+#[cfg(feature = "subscriptions")]
+pub use services::subscription::SubscriptionService;
+#[cfg(feature = "bookings")]
+pub use services::booking::BookingService;
+#[cfg(feature = "bookings")]
+pub use services::booking_calendar::BookingCalendarService;
+#[cfg(feature = "memberships")]
+pub use services::membership::MembershipService;
 pub use payments::gateway::{PaymentGateway, PaymentGatewayRegistry};
 pub use payments::stripe::StripeGateway;
 pub use payments::paypal::PayPalGateway;
+pub use labels::provider::{LabelProvider, LabelProviderRegistry};
+pub use address::provider::{AddressVerificationProvider, AddressVerificationRegistry};
+pub use vat::provider::{VatNumberProvider, VatNumberProviderRegistry};
+pub use tax::provider::{TaxProvider, TaxProviderRegistry};
+#[cfg(feature = "multi_currency")]
+pub use currency::provider::{ExchangeRateProvider, ExchangeRateProviderRegistry};
@@ -0,0 +1,38 @@
+//! CLDR number symbols and currency patterns
+//!
+//! Taken from the CLDR `latn` numbering system data for each locale. Spaces
+//! are the non-breaking spaces CLDR uses (U+00A0, and U+202F in French).
+
+use super::LocaleFormat;
+
+/// Supported locales, sorted by tag. Region-less entries are the language
+/// defaults used when a region has no entry of its own.
+pub static LOCALES: &[LocaleFormat] = &[
+    LocaleFormat { tag: "da", decimal: ",", group: ".", minus: "-", min_grouping: 1, currency_pattern: "#,##0.00\u{a0}¤" },
+    LocaleFormat { tag: "de", decimal: ",", group: ".", minus: "-", min_grouping: 1, currency_pattern: "#,##0.00\u{a0}¤" },
+    LocaleFormat { tag: "de-AT", decimal: ",", group: "\u{a0}", minus: "-", min_grouping: 1, currency_pattern: "¤\u{a0}#,##0.00" },
+    LocaleFormat { tag: "de-CH", decimal: ".", group: "’", minus: "-", min_grouping: 1, currency_pattern: "¤\u{a0}#,##0.00;¤-#,##0.00" },
+    LocaleFormat { tag: "en", decimal: ".", group: ",", minus: "-", min_grouping: 1, currency_pattern: "¤#,##0.00" },
+    LocaleFormat { tag: "en-IN", decimal: ".", group: ",", minus: "-", min_grouping: 1, currency_pattern: "¤#,##,##0.00" },
+    LocaleFormat { tag: "en-ZA", decimal: ",", group: "\u{a0}", minus: "-", min_grouping: 1, currency_pattern: "¤#,##0.00" },
+    LocaleFormat { tag: "es", decimal: ",", group: ".", minus: "-", min_grouping: 2, currency_pattern: "#,##0.00\u{a0}¤" },
+    LocaleFormat { tag: "es-MX", decimal: ".", group: ",", minus: "-", min_grouping: 1, currency_pattern: "¤#,##0.00" },
+    LocaleFormat { tag: "es-US", decimal: ".", group: ",", minus: "-", min_grouping: 1, currency_pattern: "¤#,##0.00" },
+    LocaleFormat { tag: "fi", decimal: ",", group: "\u{a0}", minus: "\u{2212}", min_grouping: 1, currency_pattern: "#,##0.00\u{a0}¤" },
+    LocaleFormat { tag: "fr", decimal: ",", group: "\u{202f}", minus: "-", min_grouping: 1, currency_pattern: "#,##0.00\u{a0}¤" },
+    LocaleFormat { tag: "fr-CA", decimal: ",", group: "\u{a0}", minus: "-", min_grouping: 1, currency_pattern: "#,##0.00\u{a0}¤" },
+    LocaleFormat { tag: "fr-CH", decimal: ",", group: "\u{202f}", minus: "-", min_grouping: 1, currency_pattern: "#,##0.00\u{a0}¤" },
+    LocaleFormat { tag: "hi", decimal: ".", group: ",", minus: "-", min_grouping: 1, currency_pattern: "¤#,##,##0.00" },
+    LocaleFormat { tag: "it", decimal: ",", group: ".", minus: "-", min_grouping: 1, currency_pattern: "#,##0.00\u{a0}¤" },
+    LocaleFormat { tag: "it-CH", decimal: ".", group: "’", minus: "-", min_grouping: 1, currency_pattern: "¤\u{a0}#,##0.00;¤-#,##0.00" },
+    LocaleFormat { tag: "ja", decimal: ".", group: ",", minus: "-", min_grouping: 1, currency_pattern: "¤#,##0.00" },
+    LocaleFormat { tag: "ko", decimal: ".", group: ",", minus: "-", min_grouping: 1, currency_pattern: "¤#,##0.00" },
+    LocaleFormat { tag: "nb", decimal: ",", group: "\u{a0}", minus: "\u{2212}", min_grouping: 1, currency_pattern: "#,##0.00\u{a0}¤" },
+    LocaleFormat { tag: "nl", decimal: ",", group: ".", minus: "-", min_grouping: 1, currency_pattern: "¤\u{a0}#,##0.00;¤\u{a0}-#,##0.00" },
+    LocaleFormat { tag: "pl", decimal: ",", group: "\u{a0}", minus: "-", min_grouping: 2, currency_pattern: "#,##0.00\u{a0}¤" },
+    LocaleFormat { tag: "pt", decimal: ",", group: ".", minus: "-", min_grouping: 1, currency_pattern: "¤\u{a0}#,##0.00" },
+    LocaleFormat { tag: "pt-PT", decimal: ",", group: "\u{a0}", minus: "-", min_grouping: 2, currency_pattern: "#,##0.00\u{a0}¤" },
+    LocaleFormat { tag: "sv", decimal: ",", group: "\u{a0}", minus: "\u{2212}", min_grouping: 1, currency_pattern: "#,##0.00\u{a0}¤" },
+    LocaleFormat { tag: "tr", decimal: ",", group: ".", minus: "-", min_grouping: 1, currency_pattern: "¤#,##0.00" },
+    LocaleFormat { tag: "zh", decimal: ".", group: ",", minus: "-", min_grouping: 1, currency_pattern: "¤#,##0.00" },
+];
@@ -0,0 +1,238 @@
+//! Locale Formatting
+//!
+//! CLDR-style number and price formatting per locale: decimal and grouping
+//! symbols, grouping sizes (including Indian lakh/crore grouping), symbol
+//! placement and spacing, and negative formats. Used wherever prices are
+//! shown to shoppers: API responses, emails, invoices and shortcodes.
+
+mod locales;
+
+use rust_decimal::{Decimal, RoundingStrategy};
+
+pub use locales::LOCALES;
+
+/// Locale used when nothing better matches
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Number formatting data for one locale
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocaleFormat {
+    /// BCP 47 tag, e.g. `de-CH`
+    pub tag: &'static str,
+    pub decimal: &'static str,
+    pub group: &'static str,
+    pub minus: &'static str,
+    /// Digits the integer part needs beyond the first group before grouping
+    /// applies (2 renders 1234 ungrouped, as in Spanish and Polish)
+    pub min_grouping: usize,
+    /// CLDR currency pattern, e.g. `#,##0.00 ¤` or `¤#,##0.00;¤-#,##0.00`
+    pub currency_pattern: &'static str,
+}
+
+impl LocaleFormat {
+    /// Format a number with the locale's separators
+    pub fn format_number(&self, value: Decimal, decimals: u32) -> String {
+        let digits = self.digits(value, decimals);
+        if is_negative(value, decimals) {
+            format!("{}{}", self.minus, digits)
+        } else {
+            digits
+        }
+    }
+
+    /// Format a price with the locale's currency pattern
+    pub fn format_currency(&self, value: Decimal, symbol: &str, decimals: u32) -> String {
+        let mut patterns = self.currency_pattern.split(';');
+        let positive = patterns.next().unwrap_or_default();
+        let digits = self.digits(value, decimals);
+
+        if !is_negative(value, decimals) {
+            return self.apply(positive, &digits, symbol);
+        }
+        match patterns.next() {
+            Some(negative) => self.apply(negative, &digits, symbol),
+            None => format!("{}{}", self.minus, self.apply(positive, &digits, symbol)),
+        }
+    }
+
+    /// Absolute value, rounded and grouped
+    fn digits(&self, value: Decimal, decimals: u32) -> String {
+        let rounded = format!("{:.prec$}", round(value.abs(), decimals), prec = decimals as usize);
+        let (integer, fraction) = match rounded.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (rounded.as_str(), None),
+        };
+
+        let grouped = self.group_digits(integer);
+        match fraction {
+            Some(fraction) if decimals > 0 => format!("{}{}{}", grouped, self.decimal, fraction),
+            _ => grouped,
+        }
+    }
+
+    fn group_digits(&self, integer: &str) -> String {
+        let (primary, secondary) = grouping_sizes(self.currency_pattern);
+        if primary == 0 || integer.len() < primary + self.min_grouping {
+            return integer.to_string();
+        }
+
+        let (head, last) = integer.split_at(integer.len() - primary);
+        let mut groups = vec![last];
+        let mut rest = head;
+        while rest.len() > secondary {
+            let (h, group) = rest.split_at(rest.len() - secondary);
+            groups.push(group);
+            rest = h;
+        }
+        if !rest.is_empty() {
+            groups.push(rest);
+        }
+        groups.reverse();
+        groups.join(self.group)
+    }
+
+    /// Substitute digits and symbol into one side of a currency pattern
+    fn apply(&self, pattern: &str, digits: &str, symbol: &str) -> String {
+        let is_number = |c: char| matches!(c, '#' | '0' | ',' | '.');
+        let start = pattern.find(is_number).unwrap_or(pattern.len());
+        let end = pattern.rfind(is_number).map_or(start, |i| i + 1);
+        let (prefix, suffix) = (&pattern[..start], &pattern[end..]);
+
+        // CLDR currency spacing: keep letter symbols ("CHF") off the digits
+        let spaced_prefix = prefix.ends_with('¤') && symbol.chars().last().is_some_and(char::is_alphabetic);
+        let spaced_suffix = suffix.starts_with('¤') && symbol.chars().next().is_some_and(char::is_alphabetic);
+
+        let affix = |s: &str| s.replace('¤', symbol).replace('-', self.minus);
+        format!(
+            "{}{}{}{}{}",
+            affix(prefix),
+            if spaced_prefix { "\u{a0}" } else { "" },
+            digits,
+            if spaced_suffix { "\u{a0}" } else { "" },
+            affix(suffix),
+        )
+    }
+}
+
+/// Shown as negative only if it doesn't round to zero
+fn is_negative(value: Decimal, decimals: u32) -> bool {
+    round(value, decimals) < Decimal::ZERO
+}
+
+fn round(value: Decimal, decimals: u32) -> Decimal {
+    value.round_dp_with_strategy(decimals, RoundingStrategy::MidpointAwayFromZero)
+}
+
+/// Primary and secondary grouping sizes from a pattern: `#,##0` is (3, 3),
+/// `#,##,##0` is (3, 2)
+fn grouping_sizes(pattern: &str) -> (usize, usize) {
+    let number: String = pattern.split(';').next().unwrap_or_default()
+        .chars()
+        .skip_while(|c| !matches!(c, '#' | '0'))
+        .take_while(|c| matches!(c, '#' | '0' | ','))
+        .collect();
+    let groups: Vec<&str> = number.split(',').collect();
+
+    match groups.as_slice() {
+        [_] => (0, 0),
+        [.., secondary, primary] if groups.len() > 2 => (primary.len(), secondary.len()),
+        [.., primary] => (primary.len(), primary.len()),
+        [] => (0, 0),
+    }
+}
+
+/// Normalize a locale tag to `language` or `language-REGION`, dropping
+/// script and variant subtags (`zh_Hant_TW` becomes `zh-TW`)
+pub fn normalize_locale(tag: &str) -> String {
+    let mut subtags = tag.trim().split(['-', '_']).filter(|s| !s.is_empty());
+    let language = subtags.next().unwrap_or_default().to_ascii_lowercase();
+    let region = subtags.find(|s| s.len() == 2 && s.chars().all(|c| c.is_ascii_alphabetic()));
+
+    match region {
+        Some(region) => format!("{}-{}", language, region.to_ascii_uppercase()),
+        None => language,
+    }
+}
+
+/// Find the formatting for a locale, falling back from `language-REGION`
+/// to `language`
+pub fn find_locale(tag: &str) -> Option<&'static LocaleFormat> {
+    let tag = normalize_locale(tag);
+    let language = tag.split('-').next().unwrap_or_default();
+
+    LOCALES.iter()
+        .find(|l| l.tag == tag)
+        .or_else(|| LOCALES.iter().find(|l| l.tag == language))
+}
+
+/// Formatting for a locale, or the default locale
+pub fn locale_format(tag: &str) -> &'static LocaleFormat {
+    find_locale(tag)
+        .or_else(|| find_locale(DEFAULT_LOCALE))
+        .expect("default locale is defined")
+}
+
+/// Pick the best supported locale from an `Accept-Language` header
+pub fn negotiate_locale(accept_language: &str) -> &'static LocaleFormat {
+    let mut ranges: Vec<(&str, f32)> = accept_language.split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.split(';');
+            let tag = parts.next()?.trim();
+            let quality = parts
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .and_then(|q| q.parse().ok())
+                .unwrap_or(1.0);
+            (!tag.is_empty() && tag != "*" && quality > 0.0).then_some((tag, quality))
+        })
+        .collect();
+    ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    ranges.into_iter()
+        .find_map(|(tag, _)| find_locale(tag))
+        .unwrap_or_else(|| locale_format(DEFAULT_LOCALE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_locales_are_sorted() {
+        assert!(LOCALES.windows(2).all(|w| w[0].tag < w[1].tag));
+    }
+
+    #[test]
+    fn test_format_currency() {
+        assert_eq!(locale_format("en-US").format_currency(dec!(1234567.891), "$", 2), "$1,234,567.89");
+        assert_eq!(locale_format("en-IN").format_currency(dec!(1234567.5), "₹", 2), "₹12,34,567.50");
+        assert_eq!(locale_format("de-DE").format_currency(dec!(1234.5), "€", 2), "1.234,50\u{a0}€");
+        assert_eq!(locale_format("fr").format_currency(dec!(1234.5), "€", 2), "1\u{202f}234,50\u{a0}€");
+        assert_eq!(locale_format("ja").format_currency(dec!(1234), "¥", 0), "¥1,234");
+        assert_eq!(locale_format("es").format_currency(dec!(1234), "€", 2), "1234,00\u{a0}€");
+        assert_eq!(locale_format("es").format_currency(dec!(12345), "€", 2), "12.345,00\u{a0}€");
+
+        // Letter symbols are spaced off the digits
+        assert_eq!(locale_format("en").format_currency(dec!(5), "CHF", 2), "CHF\u{a0}5.00");
+    }
+
+    #[test]
+    fn test_negative_formats() {
+        assert_eq!(locale_format("en").format_currency(dec!(-5), "$", 2), "-$5.00");
+        assert_eq!(locale_format("de").format_currency(dec!(-5), "€", 2), "-5,00\u{a0}€");
+        assert_eq!(locale_format("nl").format_currency(dec!(-5), "€", 2), "€\u{a0}-5,00");
+        assert_eq!(locale_format("de-CH").format_currency(dec!(-5), "CHF", 2), "CHF-5.00");
+        assert_eq!(locale_format("sv").format_number(dec!(-1234.5), 2), "\u{2212}1\u{a0}234,50");
+        assert_eq!(locale_format("en").format_currency(dec!(-0.001), "$", 2), "$0.00");
+    }
+
+    #[test]
+    fn test_locale_lookup() {
+        assert_eq!(normalize_locale("zh_Hant_TW"), "zh-TW");
+        assert_eq!(locale_format("de_AT").tag, "de-AT");
+        assert_eq!(locale_format("de-LU").tag, "de");
+        assert_eq!(locale_format("xx").tag, DEFAULT_LOCALE);
+        assert_eq!(negotiate_locale("xx, fr-CA;q=0.5, pt-BR;q=0.8").tag, "pt");
+        assert_eq!(negotiate_locale("").tag, DEFAULT_LOCALE);
+    }
+}
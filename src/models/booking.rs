@@ -5,7 +5,8 @@
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use chrono::{DateTime, Utc, NaiveDate, NaiveTime, Weekday};
+use chrono::{DateTime, Datelike, Utc, NaiveDate, NaiveTime, Weekday};
+use chrono_tz::Tz;
 use std::collections::HashMap;
 
 /// Bookable product
@@ -18,8 +19,20 @@ pub struct BookableProduct {
     pub duration_unit: DurationUnit,
     pub min_duration: Option<i32>,
     pub max_duration: Option<i32>,
-    pub buffer_before: Option<i32>,
+    pub buffer_before: Option<i32>, // In duration units
     pub buffer_after: Option<i32>,
+    /// Bookings each block takes when the product has no resources
+    #[serde(default = "default_block_capacity")]
+    pub block_capacity: i32,
+    /// IANA timezone the availability and pricing rules are written in
+    #[serde(default)]
+    pub timezone: Tz,
+    /// Cost charged once per booking
+    #[serde(default)]
+    pub base_cost: Decimal,
+    /// Cost charged for each block booked
+    #[serde(default)]
+    pub block_cost: Decimal,
     pub calendar_display: CalendarDisplay,
     pub requires_confirmation: bool,
     pub can_be_cancelled: bool,
@@ -37,6 +50,22 @@ pub struct BookableProduct {
     pub updated_at: Option<DateTime<Utc>>,
 }
 
+fn default_block_capacity() -> i32 {
+    1
+}
+
+impl BookableProduct {
+    /// Length of one booking block in minutes
+    pub fn block_minutes(&self) -> i64 {
+        self.duration_unit.minutes() * self.duration.max(1) as i64
+    }
+
+    /// Length of a buffer in minutes
+    pub fn buffer_minutes(&self, buffer: Option<i32>) -> i64 {
+        self.duration_unit.minutes() * buffer.unwrap_or(0).max(0) as i64
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum BookingType {
@@ -54,6 +83,23 @@ pub enum DurationUnit {
     Month,
 }
 
+impl DurationUnit {
+    /// Minutes in one unit (months count as 30 days)
+    pub fn minutes(&self) -> i64 {
+        match self {
+            Self::Minute => 1,
+            Self::Hour => 60,
+            Self::Day => 1440,
+            Self::Month => 30 * 1440,
+        }
+    }
+
+    /// Whether blocks are whole days rather than times of day
+    pub fn is_daily(&self) -> bool {
+        matches!(self, Self::Day | Self::Month)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum CalendarDisplay {
@@ -157,6 +203,33 @@ pub struct AvailabilityRule {
     pub specific_dates: Option<Vec<NaiveDate>>,
 }
 
+impl AvailabilityRule {
+    /// Whether the rule's date conditions cover a date
+    pub fn applies_on(&self, date: NaiveDate) -> bool {
+        self.from_date.is_none_or(|from| date >= from)
+            && self.to_date.is_none_or(|to| date <= to)
+            && self.days_of_week.as_ref().is_none_or(|days| days.contains(&date.weekday()))
+            && self.specific_dates.as_ref().is_none_or(|dates| dates.contains(&date))
+    }
+
+    /// Whether the rule only covers part of the day
+    pub fn has_time_range(&self) -> bool {
+        self.from_time.is_some() || self.to_time.is_some()
+    }
+
+    /// Whether the rule covers a local date and time. Time ranges ending at
+    /// or before their start run past midnight, so their early hours fall
+    /// on the day after a date the rule applies on.
+    pub fn covers(&self, date: NaiveDate, time: NaiveTime) -> bool {
+        covers_time_range(self.from_time, self.to_time, date, time, |date| self.applies_on(date))
+    }
+
+    /// Whether dates matching the rule can be booked; holidays never can
+    pub fn is_bookable(&self) -> bool {
+        self.bookable && self.rule_type != AvailabilityRuleType::Holiday
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum AvailabilityRuleType {
@@ -189,6 +262,55 @@ pub struct PricingRule {
     pub max_duration: Option<i32>,
 }
 
+/// Whether a local date and time fall in a time range on a date that
+/// `applies_on` accepts. Ranges ending at or before their start run past
+/// midnight into the next day.
+fn covers_time_range(
+    from: Option<NaiveTime>,
+    to: Option<NaiveTime>,
+    date: NaiveDate,
+    time: NaiveTime,
+    applies_on: impl Fn(NaiveDate) -> bool,
+) -> bool {
+    let from = from.unwrap_or(NaiveTime::MIN);
+    match to {
+        Some(to) if to > from => applies_on(date) && time >= from && time < to,
+        Some(to) if time < to => date.pred_opt().is_some_and(applies_on),
+        _ => applies_on(date) && time >= from,
+    }
+}
+
+impl PricingRule {
+    /// Whether the rule's date conditions cover a date
+    fn applies_on(&self, date: NaiveDate) -> bool {
+        self.from_date.is_none_or(|from| date >= from)
+            && self.to_date.is_none_or(|to| date <= to)
+            && self.days_of_week.as_ref().is_none_or(|days| days.contains(&date.weekday()))
+    }
+
+    /// Whether the rule applies to a block starting at a local date and
+    /// time, for a booking of `persons` lasting `blocks` blocks
+    pub fn matches(&self, date: NaiveDate, time: NaiveTime, persons: i32, blocks: i32) -> bool {
+        covers_time_range(self.from_time, self.to_time, date, time, |date| self.applies_on(date))
+            && self.min_persons.is_none_or(|min| persons >= min)
+            && self.max_persons.is_none_or(|max| persons <= max)
+            && self.min_duration.is_none_or(|min| blocks >= min)
+            && self.max_duration.is_none_or(|max| blocks <= max)
+    }
+
+    /// Apply the rule's modifier to a cost
+    pub fn apply(&self, cost: Decimal) -> Decimal {
+        match self.modifier {
+            PriceModifier::Add => cost + self.amount,
+            PriceModifier::Subtract => cost - self.amount,
+            PriceModifier::Multiply => cost * self.amount,
+            PriceModifier::Divide if self.amount.is_zero() => cost,
+            PriceModifier::Divide => cost / self.amount,
+            PriceModifier::Replace => self.amount,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum PricingRuleType {
@@ -230,6 +352,135 @@ pub struct AvailabilityCheckRequest {
     pub resource_id: Option<Uuid>,
 }
 
+/// Booking a shopper wants to price or add to the cart
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookingRequest {
+    pub product_id: Uuid,
+    pub start_date: DateTime<Utc>,
+    /// Number of blocks, for customer-defined durations
+    pub duration: Option<i32>,
+    pub persons: Option<i32>,
+    pub resource_id: Option<Uuid>,
+}
+
+/// Booking cost with the breakdown shown to the shopper
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookingCost {
+    pub blocks: i32,
+    pub base_cost: Decimal,
+    /// Block costs before pricing rules
+    pub block_cost: Decimal,
+    pub resource_cost: Decimal,
+    pub adjustments: Vec<BookingCostAdjustment>,
+    pub total: Decimal,
+}
+
+/// Change a pricing rule made to a booking's cost
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookingCostAdjustment {
+    pub rule_id: Uuid,
+    pub rule_type: PricingRuleType,
+    pub modifier: PriceModifier,
+    /// Blocks the rule applied to
+    pub blocks: i32,
+    pub amount: Decimal,
+}
+
+/// Booking details stored on a cart item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CartBooking {
+    /// Booking holding the slot while the item is in the cart
+    #[serde(default)]
+    pub booking_id: Option<Uuid>,
+    pub product_id: Uuid,
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    pub persons: i32,
+    pub resource_id: Option<Uuid>,
+    pub cost: BookingCost,
+}
+
+impl CartBooking {
+    /// Cart item meta key the booking is stored under
+    pub const META_KEY: &'static str = "_booking";
+}
+
+/// Secret-token iCalendar feed of a product's or resource's bookings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookingCalendarFeed {
+    pub id: Uuid,
+    pub site_id: Option<Uuid>,
+    /// Set for a product feed
+    pub product_id: Option<Uuid>,
+    /// Set for a resource feed
+    pub resource_id: Option<Uuid>,
+    pub token: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl BookingCalendarFeed {
+    /// Generate a feed token
+    pub fn generate_token() -> String {
+        use rand::Rng;
+        const CHARSET: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+        let mut rng = rand::thread_rng();
+        (0..32)
+            .map(|_| CHARSET[rng.gen_range(0..CHARSET.len())] as char)
+            .collect()
+    }
+
+    /// Whether a booking belongs in this feed
+    pub fn covers(&self, booking: &Booking) -> bool {
+        match (self.product_id, self.resource_id) {
+            (_, Some(resource_id)) => booking.resource_id == Some(resource_id),
+            (Some(product_id), None) => booking.product_id == product_id,
+            (None, None) => false,
+        }
+    }
+}
+
+/// External iCalendar feed imported as bookings that block availability
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalCalendar {
+    pub id: Uuid,
+    pub site_id: Option<Uuid>,
+    pub name: String,
+    pub url: String,
+    /// Product the imported bookings are recorded against
+    pub product_id: Uuid,
+    /// Resource the events block; all products using it are affected
+    pub resource_id: Option<Uuid>,
+    pub last_synced_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Store-wide booking settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BookingSettings {
+    /// Store URL used to build pay links in booking emails
+    pub store_url: String,
+    /// Minutes between checks for expired cart holds and due reminders
+    pub job_minutes: i64,
+    /// Hours before the start that the reminder email is sent
+    pub reminder_hours: i64,
+    /// Minutes between imports of external calendars
+    pub calendar_import_minutes: i64,
+}
+
+impl Default for BookingSettings {
+    fn default() -> Self {
+        Self {
+            store_url: String::new(),
+            job_minutes: 5,
+            reminder_hours: 24,
+            calendar_import_minutes: 30,
+        }
+    }
+}
+
 /// Booking availability check response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AvailabilityCheckResponse {
@@ -239,6 +490,24 @@ pub struct AvailabilityCheckResponse {
 }
 
 impl Booking {
+    /// Meta key holding the external calendar an imported booking came from
+    pub const EXTERNAL_CALENDAR_META: &'static str = "external_calendar_id";
+    /// Meta key holding the UID of the imported iCalendar event
+    pub const EXTERNAL_UID_META: &'static str = "external_uid";
+    /// Meta key holding the RECURRENCE-ID of an imported occurrence of a
+    /// recurring event
+    pub const EXTERNAL_RECURRENCE_META: &'static str = "external_recurrence_id";
+
+    /// Whether the booking was imported from an external calendar
+    pub fn is_external(&self) -> bool {
+        self.meta.contains_key(Self::EXTERNAL_UID_META)
+    }
+
+    /// Whether the booking holds its slot
+    pub fn blocks_availability(&self) -> bool {
+        !matches!(self.status, BookingStatus::Cancelled | BookingStatus::WasPInCart)
+    }
+
     /// Get duration in minutes
     pub fn duration_minutes(&self) -> i64 {
         (self.end_date - self.start_date).num_minutes()
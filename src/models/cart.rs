@@ -7,6 +7,7 @@ use uuid::Uuid;
 use std::collections::HashMap;
 
 use super::customer::Address;
+use super::membership::CartMember;
 use super::product::{Product, ProductVariation};
 
 /// Shopping cart
@@ -30,6 +31,13 @@ pub struct Cart {
     // Selected shipping method
     pub chosen_shipping_method: Option<String>,
 
+    // Currency (None for the base currency)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    /// Base-to-cart currency rate the prices were converted at
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency_rate: Option<Decimal>,
+
     // Totals (calculated)
     pub totals: CartTotals,
 
@@ -39,6 +47,10 @@ pub struct Cart {
     // Metadata
     pub meta: HashMap<String, serde_json::Value>,
 
+    // Shopper's memberships, for member prices and member-only products
+    #[serde(skip)]
+    pub member: Option<CartMember>,
+
     // Timestamps
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -59,9 +71,12 @@ impl Cart {
             billing_address: None,
             shipping_address: None,
             chosen_shipping_method: None,
+            currency: None,
+            currency_rate: None,
             totals: CartTotals::default(),
             fees: Vec::new(),
             meta: HashMap::new(),
+            member: None,
             created_at: now,
             updated_at: now,
             expires_at: now + chrono::Duration::days(30),
@@ -152,6 +167,10 @@ pub struct CartItem {
     /// Variation attributes (for display)
     pub variation_attributes: HashMap<String, String>,
 
+    /// Product categories, for category discounts
+    #[serde(default)]
+    pub category_ids: Vec<Uuid>,
+
     /// Pricing
     pub price: Decimal,           // Unit price
     pub regular_price: Decimal,   // Regular unit price
@@ -160,6 +179,13 @@ pub struct CartItem {
     pub total: Decimal,           // After discounts
     pub total_tax: Decimal,
 
+    /// Unit prices in the base currency, repriced from when the cart
+    /// switches currency
+    #[serde(default)]
+    pub base_price: Option<Decimal>,
+    #[serde(default)]
+    pub base_regular_price: Option<Decimal>,
+
     /// Flags
     pub is_virtual: bool,
     pub is_downloadable: bool,
@@ -177,6 +203,8 @@ pub struct CartItem {
 
     /// Tax
     pub tax_class: String,
+    #[serde(default)]
+    pub tax_code: Option<String>,
     pub taxes: HashMap<Uuid, Decimal>, // tax_rate_id -> amount
 
     /// Metadata (for addons, custom fields, etc.)
@@ -251,12 +279,15 @@ impl CartItem {
             product_sku: variation.and_then(|v| v.sku.clone()).or(product.sku.clone()),
             product_image: None, // Would load from product gallery
             variation_attributes,
+            category_ids: product.categories.iter().flatten().map(|c| c.id).collect(),
             price,
             regular_price,
             subtotal,
             subtotal_tax: Decimal::ZERO,
             total: subtotal,
             total_tax: Decimal::ZERO,
+            base_price: Some(price),
+            base_regular_price: Some(regular_price),
             is_virtual: variation.and_then(|v| v.is_virtual).unwrap_or(product.is_virtual),
             is_downloadable: variation.and_then(|v| v.is_downloadable).unwrap_or(product.is_downloadable),
             sold_individually: product.sold_individually,
@@ -267,6 +298,7 @@ impl CartItem {
             width: variation.and_then(|v| v.width).or(product.width),
             height: variation.and_then(|v| v.height).or(product.height),
             tax_class: product.tax_class.clone(),
+            tax_code: product.tax_code.clone(),
             taxes: HashMap::new(),
             meta,
             added_at: Utc::now(),
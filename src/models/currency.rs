@@ -162,6 +162,9 @@ pub struct CurrencySettings {
     pub exchange_rate_api: Option<String>,
     pub api_key: Option<String>,
     pub rate_update_frequency_hours: i32,
+    /// Flag rate moves larger than this percentage on refresh
+    #[serde(default)]
+    pub rate_alert_threshold: Option<Decimal>,
     pub round_converted_prices: bool,
     pub rounding_increment: Option<Decimal>,
 }
@@ -199,6 +202,7 @@ impl Default for CurrencySettings {
             exchange_rate_api: None,
             api_key: None,
             rate_update_frequency_hours: 24,
+            rate_alert_threshold: Some(Decimal::from(5)),
             round_converted_prices: true,
             rounding_increment: Some(Decimal::new(1, 2)), // 0.01
         }
@@ -206,6 +210,32 @@ impl Default for CurrencySettings {
 }
 
 impl Currency {
+    /// Create a currency from its ISO 4217 entry (name, symbol, decimals)
+    pub fn from_iso(code: &str) -> Option<Self> {
+        let iso = crate::currency::iso_currency(code)?;
+        Some(Self {
+            id: Uuid::now_v7(),
+            site_id: None,
+            code: iso.code.to_string(),
+            name: iso.name.to_string(),
+            symbol: iso.symbol.to_string(),
+            symbol_position: SymbolPosition::Left,
+            decimal_separator: ".".to_string(),
+            thousand_separator: ",".to_string(),
+            decimals: iso.minor_units as i32,
+            is_default: false,
+            is_active: true,
+            exchange_rate: Decimal::ONE,
+            auto_update_rate: true,
+            rate_markup: Decimal::ZERO,
+            rounding: RoundingMethod::Nearest,
+            min_amount: None,
+            max_amount: None,
+            created_at: Utc::now(),
+            updated_at: None,
+        })
+    }
+
     /// Format amount in this currency
     pub fn format_amount(&self, amount: Decimal) -> String {
         let rounded = self.round_amount(amount);
@@ -234,6 +264,13 @@ impl Currency {
         }
     }
 
+    /// Format amount in this currency using a locale's separators, grouping
+    /// and symbol placement instead of the currency's own settings
+    pub fn format_amount_for_locale(&self, amount: Decimal, locale: &str) -> String {
+        crate::locale::locale_format(locale)
+            .format_currency(self.round_amount(amount), &self.symbol, self.decimals.max(0) as u32)
+    }
+
     fn add_thousand_separators(&self, s: &str) -> String {
         let chars: Vec<char> = s.chars().rev().collect();
         let mut result = String::new();
@@ -0,0 +1,266 @@
+//! Fulfillment Models
+//!
+//! Shipments covering all or part of an order, with carrier tracking.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::labels::provider::LabelFormat;
+
+/// Order fulfillment status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FulfillmentStatus {
+    #[default]
+    Unfulfilled,
+    PartiallyFulfilled,
+    Fulfilled,
+}
+
+impl FulfillmentStatus {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Unfulfilled => "Unfulfilled",
+            Self::PartiallyFulfilled => "Partially fulfilled",
+            Self::Fulfilled => "Fulfilled",
+        }
+    }
+}
+
+/// Shipment status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ShipmentStatus {
+    #[default]
+    Pending,
+    Shipped,
+    InTransit,
+    OutForDelivery,
+    Delivered,
+    Exception,
+    Returned,
+    Cancelled,
+}
+
+impl ShipmentStatus {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Pending => "Pending",
+            Self::Shipped => "Shipped",
+            Self::InTransit => "In transit",
+            Self::OutForDelivery => "Out for delivery",
+            Self::Delivered => "Delivered",
+            Self::Exception => "Delivery exception",
+            Self::Returned => "Returned",
+            Self::Cancelled => "Cancelled",
+        }
+    }
+
+    /// Whether items in a shipment with this status count as fulfilled
+    pub fn counts_as_fulfilled(&self) -> bool {
+        !matches!(self, Self::Cancelled | Self::Returned)
+    }
+
+    /// Whether the shipment has left the warehouse
+    pub fn is_shipped(&self) -> bool {
+        matches!(
+            self,
+            Self::Shipped | Self::InTransit | Self::OutForDelivery | Self::Delivered | Self::Exception
+        )
+    }
+}
+
+/// Shipment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Shipment {
+    pub id: Uuid,
+    pub order_id: Uuid,
+    pub shipment_number: String,
+    pub status: ShipmentStatus,
+
+    // Tracking
+    pub carrier: String,
+    pub carrier_name: String,
+    pub tracking_number: Option<String>,
+    pub tracking_url: Option<String>,
+
+    // Contents
+    pub items: Vec<ShipmentItem>,
+
+    // Label
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<ShipmentLabel>,
+
+    // Notes
+    pub note: Option<String>,
+    pub notify_customer: bool,
+
+    // Dates
+    pub shipped_at: Option<DateTime<Utc>>,
+    pub delivered_at: Option<DateTime<Utc>>,
+
+    pub meta: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl Shipment {
+    /// Total quantity of items in the shipment
+    pub fn total_quantity(&self) -> i32 {
+        self.items.iter().map(|i| i.quantity).sum()
+    }
+}
+
+/// Shipment item (a quantity of an order line item)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShipmentItem {
+    pub id: Uuid,
+    pub shipment_id: Uuid,
+    pub order_item_id: Uuid,
+    pub quantity: i32,
+}
+
+/// Shipping label document stored with a shipment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShipmentLabel {
+    pub id: Uuid,
+    pub shipment_id: Uuid,
+    pub provider: String,
+    pub format: LabelFormat,
+    pub file_name: String,
+    pub content_type: String,
+    /// Raw document bytes (served through the label download endpoint)
+    #[serde(skip)]
+    pub document: Vec<u8>,
+    pub tracking_number: Option<String>,
+    pub cost: Option<Decimal>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Shipping carrier with a tracking URL template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShippingCarrier {
+    pub id: Uuid,
+    pub site_id: Option<Uuid>,
+    pub slug: String,
+    pub name: String,
+    /// Tracking URL with a `{tracking_number}` placeholder
+    pub tracking_url_template: String,
+    pub is_enabled: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ShippingCarrier {
+    /// Tracking URL placeholder
+    pub const TRACKING_NUMBER_PLACEHOLDER: &'static str = "{tracking_number}";
+
+    /// Build the tracking URL for a tracking number
+    pub fn tracking_url(&self, tracking_number: &str) -> Option<String> {
+        if self.tracking_url_template.is_empty() {
+            return None;
+        }
+
+        Some(self.tracking_url_template.replace(
+            Self::TRACKING_NUMBER_PLACEHOLDER,
+            &urlencoding::encode(tracking_number.trim()),
+        ))
+    }
+}
+
+/// Customer-facing tracking information for a shipment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShipmentTracking {
+    pub shipment_number: String,
+    pub status: ShipmentStatus,
+    pub status_label: String,
+    pub carrier_name: String,
+    pub tracking_number: Option<String>,
+    pub tracking_url: Option<String>,
+    pub items: Vec<ShipmentTrackingItem>,
+    pub shipped_at: Option<DateTime<Utc>>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShipmentTrackingItem {
+    pub name: String,
+    pub quantity: i32,
+}
+
+/// Customer-facing tracking information for an order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderTracking {
+    pub order_number: String,
+    pub fulfillment_status: FulfillmentStatus,
+    pub shipments: Vec<ShipmentTracking>,
+}
+
+// =============================================================================
+// DTOs for API
+// =============================================================================
+
+/// Request to create a shipment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateShipmentRequest {
+    pub items: Vec<CreateShipmentItemRequest>,
+    pub carrier: String,
+    pub tracking_number: Option<String>,
+    /// Overrides the URL built from the carrier template
+    pub tracking_url: Option<String>,
+    pub note: Option<String>,
+    pub mark_shipped: Option<bool>,
+    pub notify_customer: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateShipmentItemRequest {
+    pub order_item_id: Uuid,
+    pub quantity: i32,
+}
+
+/// Request to update a shipment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateShipmentRequest {
+    pub status: Option<ShipmentStatus>,
+    pub carrier: Option<String>,
+    pub tracking_number: Option<String>,
+    pub tracking_url: Option<String>,
+    pub note: Option<String>,
+}
+
+/// Request to generate a shipping label
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateLabelRequest {
+    pub provider: Option<String>,
+    pub format: Option<LabelFormat>,
+    pub service_code: Option<String>,
+    pub packages: Option<Vec<LabelPackageRequest>>,
+}
+
+/// Package dimensions supplied when generating a label
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabelPackageRequest {
+    pub weight: Option<Decimal>,
+    pub length: Option<Decimal>,
+    pub width: Option<Decimal>,
+    pub height: Option<Decimal>,
+    /// Shipment items in this package; defaults to all items when omitted
+    pub items: Option<Vec<CreateShipmentItemRequest>>,
+}
+
+/// Request to create a custom carrier
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateCarrierRequest {
+    pub slug: Option<String>,
+    pub name: String,
+    pub tracking_url_template: String,
+}
+
+/// Customer tracking lookup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackOrderRequest {
+    pub order_number: String,
+    pub email: String,
+}
@@ -44,6 +44,9 @@ pub struct Invoice {
     pub amount_paid: Decimal,
     pub amount_due: Decimal,
     pub currency: String,
+    /// Locale amounts are printed in
+    #[serde(default)]
+    pub locale: Option<String>,
 
     // Tax details
     pub tax_breakdown: Vec<TaxBreakdown>,
@@ -348,6 +351,16 @@ impl Invoice {
         }
     }
 
+    /// Format an amount in the invoice currency for the invoice's locale
+    pub fn format_amount(&self, amount: Decimal) -> String {
+        crate::locale::locale_format(self.locale.as_deref().unwrap_or(crate::locale::DEFAULT_LOCALE))
+            .format_currency(
+                amount,
+                crate::currency::currency_symbol(&self.currency),
+                crate::currency::minor_units(&self.currency),
+            )
+    }
+
     /// Check if fully paid
     pub fn is_paid(&self) -> bool {
         self.amount_due <= Decimal::ZERO
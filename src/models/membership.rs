@@ -108,6 +108,14 @@ pub struct UserMembership {
     pub updated_at: Option<DateTime<Utc>>,
 }
 
+/// The signed-in shopper a cart belongs to, with their memberships.
+/// Loaded alongside the cart on each request rather than stored with it.
+#[derive(Debug, Clone)]
+pub struct CartMember {
+    pub user_id: Uuid,
+    pub memberships: Vec<UserMembership>,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum MembershipStatus {
@@ -145,12 +153,51 @@ pub enum RestrictedContentType {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ContentAccessType {
+    /// Anyone can view; only members can purchase restricted products
     ViewOnly,
+    /// Only members can view, or purchase restricted products
     FullAccess,
     Discount,
     FreeAccess,
 }
 
+impl ContentAccessType {
+    /// Whether non-members are kept from viewing the content. Products
+    /// stay visible under `ViewOnly`; other content does not.
+    pub fn restricts_viewing(&self, content_type: RestrictedContentType) -> bool {
+        match self {
+            Self::FullAccess => true,
+            Self::ViewOnly => !content_type.is_product(),
+            Self::Discount | Self::FreeAccess => false,
+        }
+    }
+
+    /// Whether non-members are kept from purchasing the product
+    pub fn restricts_purchasing(&self) -> bool {
+        matches!(self, Self::ViewOnly | Self::FullAccess)
+    }
+}
+
+impl RestrictedContentType {
+    /// Whether the restriction targets products
+    pub fn is_product(&self) -> bool {
+        matches!(self, Self::Product | Self::ProductCategory)
+    }
+}
+
+impl ContentRestriction {
+    /// Whether the restriction covers an item. Product category rules
+    /// cover products in the listed categories.
+    pub fn covers(&self, content_type: RestrictedContentType, content_id: Uuid, category_ids: &[Uuid]) -> bool {
+        match (self.content_type, content_type) {
+            (RestrictedContentType::ProductCategory, RestrictedContentType::Product) => {
+                category_ids.iter().any(|id| self.content_ids.contains(id))
+            }
+            (restricted, content_type) => restricted == content_type && self.content_ids.contains(&content_id),
+        }
+    }
+}
+
 /// Member discount
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemberDiscount {
@@ -180,6 +227,32 @@ pub enum DiscountAppliesTo {
     SpecificCategories,
 }
 
+impl MemberDiscount {
+    /// Whether the discount covers a product, matched by product or
+    /// variation id, or by category
+    pub fn applies_to_product(&self, product_ids: &[Uuid], category_ids: &[Uuid], on_sale: bool) -> bool {
+        if self.discount_type == MemberDiscountType::FreeShipping || (self.exclude_sale_items && on_sale) {
+            return false;
+        }
+        match self.applies_to {
+            DiscountAppliesTo::AllProducts => true,
+            DiscountAppliesTo::SpecificProducts => self.product_ids.iter().flatten().any(|id| product_ids.contains(id)),
+            DiscountAppliesTo::SpecificCategories => self.category_ids.iter().flatten().any(|id| category_ids.contains(id)),
+        }
+    }
+
+    /// Discount off one unit at `price`. Fixed amounts are in the base
+    /// currency and converted at `rate`.
+    pub fn unit_discount(&self, price: Decimal, rate: Decimal) -> Decimal {
+        let discount = match self.discount_type {
+            MemberDiscountType::Percentage => price * self.amount / Decimal::ONE_HUNDRED,
+            MemberDiscountType::FixedAmount => self.amount * rate,
+            MemberDiscountType::FreeShipping => Decimal::ZERO,
+        };
+        discount.clamp(Decimal::ZERO, price.max(Decimal::ZERO))
+    }
+}
+
 /// Membership note
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MembershipNote {
@@ -251,6 +324,78 @@ impl UserMembership {
     }
 }
 
+/// Store-wide membership settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MembershipSettings {
+    /// How member discounts combine with coupons
+    pub coupon_stacking: CouponStacking,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CouponStacking {
+    /// Coupons apply on top of member prices
+    #[default]
+    Stack,
+    /// Only the larger of the member discount and the coupons applies
+    BestDiscount,
+    /// Coupons can't be used while a member discount applies
+    MemberDiscountOnly,
+}
+
+/// Member discount on a cart line, stored in the item's meta
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppliedMemberDiscount {
+    pub plan_id: Uuid,
+    pub discount_id: Uuid,
+    /// Discount off the whole line
+    pub amount: Decimal,
+    /// Line tax no longer charged on the discounted amount
+    #[serde(default)]
+    pub tax: Decimal,
+}
+
+impl AppliedMemberDiscount {
+    pub const META_KEY: &'static str = "_member_discount";
+
+    /// Read the member discount from a cart item's meta
+    pub fn from_meta(meta: &HashMap<String, serde_json::Value>) -> Option<Self> {
+        meta.get(Self::META_KEY).and_then(|value| serde_json::from_value(value.clone()).ok())
+    }
+}
+
+/// Member discounts on a cart, stored in the cart's meta
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemberCartDiscount {
+    pub plans: Vec<MemberPlanDiscount>,
+    pub total: Decimal,
+    #[serde(default)]
+    pub tax: Decimal,
+    /// A current plan gives free shipping
+    pub free_shipping: bool,
+}
+
+impl MemberCartDiscount {
+    pub const META_KEY: &'static str = "_member_discounts";
+
+    /// Read the member discounts from a cart's meta
+    pub fn from_meta(meta: &HashMap<String, serde_json::Value>) -> Option<Self> {
+        meta.get(Self::META_KEY).and_then(|value| serde_json::from_value(value.clone()).ok())
+    }
+}
+
+/// Member discount total for one plan, shown as its own order line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemberPlanDiscount {
+    pub plan_id: Uuid,
+    pub plan_name: String,
+    pub plan_slug: String,
+    pub amount: Decimal,
+    #[serde(default)]
+    pub tax: Decimal,
+}
+
 /// Access check result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccessCheckResult {
@@ -258,6 +403,9 @@ pub struct AccessCheckResult {
     pub reason: Option<AccessDeniedReason>,
     pub membership_ids: Vec<Uuid>,
     pub available_after: Option<DateTime<Utc>>, // For drip content
+    /// Plans that would grant access, for upgrade prompts
+    #[serde(default)]
+    pub required_plan_ids: Vec<Uuid>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
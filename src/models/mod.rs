@@ -15,6 +15,8 @@ pub mod coupon;
 pub mod shipping;
 pub mod tax;
 pub mod payment;
+pub mod fulfillment;
+pub mod pickup;
 
 // Enhanced features
 pub mod subscription;
@@ -55,6 +57,8 @@ pub use coupon::*;
 pub use shipping::*;
 pub use tax::*;
 pub use payment::*;
+pub use fulfillment::*;
+pub use pickup::*;
 
 // Re-export enhanced features
 pub use subscription::*;
@@ -6,6 +6,9 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use super::customer::Address;
+use super::fulfillment::{FulfillmentStatus, Shipment};
+use super::pickup::OrderPickup;
+use super::tax::{AppliedTaxExemption, OrderVat};
 
 /// Order status
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -15,6 +18,7 @@ pub enum OrderStatus {
     Pending,
     Processing,
     OnHold,
+    ReadyForPickup,
     Completed,
     Cancelled,
     Refunded,
@@ -28,6 +32,7 @@ impl OrderStatus {
             Self::Pending => "Pending Payment",
             Self::Processing => "Processing",
             Self::OnHold => "On Hold",
+            Self::ReadyForPickup => "Ready for Pickup",
             Self::Completed => "Completed",
             Self::Cancelled => "Cancelled",
             Self::Refunded => "Refunded",
@@ -37,7 +42,7 @@ impl OrderStatus {
     }
 
     pub fn is_paid(&self) -> bool {
-        matches!(self, Self::Processing | Self::Completed)
+        matches!(self, Self::Processing | Self::ReadyForPickup | Self::Completed)
     }
 
     pub fn can_cancel(&self) -> bool {
@@ -45,7 +50,7 @@ impl OrderStatus {
     }
 
     pub fn can_refund(&self) -> bool {
-        matches!(self, Self::Processing | Self::Completed)
+        matches!(self, Self::Processing | Self::ReadyForPickup | Self::Completed)
     }
 }
 
@@ -68,6 +73,13 @@ pub struct Order {
     // Currency
     pub currency: String,
     pub currency_symbol: String,
+    /// Base-to-order currency rate (with markup) when the order is not in
+    /// the base currency; base amount = amount / rate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_currency_rate: Option<Decimal>,
+    /// Shopper's locale at checkout, for prices in emails and invoices
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
 
     // Prices
     pub prices_include_tax: bool,
@@ -94,6 +106,20 @@ pub struct Order {
     pub shipping_method: Option<String>,
     pub shipping_method_title: Option<String>,
 
+    // Fulfillment
+    #[serde(default)]
+    pub fulfillment_status: FulfillmentStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pickup: Option<OrderPickup>,
+
+    // EU VAT
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vat: Option<OrderVat>,
+
+    // Tax exemption
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tax_exemption: Option<AppliedTaxExemption>,
+
     // Notes
     pub customer_note: Option<String>,
 
@@ -126,9 +152,69 @@ pub struct Order {
     pub notes: Option<Vec<OrderNote>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub refunds: Option<Vec<OrderRefund>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shipments: Option<Vec<Shipment>>,
 }
 
 impl Order {
+    /// Create a new pending order with no lines
+    pub fn new(order_number: String, customer_id: Option<Uuid>, currency: &str) -> Self {
+        Self {
+            id: Uuid::now_v7(),
+            site_id: None,
+            order_number,
+            customer_id,
+            customer_ip_address: None,
+            customer_user_agent: None,
+            status: OrderStatus::Pending,
+            parent_id: None,
+            currency: currency.to_string(),
+            currency_symbol: crate::currency::currency_symbol(currency).to_string(),
+            base_currency_rate: None,
+            locale: None,
+            prices_include_tax: false,
+            discount_total: Decimal::ZERO,
+            discount_tax: Decimal::ZERO,
+            shipping_total: Decimal::ZERO,
+            shipping_tax: Decimal::ZERO,
+            cart_tax: Decimal::ZERO,
+            total: Decimal::ZERO,
+            total_tax: Decimal::ZERO,
+            billing: Address::default(),
+            shipping: Address::default(),
+            payment_method: None,
+            payment_method_title: None,
+            transaction_id: None,
+            shipping_method: None,
+            shipping_method_title: None,
+            fulfillment_status: FulfillmentStatus::default(),
+            pickup: None,
+            vat: None,
+            tax_exemption: None,
+            customer_note: None,
+            date_paid: None,
+            date_completed: None,
+            cart_hash: None,
+            meta: serde_json::json!({}),
+            created_at: Utc::now(),
+            updated_at: None,
+            line_items: None,
+            shipping_lines: None,
+            tax_lines: None,
+            fee_lines: None,
+            coupon_lines: None,
+            notes: None,
+            refunds: None,
+            shipments: None,
+        }
+    }
+
+    /// Generate an order number: RC-YYYYMMDD-XXXXXXXX, with eight random
+    /// hex digits so numbers don't collide on busy days
+    pub fn generate_number(now: DateTime<Utc>) -> String {
+        format!("RC-{}-{:08X}", now.format("%Y%m%d"), rand::random::<u32>())
+    }
+
     /// Calculate subtotal (before discounts, shipping, tax)
     pub fn get_subtotal(&self) -> Decimal {
         self.line_items
@@ -137,6 +223,20 @@ impl Order {
             .unwrap_or(Decimal::ZERO)
     }
 
+    /// Convert an amount in the order's currency to the base currency
+    pub fn to_base_currency(&self, amount: Decimal) -> Decimal {
+        match self.base_currency_rate {
+            Some(rate) if rate > Decimal::ZERO => amount / rate,
+            _ => amount,
+        }
+    }
+
+    /// Format an amount in the order's currency for the shopper's locale
+    pub fn format_amount(&self, amount: Decimal) -> String {
+        crate::locale::locale_format(self.locale.as_deref().unwrap_or(crate::locale::DEFAULT_LOCALE))
+            .format_currency(amount, &self.currency_symbol, crate::currency::minor_units(&self.currency))
+    }
+
     /// Get the formatted order number
     pub fn get_formatted_number(&self) -> String {
         format!("#{}", self.order_number)
@@ -232,8 +332,18 @@ pub struct OrderTaxLine {
     pub rate_code: String,
     pub label: String,
     pub compound: bool,
+    #[serde(default)]
+    pub rate_percent: Decimal,
     pub tax_total: Decimal,
     pub shipping_tax_total: Decimal,
+    /// Net product and shipping amount taxed at this rate
+    #[serde(default)]
+    pub taxable_total: Decimal,
+    /// Tax not charged because of a tax exemption
+    #[serde(default)]
+    pub exempt_amount: Decimal,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exemption_id: Option<Uuid>,
 }
 
 /// Order item tax breakdown
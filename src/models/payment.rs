@@ -162,6 +162,36 @@ pub struct PaymentRequest {
     pub metadata: std::collections::HashMap<String, String>,
 }
 
+impl PaymentRequest {
+    /// Payment request for an order's total, in the order's currency
+    pub fn for_order(order: &super::order::Order, gateway_id: &str) -> Self {
+        let billing = &order.billing;
+        Self {
+            order_id: order.id,
+            amount: order.total,
+            currency: order.currency.clone(),
+            gateway_id: gateway_id.to_string(),
+            payment_token: None,
+            card: None,
+            save_payment_method: false,
+            customer_id: order.customer_id,
+            billing_email: billing.email.clone(),
+            billing_name: format!("{} {}", billing.first_name, billing.last_name).trim().to_string(),
+            billing_address: Some(BillingAddress {
+                line1: billing.address_1.clone(),
+                line2: Some(billing.address_2.clone()).filter(|l| !l.is_empty()),
+                city: billing.city.clone(),
+                state: billing.state.clone(),
+                postal_code: billing.postcode.clone(),
+                country: billing.country.clone(),
+            }),
+            metadata: std::collections::HashMap::from([
+                ("order_number".to_string(), order.order_number.clone()),
+            ]),
+        }
+    }
+}
+
 /// Card details for new payment
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CardDetails {
@@ -0,0 +1,160 @@
+//! Local Pickup Models
+//!
+//! Pickup locations with opening hours, closures and capacity-limited slots.
+
+use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::customer::Address;
+
+/// Pickup location
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PickupLocation {
+    pub id: Uuid,
+    pub site_id: Option<Uuid>,
+    pub name: String,
+    pub address: Address,
+    pub instructions: Option<String>,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+
+    // Schedule
+    pub opening_hours: Vec<OpeningHours>,
+    pub closures: Vec<PickupClosure>,
+    /// IANA timezone the opening hours are in
+    pub timezone: Tz,
+
+    // Slots
+    pub slot_duration_minutes: i32,
+    /// Maximum orders per slot (0 = unlimited)
+    pub slot_capacity: i32,
+    /// Minimum time between ordering and the start of a slot
+    pub lead_time_minutes: i32,
+    /// How many days ahead customers can book
+    pub max_days_ahead: i32,
+
+    pub cost: Option<Decimal>,
+    pub is_enabled: bool,
+    pub sort_order: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl PickupLocation {
+    /// Opening hours for a weekday (a day may have several periods, e.g. a lunch break)
+    pub fn hours_on(&self, weekday: Weekday) -> Vec<&OpeningHours> {
+        let mut hours: Vec<&OpeningHours> = self.opening_hours.iter()
+            .filter(|h| h.weekday == weekday)
+            .collect();
+        hours.sort_by_key(|h| h.opens);
+        hours
+    }
+
+    /// Closure covering a date, if any
+    pub fn closure_on(&self, date: NaiveDate) -> Option<&PickupClosure> {
+        self.closures.iter().find(|c| c.covers(date))
+    }
+
+    /// Whether the location opens at all on a date
+    pub fn is_open_on(&self, date: NaiveDate) -> bool {
+        self.closure_on(date).is_none() && !self.hours_on(date.weekday()).is_empty()
+    }
+}
+
+/// Opening period on a weekday
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpeningHours {
+    pub weekday: Weekday,
+    pub opens: NaiveTime,
+    pub closes: NaiveTime,
+}
+
+/// Holiday or other closure (inclusive date range)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PickupClosure {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub reason: Option<String>,
+}
+
+impl PickupClosure {
+    pub fn covers(&self, date: NaiveDate) -> bool {
+        date >= self.start_date && date <= self.end_date
+    }
+}
+
+/// Pickup time slot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PickupSlot {
+    pub location_id: Uuid,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    /// Maximum orders (0 = unlimited)
+    pub capacity: i32,
+    pub booked: i32,
+}
+
+impl PickupSlot {
+    pub fn is_available(&self) -> bool {
+        self.capacity == 0 || self.booked < self.capacity
+    }
+
+    /// Remaining places, or None when unlimited
+    pub fn remaining(&self) -> Option<i32> {
+        if self.capacity == 0 {
+            None
+        } else {
+            Some((self.capacity - self.booked).max(0))
+        }
+    }
+}
+
+/// Pickup chosen for an order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderPickup {
+    pub location_id: Uuid,
+    pub location_name: String,
+    pub slot_start: DateTime<Utc>,
+    pub slot_end: DateTime<Utc>,
+    pub ready_at: Option<DateTime<Utc>>,
+    pub picked_up_at: Option<DateTime<Utc>>,
+}
+
+// =============================================================================
+// DTOs for API
+// =============================================================================
+
+/// Request to create a pickup location
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatePickupLocationRequest {
+    pub name: String,
+    pub address: Address,
+    pub instructions: Option<String>,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+    pub opening_hours: Vec<OpeningHours>,
+    pub closures: Option<Vec<PickupClosure>>,
+    pub timezone: Option<Tz>,
+    pub slot_duration_minutes: Option<i32>,
+    pub slot_capacity: Option<i32>,
+    pub lead_time_minutes: Option<i32>,
+    pub max_days_ahead: Option<i32>,
+    pub cost: Option<Decimal>,
+}
+
+/// Pickup selection submitted at checkout
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PickupSelection {
+    pub location_id: Uuid,
+    pub slot_start: DateTime<Utc>,
+}
+
+/// Slot availability query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PickupSlotQuery {
+    pub from: Option<NaiveDate>,
+    pub days: Option<i32>,
+}
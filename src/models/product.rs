@@ -90,6 +90,9 @@ pub struct Product {
     // Tax
     pub tax_status: TaxStatus,
     pub tax_class: String,
+    /// Tax code sent to external tax providers (e.g. "P0000000")
+    #[serde(default)]
+    pub tax_code: Option<String>,
 
     // Inventory
     pub manage_stock: bool,
@@ -427,6 +430,7 @@ pub struct ProductRequest {
     pub sale_price_to: Option<DateTime<Utc>>,
     pub tax_status: Option<TaxStatus>,
     pub tax_class: Option<String>,
+    pub tax_code: Option<String>,
     pub manage_stock: Option<bool>,
     pub stock_quantity: Option<i32>,
     pub stock_status: Option<StockStatus>,
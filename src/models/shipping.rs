@@ -83,7 +83,11 @@ pub struct ShippingMethodSettings {
     pub tax_status: Option<ShippingTaxStatus>,
 
     // Local pickup
+    /// Free-text location for a single pickup point
     pub pickup_location: Option<String>,
+    /// Pickup locations offered by this method (empty = all enabled locations)
+    #[serde(default)]
+    pub pickup_location_ids: Vec<Uuid>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
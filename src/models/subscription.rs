@@ -126,6 +126,36 @@ pub struct SubscriptionProductSettings {
     pub trial_length: Option<i32>,
     pub one_time_shipping: bool,
     pub limit: SubscriptionLimit,
+    /// Changes customers can make themselves
+    #[serde(default)]
+    pub self_service: SubscriptionSelfService,
+}
+
+/// Changes customers may make to their own subscriptions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SubscriptionSelfService {
+    /// Most billing cycles a pause may last; 0 disables pausing
+    pub max_pause_cycles: i32,
+    pub allow_skip: bool,
+    /// Allow moving the next payment date, up to one cycle later
+    pub allow_date_change: bool,
+    pub min_quantity: i32,
+    pub max_quantity: Option<i32>,
+    pub allow_cancel: bool,
+}
+
+impl Default for SubscriptionSelfService {
+    fn default() -> Self {
+        Self {
+            max_pause_cycles: 3,
+            allow_skip: true,
+            allow_date_change: true,
+            min_quantity: 1,
+            max_quantity: None,
+            allow_cancel: true,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -136,6 +166,55 @@ pub enum SubscriptionLimit {
     OneEver,
 }
 
+impl SubscriptionProductSettings {
+    /// Product meta key the settings are stored under
+    pub const META_KEY: &'static str = "_subscription";
+
+    /// Read the settings from a product's meta value
+    pub fn from_meta(value: &serde_json::Value) -> Option<Self> {
+        serde_json::from_value(value.clone()).ok()
+    }
+}
+
+/// Store-wide subscription settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscriptionSettings {
+    /// Store URL used to build pay links in renewal emails
+    pub store_url: String,
+    /// How often the renewal job looks for due subscriptions
+    pub renewal_check_minutes: i64,
+    /// Days after a failed renewal payment to retry it, e.g. `[1, 3, 7]`
+    #[serde(default = "default_retry_days")]
+    pub retry_days: Vec<i64>,
+    /// What happens to a subscription once its last retry fails
+    #[serde(default)]
+    pub final_failure_status: DunningFinalStatus,
+}
+
+fn default_retry_days() -> Vec<i64> {
+    vec![1, 3, 7]
+}
+
+impl Default for SubscriptionSettings {
+    fn default() -> Self {
+        Self {
+            store_url: String::new(),
+            renewal_check_minutes: 15,
+            retry_days: default_retry_days(),
+            final_failure_status: DunningFinalStatus::default(),
+        }
+    }
+}
+
+/// Subscription status after the last renewal retry fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DunningFinalStatus {
+    #[default]
+    Cancelled,
+    Expired,
+}
+
 /// Renewal order
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RenewalOrder {
@@ -181,6 +260,9 @@ pub enum ScheduleChangeType {
     DateChange,
     Pause,
     Resume,
+    Skip,
+    PaymentMethodChange,
+    Cancellation,
 }
 
 impl Subscription {
@@ -206,6 +288,11 @@ impl Subscription {
         })
     }
 
+    /// Tax on recurring shipping: the part of `tax_total` not charged on items
+    pub fn shipping_tax(&self) -> Decimal {
+        self.tax_total - self.items.iter().map(|i| i.tax).sum::<Decimal>()
+    }
+
     /// Calculate next payment date
     pub fn calculate_next_payment(&self) -> DateTime<Utc> {
         let base_date = self.next_payment_date.unwrap_or(Utc::now());
@@ -239,6 +326,17 @@ pub struct SwitchRequest {
     pub effective_immediately: bool,
 }
 
+/// New plan for a subscription item, stored as the new value of a plan
+/// change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanSwitch {
+    pub item_id: Uuid,
+    pub product_id: Uuid,
+    pub variation_id: Option<Uuid>,
+    pub name: String,
+    pub plan: SubscriptionProductSettings,
+}
+
 /// Switch calculation result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SwitchCalculation {
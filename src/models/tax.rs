@@ -3,7 +3,10 @@
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::geo;
+use crate::vat::provider::VatNumberValidation;
 
 /// Tax rate
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,51 +41,56 @@ pub struct TaxRate {
 impl TaxRate {
     /// Check if this rate matches a location
     pub fn matches_location(&self, country: &str, state: &str, postcode: &str, city: &str) -> bool {
-        // Country must match (or be empty for all)
-        if !self.country.is_empty() && self.country != country {
+        // Country must match (or be empty for all); alpha-3 codes are accepted
+        if !self.country.is_empty() && !geo::same_country(&self.country, country) {
             return false;
         }
 
-        // State must match (or be empty for all)
-        if !self.state.is_empty() && self.state != state {
+        // State must match (or be empty for all); codes and names are interchangeable
+        if !self.state.is_empty() && !geo::same_subdivision(country, &self.state, state) {
             return false;
         }
 
-        // Postcode matching (supports wildcards)
-        if !self.postcode.is_empty() {
-            if self.postcode.contains('*') {
-                // Wildcard matching
-                let pattern = self.postcode.replace('*', "");
-                if !postcode.starts_with(&pattern) {
-                    return false;
-                }
-            } else if self.postcode.contains("...") {
-                // Range matching (e.g., "12345...12400")
-                let parts: Vec<&str> = self.postcode.split("...").collect();
-                if parts.len() == 2 {
-                    if let (Ok(start), Ok(end), Ok(pc)) = (
-                        parts[0].parse::<i64>(),
-                        parts[1].parse::<i64>(),
-                        postcode.parse::<i64>()
-                    ) {
-                        if pc < start || pc > end {
-                            return false;
-                        }
-                    }
-                }
-            } else if self.postcode != postcode {
-                return false;
-            }
+        // Postcode matching (supports wildcards, ranges and ";"-separated lists)
+        if !self.postcode.is_empty()
+            && !self.postcode.split(';').any(|pattern| Self::postcode_matches(pattern.trim(), postcode))
+        {
+            return false;
         }
 
-        // City must match (or be empty for all)
-        if !self.city.is_empty() && self.city.to_lowercase() != city.to_lowercase() {
+        // City must match (or be empty for all); ";" separates alternatives
+        if !self.city.is_empty()
+            && !self.city.split(';').any(|c| c.trim().to_lowercase() == city.to_lowercase())
+        {
             return false;
         }
 
         true
     }
 
+    /// Match a single postcode pattern
+    fn postcode_matches(pattern: &str, postcode: &str) -> bool {
+        if pattern.contains('*') {
+            // Wildcard matching
+            postcode.starts_with(&pattern.replace('*', ""))
+        } else if pattern.contains("...") {
+            // Range matching (e.g., "12345...12400")
+            let parts: Vec<&str> = pattern.split("...").collect();
+            if parts.len() == 2 {
+                if let (Ok(start), Ok(end), Ok(pc)) = (
+                    parts[0].parse::<i64>(),
+                    parts[1].parse::<i64>(),
+                    postcode.parse::<i64>()
+                ) {
+                    return pc >= start && pc <= end;
+                }
+            }
+            true
+        } else {
+            pattern == postcode
+        }
+    }
+
     /// Calculate tax for an amount
     pub fn calculate(&self, amount: Decimal) -> Decimal {
         amount * (self.rate / Decimal::from(100))
@@ -124,6 +132,9 @@ pub struct CalculatedTax {
     pub label: String,
     pub rate: Decimal,
     pub compound: bool,
+    /// Net amount the rate was charged on
+    #[serde(default)]
+    pub taxable_amount: Decimal,
     pub tax_amount: Decimal,
     pub shipping_tax_amount: Decimal,
 }
@@ -156,6 +167,163 @@ impl TaxLocation {
     }
 }
 
+/// How VAT applies to an order under the EU One-Stop-Shop rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VatTreatment {
+    /// Customer is in the store's own country
+    Domestic,
+    /// EU distance sale below the threshold, taxed at the store's rates
+    Origin,
+    /// EU distance sale taxed at the customer's rates and declared via OSS
+    Destination,
+    /// EU business customer with a validated VAT number; zero-rated
+    ReverseCharge,
+    /// Customer outside the EU; zero-rated
+    Export,
+}
+
+impl VatTreatment {
+    /// Whether VAT is charged at all
+    pub fn is_zero_rated(&self) -> bool {
+        matches!(self, Self::ReverseCharge | Self::Export)
+    }
+}
+
+impl std::fmt::Display for VatTreatment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Domestic => write!(f, "Domestic"),
+            Self::Origin => write!(f, "Origin country VAT"),
+            Self::Destination => write!(f, "Destination country VAT (OSS)"),
+            Self::ReverseCharge => write!(f, "Reverse charge"),
+            Self::Export => write!(f, "Export"),
+        }
+    }
+}
+
+/// VAT details recorded on an order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderVat {
+    /// Customer VAT number as validated, e.g. "DE123456789"
+    pub vat_number: Option<String>,
+    pub validation: Option<VatNumberValidation>,
+    pub treatment: VatTreatment,
+    /// Country whose VAT rates were applied (empty when zero-rated)
+    pub tax_country: String,
+    /// Note printed on the invoice, e.g. the reverse charge wording
+    pub note: Option<String>,
+}
+
+impl OrderVat {
+    /// Check if the order was reverse charged
+    pub fn is_reverse_charge(&self) -> bool {
+        self.treatment == VatTreatment::ReverseCharge
+    }
+}
+
+/// Tax exemption certificate held by a customer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxExemption {
+    pub id: Uuid,
+    pub site_id: Option<Uuid>,
+    pub customer_id: Uuid,
+    /// Certificate or exemption number issued by the tax authority
+    pub certificate_id: String,
+    pub exemption_type: TaxExemptionType,
+    /// Jurisdictions where the exemption applies
+    pub jurisdictions: Vec<TaxJurisdiction>,
+    pub status: TaxExemptionStatus,
+    /// Uploaded certificate document
+    pub document_url: Option<String>,
+    pub expires_at: Option<NaiveDate>,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl TaxExemption {
+    /// Approved and not expired on the given date
+    pub fn is_valid_on(&self, date: NaiveDate) -> bool {
+        self.status == TaxExemptionStatus::Approved && self.expires_at.is_none_or(|expiry| date <= expiry)
+    }
+
+    /// Jurisdiction covering a location, if any
+    pub fn covering_jurisdiction(&self, location: &TaxLocation) -> Option<&TaxJurisdiction> {
+        self.jurisdictions.iter().find(|j| j.covers(location))
+    }
+}
+
+/// Country, optionally narrowed to a state
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaxJurisdiction {
+    pub country: String,
+    /// Empty for the whole country
+    #[serde(default)]
+    pub state: String,
+}
+
+impl TaxJurisdiction {
+    /// Check if a location falls within this jurisdiction
+    pub fn covers(&self, location: &TaxLocation) -> bool {
+        geo::same_country(&self.country, &location.country)
+            && (self.state.is_empty() || self.state.eq_ignore_ascii_case(&location.state))
+    }
+
+    /// Check if a tax rate is levied by this jurisdiction. A state
+    /// exemption covers the state's rates but not country-wide ones.
+    pub fn covers_rate(&self, rate: &TaxRate) -> bool {
+        geo::same_country(&self.country, &rate.country)
+            && (self.state.is_empty() || self.state.eq_ignore_ascii_case(&rate.state))
+    }
+}
+
+impl std::fmt::Display for TaxJurisdiction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.state.is_empty() {
+            write!(f, "{}", self.country)
+        } else {
+            write!(f, "{}:{}", self.country, self.state)
+        }
+    }
+}
+
+/// Reason for a tax exemption
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TaxExemptionType {
+    /// Goods bought for resale
+    #[default]
+    Resale,
+    Nonprofit,
+    Government,
+    Education,
+    Other,
+}
+
+/// Review status of an exemption certificate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TaxExemptionStatus {
+    /// Uploaded by the customer, awaiting review
+    #[default]
+    Pending,
+    Approved,
+    Rejected,
+}
+
+/// Exemption applied to an order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppliedTaxExemption {
+    pub exemption_id: Uuid,
+    pub certificate_id: String,
+    pub exemption_type: TaxExemptionType,
+    /// Jurisdiction that matched the tax location, e.g. "US:TX"
+    pub jurisdiction: String,
+    /// Tax that would have been charged
+    pub exempt_amount: Decimal,
+}
+
 // =============================================================================
 // DTOs for API
 // =============================================================================
@@ -199,3 +367,82 @@ pub struct TaxRateFilter {
     pub page: Option<i32>,
     pub per_page: Option<i32>,
 }
+
+/// How an imported CSV is combined with the existing rates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TaxRateImportMode {
+    /// Update matching rates and add new ones; other rates are kept
+    #[default]
+    Merge,
+    /// Delete the existing rates of each imported tax class first
+    Replace,
+}
+
+/// Request to import tax rates from CSV
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxRateImportRequest {
+    pub csv: String,
+    #[serde(default)]
+    pub mode: TaxRateImportMode,
+    /// Validate and report without changing any rates
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Import into this class; rows with a blank class use it, rows for
+    /// other classes are rejected
+    pub tax_class: Option<String>,
+}
+
+/// Problem with one CSV row
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaxRateImportError {
+    /// 1-based line number in the file
+    pub line: usize,
+    pub column: Option<String>,
+    pub message: String,
+}
+
+/// Outcome of a tax rate import (or dry run)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaxRateImportSummary {
+    pub dry_run: bool,
+    pub mode: TaxRateImportMode,
+    pub rows: usize,
+    pub created: usize,
+    pub updated: usize,
+    pub deleted: usize,
+    pub unchanged: usize,
+    pub errors: Vec<TaxRateImportError>,
+}
+
+/// Tax rate export filter
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaxRateExportQuery {
+    pub tax_class: Option<String>,
+}
+
+/// Request to submit or assign a tax exemption
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxExemptionRequest {
+    pub certificate_id: String,
+    pub exemption_type: Option<TaxExemptionType>,
+    pub jurisdictions: Vec<TaxJurisdiction>,
+    pub document_url: Option<String>,
+    pub expires_at: Option<NaiveDate>,
+    pub notes: Option<String>,
+}
+
+/// Request to approve or reject a tax exemption
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxExemptionReviewRequest {
+    pub status: TaxExemptionStatus,
+    pub notes: Option<String>,
+}
+
+/// Filter for exempted sales report
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaxExemptionReportFilter {
+    pub date_min: Option<NaiveDate>,
+    pub date_max: Option<NaiveDate>,
+    pub customer_id: Option<Uuid>,
+}
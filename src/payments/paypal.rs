@@ -3,7 +3,7 @@
 //! Integration with PayPal for PayPal and credit card payments.
 
 use async_trait::async_trait;
-use rust_decimal::Decimal;
+use rust_decimal::{Decimal, RoundingStrategy};
 use uuid::Uuid;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -12,6 +12,7 @@ use super::gateway::{
     PaymentGateway, GatewayError, GatewaySettingField, SettingFieldType,
     TokenizeRequest, WebhookResult, WebhookEventType,
 };
+use crate::currency::iso;
 use crate::models::payment::{
     PaymentRequest, PaymentResult, RefundRequest, RefundResult,
     PaymentToken, PaymentTokenType, GatewayFeature, TransactionStatus,
@@ -131,10 +132,7 @@ impl PayPalGateway {
         Ok(PayPalCapture {
             id: format!("CAPTURE_{}", Uuid::now_v7()),
             status: "COMPLETED".to_string(),
-            amount: PayPalAmount {
-                currency_code: "USD".to_string(),
-                value: "0.00".to_string(),
-            },
+            amount: PayPalAmount::new(Decimal::ZERO, "USD"),
         })
     }
 }
@@ -337,6 +335,18 @@ struct PayPalAmount {
     value: String,
 }
 
+impl PayPalAmount {
+    /// PayPal expects the value with exactly the currency's minor units
+    fn new(amount: Decimal, currency: &str) -> Self {
+        let decimals = iso::minor_units(currency);
+        let value = amount.round_dp_with_strategy(decimals, RoundingStrategy::MidpointAwayFromZero);
+        Self {
+            currency_code: currency.to_uppercase(),
+            value: format!("{:.prec$}", value, prec = decimals as usize),
+        }
+    }
+}
+
 /// PayPal webhook event
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct PayPalWebhookEvent {
@@ -375,4 +385,11 @@ mod tests {
         let gateway = PayPalGateway::new(PayPalConfig::default());
         assert!(!gateway.is_available());
     }
+
+    #[test]
+    fn test_amount_minor_units() {
+        assert_eq!(PayPalAmount::new(Decimal::new(1999, 2), "usd").value, "19.99");
+        assert_eq!(PayPalAmount::new(Decimal::new(12345, 1), "JPY").value, "1235");
+        assert_eq!(PayPalAmount::new(Decimal::from(5), "KWD").value, "5.000");
+    }
 }
@@ -3,7 +3,8 @@
 //! Integration with Stripe for credit card and other payments.
 
 use async_trait::async_trait;
-use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
 use uuid::Uuid;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -12,6 +13,7 @@ use super::gateway::{
     PaymentGateway, GatewayError, GatewaySettingField, SettingFieldType,
     TokenizeRequest, WebhookResult, WebhookEventType,
 };
+use crate::currency::iso;
 use crate::models::payment::{
     PaymentRequest, PaymentResult, RefundRequest, RefundResult,
     PaymentToken, PaymentTokenType, CardType, GatewayFeature,
@@ -69,15 +71,12 @@ impl StripeGateway {
         "https://api.stripe.com/v1"
     }
 
-    /// Convert amount to smallest currency unit (cents)
+    /// Convert amount to smallest currency unit (ISO 4217 minor units)
     fn to_smallest_unit(&self, amount: Decimal, currency: &str) -> i64 {
-        // Most currencies use 2 decimal places
-        let multiplier = match currency.to_uppercase().as_str() {
-            "JPY" | "KRW" | "VND" => 1,
-            "BHD" | "KWD" | "OMR" => 1000,
-            _ => 100,
-        };
-        (amount * Decimal::from(multiplier)).to_string().parse().unwrap_or(0)
+        let minor_units = iso::minor_units(currency);
+        // Stripe takes three-decimal currencies in thousandths but only accepts multiples of ten
+        let rounded = amount.round_dp_with_strategy(minor_units.min(2), RoundingStrategy::MidpointAwayFromZero);
+        (rounded * Decimal::from(10_i64.pow(minor_units))).to_i64().unwrap_or(0)
     }
 
     /// Create payment intent
@@ -405,5 +404,8 @@ mod tests {
         let gateway = StripeGateway::new(StripeConfig::default());
         assert_eq!(gateway.to_smallest_unit(Decimal::from(10), "USD"), 1000);
         assert_eq!(gateway.to_smallest_unit(Decimal::from(10), "JPY"), 10);
+        assert_eq!(gateway.to_smallest_unit(Decimal::new(19995, 3), "usd"), 2000);
+        assert_eq!(gateway.to_smallest_unit(Decimal::new(12345, 3), "KWD"), 12350);
+        assert_eq!(gateway.to_smallest_unit(Decimal::new(12345, 1), "ISK"), 1235);
     }
 }
@@ -1,17 +1,49 @@
 //! RustCommerce Plugin Implementation
 
 use async_trait::async_trait;
+#[cfg(any(feature = "subscriptions", feature = "bookings"))]
+use chrono::{DateTime, Utc};
 use rustpress_core::context::AppContext;
 use rustpress_core::error::Result;
 use rustpress_core::plugin::{Plugin, PluginInfo, PluginState};
 use semver::Version;
 use std::sync::Arc;
-use parking_lot::RwLock;
-use tracing::{info, debug, error};
+use parking_lot::{Mutex, RwLock};
+use tokio::task::JoinHandle;
+use tracing::{info, debug, error, warn};
 
+use crate::payments::PaymentGatewayRegistry;
 use crate::settings::RustCommerceSettings;
 use crate::services::*;
 
+/// Loads the renewals due at a time, and saves each renewal's result
+#[cfg(feature = "subscriptions")]
+struct RenewalJobs {
+    load_due: Arc<dyn Fn(DateTime<Utc>) -> subscription::DueWork + Send + Sync>,
+    on_renewal: Arc<dyn Fn(subscription::RenewalRun) + Send + Sync>,
+}
+
+/// Loads the external calendars due for import, and saves each import
+#[cfg(feature = "bookings")]
+struct CalendarImportJobs {
+    load_due: Arc<dyn Fn(DateTime<Utc>) -> Vec<booking_calendar::DueImport> + Send + Sync>,
+    on_import: Arc<dyn Fn(booking_calendar::ImportRun) + Send + Sync>,
+}
+
+/// Loads the in-cart booking holds with their carts, and saves released holds
+#[cfg(feature = "bookings")]
+struct HoldReleaseJobs {
+    load_holds: Arc<dyn Fn(DateTime<Utc>) -> booking::DueHolds + Send + Sync>,
+    on_release: Arc<dyn Fn(Vec<crate::models::booking::Booking>) + Send + Sync>,
+}
+
+/// Loads upcoming bookings, and saves and emails each reminder
+#[cfg(feature = "bookings")]
+struct ReminderJobs {
+    load_upcoming: Arc<dyn Fn(DateTime<Utc>) -> Vec<booking::DueReminder> + Send + Sync>,
+    on_reminder: Arc<dyn Fn(crate::models::booking::Booking, crate::models::email_templates::SendEmailRequest) + Send + Sync>,
+}
+
 /// The main RustCommerce plugin
 pub struct RustCommercePlugin {
     info: PluginInfo,
@@ -27,6 +59,34 @@ pub struct RustCommercePlugin {
     tax_service: RwLock<Option<Arc<tax::TaxService>>>,
     order_service: RwLock<Option<Arc<order::OrderService>>>,
     customer_service: RwLock<Option<Arc<customer::CustomerService>>>,
+    /// Shared with the exchange rate refresh, which swaps in updated rates
+    #[cfg(feature = "multi_currency")]
+    currency_service: Arc<RwLock<Option<Arc<currency::CurrencyService>>>>,
+    #[cfg(feature = "multi_currency")]
+    exchange_rate_service: RwLock<Option<Arc<exchange_rate::ExchangeRateService>>>,
+    #[cfg(feature = "subscriptions")]
+    subscription_service: RwLock<Option<Arc<subscription::SubscriptionService>>>,
+    #[cfg(feature = "bookings")]
+    booking_service: RwLock<Option<Arc<booking::BookingService>>>,
+    #[cfg(feature = "bookings")]
+    booking_calendar_service: RwLock<Option<Arc<booking_calendar::BookingCalendarService>>>,
+    #[cfg(feature = "memberships")]
+    membership_service: RwLock<Option<Arc<membership::MembershipService>>>,
+
+    payment_gateways: Arc<PaymentGatewayRegistry>,
+    #[cfg(feature = "bookings")]
+    booking_settings: crate::models::booking::BookingSettings,
+    #[cfg(feature = "subscriptions")]
+    renewal_jobs: Option<RenewalJobs>,
+    #[cfg(feature = "bookings")]
+    calendar_import_jobs: Option<CalendarImportJobs>,
+    #[cfg(feature = "bookings")]
+    hold_release_jobs: Option<HoldReleaseJobs>,
+    #[cfg(feature = "bookings")]
+    reminder_jobs: Option<ReminderJobs>,
+
+    // Background workers, stopped on deactivation
+    workers: Mutex<Vec<JoinHandle<()>>>,
 }
 
 impl RustCommercePlugin {
@@ -52,9 +112,98 @@ impl RustCommercePlugin {
             tax_service: RwLock::new(None),
             order_service: RwLock::new(None),
             customer_service: RwLock::new(None),
+            #[cfg(feature = "multi_currency")]
+            currency_service: Arc::new(RwLock::new(None)),
+            #[cfg(feature = "multi_currency")]
+            exchange_rate_service: RwLock::new(None),
+            #[cfg(feature = "subscriptions")]
+            subscription_service: RwLock::new(None),
+            #[cfg(feature = "bookings")]
+            booking_service: RwLock::new(None),
+            #[cfg(feature = "bookings")]
+            booking_calendar_service: RwLock::new(None),
+            #[cfg(feature = "memberships")]
+            membership_service: RwLock::new(None),
+            payment_gateways: Arc::new(PaymentGatewayRegistry::new()),
+            #[cfg(feature = "bookings")]
+            booking_settings: Default::default(),
+            #[cfg(feature = "subscriptions")]
+            renewal_jobs: None,
+            #[cfg(feature = "bookings")]
+            calendar_import_jobs: None,
+            #[cfg(feature = "bookings")]
+            hold_release_jobs: None,
+            #[cfg(feature = "bookings")]
+            reminder_jobs: None,
+            workers: Mutex::new(Vec::new()),
         }
     }
 
+    /// Use the host's payment gateways, e.g. to charge subscription renewals
+    pub fn with_payment_gateways(mut self, gateways: Arc<PaymentGatewayRegistry>) -> Self {
+        self.payment_gateways = gateways;
+        self
+    }
+
+    /// Use the store's booking settings for holds, reminders and calendars
+    #[cfg(feature = "bookings")]
+    pub fn with_booking_settings(mut self, settings: crate::models::booking::BookingSettings) -> Self {
+        self.booking_settings = settings;
+        self
+    }
+
+    /// Schedule subscription renewals with rustpress-jobs once activated.
+    /// `load_due` returns the work due at the given time; `on_renewal`
+    /// saves each result.
+    #[cfg(feature = "subscriptions")]
+    pub fn with_renewal_jobs<L, F>(mut self, load_due: L, on_renewal: F) -> Self
+    where
+        L: Fn(DateTime<Utc>) -> subscription::DueWork + Send + Sync + 'static,
+        F: Fn(subscription::RenewalRun) + Send + Sync + 'static,
+    {
+        self.renewal_jobs = Some(RenewalJobs { load_due: Arc::new(load_due), on_renewal: Arc::new(on_renewal) });
+        self
+    }
+
+    /// Import external booking calendars in the background once activated.
+    /// `load_due` returns the calendars to import; `on_import` saves each
+    /// result.
+    #[cfg(feature = "bookings")]
+    pub fn with_calendar_import_jobs<L, F>(mut self, load_due: L, on_import: F) -> Self
+    where
+        L: Fn(DateTime<Utc>) -> Vec<booking_calendar::DueImport> + Send + Sync + 'static,
+        F: Fn(booking_calendar::ImportRun) + Send + Sync + 'static,
+    {
+        self.calendar_import_jobs = Some(CalendarImportJobs { load_due: Arc::new(load_due), on_import: Arc::new(on_import) });
+        self
+    }
+
+    /// Release booking holds whose carts expired, in the background once
+    /// started. `load_holds` returns the in-cart holds with their carts;
+    /// `on_release` saves the released bookings.
+    #[cfg(feature = "bookings")]
+    pub fn with_hold_release_jobs<L, F>(mut self, load_holds: L, on_release: F) -> Self
+    where
+        L: Fn(DateTime<Utc>) -> booking::DueHolds + Send + Sync + 'static,
+        F: Fn(Vec<crate::models::booking::Booking>) + Send + Sync + 'static,
+    {
+        self.hold_release_jobs = Some(HoldReleaseJobs { load_holds: Arc::new(load_holds), on_release: Arc::new(on_release) });
+        self
+    }
+
+    /// Send booking reminders in the background once started.
+    /// `load_upcoming` returns the bookings starting soon; `on_reminder`
+    /// saves each reminded booking and sends its email.
+    #[cfg(feature = "bookings")]
+    pub fn with_reminder_jobs<L, F>(mut self, load_upcoming: L, on_reminder: F) -> Self
+    where
+        L: Fn(DateTime<Utc>) -> Vec<booking::DueReminder> + Send + Sync + 'static,
+        F: Fn(crate::models::booking::Booking, crate::models::email_templates::SendEmailRequest) + Send + Sync + 'static,
+    {
+        self.reminder_jobs = Some(ReminderJobs { load_upcoming: Arc::new(load_upcoming), on_reminder: Arc::new(on_reminder) });
+        self
+    }
+
     /// Get the current settings
     pub fn settings(&self) -> RustCommerceSettings {
         self.settings.read().clone()
@@ -105,6 +254,36 @@ impl RustCommercePlugin {
         self.customer_service.read().clone()
     }
 
+    /// Get currency service
+    #[cfg(feature = "multi_currency")]
+    pub fn currency(&self) -> Option<Arc<currency::CurrencyService>> {
+        self.currency_service.read().clone()
+    }
+
+    /// Get subscription service
+    #[cfg(feature = "subscriptions")]
+    pub fn subscriptions(&self) -> Option<Arc<subscription::SubscriptionService>> {
+        self.subscription_service.read().clone()
+    }
+
+    /// Get booking service
+    #[cfg(feature = "bookings")]
+    pub fn bookings(&self) -> Option<Arc<booking::BookingService>> {
+        self.booking_service.read().clone()
+    }
+
+    /// Get booking calendar service
+    #[cfg(feature = "bookings")]
+    pub fn booking_calendars(&self) -> Option<Arc<booking_calendar::BookingCalendarService>> {
+        self.booking_calendar_service.read().clone()
+    }
+
+    /// Get membership service
+    #[cfg(feature = "memberships")]
+    pub fn memberships(&self) -> Option<Arc<membership::MembershipService>> {
+        self.membership_service.read().clone()
+    }
+
     /// Initialize services
     async fn init_services(&self, ctx: &AppContext) -> Result<()> {
         let settings = self.settings();
@@ -125,13 +304,27 @@ impl RustCommercePlugin {
         let inventory = Arc::new(inventory::InventoryService::new(settings.clone()));
         *self.inventory_service.write() = Some(inventory.clone());
 
+        // Initialize membership service (plans are loaded from the database)
+        #[cfg(feature = "memberships")]
+        let memberships = {
+            let memberships = Arc::new(membership::MembershipService::new(
+                settings.clone(),
+                crate::models::membership::MembershipSettings::default(),
+            ));
+            *self.membership_service.write() = Some(memberships.clone());
+            memberships
+        };
+
         // Initialize cart service
-        let cart = Arc::new(cart::CartService::new(
+        let cart = cart::CartService::new(
             pricing.clone(),
             tax.clone(),
             shipping.clone(),
             settings.clone(),
-        ));
+        );
+        #[cfg(feature = "memberships")]
+        let cart = cart.with_memberships(memberships.clone());
+        let cart = Arc::new(cart);
         *self.cart_service.write() = Some(cart.clone());
 
         // Initialize order service
@@ -142,21 +335,160 @@ impl RustCommercePlugin {
         let customer = Arc::new(customer::CustomerService::new());
         *self.customer_service.write() = Some(customer.clone());
 
+        // Initialize subscription service
+        #[cfg(feature = "subscriptions")]
+        let subscriptions = {
+            let subscriptions = Arc::new(subscription::SubscriptionService::new(
+                settings.clone(),
+                crate::models::subscription::SubscriptionSettings::default(),
+                self.payment_gateways.clone(),
+            ));
+            *self.subscription_service.write() = Some(subscriptions.clone());
+            subscriptions
+        };
+
+        // Initialize booking services
+        #[cfg(feature = "bookings")]
+        {
+            let bookings = booking::BookingService::new(
+                settings.clone(),
+                self.booking_settings.clone(),
+                self.payment_gateways.clone(),
+            );
+            *self.booking_service.write() = Some(Arc::new(bookings));
+            let calendars = booking_calendar::BookingCalendarService::new(self.booking_settings.clone());
+            *self.booking_calendar_service.write() = Some(Arc::new(calendars));
+        }
+
         // Initialize checkout service
-        let checkout = Arc::new(checkout::CheckoutService::new(
+        let checkout = checkout::CheckoutService::new(
             cart.clone(),
             order.clone(),
             inventory.clone(),
             shipping.clone(),
             tax.clone(),
             settings.clone(),
-        ));
-        *self.checkout_service.write() = Some(checkout);
+        );
+        #[cfg(feature = "subscriptions")]
+        let checkout = checkout.with_subscriptions(subscriptions);
+        #[cfg(feature = "memberships")]
+        let checkout = checkout.with_memberships(memberships);
+        *self.checkout_service.write() = Some(Arc::new(checkout));
+
+        // Initialize currency services (currencies, zones and fixed prices are loaded from the database)
+        #[cfg(feature = "multi_currency")]
+        {
+            let currency_settings = crate::models::currency::CurrencySettings {
+                base_currency: settings.general.currency.clone(),
+                ..Default::default()
+            };
+            let exchange_rates = exchange_rate::ExchangeRateService::new(currency_settings.clone(), Vec::new());
+            *self.exchange_rate_service.write() = Some(Arc::new(exchange_rates));
+            let currency = currency::CurrencyService::new(currency_settings, Vec::new(), Vec::new());
+            *self.currency_service.write() = Some(Arc::new(currency));
+        }
 
         info!("RustCommerce services initialized");
         Ok(())
     }
 
+    /// Refresh exchange rates on schedule and hand them to the currency service
+    #[cfg(feature = "multi_currency")]
+    fn start_exchange_rate_refresh(&self) {
+        let Some(exchange_rates) = self.exchange_rate_service.read().clone() else {
+            return;
+        };
+
+        let config = crate::currency::EcbConfig::default();
+        let provider = Arc::new(crate::currency::EcbRateProvider::new(config));
+        let currency_service = self.currency_service.clone();
+
+        let worker = exchange_rates.spawn_scheduled_refresh(provider, move |result| match result {
+            Ok(summary) => {
+                let mut slot = currency_service.write();
+                if let Some(current) = slot.as_ref() {
+                    let mut updated = (**current).clone();
+                    updated.update_rates(&summary.rates);
+                    *slot = Some(Arc::new(updated));
+                }
+                for alert in summary.alerts() {
+                    warn!("Exchange rate for {} moved {}%", alert.currency, alert.change_percent);
+                }
+            }
+            Err(e) => error!("Exchange rate refresh failed: {}", e),
+        });
+        self.workers.lock().push(worker);
+    }
+
+    /// Schedule the subscription renewal job, if the host registered where
+    /// renewals are loaded from and saved to
+    #[cfg(feature = "subscriptions")]
+    fn schedule_renewal_job(&self, ctx: &AppContext) {
+        let Some(subscriptions) = self.subscriptions() else {
+            return;
+        };
+        let Some(ref jobs) = self.renewal_jobs else {
+            warn!("No renewal jobs registered; subscription renewals will not run");
+            return;
+        };
+
+        let (load_due, on_renewal) = (jobs.load_due.clone(), jobs.on_renewal.clone());
+        let interval = subscriptions.renewal_interval();
+        let job = subscriptions.renewal_job(move |now| load_due(now), move |run| on_renewal(run));
+        ctx.jobs().schedule_every(interval, job);
+    }
+
+    /// Start the external calendar import worker, if the host registered
+    /// where calendars are loaded from and saved to
+    #[cfg(feature = "bookings")]
+    fn start_calendar_import_worker(&self) {
+        let Some(calendars) = self.booking_calendars() else {
+            return;
+        };
+        let Some(ref jobs) = self.calendar_import_jobs else {
+            warn!("No calendar import jobs registered; external calendars will not be imported");
+            return;
+        };
+
+        let (load_due, on_import) = (jobs.load_due.clone(), jobs.on_import.clone());
+        let worker = calendars.spawn_import_worker(move |now| load_due(now), move |run| on_import(run));
+        self.workers.lock().push(worker);
+    }
+
+    /// Start the booking hold release worker, if the host registered where
+    /// holds are loaded from and saved to
+    #[cfg(feature = "bookings")]
+    fn start_hold_release_worker(&self) {
+        let Some(bookings) = self.bookings() else {
+            return;
+        };
+        let Some(ref jobs) = self.hold_release_jobs else {
+            warn!("No hold release jobs registered; booking holds will not be released");
+            return;
+        };
+
+        let (load_holds, on_release) = (jobs.load_holds.clone(), jobs.on_release.clone());
+        let worker = bookings.spawn_hold_release_worker(move |now| load_holds(now), move |released| on_release(released));
+        self.workers.lock().push(worker);
+    }
+
+    /// Start the booking reminder worker, if the host registered where
+    /// upcoming bookings are loaded from and saved to
+    #[cfg(feature = "bookings")]
+    fn start_reminder_worker(&self) {
+        let Some(bookings) = self.bookings() else {
+            return;
+        };
+        let Some(ref jobs) = self.reminder_jobs else {
+            warn!("No reminder jobs registered; booking reminders will not be sent");
+            return;
+        };
+
+        let (load_upcoming, on_reminder) = (jobs.load_upcoming.clone(), jobs.on_reminder.clone());
+        let worker = bookings.spawn_reminder_worker(move |now| load_upcoming(now), move |booking, email| on_reminder(booking, email));
+        self.workers.lock().push(worker);
+    }
+
     /// Register hooks
     fn register_hooks(&self, ctx: &AppContext) {
         // Register WordPress-like hooks
@@ -211,19 +543,56 @@ impl RustCommercePlugin {
         // /rc/v1/products/attributes
         // /rc/v1/orders
         // /rc/v1/orders/{id}
+        // /rc/v1/orders/{id}/shipments
+        // /rc/v1/orders/{id}/shipments/{shipment_id}/label
+        // /rc/v1/orders/{id}/ready-for-pickup
+        // /rc/v1/orders/{id}/picked-up
+        // /rc/v1/track
         // /rc/v1/customers
+        // /rc/v1/customers/{id}/tax-exemptions
+        // /rc/v1/tax-exemptions/{id}
         // /rc/v1/cart
         // /rc/v1/cart/add
         // /rc/v1/cart/remove
         // /rc/v1/cart/update
         // /rc/v1/checkout
+        // /rc/v1/checkout/verify-address
+        // /rc/v1/checkout/validate-vat
         // /rc/v1/coupons
         // /rc/v1/shipping/zones
         // /rc/v1/shipping/methods
+        // /rc/v1/shipping/label-providers
+        // /rc/v1/pickup/locations
+        // /rc/v1/pickup/locations/{id}/slots
         // /rc/v1/taxes
+        // /rc/v1/taxes/export
+        // /rc/v1/taxes/import
+        // /rc/v1/subscriptions/{id}/pause
+        // /rc/v1/subscriptions/{id}/resume
+        // /rc/v1/subscriptions/{id}/skip
+        // /rc/v1/subscriptions/{id}/renewal-date
+        // /rc/v1/subscriptions/{id}/items/{item_id}/quantity
+        // /rc/v1/subscriptions/{id}/payment-method
+        // /rc/v1/subscriptions/{id}/cancel
+        // /rc/v1/bookings/availability (bookings)
+        // /rc/v1/bookings/cost (bookings)
+        // /rc/v1/bookings/hold (bookings)
+        // /rc/v1/bookings/pending-confirmation (bookings)
+        // /rc/v1/bookings/{id}/confirm (bookings)
+        // /rc/v1/bookings/{id}/reject (bookings)
+        // /rc/v1/bookings/{id}/cancel (bookings)
+        // /rc/v1/bookings/calendar-feeds (bookings)
+        // /rc/v1/bookings/calendar/{token} (bookings)
+        // /rc/v1/bookings/external-calendars (bookings)
+        // /rc/v1/bookings/external-calendars/{id}/sync (bookings)
+        // /rc/v1/memberships/access (memberships)
         // /rc/v1/reports/sales
         // /rc/v1/reports/products
         // /rc/v1/reports/customers
+        // /rc/v1/reports/taxes
+        // /rc/v1/reports/oss
+        // /rc/v1/reports/tax-exemptions
+        // /rc/v1/reports/dunning (subscriptions)
     }
 
     /// Register admin menus
@@ -299,15 +668,30 @@ impl Plugin for RustCommercePlugin {
         // Register admin menus
         self.register_admin_menus(ctx);
 
+        // Start background workers
+        #[cfg(feature = "multi_currency")]
+        self.start_exchange_rate_refresh();
+        #[cfg(feature = "subscriptions")]
+        self.schedule_renewal_job(ctx);
+        #[cfg(feature = "bookings")]
+        self.start_calendar_import_worker();
+
         *self.state.write() = PluginState::Active;
         info!("RustCommerce plugin activated successfully");
 
         Ok(())
     }
 
-    async fn deactivate(&self, _ctx: &AppContext) -> Result<()> {
+    async fn deactivate(&self, ctx: &AppContext) -> Result<()> {
         info!("Deactivating RustCommerce plugin");
 
+        // Stop background workers and jobs
+        for worker in self.workers.lock().drain(..) {
+            worker.abort();
+        }
+        #[cfg(feature = "subscriptions")]
+        ctx.jobs().cancel(subscription::RENEWAL_JOB);
+
         // Clear services
         *self.pricing_service.write() = None;
         *self.cart_service.write() = None;
@@ -317,6 +701,24 @@ impl Plugin for RustCommercePlugin {
         *self.tax_service.write() = None;
         *self.order_service.write() = None;
         *self.customer_service.write() = None;
+        #[cfg(feature = "multi_currency")]
+        {
+            *self.currency_service.write() = None;
+            *self.exchange_rate_service.write() = None;
+        }
+        #[cfg(feature = "subscriptions")]
+        {
+            *self.subscription_service.write() = None;
+        }
+        #[cfg(feature = "bookings")]
+        {
+            *self.booking_service.write() = None;
+            *self.booking_calendar_service.write() = None;
+        }
+        #[cfg(feature = "memberships")]
+        {
+            *self.membership_service.write() = None;
+        }
 
         *self.state.write() = PluginState::Inactive;
         info!("RustCommerce plugin deactivated");
@@ -332,6 +734,12 @@ impl Plugin for RustCommercePlugin {
         // - Low stock notifications
         // - Abandoned cart emails
         // - Report generation
+        // - Membership status updates (memberships)
+        #[cfg(feature = "bookings")]
+        {
+            self.start_hold_release_worker();
+            self.start_reminder_worker();
+        }
 
         Ok(())
     }
@@ -0,0 +1,1817 @@
+//! Booking Service
+//!
+//! Works out when bookable products can be booked. Availability rules are
+//! resolved by priority into bookable periods for each day, the periods are
+//! split into booking blocks, and existing bookings, padded with the
+//! product's buffers, are subtracted from each resource's capacity.
+//! Booking costs are built from base, block and resource costs, with
+//! pricing rules adjusting each block they match.
+//!
+//! Slots are held while a booking sits in a cart and released when the
+//! cart expires. Products that require confirmation wait in an admin
+//! queue; customers can cancel until the product's cancellation limit,
+//! with paid bookings refunded automatically.
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use rust_decimal::Decimal;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::models::booking::{
+    AvailabilityCheckRequest, AvailabilityCheckResponse, AvailabilityRule, BookableProduct, Booking,
+    BookingCost, BookingCostAdjustment, BookingRequest, BookingResource, BookingSettings, BookingStatus,
+    BookingType, CartBooking, DefaultAvailability, DurationUnit, PricingRule, TimeSlot,
+};
+use crate::models::cart::{Cart, CartItem};
+use crate::models::email_templates::SendEmailRequest;
+use crate::models::order::Order;
+use crate::models::payment::{RefundRequest, RefundResult};
+use crate::models::product::Product;
+use crate::payments::gateway::PaymentGatewayRegistry;
+use crate::services::booking_calendar::{BookingCalendarService, BookingEvent};
+use crate::settings::RustCommerceSettings;
+
+/// Start and end of a booked or bookable period
+type Span = (DateTime<Utc>, DateTime<Utc>);
+
+/// Booking errors
+#[derive(Debug, Clone)]
+pub enum BookingError {
+    InvalidDateRange,
+    DateRangeTooLong(i64),
+    TooFewPersons(i32),
+    TooManyPersons(i32),
+    ResourceNotFound,
+    InvalidDuration { min: i32, max: Option<i32> },
+    SlotUnavailable,
+    HoldExpired,
+    InvalidStatus(BookingStatus),
+    NotCancellable,
+    CancellationWindowClosed(DateTime<Utc>),
+}
+
+impl std::fmt::Display for BookingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidDateRange => write!(f, "The end date must not be before the start date"),
+            Self::DateRangeTooLong(days) => write!(f, "Availability can be checked for at most {} days", days),
+            Self::TooFewPersons(min) => write!(f, "Bookings need at least {} persons", min),
+            Self::TooManyPersons(max) => write!(f, "Bookings are limited to {} persons", max),
+            Self::ResourceNotFound => write!(f, "The selected resource is not available for this product"),
+            Self::InvalidDuration { min, max: Some(max) } => write!(f, "Bookings must last {} to {} blocks", min, max),
+            Self::InvalidDuration { min, max: None } => write!(f, "Bookings must last at least {} blocks", min),
+            Self::SlotUnavailable => write!(f, "The selected time is no longer available"),
+            Self::HoldExpired => write!(f, "The hold on this booking has expired"),
+            Self::InvalidStatus(status) => write!(f, "Booking cannot be updated while {:?}", status),
+            Self::NotCancellable => write!(f, "This booking cannot be cancelled"),
+            Self::CancellationWindowClosed(deadline) => {
+                write!(f, "Bookings could only be cancelled until {}", deadline.format("%Y-%m-%d %H:%M UTC"))
+            }
+        }
+    }
+}
+
+impl std::error::Error for BookingError {}
+
+/// Bookings holding one resource's capacity, sorted by start and padded
+/// with buffers
+struct BookedTimes {
+    spans: Vec<Span>,
+    longest: Duration,
+}
+
+impl BookedTimes {
+    fn new(mut spans: Vec<Span>) -> Self {
+        spans.sort();
+        let longest = spans.iter().map(|(start, end)| *end - *start).max().unwrap_or_else(Duration::zero);
+        Self { spans, longest }
+    }
+
+    /// Number of bookings overlapping a period
+    fn overlapping(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> i32 {
+        // Only bookings starting within `longest` before the period can reach into it
+        let from = self.spans.partition_point(|span| span.0 < start - self.longest);
+        let to = self.spans.partition_point(|span| span.0 < end);
+        self.spans[from..to].iter().filter(|span| span.1 > start).count() as i32
+    }
+}
+
+/// Result of cancelling a booking
+#[derive(Debug, Clone)]
+pub struct BookingCancellation {
+    /// Refund of a paid booking; a failed refund is left for the admin
+    pub refund: Option<RefundResult>,
+    pub email: Option<SendEmailRequest>,
+}
+
+/// In-cart holds to check, with the carts they belong to
+#[derive(Debug, Clone, Default)]
+pub struct DueHolds {
+    pub bookings: Vec<Booking>,
+    pub carts: Vec<Cart>,
+}
+
+/// An upcoming booking that may need a reminder, with its product
+#[derive(Debug, Clone)]
+pub struct DueReminder {
+    pub booking: Booking,
+    pub product: BookableProduct,
+    pub product_name: String,
+}
+
+/// Booking service
+pub struct BookingService {
+    settings: RustCommerceSettings,
+    booking_settings: BookingSettings,
+    gateways: Arc<PaymentGatewayRegistry>,
+}
+
+impl BookingService {
+    /// Longest date range one availability check may cover
+    pub const MAX_RANGE_DAYS: i64 = 92;
+    /// Booking meta key holding the cart an in-cart hold belongs to
+    pub const HOLD_CART_META: &'static str = "hold_cart_id";
+    /// Booking meta key holding when the hold's cart was due to expire
+    /// when the hold was made
+    pub const HOLD_EXPIRES_META: &'static str = "hold_expires_at";
+    /// Booking meta key recording payment of a booking awaiting confirmation
+    pub const PAID_META: &'static str = "paid_at";
+    /// Booking meta key recording that the reminder email was sent
+    pub const REMINDER_SENT_META: &'static str = "reminder_sent_at";
+    /// Template key for the booking confirmed email
+    pub const CONFIRMED_TEMPLATE: &'static str = "booking_confirmed";
+    /// Template key for the booking cancelled email
+    pub const CANCELLED_TEMPLATE: &'static str = "booking_cancelled";
+    /// Template key for the booking reminder email
+    pub const REMINDER_TEMPLATE: &'static str = "booking_reminder";
+
+    /// Create a new booking service
+    pub fn new(
+        settings: RustCommerceSettings,
+        booking_settings: BookingSettings,
+        gateways: Arc<PaymentGatewayRegistry>,
+    ) -> Self {
+        Self { settings, booking_settings, gateways }
+    }
+
+    /// A local time in the product's timezone. Times repeated when clocks
+    /// go back resolve to the first; times skipped when they go forward
+    /// don't exist.
+    fn to_utc(timezone: &Tz, local: NaiveDateTime) -> Option<DateTime<Utc>> {
+        timezone.from_local_datetime(&local).earliest().map(|dt| dt.with_timezone(&Utc))
+    }
+
+    /// Rules that apply to a resource, winning rule first: lowest priority
+    /// number, then resource rules over product rules, then later rules
+    /// over earlier ones
+    pub fn ranked_rules<'a>(
+        product: &'a BookableProduct,
+        resource: Option<&'a BookingResource>,
+    ) -> Vec<&'a AvailabilityRule> {
+        let resource_rules = resource.map_or(&[][..], |r| &r.availability_rules[..]);
+        let mut ranked: Vec<_> = resource_rules.iter().enumerate()
+            .map(|(i, rule)| ((rule.priority, 0, Reverse(i)), rule))
+            .chain(product.availability_rules.iter().enumerate()
+                .map(|(i, rule)| ((rule.priority, 1, Reverse(i)), rule)))
+            .collect();
+        ranked.sort_by_key(|(rank, _)| *rank);
+        ranked.into_iter().map(|(_, rule)| rule).collect()
+    }
+
+    /// Whether a local date, or a time on it, is bookable. Without a time
+    /// only rules covering whole days count.
+    pub fn is_bookable(
+        product: &BookableProduct,
+        rules: &[&AvailabilityRule],
+        date: NaiveDate,
+        time: Option<NaiveTime>,
+    ) -> bool {
+        rules.iter()
+            .find(|rule| {
+                match time {
+                    Some(time) => rule.covers(date, time),
+                    None => rule.applies_on(date) && !rule.has_time_range(),
+                }
+            })
+            .map_or(product.default_date_availability == DefaultAvailability::Available, |rule| rule.is_bookable())
+    }
+
+    /// Bookable periods of a local date
+    pub fn bookable_periods(
+        product: &BookableProduct,
+        rules: &[&AvailabilityRule],
+        date: NaiveDate,
+    ) -> Vec<(NaiveDateTime, NaiveDateTime)> {
+        // Availability only changes where a rule's time range starts or
+        // ends, including ranges running past midnight from the day before
+        let previous = date.pred_opt();
+        let mut changes = vec![NaiveTime::MIN];
+        for rule in rules.iter().filter(|r| r.applies_on(date) || previous.is_some_and(|d| r.applies_on(d))) {
+            changes.extend(rule.from_time);
+            changes.extend(rule.to_time);
+        }
+        changes.sort();
+        changes.dedup();
+
+        let end_of_day = date.and_time(NaiveTime::MIN) + Duration::days(1);
+        let mut periods: Vec<(NaiveDateTime, NaiveDateTime)> = Vec::new();
+        for (i, time) in changes.iter().enumerate() {
+            if !Self::is_bookable(product, rules, date, Some(*time)) {
+                continue;
+            }
+            let start = date.and_time(*time);
+            let end = changes.get(i + 1).map_or(end_of_day, |next| date.and_time(*next));
+            match periods.last_mut() {
+                Some(last) if last.1 == start => last.1 = end,
+                _ => periods.push((start, end)),
+            }
+        }
+        periods
+    }
+
+    /// Booking blocks starting on a local date that the rules allow
+    fn blocks_on(
+        product: &BookableProduct,
+        rules: &[&AvailabilityRule],
+        date: NaiveDate,
+    ) -> Vec<Span> {
+        let timezone = product.timezone;
+        let block = Duration::minutes(product.block_minutes());
+
+        if product.duration_unit.is_daily() {
+            let days = product.block_minutes() / DurationUnit::Day.minutes();
+            let bookable = (0..days).all(|i| Self::is_bookable(product, rules, date + Duration::days(i), None));
+            return match Self::to_utc(&timezone, date.and_time(NaiveTime::MIN)) {
+                Some(start) if bookable => vec![(start, start + block)],
+                _ => vec![],
+            };
+        }
+
+        let mut blocks = Vec::new();
+        for (opens, closes) in Self::bookable_periods(product, rules, date) {
+            let mut start = opens;
+            while start + block <= closes {
+                if let Some(starts_at) = Self::to_utc(&timezone, start) {
+                    blocks.push((starts_at, starts_at + block));
+                }
+                start += block;
+            }
+        }
+        blocks
+    }
+
+    /// Check the requested persons against the product's limits
+    pub fn validate_persons(product: &BookableProduct, persons: Option<i32>) -> Result<(), BookingError> {
+        if !product.has_persons {
+            return Ok(());
+        }
+        let persons = persons.unwrap_or(1);
+        if let Some(min) = product.min_persons.filter(|min| persons < *min) {
+            return Err(BookingError::TooFewPersons(min));
+        }
+        if let Some(max) = product.max_persons.filter(|max| persons > *max) {
+            return Err(BookingError::TooManyPersons(max));
+        }
+        Ok(())
+    }
+
+    /// Number of blocks a booking covers. Only customer-defined bookings
+    /// can last more than one block.
+    pub fn booking_blocks(product: &BookableProduct, request: &BookingRequest) -> Result<i32, BookingError> {
+        if product.booking_type != BookingType::CustomerDefined {
+            return Ok(1);
+        }
+        let (min, max) = (product.min_duration.unwrap_or(1).max(1), product.max_duration);
+        let blocks = request.duration.unwrap_or(min);
+        if blocks < min || max.is_some_and(|max| blocks > max) {
+            return Err(BookingError::InvalidDuration { min, max });
+        }
+        Ok(blocks)
+    }
+
+    /// Calculate a booking's cost. Base costs are charged once; pricing
+    /// rules adjust the cost of each block they match, in priority order.
+    /// The total is rounded to the minor unit of `currency`.
+    pub fn calculate_cost(
+        &self,
+        product: &BookableProduct,
+        resource: Option<&BookingResource>,
+        request: &BookingRequest,
+        currency: &str,
+    ) -> Result<BookingCost, BookingError> {
+        Self::validate_persons(product, request.persons)?;
+        let blocks = Self::booking_blocks(product, request)?;
+        let persons = request.persons.unwrap_or(1);
+        let timezone = product.timezone;
+        let block = Duration::minutes(product.block_minutes());
+
+        // Stable sort, so rules with equal priority apply in list order
+        let mut rules: Vec<&PricingRule> = product.pricing_rules.iter().collect();
+        rules.sort_by_key(|rule| rule.priority);
+
+        let resource_block_cost = resource.and_then(|r| r.block_cost).unwrap_or(Decimal::ZERO);
+        let mut adjustments: Vec<BookingCostAdjustment> = Vec::new();
+        let mut blocks_total = Decimal::ZERO;
+        for i in 0..blocks {
+            let local = (request.start_date + block * i).with_timezone(&timezone).naive_local();
+            let mut cost = product.block_cost + resource_block_cost;
+            for rule in rules.iter().filter(|r| r.matches(local.date(), local.time(), persons, blocks)) {
+                let adjusted = rule.apply(cost);
+                match adjustments.iter_mut().find(|a| a.rule_id == rule.id) {
+                    Some(adjustment) => {
+                        adjustment.blocks += 1;
+                        adjustment.amount += adjusted - cost;
+                    }
+                    None => adjustments.push(BookingCostAdjustment {
+                        rule_id: rule.id,
+                        rule_type: rule.rule_type,
+                        modifier: rule.modifier,
+                        blocks: 1,
+                        amount: adjusted - cost,
+                    }),
+                }
+                cost = adjusted;
+            }
+            blocks_total += cost;
+        }
+
+        let resource_base_cost = resource.and_then(|r| r.base_cost).unwrap_or(Decimal::ZERO);
+        let decimals = crate::currency::minor_units(currency);
+        let total = (product.base_cost + resource_base_cost + blocks_total).max(Decimal::ZERO);
+
+        Ok(BookingCost {
+            blocks,
+            base_cost: product.base_cost,
+            block_cost: product.block_cost * Decimal::from(blocks),
+            resource_cost: resource_base_cost + resource_block_cost * Decimal::from(blocks),
+            adjustments,
+            total: total.round_dp(decimals),
+        })
+    }
+
+    /// Find room for a booking, checking every block it covers is bookable
+    /// and has capacity. Returns the booked period and, for products with
+    /// resources, the resource to book, trying resources in sort order.
+    pub fn find_slot(
+        &self,
+        product: &BookableProduct,
+        resources: &[BookingResource],
+        bookings: &[Booking],
+        request: &BookingRequest,
+        now: DateTime<Utc>,
+    ) -> Result<(Span, Option<Uuid>), BookingError> {
+        Self::validate_persons(product, request.persons)?;
+        let blocks = Self::booking_blocks(product, request)?;
+        if request.start_date < now {
+            return Err(BookingError::SlotUnavailable);
+        }
+
+        let timezone = product.timezone;
+        let block = Duration::minutes(product.block_minutes());
+        let period = (request.start_date, request.start_date + block * blocks);
+
+        let mut pools = Self::capacity_pools(product, resources, request.resource_id)?;
+        pools.sort_by_key(|(resource, _)| resource.map(|r| r.sort_order));
+        for (resource, capacity) in pools {
+            let rules = Self::ranked_rules(product, resource);
+            let booked = Self::booked_times(product, resource, bookings);
+            let fits = (0..blocks).all(|i| {
+                let start = period.0 + block * i;
+                let date = start.with_timezone(&timezone).date_naive();
+                Self::blocks_on(product, &rules, date).contains(&(start, start + block))
+                    && booked.overlapping(start, start + block) < capacity
+            });
+            if fits {
+                return Ok((period, resource.map(|r| r.id)));
+            }
+        }
+        Err(BookingError::SlotUnavailable)
+    }
+
+    /// Hold a slot for a booking being added to a cart. The hold lasts
+    /// until the cart expires.
+    pub fn hold(
+        &self,
+        product: &BookableProduct,
+        resources: &[BookingResource],
+        bookings: &[Booking],
+        request: &BookingRequest,
+        cart: &Cart,
+        now: DateTime<Utc>,
+    ) -> Result<(Booking, BookingCost), BookingError> {
+        let ((start_date, end_date), resource_id) = self.find_slot(product, resources, bookings, request, now)?;
+        let resource = resource_id.and_then(|id| resources.iter().find(|r| r.id == id));
+        let currency = cart.currency.as_deref().unwrap_or(&self.settings.general.currency);
+        let cost = self.calculate_cost(product, resource, request, currency)?;
+
+        let booking = Booking {
+            id: Uuid::now_v7(),
+            site_id: None,
+            product_id: product.product_id,
+            order_id: None,
+            order_item_id: None,
+            customer_id: cart.customer_id,
+            status: BookingStatus::InCart,
+            start_date,
+            end_date,
+            all_day: product.duration_unit.is_daily(),
+            persons: request.persons.unwrap_or(1),
+            resource_id,
+            cost: cost.total,
+            customer_name: None,
+            customer_email: None,
+            customer_phone: None,
+            customer_note: None,
+            meta: HashMap::from([
+                (Self::HOLD_CART_META.to_string(), cart.id.to_string()),
+                (Self::HOLD_EXPIRES_META.to_string(), cart.expires_at.to_rfc3339()),
+            ]),
+            created_at: now,
+            updated_at: None,
+        };
+        Ok((booking, cost))
+    }
+
+    fn meta_date(booking: &Booking, key: &str) -> Option<DateTime<Utc>> {
+        booking.meta.get(key)
+            .and_then(|date| DateTime::parse_from_rfc3339(date).ok())
+            .map(|date| date.with_timezone(&Utc))
+    }
+
+    /// Release an in-cart hold, e.g. when the item is removed from the cart
+    pub fn release_hold(booking: &mut Booking, now: DateTime<Utc>) -> bool {
+        if booking.status != BookingStatus::InCart {
+            return false;
+        }
+        booking.status = BookingStatus::WasPInCart;
+        booking.meta.remove(Self::HOLD_CART_META);
+        booking.meta.remove(Self::HOLD_EXPIRES_META);
+        booking.updated_at = Some(now);
+        true
+    }
+
+    /// When a hold runs out: when its cart expires, or when the cart was
+    /// due to expire if it isn't among `carts`
+    fn hold_expires_at(booking: &Booking, carts: &[Cart]) -> Option<DateTime<Utc>> {
+        let cart = booking.meta.get(Self::HOLD_CART_META)
+            .and_then(|id| Uuid::parse_str(id).ok())
+            .and_then(|id| carts.iter().find(|c| c.id == id));
+        match cart {
+            Some(cart) => Some(cart.expires_at),
+            None => Self::meta_date(booking, Self::HOLD_EXPIRES_META),
+        }
+    }
+
+    /// Release every hold whose cart has expired, returning the released
+    /// bookings. `carts` are the carts the holds belong to, so holds follow
+    /// carts whose expiry was extended.
+    pub fn release_expired_holds(bookings: &mut [Booking], carts: &[Cart], now: DateTime<Utc>) -> Vec<Uuid> {
+        bookings.iter_mut()
+            .filter(|b| b.status == BookingStatus::InCart)
+            .filter(|b| Self::hold_expires_at(b, carts).is_none_or(|until| until <= now))
+            .filter_map(|b| Self::release_hold(b, now).then_some(b.id))
+            .collect()
+    }
+
+    /// Turn a held booking into an order's booking. Products that require
+    /// confirmation join the confirmation queue.
+    pub fn place_order(
+        &self,
+        product: &BookableProduct,
+        booking: &mut Booking,
+        order: &Order,
+        order_item_id: Option<Uuid>,
+        now: DateTime<Utc>,
+    ) -> Result<(), BookingError> {
+        match booking.status {
+            BookingStatus::InCart => {}
+            BookingStatus::WasPInCart => return Err(BookingError::HoldExpired),
+            status => return Err(BookingError::InvalidStatus(status)),
+        }
+
+        booking.status = if product.requires_confirmation {
+            BookingStatus::Pending
+        } else {
+            BookingStatus::Unpaid
+        };
+        booking.order_id = Some(order.id);
+        booking.order_item_id = order_item_id;
+        booking.customer_id = order.customer_id.or(booking.customer_id);
+        booking.customer_name = Some(order.get_customer_name());
+        booking.customer_email = Some(order.billing.email.clone()).filter(|e| !e.is_empty());
+        booking.customer_phone = Some(order.billing.phone.clone()).filter(|p| !p.is_empty());
+        booking.customer_note = order.customer_note.clone();
+        booking.meta.remove(Self::HOLD_CART_META);
+        booking.meta.remove(Self::HOLD_EXPIRES_META);
+        booking.updated_at = Some(now);
+        Ok(())
+    }
+
+    fn is_paid(booking: &Booking) -> bool {
+        matches!(booking.status, BookingStatus::Paid | BookingStatus::Complete)
+            || booking.meta.contains_key(Self::PAID_META)
+    }
+
+    /// Record payment of a booking's order. Bookings awaiting confirmation
+    /// stay in the queue and become paid once confirmed. Bookings that need
+    /// no confirmation are confirmed by the payment, so their confirmation
+    /// email is returned.
+    pub fn mark_paid(
+        &self,
+        booking: &mut Booking,
+        product: &BookableProduct,
+        product_name: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<SendEmailRequest>, BookingError> {
+        let confirmed_by_payment = match booking.status {
+            BookingStatus::Unpaid => true,
+            BookingStatus::Confirmed => false,
+            BookingStatus::Pending => {
+                booking.meta.insert(Self::PAID_META.to_string(), now.to_rfc3339());
+                booking.updated_at = Some(now);
+                return Ok(None);
+            }
+            status => return Err(BookingError::InvalidStatus(status)),
+        };
+        booking.status = BookingStatus::Paid;
+        booking.updated_at = Some(now);
+        if !confirmed_by_payment {
+            return Ok(None);
+        }
+        Ok(self.confirmation_email(booking, product, product_name))
+    }
+
+    /// Bookings waiting for an admin to confirm them, soonest first
+    pub fn confirmation_queue(bookings: &[Booking]) -> Vec<&Booking> {
+        let mut queue: Vec<&Booking> = bookings.iter()
+            .filter(|b| b.status == BookingStatus::Pending)
+            .collect();
+        queue.sort_by_key(|b| b.start_date);
+        queue
+    }
+
+    /// Confirm a booking from the queue and build the customer email
+    pub fn confirm(
+        &self,
+        booking: &mut Booking,
+        product: &BookableProduct,
+        product_name: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<SendEmailRequest>, BookingError> {
+        if booking.status != BookingStatus::Pending {
+            return Err(BookingError::InvalidStatus(booking.status));
+        }
+
+        booking.status = if Self::is_paid(booking) { BookingStatus::Paid } else { BookingStatus::Confirmed };
+        booking.meta.remove(Self::PAID_META);
+        booking.updated_at = Some(now);
+        Ok(self.confirmation_email(booking, product, product_name))
+    }
+
+    /// Booking confirmed email with the booking as a calendar attachment,
+    /// and a pay link when the order has not been paid yet
+    pub fn confirmation_email(
+        &self,
+        booking: &Booking,
+        product: &BookableProduct,
+        product_name: &str,
+    ) -> Option<SendEmailRequest> {
+        let mut variables = HashMap::new();
+        if !Self::is_paid(booking) {
+            variables.insert("pay_url".to_string(), serde_json::json!(booking.order_id.map(|id| self.pay_url(id))));
+        }
+        let mut email = self.booking_email(Self::CONFIRMED_TEMPLATE, booking, product, product_name, variables)?;
+        email.attachments = Some(vec![BookingCalendarService::attachment(&BookingEvent {
+            booking,
+            product,
+            product_name,
+        })]);
+        Some(email)
+    }
+
+    /// Decline a booking from the queue, refunding it if it was paid
+    pub async fn reject(
+        &self,
+        booking: &mut Booking,
+        product: &BookableProduct,
+        product_name: &str,
+        order: Option<&Order>,
+        reason: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Result<BookingCancellation, BookingError> {
+        if booking.status != BookingStatus::Pending {
+            return Err(BookingError::InvalidStatus(booking.status));
+        }
+        let refund = self.cancel(booking, order, "Booking not confirmed", now).await;
+
+        let mut variables = HashMap::new();
+        variables.insert("reason".to_string(), serde_json::json!(reason));
+        let email = self.booking_email(Self::CANCELLED_TEMPLATE, booking, product, product_name, variables);
+        Ok(BookingCancellation { refund, email })
+    }
+
+    /// Cancel a booking for the customer. Cancellation closes
+    /// `cancel_limit` hours before the start; paid bookings are refunded.
+    pub async fn cancel_by_customer(
+        &self,
+        booking: &mut Booking,
+        product: &BookableProduct,
+        product_name: &str,
+        order: Option<&Order>,
+        now: DateTime<Utc>,
+    ) -> Result<BookingCancellation, BookingError> {
+        if !product.can_be_cancelled {
+            return Err(BookingError::NotCancellable);
+        }
+        if !matches!(
+            booking.status,
+            BookingStatus::Unpaid | BookingStatus::Pending | BookingStatus::Confirmed | BookingStatus::Paid
+        ) {
+            return Err(BookingError::InvalidStatus(booking.status));
+        }
+        let deadline = booking.start_date - Duration::hours(product.cancel_limit.unwrap_or(0).max(0) as i64);
+        if now >= deadline {
+            return Err(BookingError::CancellationWindowClosed(deadline));
+        }
+
+        let refund = self.cancel(booking, order, "Booking cancelled by customer", now).await;
+        let email = self.booking_email(Self::CANCELLED_TEMPLATE, booking, product, product_name, HashMap::new());
+        Ok(BookingCancellation { refund, email })
+    }
+
+    /// Cancel a booking and refund its cost to the order's payment method
+    /// if it was paid
+    async fn cancel(
+        &self,
+        booking: &mut Booking,
+        order: Option<&Order>,
+        reason: &str,
+        now: DateTime<Utc>,
+    ) -> Option<RefundResult> {
+        let paid = Self::is_paid(booking);
+        booking.status = BookingStatus::Cancelled;
+        booking.meta.remove(Self::PAID_META);
+        booking.updated_at = Some(now);
+
+        if !paid || booking.cost <= Decimal::ZERO {
+            return None;
+        }
+        let order = order?;
+        let gateway_id = order.payment_method.as_deref()?;
+        let request = RefundRequest {
+            transaction_id: order.transaction_id.clone()?,
+            amount: Some(booking.cost),
+            reason: Some(reason.to_string()),
+        };
+
+        Some(self.gateways.process_refund(gateway_id, request).await.unwrap_or_else(|e| RefundResult {
+            success: false,
+            refund_id: None,
+            amount: Decimal::ZERO,
+            message: Some(e.to_string()),
+            raw_response: None,
+        }))
+    }
+
+    /// Paid and confirmed bookings starting within `reminder_hours` that
+    /// have not been reminded yet
+    pub fn reminders_due<'a>(&self, bookings: &'a [Booking], now: DateTime<Utc>) -> Vec<&'a Booking> {
+        bookings.iter().filter(|b| self.is_reminder_due(b, now)).collect()
+    }
+
+    fn is_reminder_due(&self, booking: &Booking, now: DateTime<Utc>) -> bool {
+        let window = Duration::hours(self.booking_settings.reminder_hours);
+        matches!(booking.status, BookingStatus::Paid | BookingStatus::Confirmed)
+            && !booking.is_external()
+            && booking.start_date > now
+            && booking.start_date - window <= now
+            && !booking.meta.contains_key(Self::REMINDER_SENT_META)
+    }
+
+    /// Build the reminder email and record that it was sent
+    pub fn reminder_email(
+        &self,
+        booking: &mut Booking,
+        product: &BookableProduct,
+        product_name: &str,
+        now: DateTime<Utc>,
+    ) -> Option<SendEmailRequest> {
+        let email = self.booking_email(Self::REMINDER_TEMPLATE, booking, product, product_name, HashMap::new())?;
+        booking.meta.insert(Self::REMINDER_SENT_META.to_string(), now.to_rfc3339());
+        Some(email)
+    }
+
+    /// Release the holds whose carts have expired, returning the released
+    /// bookings
+    pub fn release_due_holds(due: DueHolds, now: DateTime<Utc>) -> Vec<Booking> {
+        let DueHolds { mut bookings, carts } = due;
+        let released = Self::release_expired_holds(&mut bookings, &carts, now);
+        bookings.retain(|b| released.contains(&b.id));
+        bookings
+    }
+
+    /// Reminder emails for the bookings that are due one, each with its
+    /// booking marked as reminded
+    pub fn reminder_emails(&self, due: Vec<DueReminder>, now: DateTime<Utc>) -> Vec<(Booking, SendEmailRequest)> {
+        due.into_iter()
+            .filter(|d| self.is_reminder_due(&d.booking, now))
+            .filter_map(|DueReminder { mut booking, product, product_name }| {
+                let email = self.reminder_email(&mut booking, &product, &product_name, now)?;
+                Some((booking, email))
+            })
+            .collect()
+    }
+
+    /// Link for paying a booking's order
+    pub fn pay_url(&self, order_id: Uuid) -> String {
+        format!(
+            "{}/checkout/order-pay/{}",
+            self.booking_settings.store_url.trim_end_matches('/'),
+            order_id
+        )
+    }
+
+    fn booking_email(
+        &self,
+        template_key: &str,
+        booking: &Booking,
+        product: &BookableProduct,
+        product_name: &str,
+        mut variables: HashMap<String, serde_json::Value>,
+    ) -> Option<SendEmailRequest> {
+        let to_email = booking.customer_email.clone().filter(|e| !e.is_empty())?;
+        let timezone = product.timezone;
+        let format = if booking.all_day { "%Y-%m-%d" } else { "%Y-%m-%d %H:%M" };
+
+        variables.insert("store_name".to_string(), serde_json::json!(self.settings.general.store_name));
+        variables.insert("customer_name".to_string(), serde_json::json!(booking.customer_name));
+        variables.insert("product_name".to_string(), serde_json::json!(product_name));
+        variables.insert("booking_id".to_string(), serde_json::json!(booking.id));
+        variables.insert("booking_start".to_string(), serde_json::json!(
+            booking.start_date.with_timezone(&timezone).format(format).to_string()
+        ));
+        variables.insert("booking_end".to_string(), serde_json::json!(
+            booking.end_date.with_timezone(&timezone).format(format).to_string()
+        ));
+        variables.insert("persons".to_string(), serde_json::json!(booking.persons));
+
+        Some(SendEmailRequest {
+            template_id: None,
+            template_key: Some(template_key.to_string()),
+            to_email,
+            to_name: booking.customer_name.clone(),
+            variables,
+            cc: None,
+            bcc: None,
+            attachments: None,
+            schedule_at: None,
+            subject_override: None,
+            from_name_override: None,
+            from_email_override: None,
+            reply_to_override: None,
+        })
+    }
+
+    /// Cart item for a held booking. The booking details are stored in the
+    /// item's meta, so each booking is its own cart line.
+    pub fn cart_item(
+        &self,
+        product: &Product,
+        hold: &Booking,
+        cost: BookingCost,
+    ) -> CartItem {
+        let price = cost.total;
+        let booking = CartBooking {
+            booking_id: Some(hold.id),
+            product_id: hold.product_id,
+            start_date: hold.start_date,
+            end_date: hold.end_date,
+            persons: hold.persons,
+            resource_id: hold.resource_id,
+            cost,
+        };
+        let meta = HashMap::from([(
+            CartBooking::META_KEY.to_string(),
+            serde_json::to_value(&booking).unwrap_or_default(),
+        )]);
+
+        let mut item = CartItem::from_product(product, 1, None, meta);
+        item.price = price;
+        item.regular_price = price;
+        item.base_price = Some(price);
+        item.base_regular_price = Some(price);
+        item.sold_individually = true;
+        item.set_quantity(1);
+        item
+    }
+
+    /// Resources to check with their capacity, or the product's own block
+    /// capacity when it has no resources
+    fn capacity_pools<'a>(
+        product: &BookableProduct,
+        resources: &'a [BookingResource],
+        resource_id: Option<Uuid>,
+    ) -> Result<Vec<(Option<&'a BookingResource>, i32)>, BookingError> {
+        if !product.has_resources {
+            return Ok(vec![(None, product.block_capacity.max(0))]);
+        }
+        match resource_id {
+            Some(id) => resources.iter()
+                .find(|r| r.id == id)
+                .map(|r| vec![(Some(r), r.quantity.max(0))])
+                .ok_or(BookingError::ResourceNotFound),
+            None => Ok(resources.iter().map(|r| (Some(r), r.quantity.max(0))).collect()),
+        }
+    }
+
+    /// Bookings holding a pool's capacity, padded with the product's buffers.
+    /// Resources are shared, so their bookings for any product count.
+    fn booked_times(product: &BookableProduct, resource: Option<&BookingResource>, bookings: &[Booking]) -> BookedTimes {
+        let before = Duration::minutes(product.buffer_minutes(product.buffer_before));
+        let after = Duration::minutes(product.buffer_minutes(product.buffer_after));
+        let spans = bookings.iter()
+            .filter(|b| b.blocks_availability())
+            .filter(|b| match resource {
+                Some(resource) => b.resource_id == Some(resource.id),
+                None => b.product_id == product.product_id,
+            })
+            .map(|b| (b.start_date - before, b.end_date + after))
+            .collect();
+        BookedTimes::new(spans)
+    }
+
+    /// Available blocks between two local dates, with full and unavailable
+    /// dates for a calendar view. Each block is priced as a booking starting
+    /// on it, for the minimum duration, on the first resource in sort order
+    /// with room, in `currency`.
+    pub fn check_availability(
+        &self,
+        product: &BookableProduct,
+        resources: &[BookingResource],
+        bookings: &[Booking],
+        request: &AvailabilityCheckRequest,
+        currency: &str,
+        now: DateTime<Utc>,
+    ) -> Result<AvailabilityCheckResponse, BookingError> {
+        let end_date = request.end_date.unwrap_or(request.start_date);
+        if end_date < request.start_date {
+            return Err(BookingError::InvalidDateRange);
+        }
+        if (end_date - request.start_date).num_days() >= Self::MAX_RANGE_DAYS {
+            return Err(BookingError::DateRangeTooLong(Self::MAX_RANGE_DAYS));
+        }
+        Self::validate_persons(product, request.persons)?;
+
+        let dates: Vec<NaiveDate> = request.start_date.iter_days().take_while(|d| *d <= end_date).collect();
+        // Free capacity of each block, and its price on the first pool with room
+        let mut remaining: BTreeMap<NaiveDate, BTreeMap<Span, (i32, Option<Decimal>)>> =
+            dates.iter().map(|date| (*date, BTreeMap::new())).collect();
+
+        let mut pools = Self::capacity_pools(product, resources, request.resource_id)?;
+        pools.sort_by_key(|(resource, _)| resource.map(|r| r.sort_order));
+        for (resource, capacity) in pools {
+            let rules = Self::ranked_rules(product, resource);
+            let booked = Self::booked_times(product, resource, bookings);
+            for date in &dates {
+                let blocks = remaining.entry(*date).or_default();
+                for (start, end) in Self::blocks_on(product, &rules, *date) {
+                    if start < now {
+                        continue;
+                    }
+                    let free = (capacity - booked.overlapping(start, end)).max(0);
+                    let (total, price) = blocks.entry((start, end)).or_insert((0, None));
+                    *total += free;
+                    if free > 0 && price.is_none() {
+                        let booking = BookingRequest {
+                            product_id: product.product_id,
+                            start_date: start,
+                            duration: None,
+                            persons: request.persons,
+                            resource_id: resource.map(|r| r.id),
+                        };
+                        *price = Some(self.calculate_cost(product, resource, &booking, currency)?.total);
+                    }
+                }
+            }
+        }
+
+        let mut response = AvailabilityCheckResponse {
+            available_slots: Vec::new(),
+            unavailable_dates: Vec::new(),
+            fully_booked_dates: Vec::new(),
+        };
+        for (date, blocks) in remaining {
+            if blocks.is_empty() {
+                response.unavailable_dates.push(date);
+                continue;
+            }
+            let before = response.available_slots.len();
+            response.available_slots.extend(blocks.into_iter()
+                .filter_map(|((start, end), (free, price))| price.map(|price| TimeSlot {
+                    start,
+                    end,
+                    available: true,
+                    remaining_capacity: free,
+                    price,
+                })));
+            if response.available_slots.len() == before {
+                response.fully_booked_dates.push(date);
+            }
+        }
+
+        Ok(response)
+    }
+
+    fn job_interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.booking_settings.job_minutes.max(1) as u64 * 60)
+    }
+
+    /// Release expired holds every `job_minutes`. `load_holds` returns the
+    /// in-cart holds with their carts; `on_release` receives the released
+    /// bookings for saving.
+    pub fn spawn_hold_release_worker<L, F>(&self, load_holds: L, on_release: F) -> tokio::task::JoinHandle<()>
+    where
+        L: Fn(DateTime<Utc>) -> DueHolds + Send + Sync + 'static,
+        F: Fn(Vec<Booking>) + Send + Sync + 'static,
+    {
+        let period = self.job_interval();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                let now = Utc::now();
+                let released = Self::release_due_holds(load_holds(now), now);
+                if !released.is_empty() {
+                    on_release(released);
+                }
+            }
+        })
+    }
+
+    /// Send booking reminders every `job_minutes`. `load_upcoming` returns
+    /// the bookings starting soon; `on_reminder` receives each reminded
+    /// booking for saving, with its email to send.
+    pub fn spawn_reminder_worker<L, F>(self: Arc<Self>, load_upcoming: L, on_reminder: F) -> tokio::task::JoinHandle<()>
+    where
+        L: Fn(DateTime<Utc>) -> Vec<DueReminder> + Send + Sync + 'static,
+        F: Fn(Booking, SendEmailRequest) + Send + Sync + 'static,
+    {
+        let period = self.job_interval();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                let now = Utc::now();
+                for (booking, email) in self.reminder_emails(load_upcoming(now), now) {
+                    on_reminder(booking, email);
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::booking::{
+        AvailabilityRuleType, CalendarDisplay, PriceModifier, PricingRuleType, ResourceAssignment,
+    };
+    use crate::models::customer::Address;
+    use crate::models::order::OrderStatus;
+    use crate::models::payment::{GatewayFeature, PaymentRequest, PaymentResult};
+    use crate::payments::gateway::{GatewayError, GatewaySettingField, PaymentGateway};
+    use async_trait::async_trait;
+    use chrono::Weekday;
+    use rust_decimal_macros::dec;
+    use std::collections::HashMap;
+
+    struct TestGateway;
+
+    #[async_trait]
+    impl PaymentGateway for TestGateway {
+        fn id(&self) -> &str { "test" }
+        fn title(&self) -> &str { "Test" }
+        fn description(&self) -> &str { "Test gateway" }
+        fn supports(&self) -> Vec<GatewayFeature> { vec![GatewayFeature::Products, GatewayFeature::Refunds] }
+        fn is_available(&self) -> bool { true }
+        fn get_settings_fields(&self) -> Vec<GatewaySettingField> { Vec::new() }
+
+        async fn process_payment(&self, _request: PaymentRequest) -> Result<PaymentResult, GatewayError> {
+            Err(GatewayError::UnsupportedFeature)
+        }
+
+        async fn process_refund(&self, request: RefundRequest) -> Result<RefundResult, GatewayError> {
+            assert_eq!(request.transaction_id, "txn_booking");
+            Ok(RefundResult {
+                success: true,
+                refund_id: Some("re_booking".to_string()),
+                amount: request.amount.unwrap_or(Decimal::ZERO),
+                message: None,
+                raw_response: None,
+            })
+        }
+    }
+
+    fn service() -> BookingService {
+        let mut gateways = PaymentGatewayRegistry::new();
+        gateways.register(Arc::new(TestGateway));
+        let settings = BookingSettings {
+            store_url: "https://shop.example/".to_string(),
+            ..Default::default()
+        };
+        BookingService::new(RustCommerceSettings::default(), settings, Arc::new(gateways))
+    }
+
+    fn product(unit: DurationUnit, default: DefaultAvailability) -> BookableProduct {
+        BookableProduct {
+            id: Uuid::now_v7(),
+            product_id: Uuid::now_v7(),
+            booking_type: BookingType::FixedDuration,
+            duration: 1,
+            duration_unit: unit,
+            min_duration: None,
+            max_duration: None,
+            buffer_before: None,
+            buffer_after: None,
+            block_capacity: 1,
+            timezone: Tz::UTC,
+            base_cost: Decimal::ZERO,
+            block_cost: Decimal::ZERO,
+            calendar_display: CalendarDisplay::Always,
+            requires_confirmation: false,
+            can_be_cancelled: true,
+            cancel_limit: None,
+            has_persons: false,
+            min_persons: None,
+            max_persons: None,
+            has_resources: false,
+            resources_assignment: ResourceAssignment::AutoAssign,
+            default_date_availability: default,
+            check_availability: true,
+            availability_rules: Vec::new(),
+            pricing_rules: Vec::new(),
+            created_at: Utc::now(),
+            updated_at: None,
+        }
+    }
+
+    fn rule(rule_type: AvailabilityRuleType, priority: i32, bookable: bool) -> AvailabilityRule {
+        AvailabilityRule {
+            id: Uuid::now_v7(),
+            rule_type,
+            priority,
+            bookable,
+            from_date: None,
+            to_date: None,
+            from_time: None,
+            to_time: None,
+            days_of_week: None,
+            specific_dates: None,
+        }
+    }
+
+    fn hours(from: u32, to: u32, priority: i32, bookable: bool) -> AvailabilityRule {
+        AvailabilityRule {
+            from_time: NaiveTime::from_hms_opt(from, 0, 0),
+            to_time: NaiveTime::from_hms_opt(to, 0, 0),
+            ..rule(AvailabilityRuleType::TimeRange, priority, bookable)
+        }
+    }
+
+    fn resource(quantity: i32, rules: Vec<AvailabilityRule>) -> BookingResource {
+        BookingResource {
+            id: Uuid::now_v7(),
+            site_id: None,
+            name: "Court".to_string(),
+            quantity,
+            base_cost: None,
+            block_cost: None,
+            availability_rules: rules,
+            sort_order: 0,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn booking(product: &BookableProduct, resource_id: Option<Uuid>, start: DateTime<Utc>, hours: i64) -> Booking {
+        Booking {
+            id: Uuid::now_v7(),
+            site_id: None,
+            product_id: product.product_id,
+            order_id: None,
+            order_item_id: None,
+            customer_id: None,
+            status: BookingStatus::Paid,
+            start_date: start,
+            end_date: start + Duration::hours(hours),
+            all_day: false,
+            persons: 1,
+            resource_id,
+            cost: Decimal::ZERO,
+            customer_name: None,
+            customer_email: None,
+            customer_phone: None,
+            customer_note: None,
+            meta: HashMap::new(),
+            created_at: Utc::now(),
+            updated_at: None,
+        }
+    }
+
+    fn request(product: &BookableProduct, from: NaiveDate, to: NaiveDate) -> AvailabilityCheckRequest {
+        AvailabilityCheckRequest {
+            product_id: product.product_id,
+            start_date: from,
+            end_date: Some(to),
+            persons: None,
+            resource_id: None,
+        }
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2030, 6, day).unwrap()
+    }
+
+    fn at(day: u32, hour: u32) -> DateTime<Utc> {
+        date(day).and_hms_opt(hour, 0, 0).unwrap().and_utc()
+    }
+
+    fn now() -> DateTime<Utc> {
+        at(1, 0) - Duration::days(1)
+    }
+
+    #[test]
+    fn test_rule_priority_resolves_dates() {
+        let service = service();
+        let mut product = product(DurationUnit::Day, DefaultAvailability::Available);
+        product.block_cost = dec!(50);
+        // 2030-06-01 and 06-08 are Saturdays
+        product.availability_rules = vec![
+            AvailabilityRule {
+                days_of_week: Some(vec![Weekday::Sat, Weekday::Sun]),
+                ..rule(AvailabilityRuleType::DayOfWeek, 10, false)
+            },
+            AvailabilityRule {
+                specific_dates: Some(vec![date(8)]),
+                ..rule(AvailabilityRuleType::SpecificDate, 1, true)
+            },
+            AvailabilityRule {
+                from_date: Some(date(4)),
+                to_date: Some(date(5)),
+                ..rule(AvailabilityRuleType::Holiday, 5, true)
+            },
+        ];
+
+        let response = service
+            .check_availability(&product, &[], &[], &request(&product, date(1), date(9)), "USD", now())
+            .unwrap();
+        assert_eq!(response.unavailable_dates, vec![date(1), date(2), date(4), date(5), date(9)]);
+        assert_eq!(response.available_slots.len(), 4);
+        assert!(response.available_slots.iter().any(|slot| slot.start == at(8, 0)));
+        assert_eq!(response.available_slots[0].price, dec!(50));
+    }
+
+    #[test]
+    fn test_time_rules_build_blocks() {
+        let service = service();
+        let mut product = product(DurationUnit::Hour, DefaultAvailability::NotAvailable);
+        product.availability_rules = vec![hours(9, 17, 10, true), hours(12, 13, 5, false)];
+
+        let response = service
+            .check_availability(&product, &[], &[], &request(&product, date(3), date(3)), "USD", now())
+            .unwrap();
+        let starts: Vec<u32> = response.available_slots.iter()
+            .map(|slot| slot.start.time().format("%H").to_string().parse().unwrap())
+            .collect();
+        assert_eq!(starts, vec![9, 10, 11, 13, 14, 15, 16]);
+
+        // Blocks that have already started are not offered
+        let response = service
+            .check_availability(&product, &[], &[], &request(&product, date(3), date(3)), "USD", at(3, 14))
+            .unwrap();
+        assert_eq!(response.available_slots.len(), 3);
+    }
+
+    #[test]
+    fn test_resource_rules_win_ties() {
+        let mut product = product(DurationUnit::Hour, DefaultAvailability::NotAvailable);
+        product.has_resources = true;
+        product.availability_rules = vec![hours(9, 17, 10, true)];
+        let court = resource(1, vec![hours(9, 11, 10, false)]);
+
+        let rules = BookingService::ranked_rules(&product, Some(&court));
+        let bookable = |hour| BookingService::is_bookable(&product, &rules, date(3), NaiveTime::from_hms_opt(hour, 0, 0));
+        assert!(!bookable(9));
+        assert!(!bookable(10));
+        assert!(bookable(11));
+        assert!(!bookable(17));
+    }
+
+    #[test]
+    fn test_overnight_hours_belong_to_next_day() {
+        let mut product = product(DurationUnit::Hour, DefaultAvailability::NotAvailable);
+        // 2030-06-07 is a Friday
+        product.availability_rules = vec![AvailabilityRule {
+            days_of_week: Some(vec![Weekday::Fri]),
+            ..hours(22, 2, 10, true)
+        }];
+
+        let rules = BookingService::ranked_rules(&product, None);
+        let bookable = |day, hour| BookingService::is_bookable(&product, &rules, date(day), NaiveTime::from_hms_opt(hour, 0, 0));
+        assert!(bookable(7, 23));
+        assert!(bookable(8, 1));
+        assert!(!bookable(7, 1));
+        assert!(!bookable(8, 23));
+        assert!(!bookable(8, 2));
+    }
+
+    #[test]
+    fn test_blocks_follow_product_timezone() {
+        let service = service();
+        let mut product = product(DurationUnit::Hour, DefaultAvailability::NotAvailable);
+        product.timezone = chrono_tz::Europe::Berlin;
+        product.availability_rules = vec![hours(9, 11, 10, true)];
+
+        // Berlin is two hours ahead of UTC in June and one in December
+        let response = service
+            .check_availability(&product, &[], &[], &request(&product, date(3), date(3)), "USD", now())
+            .unwrap();
+        let starts: Vec<_> = response.available_slots.iter().map(|slot| slot.start).collect();
+        assert_eq!(starts, vec![at(3, 7), at(3, 8)]);
+
+        let december = NaiveDate::from_ymd_opt(2030, 12, 3).unwrap();
+        let response = service
+            .check_availability(&product, &[], &[], &request(&product, december, december), "USD", now())
+            .unwrap();
+        assert_eq!(response.available_slots[0].start, december.and_hms_opt(8, 0, 0).unwrap().and_utc());
+    }
+
+    #[test]
+    fn test_bookings_and_buffers_use_capacity() {
+        let service = service();
+        let mut product = product(DurationUnit::Hour, DefaultAvailability::NotAvailable);
+        product.has_resources = true;
+        product.buffer_after = Some(1);
+        product.availability_rules = vec![hours(9, 13, 10, true)];
+        let courts = resource(2, Vec::new());
+        let mut cancelled = booking(&product, Some(courts.id), at(3, 9), 1);
+        cancelled.status = BookingStatus::Cancelled;
+        let bookings = vec![
+            booking(&product, Some(courts.id), at(3, 10), 1),
+            booking(&product, Some(courts.id), at(3, 12), 1),
+            booking(&product, Some(courts.id), at(3, 12), 1),
+            cancelled,
+        ];
+
+        let resources = vec![courts];
+        let response = service
+            .check_availability(&product, &resources, &bookings, &request(&product, date(3), date(3)), "USD", now())
+            .unwrap();
+        let free: Vec<(DateTime<Utc>, i32)> = response.available_slots.iter()
+            .map(|slot| (slot.start, slot.remaining_capacity))
+            .collect();
+        // 10:00 is booked, 11:00 is its buffer, 12:00 is full
+        assert_eq!(free, vec![(at(3, 9), 2), (at(3, 10), 1), (at(3, 11), 1)]);
+
+        let mut request = request(&product, date(3), date(3));
+        request.resource_id = Some(Uuid::now_v7());
+        assert!(matches!(
+            service.check_availability(&product, &resources, &bookings, &request, "USD", now()),
+            Err(BookingError::ResourceNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_fully_booked_dates() {
+        let service = service();
+        let product = product(DurationUnit::Day, DefaultAvailability::Available);
+        let bookings = vec![booking(&product, None, at(3, 0), 24)];
+
+        let response = service
+            .check_availability(&product, &[], &bookings, &request(&product, date(3), date(4)), "USD", now())
+            .unwrap();
+        assert_eq!(response.fully_booked_dates, vec![date(3)]);
+        assert_eq!(response.available_slots.len(), 1);
+        assert_eq!(response.available_slots[0].start, at(4, 0));
+    }
+
+    #[test]
+    fn test_rejects_invalid_requests() {
+        let service = service();
+        let mut product = product(DurationUnit::Hour, DefaultAvailability::Available);
+        product.has_persons = true;
+        product.min_persons = Some(2);
+        product.max_persons = Some(6);
+
+        let mut check = request(&product, date(3), date(3));
+        check.persons = Some(8);
+        assert!(matches!(
+            service.check_availability(&product, &[], &[], &check, "USD", now()),
+            Err(BookingError::TooManyPersons(6))
+        ));
+        check.persons = None;
+        assert!(matches!(
+            service.check_availability(&product, &[], &[], &check, "USD", now()),
+            Err(BookingError::TooFewPersons(2))
+        ));
+        check.persons = Some(4);
+        check.end_date = Some(date(2));
+        assert!(matches!(
+            service.check_availability(&product, &[], &[], &check, "USD", now()),
+            Err(BookingError::InvalidDateRange)
+        ));
+        check.end_date = Some(date(3) + Duration::days(120));
+        assert!(matches!(
+            service.check_availability(&product, &[], &[], &check, "USD", now()),
+            Err(BookingError::DateRangeTooLong(_))
+        ));
+    }
+
+    fn pricing(rule_type: PricingRuleType, priority: i32, modifier: PriceModifier, amount: Decimal) -> PricingRule {
+        PricingRule {
+            id: Uuid::now_v7(),
+            rule_type,
+            priority,
+            modifier,
+            amount,
+            from_date: None,
+            to_date: None,
+            from_time: None,
+            to_time: None,
+            days_of_week: None,
+            min_persons: None,
+            max_persons: None,
+            min_duration: None,
+            max_duration: None,
+        }
+    }
+
+    fn booking_request(product: &BookableProduct, start: DateTime<Utc>, duration: Option<i32>, persons: i32) -> BookingRequest {
+        BookingRequest {
+            product_id: product.product_id,
+            start_date: start,
+            duration,
+            persons: Some(persons),
+            resource_id: None,
+        }
+    }
+
+    fn day_product() -> BookableProduct {
+        let mut product = product(DurationUnit::Day, DefaultAvailability::Available);
+        product.booking_type = BookingType::CustomerDefined;
+        product.min_duration = Some(1);
+        product.max_duration = Some(14);
+        product.base_cost = dec!(25);
+        product.block_cost = dec!(100);
+        product
+    }
+
+    #[test]
+    fn test_cost_applies_rules_per_block() {
+        let service = service();
+        let mut product = day_product();
+        // Weekends cost half as much again; weeks or longer get 10% off every night
+        product.pricing_rules = vec![
+            pricing(PricingRuleType::Duration, 20, PriceModifier::Multiply, dec!(0.9)),
+            PricingRule {
+                days_of_week: Some(vec![Weekday::Sat, Weekday::Sun]),
+                ..pricing(PricingRuleType::DayOfWeek, 10, PriceModifier::Multiply, dec!(1.5))
+            },
+        ];
+        product.pricing_rules[0].min_duration = Some(7);
+
+        // Thursday to Sunday: two weekday and two weekend nights
+        let cost = service.calculate_cost(&product, None, &booking_request(&product, at(6, 0), Some(4), 1), "USD").unwrap();
+        assert_eq!(cost.blocks, 4);
+        assert_eq!(cost.block_cost, dec!(400));
+        assert_eq!(cost.adjustments.len(), 1);
+        assert_eq!(cost.adjustments[0].blocks, 2);
+        assert_eq!(cost.adjustments[0].amount, dec!(100));
+        assert_eq!(cost.total, dec!(525));
+
+        // A week: weekend nights are raised before the long-stay discount
+        let cost = service.calculate_cost(&product, None, &booking_request(&product, at(3, 0), Some(7), 1), "USD").unwrap();
+        assert_eq!(cost.total, dec!(25) + (dec!(500) + dec!(300)) * dec!(0.9));
+        assert_eq!(cost.adjustments.len(), 2);
+    }
+
+    #[test]
+    fn test_cost_includes_resource_and_person_rules() {
+        let service = service();
+        let mut product = product(DurationUnit::Hour, DefaultAvailability::Available);
+        product.has_persons = true;
+        product.max_persons = Some(10);
+        product.block_cost = dec!(40);
+        product.pricing_rules = vec![
+            PricingRule {
+                min_persons: Some(5),
+                ..pricing(PricingRuleType::PersonCount, 10, PriceModifier::Add, dec!(20))
+            },
+            PricingRule {
+                from_time: NaiveTime::from_hms_opt(18, 0, 0),
+                ..pricing(PricingRuleType::TimeRange, 1, PriceModifier::Replace, dec!(60))
+            },
+        ];
+        let mut court = resource(1, Vec::new());
+        court.base_cost = Some(dec!(5));
+        court.block_cost = Some(dec!(10));
+
+        let cost = service.calculate_cost(&product, Some(&court), &booking_request(&product, at(3, 10), None, 2), "USD").unwrap();
+        assert_eq!(cost.resource_cost, dec!(15));
+        assert!(cost.adjustments.is_empty());
+        assert_eq!(cost.total, dec!(55));
+
+        // The evening price replaces the block and resource cost, then the group surcharge is added
+        let cost = service.calculate_cost(&product, Some(&court), &booking_request(&product, at(3, 19), None, 6), "USD").unwrap();
+        assert_eq!(cost.total, dec!(85));
+        assert_eq!(cost.adjustments[0].amount, dec!(10));
+        assert_eq!(cost.adjustments[1].amount, dec!(20));
+
+        assert!(matches!(
+            service.calculate_cost(&product, None, &booking_request(&product, at(3, 10), None, 12), "USD"),
+            Err(BookingError::TooManyPersons(10))
+        ));
+
+        // Totals are rounded to the document currency
+        product.block_cost = dec!(40.4);
+        let cost = service.calculate_cost(&product, Some(&court), &booking_request(&product, at(3, 10), None, 2), "JPY").unwrap();
+        assert_eq!(cost.total, dec!(55));
+    }
+
+    #[test]
+    fn test_slots_priced_by_rules_and_resource() {
+        let service = service();
+        let mut product = product(DurationUnit::Hour, DefaultAvailability::NotAvailable);
+        product.has_resources = true;
+        product.block_cost = dec!(40);
+        product.availability_rules = vec![hours(17, 20, 10, true)];
+        product.pricing_rules = vec![PricingRule {
+            from_time: NaiveTime::from_hms_opt(18, 0, 0),
+            ..pricing(PricingRuleType::TimeRange, 1, PriceModifier::Add, dec!(15))
+        }];
+        let mut indoor = resource(1, Vec::new());
+        indoor.block_cost = Some(dec!(10));
+        let mut outdoor = resource(1, Vec::new());
+        outdoor.sort_order = 1;
+        let resources = vec![outdoor.clone(), indoor.clone()];
+
+        let prices = |bookings: &[Booking]| -> Vec<Decimal> {
+            service
+                .check_availability(&product, &resources, bookings, &request(&product, date(3), date(3)), "USD", now())
+                .unwrap()
+                .available_slots.iter().map(|slot| slot.price).collect()
+        };
+        assert_eq!(prices(&[]), vec![dec!(50), dec!(65), dec!(65)]);
+
+        // Once the first resource is taken, the block is priced on the next one
+        let taken = booking(&product, Some(indoor.id), at(3, 18), 1);
+        assert_eq!(prices(&[taken]), vec![dec!(50), dec!(55), dec!(65)]);
+    }
+
+    #[test]
+    fn test_cost_rejects_invalid_duration() {
+        let service = service();
+        let product = day_product();
+        assert!(matches!(
+            service.calculate_cost(&product, None, &booking_request(&product, at(3, 0), Some(15), 1), "USD"),
+            Err(BookingError::InvalidDuration { min: 1, max: Some(14) })
+        ));
+    }
+
+    #[test]
+    fn test_cart_item_carries_booking() {
+        let service = service();
+        let bookable = day_product();
+        let product: Product = serde_json::from_value(serde_json::json!({
+            "id": bookable.product_id,
+            "name": "Lake Cabin",
+            "slug": "lake-cabin",
+            "product_type": "booking",
+            "status": "publish",
+            "regular_price": "100",
+            "tax_status": "taxable",
+            "tax_class": "",
+            "manage_stock": false,
+            "stock_quantity": 0,
+            "stock_status": "in_stock",
+            "backorders": "no",
+            "sold_individually": false,
+            "is_virtual": true,
+            "is_downloadable": false,
+            "download_limit": 0,
+            "download_expiry": 0,
+            "reviews_allowed": false,
+            "average_rating": "0",
+            "rating_count": 0,
+            "featured": false,
+            "catalog_visibility": "visible",
+            "menu_order": 0,
+            "total_sales": 0,
+            "created_at": Utc::now(),
+        }))
+        .unwrap();
+
+        let request = booking_request(&bookable, at(3, 0), Some(2), 2);
+        let (hold, cost) = service.hold(&bookable, &[], &[], &request, &Cart::new(None, None), now()).unwrap();
+        let item = service.cart_item(&product, &hold, cost);
+        assert_eq!(item.price, dec!(225));
+        assert_eq!(item.subtotal, dec!(225));
+        assert_eq!(item.quantity, 1);
+
+        let booking: CartBooking = serde_json::from_value(item.meta[CartBooking::META_KEY].clone()).unwrap();
+        assert_eq!(booking.booking_id, Some(hold.id));
+        assert_eq!(booking.end_date, at(5, 0));
+        assert_eq!(booking.persons, 2);
+        assert_eq!(booking.cost.total, dec!(225));
+    }
+
+    fn order() -> Order {
+        Order {
+            id: Uuid::now_v7(),
+            site_id: None,
+            order_number: "RC-20300601-0001".to_string(),
+            customer_id: Some(Uuid::now_v7()),
+            customer_ip_address: None,
+            customer_user_agent: None,
+            status: OrderStatus::Processing,
+            parent_id: None,
+            currency: "USD".to_string(),
+            currency_symbol: "$".to_string(),
+            base_currency_rate: None,
+            locale: None,
+            prices_include_tax: false,
+            discount_total: Decimal::ZERO,
+            discount_tax: Decimal::ZERO,
+            shipping_total: Decimal::ZERO,
+            shipping_tax: Decimal::ZERO,
+            cart_tax: Decimal::ZERO,
+            total: dec!(125),
+            total_tax: Decimal::ZERO,
+            billing: Address {
+                first_name: "Ada".to_string(),
+                last_name: "Lovelace".to_string(),
+                email: "ada@example.com".to_string(),
+                ..Default::default()
+            },
+            shipping: Address::default(),
+            payment_method: Some("test".to_string()),
+            payment_method_title: None,
+            transaction_id: Some("txn_booking".to_string()),
+            shipping_method: None,
+            shipping_method_title: None,
+            fulfillment_status: Default::default(),
+            pickup: None,
+            vat: None,
+            tax_exemption: None,
+            customer_note: Some("Late arrival".to_string()),
+            date_paid: None,
+            date_completed: None,
+            cart_hash: None,
+            meta: serde_json::json!({}),
+            created_at: Utc::now(),
+            updated_at: None,
+            line_items: None,
+            shipping_lines: None,
+            tax_lines: None,
+            fee_lines: None,
+            coupon_lines: None,
+            notes: None,
+            refunds: None,
+            shipments: None,
+        }
+    }
+
+    fn cart() -> Cart {
+        let mut cart = Cart::new(None, None);
+        cart.expires_at = now() + Duration::days(2);
+        cart
+    }
+
+    /// Hold a one-night stay and place it on an order
+    fn placed(service: &BookingService, product: &BookableProduct, day: u32) -> (Booking, Order) {
+        let request = booking_request(product, at(day, 0), Some(1), 1);
+        let (mut booking, _) = service.hold(product, &[], &[], &request, &cart(), now()).unwrap();
+        let order = order();
+        service.place_order(product, &mut booking, &order, None, now()).unwrap();
+        (booking, order)
+    }
+
+    #[test]
+    fn test_holds_block_slots_until_released() {
+        let service = service();
+        let mut product = product(DurationUnit::Hour, DefaultAvailability::NotAvailable);
+        product.booking_type = BookingType::CustomerDefined;
+        product.availability_rules = vec![hours(9, 17, 10, true)];
+        let request = booking_request(&product, at(3, 10), Some(2), 1);
+
+        let mut held_cart = cart();
+        let (held, _) = service.hold(&product, &[], &[], &request, &held_cart, now()).unwrap();
+        assert_eq!(held.status, BookingStatus::InCart);
+        assert_eq!((held.start_date, held.end_date), (at(3, 10), at(3, 12)));
+        let mut holds = vec![held];
+
+        // The held slot can't be taken by another cart
+        let overlapping = booking_request(&product, at(3, 11), Some(1), 1);
+        assert!(matches!(
+            service.hold(&product, &[], &holds, &overlapping, &cart(), now()),
+            Err(BookingError::SlotUnavailable)
+        ));
+        // Blocks outside the opening hours can't be booked either
+        let late = booking_request(&product, at(3, 16), Some(2), 1);
+        assert!(matches!(service.hold(&product, &[], &[], &late, &cart(), now()), Err(BookingError::SlotUnavailable)));
+
+        // Holds last as long as their cart, following its extended expiry
+        assert!(BookingService::release_expired_holds(&mut holds, &[], now() + Duration::hours(47)).is_empty());
+        held_cart.expires_at = now() + Duration::days(3);
+        assert!(BookingService::release_expired_holds(&mut holds, &[held_cart.clone()], now() + Duration::days(2)).is_empty());
+
+        // and can be checked out while the cart lives
+        let mut checked_out = holds[0].clone();
+        service.place_order(&product, &mut checked_out, &order(), None, now() + Duration::days(2)).unwrap();
+        assert_eq!(checked_out.status, BookingStatus::Unpaid);
+
+        // Once the cart expires the slot frees up
+        assert_eq!(BookingService::release_expired_holds(&mut holds, &[held_cart], now() + Duration::days(3)), vec![holds[0].id]);
+        assert_eq!(holds[0].status, BookingStatus::WasPInCart);
+        assert!(service.hold(&product, &[], &holds, &overlapping, &cart(), now()).is_ok());
+
+        // An expired hold can't be checked out
+        assert!(matches!(
+            service.place_order(&product, &mut holds[0], &order(), None, now()),
+            Err(BookingError::HoldExpired)
+        ));
+    }
+
+    #[test]
+    fn test_hold_ends_with_cart_and_assigns_resources() {
+        let service = service();
+        let mut product = product(DurationUnit::Hour, DefaultAvailability::Available);
+        product.has_resources = true;
+        let (mut first, mut second) = (resource(1, Vec::new()), resource(1, Vec::new()));
+        first.sort_order = 1;
+        second.sort_order = 2;
+        let resources = vec![second, first];
+        let request = booking_request(&product, at(3, 10), Some(1), 1);
+
+        let mut cart = cart();
+        cart.expires_at = now() + Duration::minutes(15);
+        let (held, _) = service.hold(&product, &resources, &[], &request, &cart, now()).unwrap();
+        assert_eq!(held.resource_id, Some(resources[1].id));
+        assert_eq!(held.meta[BookingService::HOLD_CART_META], cart.id.to_string());
+        assert_eq!(
+            held.meta[BookingService::HOLD_EXPIRES_META],
+            (now() + Duration::minutes(15)).to_rfc3339()
+        );
+
+        let (next, _) = service.hold(&product, &resources, &[held], &request, &cart, now()).unwrap();
+        assert_eq!(next.resource_id, Some(resources[0].id));
+    }
+
+    #[tokio::test]
+    async fn test_confirmation_queue_flow() {
+        let service = service();
+        let mut product = day_product();
+        product.requires_confirmation = true;
+
+        let (mut later, _) = placed(&service, &product, 10);
+        let (mut sooner, order) = placed(&service, &product, 5);
+        assert_eq!(sooner.status, BookingStatus::Pending);
+        assert_eq!(sooner.customer_email.as_deref(), Some("ada@example.com"));
+        assert_eq!(sooner.customer_note.as_deref(), Some("Late arrival"));
+        assert!(!sooner.meta.contains_key(BookingService::HOLD_EXPIRES_META));
+
+        let bookings = vec![later.clone(), sooner.clone()];
+        let queue: Vec<Uuid> = BookingService::confirmation_queue(&bookings).iter().map(|b| b.id).collect();
+        assert_eq!(queue, vec![sooner.id, later.id]);
+
+        // Unpaid bookings are confirmed with a link to pay
+        let email = service.confirm(&mut sooner, &product, "Lake Cabin", now()).unwrap().unwrap();
+        assert_eq!(sooner.status, BookingStatus::Confirmed);
+        assert_eq!(email.template_key.as_deref(), Some(BookingService::CONFIRMED_TEMPLATE));
+        assert_eq!(
+            email.variables["pay_url"],
+            serde_json::json!(format!("https://shop.example/checkout/order-pay/{}", order.id))
+        );
+        assert_eq!(email.variables["booking_start"], serde_json::json!("2030-06-05"));
+        assert_eq!(email.attachments.unwrap()[0].filename, format!("booking-{}.ics", sooner.id));
+        // It was emailed on confirmation, so payment sends nothing more
+        assert!(service.mark_paid(&mut sooner, &product, "Lake Cabin", now()).unwrap().is_none());
+        assert_eq!(sooner.status, BookingStatus::Paid);
+
+        // Paid bookings stay queued, and are refunded when rejected
+        assert!(service.mark_paid(&mut later, &product, "Lake Cabin", now()).unwrap().is_none());
+        assert_eq!(later.status, BookingStatus::Pending);
+        let rejected = service
+            .reject(&mut later, &product, "Lake Cabin", Some(&order), Some("Closed for repairs"), now())
+            .await
+            .unwrap();
+        assert_eq!(later.status, BookingStatus::Cancelled);
+        assert_eq!(rejected.refund.unwrap().amount, dec!(125));
+        assert_eq!(rejected.email.unwrap().variables["reason"], serde_json::json!("Closed for repairs"));
+        assert!(matches!(
+            service.confirm(&mut later, &product, "Lake Cabin", now()),
+            Err(BookingError::InvalidStatus(BookingStatus::Cancelled))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_customer_cancellation_window_and_refund() {
+        let service = service();
+        let mut product = day_product();
+        product.cancel_limit = Some(48);
+
+        let (mut booking, order) = placed(&service, &product, 5);
+        assert_eq!(booking.status, BookingStatus::Unpaid);
+
+        // Paying a booking that needs no confirmation sends the confirmation with its calendar entry
+        let email = service.mark_paid(&mut booking, &product, "Lake Cabin", now()).unwrap().unwrap();
+        assert_eq!(email.template_key.as_deref(), Some(BookingService::CONFIRMED_TEMPLATE));
+        assert!(!email.variables.contains_key("pay_url"));
+        assert_eq!(email.attachments.unwrap()[0].filename, format!("booking-{}.ics", booking.id));
+
+        let deadline = at(3, 0);
+        assert!(matches!(
+            service.cancel_by_customer(&mut booking, &product, "Lake Cabin", Some(&order), deadline).await,
+            Err(BookingError::CancellationWindowClosed(d)) if d == deadline
+        ));
+        assert_eq!(booking.status, BookingStatus::Paid);
+
+        let cancelled = service
+            .cancel_by_customer(&mut booking, &product, "Lake Cabin", Some(&order), deadline - Duration::minutes(1))
+            .await
+            .unwrap();
+        assert_eq!(booking.status, BookingStatus::Cancelled);
+        let refund = cancelled.refund.unwrap();
+        assert!(refund.success);
+        assert_eq!(refund.amount, dec!(125));
+        assert_eq!(cancelled.email.unwrap().template_key.as_deref(), Some(BookingService::CANCELLED_TEMPLATE));
+
+        // Unpaid bookings are cancelled without a refund
+        let (mut unpaid, order) = placed(&service, &product, 10);
+        let cancelled = service.cancel_by_customer(&mut unpaid, &product, "Lake Cabin", Some(&order), now()).await.unwrap();
+        assert!(cancelled.refund.is_none());
+
+        product.can_be_cancelled = false;
+        let (mut fixed, order) = placed(&service, &product, 12);
+        assert!(matches!(
+            service.cancel_by_customer(&mut fixed, &product, "Lake Cabin", Some(&order), now()).await,
+            Err(BookingError::NotCancellable)
+        ));
+    }
+
+    #[test]
+    fn test_reminders_sent_once() {
+        let service = service();
+        let mut product = product(DurationUnit::Hour, DefaultAvailability::Available);
+        product.timezone = chrono_tz::Europe::Berlin;
+        let mut soon = booking(&product, None, at(3, 10), 1);
+        soon.customer_email = Some("ada@example.com".to_string());
+        let mut unpaid = booking(&product, None, at(3, 11), 1);
+        unpaid.status = BookingStatus::Unpaid;
+        let later = booking(&product, None, at(5, 10), 1);
+        let bookings = vec![soon.clone(), unpaid, later];
+
+        let due: Vec<Uuid> = service.reminders_due(&bookings, at(2, 12)).iter().map(|b| b.id).collect();
+        assert_eq!(due, vec![soon.id]);
+
+        let email = service.reminder_email(&mut soon, &product, "Tennis Court", at(2, 12)).unwrap();
+        assert_eq!(email.template_key.as_deref(), Some(BookingService::REMINDER_TEMPLATE));
+        assert_eq!(email.variables["booking_start"], serde_json::json!("2030-06-03 12:00"));
+        assert!(service.reminders_due(&[soon.clone()], at(2, 12)).is_empty());
+
+        // The reminder job skips bookings already reminded
+        let due = |booking: &Booking| DueReminder {
+            booking: booking.clone(),
+            product: product.clone(),
+            product_name: "Tennis Court".to_string(),
+        };
+        let sent = service.reminder_emails(vec![due(&soon), due(&bookings[0]), due(&bookings[2])], at(2, 12));
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].0.id, soon.id);
+        assert!(sent[0].0.meta.contains_key(BookingService::REMINDER_SENT_META));
+    }
+
+    #[test]
+    fn test_release_due_holds_returns_released_bookings() {
+        let service = service();
+        let product = day_product();
+        let (mut abandoned, live) = (cart(), cart());
+        abandoned.expires_at = now() + Duration::hours(1);
+
+        let (expired, _) = service.hold(&product, &[], &[], &booking_request(&product, at(5, 0), Some(1), 1), &abandoned, now()).unwrap();
+        let (kept, _) = service.hold(&product, &[], &[], &booking_request(&product, at(8, 0), Some(1), 1), &live, now()).unwrap();
+
+        let due = DueHolds { bookings: vec![expired.clone(), kept], carts: vec![abandoned, live] };
+        let released = BookingService::release_due_holds(due, now() + Duration::hours(2));
+        assert_eq!(released.len(), 1);
+        assert_eq!(released[0].id, expired.id);
+        assert_eq!(released[0].status, BookingStatus::WasPInCart);
+        assert!(!released[0].meta.contains_key(BookingService::HOLD_CART_META));
+    }
+}
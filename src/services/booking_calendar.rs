@@ -0,0 +1,1266 @@
+//! Booking Calendar Service
+//!
+//! iCalendar (RFC 5545) export and import for bookings. Products and
+//! resources get secret-token feeds of their confirmed bookings, and
+//! confirmation emails carry an `.ics` attachment.
+//!
+//! External feeds are imported as bookings against a product or resource,
+//! so they block availability like any other booking. Recurring events
+//! are expanded up to a year ahead for the common RRULE forms (a daily,
+//! weekly, monthly or yearly frequency with an interval, count, end date
+//! and weekly days); events with other recurrence rules are skipped
+//! rather than imported as a single occurrence.
+
+use base64::Engine;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use rust_decimal::Decimal;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::models::booking::{
+    BookableProduct, Booking, BookingCalendarFeed, BookingSettings, BookingStatus, ExternalCalendar,
+};
+use crate::models::email_templates::EmailAttachment;
+use crate::services::booking::BookingService;
+
+/// Product identifier written to exported calendars
+const PRODID: &str = "-//RustCommerce//Bookings//EN";
+
+/// Longest line in an iCalendar file, in octets
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Most repetitions of a recurrence rule walked through, so a rule
+/// starting decades ago still ends
+const MAX_RECURRENCE_PERIODS: u32 = 100_000;
+
+/// Booking calendar errors
+#[derive(Debug, Clone)]
+pub enum BookingCalendarError {
+    InvalidUrl(String),
+    Fetch(String),
+    InvalidCalendar(String),
+}
+
+impl std::fmt::Display for BookingCalendarError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidUrl(url) => write!(f, "Calendar URL must be http(s) or webcal: {}", url),
+            Self::Fetch(msg) => write!(f, "Could not fetch calendar: {}", msg),
+            Self::InvalidCalendar(msg) => write!(f, "Invalid calendar: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for BookingCalendarError {}
+
+/// A booking with the product details shown in calendars
+#[derive(Debug, Clone, Copy)]
+pub struct BookingEvent<'a> {
+    pub booking: &'a Booking,
+    pub product: &'a BookableProduct,
+    pub product_name: &'a str,
+}
+
+/// An event read from an iCalendar feed
+#[derive(Debug, Clone, PartialEq)]
+pub struct IcsEvent {
+    pub uid: String,
+    pub summary: Option<String>,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub all_day: bool,
+    /// Original start of a recurring event's occurrence; together with
+    /// the UID it identifies the occurrence
+    pub recurrence_id: Option<DateTime<Utc>>,
+}
+
+/// Events read from an iCalendar feed
+#[derive(Debug, Clone, Default)]
+pub struct ParsedCalendar {
+    pub events: Vec<IcsEvent>,
+    /// Events without a UID or valid dates, or with a recurrence rule
+    /// that isn't expanded
+    pub skipped: usize,
+}
+
+/// Booking changes from importing an external calendar
+#[derive(Debug, Clone, Default)]
+pub struct ExternalCalendarSync {
+    pub created: Vec<Booking>,
+    pub updated: Vec<Booking>,
+    /// Imported bookings whose event left the feed
+    pub cancelled: Vec<Booking>,
+    pub skipped: usize,
+}
+
+/// An external calendar due for import, with its product and the
+/// bookings previously imported from it
+#[derive(Debug, Clone)]
+pub struct DueImport {
+    pub calendar: ExternalCalendar,
+    pub product: BookableProduct,
+    pub bookings: Vec<Booking>,
+}
+
+/// Result of importing one external calendar; `calendar` carries the
+/// updated sync status
+#[derive(Debug)]
+pub struct ImportRun {
+    pub calendar: ExternalCalendar,
+    pub result: Result<ExternalCalendarSync, BookingCalendarError>,
+}
+
+/// A content line split into name, parameters and value
+#[derive(Debug, Clone)]
+struct Property {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+impl Property {
+    fn parse(line: &str) -> Option<Self> {
+        let mut quoted = false;
+        let mut parts = Vec::new();
+        let mut start = 0;
+        for (i, c) in line.char_indices() {
+            match c {
+                '"' => quoted = !quoted,
+                ';' if !quoted => {
+                    parts.push(&line[start..i]);
+                    start = i + 1;
+                }
+                ':' if !quoted => {
+                    parts.push(&line[start..i]);
+                    let mut parts = parts.into_iter();
+                    let name = parts.next()?.trim().to_ascii_uppercase();
+                    let params = parts
+                        .filter_map(|p| p.split_once('='))
+                        .map(|(k, v)| (k.trim().to_ascii_uppercase(), v.trim_matches('"').to_string()))
+                        .collect();
+                    return Some(Self { name, params, value: line[i + 1..].to_string() });
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+    }
+}
+
+/// An event as read from the feed, before its recurrence is expanded
+#[derive(Debug)]
+struct ReadEvent {
+    event: IcsEvent,
+    recurrence: Option<Recurrence>,
+}
+
+/// How often a recurring event repeats
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// A recurrence rule (RRULE) with its excluded dates (EXDATE)
+#[derive(Debug)]
+struct Recurrence {
+    frequency: Frequency,
+    interval: u32,
+    count: Option<u32>,
+    until: Option<DateTime<Utc>>,
+    /// BYDAY of a weekly rule
+    weekdays: Vec<Weekday>,
+    exdates: HashSet<DateTime<Utc>>,
+    /// Zone of DTSTART; occurrences keep their local time across DST changes
+    zone: Tz,
+}
+
+impl Recurrence {
+    /// Start of every occurrence from `start`, in order, stopping at the
+    /// rule's COUNT or UNTIL or at `horizon`. Excluded dates count towards
+    /// COUNT but aren't returned.
+    fn occurrences(&self, start: DateTime<Utc>, horizon: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+        let first = start.with_timezone(&self.zone).naive_local();
+        let mut starts = Vec::new();
+        let mut counted = 0;
+        for period in 0..MAX_RECURRENCE_PERIODS {
+            for local in self.period_starts(first, period * self.interval) {
+                // Local times skipped by a DST change don't occur
+                let Some(at) = self.zone.from_local_datetime(&local).earliest() else { continue };
+                let at = at.with_timezone(&Utc);
+                if at < start {
+                    continue;
+                }
+                if at >= horizon
+                    || self.until.is_some_and(|until| at > until)
+                    || self.count.is_some_and(|count| counted >= count)
+                {
+                    return starts;
+                }
+                counted += 1;
+                if !self.exdates.contains(&at) {
+                    starts.push(at);
+                }
+            }
+        }
+        starts
+    }
+
+    /// Local starts in the period `offset` days, weeks, months or years
+    /// after the first. Days that don't exist in a month, such as the 31st,
+    /// are skipped.
+    fn period_starts(&self, first: NaiveDateTime, offset: u32) -> Vec<NaiveDateTime> {
+        let offset_days = |days: i64| first.checked_add_signed(Duration::days(days));
+        let on = |year: i32, month: u32| {
+            NaiveDate::from_ymd_opt(year, month, first.day()).map(|date| date.and_time(first.time()))
+        };
+        match self.frequency {
+            Frequency::Daily => offset_days(offset as i64).into_iter().collect(),
+            Frequency::Weekly if self.weekdays.is_empty() => offset_days(offset as i64 * 7).into_iter().collect(),
+            Frequency::Weekly => {
+                // Weeks start on Monday
+                let monday = first.weekday().num_days_from_monday() as i64;
+                let mut days: Vec<i64> = self.weekdays.iter()
+                    .map(|day| offset as i64 * 7 - monday + day.num_days_from_monday() as i64)
+                    .collect();
+                days.sort_unstable();
+                days.dedup();
+                days.into_iter().filter_map(offset_days).collect()
+            }
+            Frequency::Monthly => {
+                let months = first.month0() + offset;
+                on(first.year() + (months / 12) as i32, months % 12 + 1).into_iter().collect()
+            }
+            Frequency::Yearly => on(first.year() + offset as i32, first.month()).into_iter().collect(),
+        }
+    }
+}
+
+/// Booking calendar service
+pub struct BookingCalendarService {
+    settings: BookingSettings,
+}
+
+impl BookingCalendarService {
+    /// Seconds to wait for an external calendar
+    pub const FETCH_TIMEOUT_SECONDS: u64 = 15;
+
+    /// Create a new booking calendar service
+    pub fn new(settings: BookingSettings) -> Self {
+        Self { settings }
+    }
+
+    /// How far ahead recurring events are expanded, in days
+    pub const RECURRENCE_HORIZON_DAYS: i64 = 365;
+
+    /// Subscription URL for a feed
+    pub fn feed_url(&self, feed: &BookingCalendarFeed) -> String {
+        format!(
+            "{}/rc/v1/bookings/calendar/{}.ics",
+            self.settings.store_url.trim_end_matches('/'),
+            feed.token
+        )
+    }
+
+    /// Find the feed a token belongs to. Tokens are compared by their
+    /// SHA-256 digests in constant time, so neither their content nor
+    /// their length can be guessed from response times.
+    pub fn find_feed<'a>(feeds: &'a [BookingCalendarFeed], token: &str) -> Option<&'a BookingCalendarFeed> {
+        let token = Sha256::digest(token.trim_end_matches(".ics").as_bytes());
+        feeds.iter().find(|feed| {
+            let expected = Sha256::digest(feed.token.as_bytes());
+            expected.iter().zip(token.iter()).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+        })
+    }
+
+    /// Bookings shown in a feed: confirmed, paid and complete bookings made
+    /// in this store, soonest first
+    pub fn feed_bookings<'a>(feed: &BookingCalendarFeed, bookings: &'a [Booking]) -> Vec<&'a Booking> {
+        let mut shown: Vec<&Booking> = bookings.iter()
+            .filter(|b| matches!(b.status, BookingStatus::Confirmed | BookingStatus::Paid | BookingStatus::Complete))
+            .filter(|b| !b.is_external() && feed.covers(b))
+            .collect();
+        shown.sort_by_key(|b| b.start_date);
+        shown
+    }
+
+    /// Staff calendar of bookings, including customer contact details
+    pub fn feed(name: &str, events: &[BookingEvent<'_>]) -> String {
+        let mut lines = Self::calendar_header();
+        lines.push(format!("X-WR-CALNAME:{}", escape(name)));
+        for event in events {
+            let booking = event.booking;
+            let summary = match booking.customer_name.as_deref().filter(|n| !n.is_empty()) {
+                Some(customer) => format!("{} - {}", event.product_name, customer),
+                None => event.product_name.to_string(),
+            };
+            let details = [
+                Some(format!("Persons: {}", booking.persons)),
+                booking.customer_email.as_ref().map(|email| format!("Email: {}", email)),
+                booking.customer_phone.as_ref().map(|phone| format!("Phone: {}", phone)),
+                booking.customer_note.as_ref().map(|note| format!("Note: {}", note)),
+            ];
+            let description = details.into_iter().flatten().collect::<Vec<_>>().join("\n");
+            Self::push_event(&mut lines, event, &summary, &description);
+        }
+        lines.push("END:VCALENDAR".to_string());
+        Self::render(&lines)
+    }
+
+    /// Calendar file for the customer's confirmation email
+    pub fn attachment(event: &BookingEvent<'_>) -> EmailAttachment {
+        let mut lines = Self::calendar_header();
+        let description = format!("Booking {}\nPersons: {}", event.booking.id, event.booking.persons);
+        Self::push_event(&mut lines, event, event.product_name, &description);
+        lines.push("END:VCALENDAR".to_string());
+
+        EmailAttachment {
+            filename: format!("booking-{}.ics", event.booking.id),
+            content_type: "text/calendar; charset=utf-8; method=PUBLISH".to_string(),
+            content_base64: base64::engine::general_purpose::STANDARD.encode(Self::render(&lines)),
+        }
+    }
+
+    fn calendar_header() -> Vec<String> {
+        vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            format!("PRODID:{}", PRODID),
+            "CALSCALE:GREGORIAN".to_string(),
+            "METHOD:PUBLISH".to_string(),
+        ]
+    }
+
+    fn push_event(lines: &mut Vec<String>, event: &BookingEvent<'_>, summary: &str, description: &str) {
+        let booking = event.booking;
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}@rustcommerce", booking.id));
+        lines.push(format!("DTSTAMP:{}", utc_stamp(booking.updated_at.unwrap_or(booking.created_at))));
+        if booking.all_day {
+            // Dates are the product's local dates; the end date is exclusive
+            let timezone = event.product.timezone;
+            lines.push(format!("DTSTART;VALUE=DATE:{}", booking.start_date.with_timezone(&timezone).format("%Y%m%d")));
+            lines.push(format!("DTEND;VALUE=DATE:{}", booking.end_date.with_timezone(&timezone).format("%Y%m%d")));
+        } else {
+            lines.push(format!("DTSTART:{}", utc_stamp(booking.start_date)));
+            lines.push(format!("DTEND:{}", utc_stamp(booking.end_date)));
+        }
+        lines.push(format!("SUMMARY:{}", escape(summary)));
+        if !description.is_empty() {
+            lines.push(format!("DESCRIPTION:{}", escape(description)));
+        }
+        lines.push("STATUS:CONFIRMED".to_string());
+        lines.push("END:VEVENT".to_string());
+    }
+
+    fn render(lines: &[String]) -> String {
+        lines.iter().map(|line| fold(line) + "\r\n").collect()
+    }
+
+    /// Read the events of an iCalendar file. Times with a TZID are read in
+    /// that zone; dates and floating times are read in `timezone`, the
+    /// product's timezone. Cancelled and free (transparent) events are
+    /// left out.
+    ///
+    /// Recurring events are expanded into the occurrences that haven't
+    /// ended by `now`, up to `RECURRENCE_HORIZON_DAYS` ahead. Occurrences
+    /// excluded by EXDATE or replaced by an event with the same UID and a
+    /// RECURRENCE-ID are left out.
+    pub fn parse(ics: &str, timezone: Tz, now: DateTime<Utc>) -> Result<ParsedCalendar, BookingCalendarError> {
+        let lines = unfold(ics);
+        if !lines.iter().any(|line| line.trim().eq_ignore_ascii_case("BEGIN:VCALENDAR")) {
+            return Err(BookingCalendarError::InvalidCalendar("missing BEGIN:VCALENDAR".to_string()));
+        }
+
+        let mut parsed = ParsedCalendar::default();
+        let mut read = Vec::new();
+        // Occurrences replaced by another event, including cancelled ones
+        let mut overridden = HashSet::new();
+        let mut event: Option<Vec<Property>> = None;
+        // Components nested in an event, e.g. alarms
+        let mut nested = 0;
+        for property in lines.iter().filter_map(|line| Property::parse(line)) {
+            let value = property.value.trim().to_ascii_uppercase();
+            match (property.name.as_str(), event.as_mut()) {
+                ("BEGIN", None) if value == "VEVENT" => {
+                    event = Some(Vec::new());
+                    nested = 0;
+                }
+                ("BEGIN", Some(_)) => nested += 1,
+                ("END", Some(_)) if nested > 0 => nested -= 1,
+                ("END", Some(_)) if value == "VEVENT" => {
+                    let properties = event.take().unwrap_or_default();
+                    if let Some(occurrence) = read_override(&properties, timezone) {
+                        overridden.insert(occurrence);
+                    }
+                    match read_event(&properties, timezone) {
+                        Ok(Some(e)) => read.push(e),
+                        Ok(None) => {}
+                        Err(()) => parsed.skipped += 1,
+                    }
+                }
+                (_, Some(properties)) if nested == 0 => properties.push(property),
+                _ => {}
+            }
+        }
+
+        let horizon = now + Duration::days(Self::RECURRENCE_HORIZON_DAYS);
+        for ReadEvent { event, recurrence } in read {
+            let Some(recurrence) = recurrence else {
+                parsed.events.push(event);
+                continue;
+            };
+            let length = event.end - event.start;
+            for start in recurrence.occurrences(event.start, horizon) {
+                if start + length <= now || overridden.contains(&(event.uid.clone(), start)) {
+                    continue;
+                }
+                parsed.events.push(IcsEvent { start, end: start + length, recurrence_id: Some(start), ..event.clone() });
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// Bookings to create, update and cancel so a calendar's imported
+    /// bookings match its feed. Events are matched on their UID and
+    /// RECURRENCE-ID, so each occurrence of a recurring event is its own
+    /// booking. Events that have ended are left alone.
+    pub fn sync(
+        calendar: &ExternalCalendar,
+        events: &[IcsEvent],
+        existing: &[Booking],
+        now: DateTime<Utc>,
+    ) -> ExternalCalendarSync {
+        let calendar_id = calendar.id.to_string();
+        let imported: Vec<((&str, Option<String>), &Booking)> = existing.iter()
+            .filter(|b| b.blocks_availability())
+            .filter(|b| b.meta.get(Booking::EXTERNAL_CALENDAR_META) == Some(&calendar_id))
+            .filter_map(|b| {
+                let uid = b.meta.get(Booking::EXTERNAL_UID_META)?;
+                Some(((uid.as_str(), b.meta.get(Booking::EXTERNAL_RECURRENCE_META).cloned()), b))
+            })
+            .collect();
+        let by_occurrence: HashMap<&(&str, Option<String>), &Booking> =
+            imported.iter().map(|(key, booking)| (key, *booking)).collect();
+
+        let mut sync = ExternalCalendarSync::default();
+        let mut seen = HashSet::new();
+        for event in events {
+            // Only the first event for an occurrence counts
+            let key = (event.uid.as_str(), event.recurrence_id.map(utc_stamp));
+            if event.end <= now || seen.contains(&key) {
+                continue;
+            }
+            let booking = by_occurrence.get(&key).copied();
+            seen.insert(key);
+            match booking {
+                Some(booking)
+                    if booking.start_date == event.start
+                        && booking.end_date == event.end
+                        && booking.customer_note == event.summary => {}
+                Some(booking) => {
+                    let mut booking = booking.clone();
+                    booking.start_date = event.start;
+                    booking.end_date = event.end;
+                    booking.all_day = event.all_day;
+                    booking.customer_note = event.summary.clone();
+                    booking.updated_at = Some(now);
+                    sync.updated.push(booking);
+                }
+                None => sync.created.push(Self::external_booking(calendar, event, now)),
+            }
+        }
+
+        for (key, booking) in imported {
+            if !seen.contains(&key) && booking.end_date > now {
+                let mut booking = booking.clone();
+                booking.status = BookingStatus::Cancelled;
+                booking.updated_at = Some(now);
+                sync.cancelled.push(booking);
+            }
+        }
+        sync
+    }
+
+    fn external_booking(calendar: &ExternalCalendar, event: &IcsEvent, now: DateTime<Utc>) -> Booking {
+        Booking {
+            id: Uuid::now_v7(),
+            site_id: calendar.site_id,
+            product_id: calendar.product_id,
+            order_id: None,
+            order_item_id: None,
+            customer_id: None,
+            status: BookingStatus::Confirmed,
+            start_date: event.start,
+            end_date: event.end,
+            all_day: event.all_day,
+            persons: 1,
+            resource_id: calendar.resource_id,
+            cost: Decimal::ZERO,
+            customer_name: Some(calendar.name.clone()),
+            customer_email: None,
+            customer_phone: None,
+            customer_note: event.summary.clone(),
+            meta: [
+                (Booking::EXTERNAL_CALENDAR_META, Some(calendar.id.to_string())),
+                (Booking::EXTERNAL_UID_META, Some(event.uid.clone())),
+                (Booking::EXTERNAL_RECURRENCE_META, event.recurrence_id.map(utc_stamp)),
+            ]
+            .into_iter()
+            .filter_map(|(key, value)| Some((key.to_string(), value?)))
+            .collect(),
+            created_at: now,
+            updated_at: None,
+        }
+    }
+
+    /// Download an external calendar. `webcal://` URLs are fetched over https.
+    pub async fn fetch(&self, calendar: &ExternalCalendar) -> Result<String, BookingCalendarError> {
+        let url = calendar.url.trim();
+        let url = match url.strip_prefix("webcal://") {
+            Some(rest) => format!("https://{}", rest),
+            None if url.starts_with("https://") || url.starts_with("http://") => url.to_string(),
+            None => return Err(BookingCalendarError::InvalidUrl(url.to_string())),
+        };
+
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(Self::FETCH_TIMEOUT_SECONDS))
+            .build()
+            .map_err(|e| BookingCalendarError::Fetch(e.to_string()))?;
+        let response = client.get(&url)
+            .send()
+            .await
+            .map_err(|e| BookingCalendarError::Fetch(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(BookingCalendarError::Fetch(format!("{} returned {}", url, response.status())));
+        }
+        response.text().await.map_err(|e| BookingCalendarError::Fetch(e.to_string()))
+    }
+
+    /// Fetch and sync an external calendar, recording the outcome on it
+    pub async fn import(
+        &self,
+        calendar: &mut ExternalCalendar,
+        product: &BookableProduct,
+        existing: &[Booking],
+        now: DateTime<Utc>,
+    ) -> Result<ExternalCalendarSync, BookingCalendarError> {
+        let parsed = match self.fetch(calendar).await {
+            Ok(body) => Self::parse(&body, product.timezone, now),
+            Err(e) => Err(e),
+        };
+        calendar.updated_at = Some(now);
+
+        match parsed {
+            Ok(parsed) => {
+                calendar.last_synced_at = Some(now);
+                calendar.last_error = None;
+                let mut sync = Self::sync(calendar, &parsed.events, existing, now);
+                sync.skipped = parsed.skipped;
+                Ok(sync)
+            }
+            Err(e) => {
+                calendar.last_error = Some(e.to_string());
+                Err(e)
+            }
+        }
+    }
+
+    /// Import external calendars every `calendar_import_minutes`.
+    /// `load_due` returns the calendars to import; `on_import` receives each
+    /// result for saving the bookings and calendar.
+    pub fn spawn_import_worker<L, F>(
+        self: Arc<Self>,
+        load_due: L,
+        on_import: F,
+    ) -> tokio::task::JoinHandle<()>
+    where
+        L: Fn(DateTime<Utc>) -> Vec<DueImport> + Send + Sync + 'static,
+        F: Fn(ImportRun) + Send + Sync + 'static,
+    {
+        let minutes = self.settings.calendar_import_minutes.max(1) as u64;
+        let period = std::time::Duration::from_secs(minutes * 60);
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                let now = Utc::now();
+                for DueImport { mut calendar, product, bookings } in load_due(now) {
+                    let result = self.import(&mut calendar, &product, &bookings, now).await;
+                    on_import(ImportRun { calendar, result });
+                }
+            }
+        })
+    }
+}
+
+/// The UID and RECURRENCE-ID of an event replacing one occurrence of a
+/// recurring event
+fn read_override(properties: &[Property], timezone: Tz) -> Option<(String, DateTime<Utc>)> {
+    let get = |name: &str| properties.iter().find(|p| p.name == name);
+    let uid = get("UID").map(|p| p.value.trim()).filter(|uid| !uid.is_empty())?;
+    let (recurrence_id, _) = get("RECURRENCE-ID").and_then(|p| read_date(p, timezone))?;
+    Some((uid.to_string(), recurrence_id))
+}
+
+/// Read an event's properties. `Ok(None)` is an event that doesn't block
+/// time; `Err` is one that can't be read or whose recurrence isn't
+/// supported.
+fn read_event(properties: &[Property], timezone: Tz) -> Result<Option<ReadEvent>, ()> {
+    let get = |name: &str| properties.iter().find(|p| p.name == name);
+    let is = |name: &str, value: &str| get(name).is_some_and(|p| p.value.trim().eq_ignore_ascii_case(value));
+    if is("STATUS", "CANCELLED") || is("TRANSP", "TRANSPARENT") {
+        return Ok(None);
+    }
+
+    let uid = get("UID").map(|p| p.value.trim()).filter(|uid| !uid.is_empty()).ok_or(())?;
+    let dtstart = get("DTSTART").ok_or(())?;
+    let (start, all_day) = read_date(dtstart, timezone).ok_or(())?;
+    let end = match (get("DTEND"), get("DURATION")) {
+        (Some(end), _) => read_date(end, timezone).ok_or(())?.0,
+        (None, Some(duration)) => start + read_duration(&duration.value).ok_or(())?,
+        (None, None) if all_day => start + Duration::days(1),
+        (None, None) => start,
+    };
+    if end < start {
+        return Err(());
+    }
+    if end == start {
+        return Ok(None);
+    }
+
+    let recurrence_id = match get("RECURRENCE-ID") {
+        Some(property) => Some(read_date(property, timezone).ok_or(())?.0),
+        None => None,
+    };
+    // Recurrences other than a single RRULE aren't expanded
+    if get("RDATE").is_some() || get("EXRULE").is_some() {
+        return Err(());
+    }
+    let recurrence = match get("RRULE") {
+        Some(rule) if recurrence_id.is_none() => {
+            let zone = date_zone(dtstart, timezone);
+            let mut recurrence = read_rule(&rule.value, zone).ok_or(())?;
+            for exdate in properties.iter().filter(|p| p.name == "EXDATE") {
+                for value in exdate.value.split(',') {
+                    let date = Property { value: value.to_string(), ..exdate.clone() };
+                    recurrence.exdates.insert(read_date(&date, timezone).ok_or(())?.0);
+                }
+            }
+            Some(recurrence)
+        }
+        _ => None,
+    };
+
+    Ok(Some(ReadEvent {
+        event: IcsEvent {
+            uid: uid.to_string(),
+            summary: get("SUMMARY").map(|p| unescape(p.value.trim())).filter(|s| !s.is_empty()),
+            start,
+            end,
+            all_day,
+            recurrence_id,
+        },
+        recurrence,
+    }))
+}
+
+/// Read an RRULE value. Only a frequency with INTERVAL, COUNT, UNTIL and,
+/// for weekly rules, plain BYDAY days is supported; any other rule part
+/// returns `None`.
+fn read_rule(value: &str, zone: Tz) -> Option<Recurrence> {
+    let mut frequency = None;
+    let mut interval = 1;
+    let mut count = None;
+    let mut until = None;
+    let mut weekdays = Vec::new();
+    let mut week_start = Weekday::Mon;
+    for part in value.trim().split(';').filter(|part| !part.is_empty()) {
+        let (name, value) = part.split_once('=')?;
+        let value = value.trim().to_ascii_uppercase();
+        match name.trim().to_ascii_uppercase().as_str() {
+            "FREQ" => {
+                frequency = Some(match value.as_str() {
+                    "DAILY" => Frequency::Daily,
+                    "WEEKLY" => Frequency::Weekly,
+                    "MONTHLY" => Frequency::Monthly,
+                    "YEARLY" => Frequency::Yearly,
+                    _ => return None,
+                });
+            }
+            "INTERVAL" => interval = value.parse().ok().filter(|n| *n > 0)?,
+            "COUNT" => count = Some(value.parse().ok()?),
+            "UNTIL" => {
+                let property = Property { name: name.to_string(), params: Vec::new(), value };
+                until = Some(read_date(&property, zone)?.0);
+            }
+            "BYDAY" => weekdays = value.split(',').map(read_weekday).collect::<Option<_>>()?,
+            "WKST" => week_start = read_weekday(&value)?,
+            _ => return None,
+        }
+    }
+
+    let frequency = frequency?;
+    if !weekdays.is_empty() && frequency != Frequency::Weekly {
+        return None;
+    }
+    // Weeks are counted from Monday, which only matters for every other week or more
+    if week_start != Weekday::Mon && interval > 1 && !weekdays.is_empty() {
+        return None;
+    }
+    Some(Recurrence { frequency, interval, count, until, weekdays, exdates: HashSet::new(), zone })
+}
+
+/// Read a BYDAY day such as `MO`; days with an occurrence number such as
+/// `1MO` aren't supported
+fn read_weekday(value: &str) -> Option<Weekday> {
+    match value.trim() {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// Read a DATE or DATE-TIME value, returning whether it was a date. Times
+/// with a TZID known to chrono-tz are read in that zone; dates, floating
+/// times and unknown zones are read in `timezone`.
+fn read_date(property: &Property, timezone: Tz) -> Option<(DateTime<Utc>, bool)> {
+    let value = property.value.trim();
+    let local = |dt: NaiveDateTime, timezone: Tz| {
+        timezone.from_local_datetime(&dt).earliest().map(|dt| dt.with_timezone(&Utc))
+    };
+
+    if property.param("VALUE").is_some_and(|v| v.eq_ignore_ascii_case("DATE")) || value.len() == 8 {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        return local(date.and_hms_opt(0, 0, 0)?, timezone).map(|dt| (dt, true));
+    }
+    match value.strip_suffix(['Z', 'z']) {
+        Some(utc) => NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok().map(|dt| (dt.and_utc(), false)),
+        None => {
+            let zone = date_zone(property, timezone);
+            local(NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?, zone).map(|dt| (dt, false))
+        }
+    }
+}
+
+/// Zone a DATE or DATE-TIME value is read in
+fn date_zone(property: &Property, timezone: Tz) -> Tz {
+    let value = property.value.trim();
+    if value.ends_with(['Z', 'z']) {
+        return Tz::UTC;
+    }
+    if property.param("VALUE").is_some_and(|v| v.eq_ignore_ascii_case("DATE")) || value.len() == 8 {
+        return timezone;
+    }
+    property.param("TZID")
+        .and_then(|tzid| tzid.trim_start_matches('/').parse::<Tz>().ok())
+        .unwrap_or(timezone)
+}
+
+/// Read a positive DURATION value such as `P1D` or `PT1H30M`
+fn read_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let rest = value.strip_prefix('+').unwrap_or(value).strip_prefix('P')?;
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    let mut time = false;
+    for c in rest.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' => time = true,
+            unit => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                total += match (unit, time) {
+                    ('W', false) => Duration::weeks(n),
+                    ('D', false) => Duration::days(n),
+                    ('H', true) => Duration::hours(n),
+                    ('M', true) => Duration::minutes(n),
+                    ('S', true) => Duration::seconds(n),
+                    _ => return None,
+                };
+            }
+        }
+    }
+    number.is_empty().then_some(total)
+}
+
+/// Join folded lines back into content lines
+fn unfold(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in ics.split('\n').map(|line| line.strip_suffix('\r').unwrap_or(line)) {
+        match lines.last_mut() {
+            Some(last) if line.starts_with([' ', '\t']) => last.push_str(&line[1..]),
+            _ if !line.trim().is_empty() => lines.push(line.to_string()),
+            _ => {}
+        }
+    }
+    lines
+}
+
+/// Split a content line into lines of at most 75 octets
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + line.len() / MAX_LINE_OCTETS * 3);
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            // The leading space counts towards the continuation line
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => unescaped.push('\n'),
+            Some(escaped) => unescaped.push(escaped),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+fn utc_stamp(date: DateTime<Utc>) -> String {
+    date.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::booking::{
+        BookingType, CalendarDisplay, DefaultAvailability, DurationUnit, ResourceAssignment,
+    };
+
+    fn product(unit: DurationUnit) -> BookableProduct {
+        BookableProduct {
+            id: Uuid::now_v7(),
+            product_id: Uuid::now_v7(),
+            booking_type: BookingType::FixedDuration,
+            duration: 1,
+            duration_unit: unit,
+            min_duration: None,
+            max_duration: None,
+            buffer_before: None,
+            buffer_after: None,
+            block_capacity: 1,
+            timezone: chrono_tz::Europe::Berlin,
+            base_cost: Decimal::ZERO,
+            block_cost: Decimal::ZERO,
+            calendar_display: CalendarDisplay::Always,
+            requires_confirmation: false,
+            can_be_cancelled: true,
+            cancel_limit: None,
+            has_persons: false,
+            min_persons: None,
+            max_persons: None,
+            has_resources: false,
+            resources_assignment: ResourceAssignment::AutoAssign,
+            default_date_availability: DefaultAvailability::Available,
+            check_availability: true,
+            availability_rules: Vec::new(),
+            pricing_rules: Vec::new(),
+            created_at: Utc::now(),
+            updated_at: None,
+        }
+    }
+
+    fn booking(product: &BookableProduct, status: BookingStatus, start: DateTime<Utc>, end: DateTime<Utc>) -> Booking {
+        Booking {
+            id: Uuid::now_v7(),
+            site_id: None,
+            product_id: product.product_id,
+            order_id: None,
+            order_item_id: None,
+            customer_id: None,
+            status,
+            start_date: start,
+            end_date: end,
+            all_day: product.duration_unit.is_daily(),
+            persons: 2,
+            resource_id: None,
+            cost: Decimal::ZERO,
+            customer_name: Some("Ada Lovelace".to_string()),
+            customer_email: Some("ada@example.com".to_string()),
+            customer_phone: None,
+            customer_note: Some("Window seat, please; thanks".to_string()),
+            meta: HashMap::new(),
+            created_at: at(1, 9),
+            updated_at: None,
+        }
+    }
+
+    fn calendar(product: &BookableProduct) -> ExternalCalendar {
+        ExternalCalendar {
+            id: Uuid::now_v7(),
+            site_id: None,
+            name: "Holiday lettings".to_string(),
+            url: "webcal://example.com/cabin.ics".to_string(),
+            product_id: product.product_id,
+            resource_id: None,
+            last_synced_at: None,
+            last_error: None,
+            created_at: at(1, 0),
+            updated_at: None,
+        }
+    }
+
+    fn at(day: u32, hour: u32) -> DateTime<Utc> {
+        NaiveDate::from_ymd_opt(2030, 6, day).unwrap().and_hms_opt(hour, 0, 0).unwrap().and_utc()
+    }
+
+    fn local(product: &BookableProduct) -> Tz {
+        product.timezone
+    }
+
+    #[test]
+    fn test_feed_lists_confirmed_bookings() {
+        let product = product(DurationUnit::Hour);
+        let feed = BookingCalendarFeed {
+            id: Uuid::now_v7(),
+            site_id: None,
+            product_id: Some(product.product_id),
+            resource_id: None,
+            token: BookingCalendarFeed::generate_token(),
+            created_at: at(1, 0),
+        };
+        let mut external = booking(&product, BookingStatus::Confirmed, at(4, 9), at(4, 10));
+        external.meta.insert(Booking::EXTERNAL_UID_META.to_string(), "abc".to_string());
+        let bookings = vec![
+            booking(&product, BookingStatus::Paid, at(3, 9), at(3, 10)),
+            booking(&product, BookingStatus::Confirmed, at(2, 9), at(2, 11)),
+            booking(&product, BookingStatus::Unpaid, at(2, 12), at(2, 13)),
+            booking(&product, BookingStatus::Cancelled, at(2, 14), at(2, 15)),
+            external,
+        ];
+
+        let shown = BookingCalendarService::feed_bookings(&feed, &bookings);
+        assert_eq!(shown.iter().map(|b| b.id).collect::<Vec<_>>(), vec![bookings[1].id, bookings[0].id]);
+
+        let events: Vec<BookingEvent> = shown.iter()
+            .map(|booking| BookingEvent { booking, product: &product, product_name: "Tennis Court" })
+            .collect();
+        let ics = BookingCalendarService::feed("Tennis Court", &events);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+        assert!(ics.contains("\r\nDTSTART:20300602T090000Z\r\nDTEND:20300602T110000Z\r\n"));
+        assert!(ics.contains("\r\nSUMMARY:Tennis Court - Ada Lovelace\r\n"));
+        // Long lines are folded
+        assert!(ics.replace("\r\n ", "").contains("Note: Window seat\\, please\\; thanks"));
+        assert!(ics.split("\r\n").all(|line| line.len() <= MAX_LINE_OCTETS));
+
+        let service = BookingCalendarService::new(BookingSettings {
+            store_url: "https://shop.example/".to_string(),
+            ..Default::default()
+        });
+        assert_eq!(
+            service.feed_url(&feed),
+            format!("https://shop.example/rc/v1/bookings/calendar/{}.ics", feed.token)
+        );
+        let feeds = [feed];
+        let token = format!("{}.ics", feeds[0].token);
+        assert!(BookingCalendarService::find_feed(&feeds, &token).is_some());
+        assert!(BookingCalendarService::find_feed(&feeds, "not-the-token").is_none());
+    }
+
+    #[test]
+    fn test_attachment_uses_local_dates_for_daily_bookings() {
+        let product = product(DurationUnit::Day);
+        // Local midnight at UTC+2
+        let stay = booking(&product, BookingStatus::Paid, at(2, 22), at(4, 22));
+        let attachment = BookingCalendarService::attachment(&BookingEvent {
+            booking: &stay,
+            product: &product,
+            product_name: "Lake Cabin",
+        });
+        assert_eq!(attachment.filename, format!("booking-{}.ics", stay.id));
+
+        let bytes = base64::engine::general_purpose::STANDARD.decode(&attachment.content_base64).unwrap();
+        let ics = String::from_utf8(bytes).unwrap();
+        assert!(ics.contains("\r\nDTSTART;VALUE=DATE:20300603\r\nDTEND;VALUE=DATE:20300605\r\n"));
+        assert!(!ics.contains("ada@example.com"));
+
+        // Exported events read back as the same booking
+        let parsed = BookingCalendarService::parse(&ics, local(&product), at(1, 0)).unwrap();
+        assert_eq!(parsed.events.len(), 1);
+        assert_eq!((parsed.events[0].start, parsed.events[0].end), (stay.start_date, stay.end_date));
+        assert!(parsed.events[0].all_day);
+    }
+
+    #[test]
+    fn test_parse_external_feed() {
+        let product = product(DurationUnit::Day);
+        let ics = "BEGIN:VCALENDAR\r\n\
+            VERSION:2.0\r\n\
+            BEGIN:VEVENT\r\n\
+            UID:stay-1@lettings\r\n\
+            DTSTART;VALUE=DATE:20300610\r\n\
+            DTEND;VALUE=DATE:20300613\r\n\
+            SUMMARY:Reserved\\,\r\n  deposit paid\r\n\
+            BEGIN:VALARM\r\n\
+            TRIGGER:-PT15M\r\n\
+            SUMMARY:Alarm\r\n\
+            END:VALARM\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            UID:viewing-2@lettings\r\n\
+            DTSTART;TZID=America/New_York:20300611T140000\r\n\
+            DURATION:PT1H30M\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            UID:cleaning-4@lettings\r\n\
+            DTSTART:20300612T090000\r\n\
+            DTEND;TZID=\"/Europe/Nowhere\":20300612T100000\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            UID:cancelled-3@lettings\r\n\
+            DTSTART:20300612T100000Z\r\n\
+            DTEND:20300612T110000Z\r\n\
+            STATUS:CANCELLED\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            DTSTART:20300612T100000Z\r\n\
+            END:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+
+        let parsed = BookingCalendarService::parse(ics, local(&product), at(1, 0)).unwrap();
+        assert_eq!(parsed.skipped, 1);
+        assert_eq!(parsed.events, vec![
+            IcsEvent {
+                uid: "stay-1@lettings".to_string(),
+                summary: Some("Reserved, deposit paid".to_string()),
+                start: at(9, 22),
+                end: at(12, 22),
+                all_day: true,
+                recurrence_id: None,
+            },
+            IcsEvent {
+                uid: "viewing-2@lettings".to_string(),
+                summary: None,
+                start: at(11, 18),
+                end: at(11, 18) + Duration::minutes(90),
+                all_day: false,
+                recurrence_id: None,
+            },
+            // Floating times and unknown zones fall back to the product's
+            IcsEvent {
+                uid: "cleaning-4@lettings".to_string(),
+                summary: None,
+                start: at(12, 7),
+                end: at(12, 8),
+                all_day: false,
+                recurrence_id: None,
+            },
+        ]);
+
+        assert!(matches!(
+            BookingCalendarService::parse("<html></html>", local(&product), at(1, 0)),
+            Err(BookingCalendarError::InvalidCalendar(_))
+        ));
+    }
+
+    #[test]
+    fn test_sync_creates_updates_and_cancels() {
+        let product = product(DurationUnit::Day);
+        let calendar = calendar(&product);
+        let event = |uid: &str, start: DateTime<Utc>, end: DateTime<Utc>| IcsEvent {
+            uid: uid.to_string(),
+            summary: None,
+            start,
+            end,
+            all_day: true,
+            recurrence_id: None,
+        };
+        let now = at(5, 0);
+
+        let first = BookingCalendarService::sync(
+            &calendar,
+            &[event("a", at(10, 0), at(12, 0)), event("b", at(14, 0), at(15, 0)), event("old", at(1, 0), at(2, 0))],
+            &[],
+            now,
+        );
+        assert_eq!(first.created.len(), 2);
+        let a = &first.created[0];
+        assert_eq!(a.status, BookingStatus::Confirmed);
+        assert!(a.blocks_availability() && a.is_external());
+        assert_eq!(a.meta[Booking::EXTERNAL_CALENDAR_META], calendar.id.to_string());
+
+        // "a" moved, "b" left the feed, "c" is new
+        let second = BookingCalendarService::sync(
+            &calendar,
+            &[event("a", at(11, 0), at(13, 0)), event("c", at(20, 0), at(21, 0))],
+            &first.created,
+            now,
+        );
+        assert_eq!(second.created.len(), 1);
+        assert_eq!(second.updated.len(), 1);
+        assert_eq!((second.updated[0].id, second.updated[0].start_date), (a.id, at(11, 0)));
+        assert_eq!(second.cancelled.len(), 1);
+        assert_eq!(second.cancelled[0].meta[Booking::EXTERNAL_UID_META], "b");
+        assert_eq!(second.cancelled[0].status, BookingStatus::Cancelled);
+
+        // Another calendar's bookings are left alone
+        let other_calendar = ExternalCalendar { id: Uuid::now_v7(), ..calendar.clone() };
+        let other = BookingCalendarService::sync(&other_calendar, &[], &first.created, now);
+        assert!(other.cancelled.is_empty());
+    }
+
+    #[test]
+    fn test_parse_expands_recurring_events() {
+        let product = product(DurationUnit::Hour);
+        let ics = "BEGIN:VCALENDAR\r\n\
+            BEGIN:VEVENT\r\n\
+            UID:lesson@school\r\n\
+            DTSTART;TZID=Europe/Berlin:20300603T090000\r\n\
+            DTEND;TZID=Europe/Berlin:20300603T100000\r\n\
+            RRULE:FREQ=WEEKLY;BYDAY=MO,TH;COUNT=6\r\n\
+            EXDATE;TZID=Europe/Berlin:20300613T090000\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            UID:lesson@school\r\n\
+            RECURRENCE-ID;TZID=Europe/Berlin:20300610T090000\r\n\
+            DTSTART;TZID=Europe/Berlin:20300610T140000\r\n\
+            DTEND;TZID=Europe/Berlin:20300610T150000\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            UID:lesson@school\r\n\
+            RECURRENCE-ID;TZID=Europe/Berlin:20300617T090000\r\n\
+            DTSTART;TZID=Europe/Berlin:20300617T090000\r\n\
+            DTEND;TZID=Europe/Berlin:20300617T100000\r\n\
+            STATUS:CANCELLED\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            UID:rota@school\r\n\
+            DTSTART:20300601T120000Z\r\n\
+            DTEND:20300601T130000Z\r\n\
+            RRULE:FREQ=MONTHLY;BYMONTHDAY=1,15\r\n\
+            END:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+
+        // The first Monday has ended; 13 June is excluded, 10 June moved
+        // and 17 June cancelled
+        let parsed = BookingCalendarService::parse(ics, local(&product), at(4, 0)).unwrap();
+        assert_eq!(parsed.skipped, 1);
+        let occurrences: Vec<_> = parsed.events.iter().map(|e| (e.start, e.recurrence_id)).collect();
+        assert_eq!(occurrences, vec![
+            (at(6, 7), Some(at(6, 7))),
+            (at(20, 7), Some(at(20, 7))),
+            (at(10, 12), Some(at(10, 7))),
+        ]);
+        assert!(parsed.events.iter().all(|e| e.uid == "lesson@school" && e.end - e.start == Duration::hours(1)));
+    }
+
+    #[test]
+    fn test_recurrence_keeps_local_time_and_stops_at_horizon() {
+        let product = product(DurationUnit::Hour);
+        let ics = "BEGIN:VCALENDAR\r\n\
+            BEGIN:VEVENT\r\n\
+            UID:standup@office\r\n\
+            DTSTART;TZID=Europe/Berlin:20300131T090000\r\n\
+            DURATION:PT15M\r\n\
+            RRULE:FREQ=MONTHLY;UNTIL=20300430T235959Z\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            UID:daily@office\r\n\
+            DTSTART:20300101T080000Z\r\n\
+            DURATION:PT1H\r\n\
+            RRULE:FREQ=DAILY\r\n\
+            END:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+        let now = NaiveDate::from_ymd_opt(2030, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap().and_utc();
+
+        let parsed = BookingCalendarService::parse(ics, local(&product), now).unwrap();
+        // February and April have no 31st; March is in summer time
+        let standups: Vec<_> = parsed.events.iter()
+            .filter(|e| e.uid == "standup@office")
+            .map(|e| e.start.with_timezone(&product.timezone).format("%Y-%m-%d %H:%M").to_string())
+            .collect();
+        assert_eq!(standups, vec!["2030-01-31 09:00", "2030-03-31 09:00"]);
+
+        let daily = parsed.events.iter().filter(|e| e.uid == "daily@office").count();
+        assert_eq!(daily as i64, BookingCalendarService::RECURRENCE_HORIZON_DAYS);
+    }
+
+    #[test]
+    fn test_sync_matches_occurrences_by_recurrence_id() {
+        let product = product(DurationUnit::Hour);
+        let calendar = calendar(&product);
+        let occurrence = |start: DateTime<Utc>, recurrence_id: DateTime<Utc>| IcsEvent {
+            uid: "lesson@school".to_string(),
+            summary: None,
+            start,
+            end: start + Duration::hours(1),
+            all_day: false,
+            recurrence_id: Some(recurrence_id),
+        };
+        let now = at(5, 0);
+
+        let first = BookingCalendarService::sync(
+            &calendar,
+            &[occurrence(at(6, 7), at(6, 7)), occurrence(at(13, 7), at(13, 7))],
+            &[],
+            now,
+        );
+        assert_eq!(first.created.len(), 2);
+        assert_eq!(first.created[1].meta[Booking::EXTERNAL_RECURRENCE_META], "20300613T070000Z");
+
+        // The second occurrence moved; the first is unchanged
+        let second = BookingCalendarService::sync(
+            &calendar,
+            &[occurrence(at(6, 7), at(6, 7)), occurrence(at(13, 12), at(13, 7))],
+            &first.created,
+            now,
+        );
+        assert!(second.created.is_empty() && second.cancelled.is_empty());
+        assert_eq!(second.updated.len(), 1);
+        assert_eq!((second.updated[0].id, second.updated[0].start_date), (first.created[1].id, at(13, 12)));
+    }
+
+    #[tokio::test]
+    async fn test_import_records_errors() {
+        let product = product(DurationUnit::Day);
+        let mut calendar = calendar(&product);
+        calendar.url = "ftp://example.com/cabin.ics".to_string();
+        let service = BookingCalendarService::new(BookingSettings::default());
+
+        let result = service.import(&mut calendar, &product, &[], at(5, 0)).await;
+        assert!(matches!(result, Err(BookingCalendarError::InvalidUrl(_))));
+        assert!(calendar.last_error.is_some());
+        assert!(calendar.last_synced_at.is_none());
+    }
+}
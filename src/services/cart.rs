@@ -6,10 +6,17 @@ use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use uuid::Uuid;
 use std::collections::HashMap;
+#[cfg(any(feature = "multi_currency", feature = "memberships"))]
+use std::sync::Arc;
 
 use crate::models::cart::{Cart, CartItem, CartTotals, AppliedCoupon, CartFee};
+use crate::models::membership::{AppliedMemberDiscount, MemberCartDiscount};
 use crate::models::product::{Product, ProductVariation};
 use crate::models::coupon::Coupon;
+#[cfg(feature = "multi_currency")]
+use crate::services::currency::CurrencyService;
+#[cfg(feature = "memberships")]
+use crate::services::membership::MembershipService;
 use crate::services::pricing::PricingService;
 use crate::services::inventory::InventoryService;
 use crate::settings::RustCommerceSettings;
@@ -18,6 +25,10 @@ use crate::settings::RustCommerceSettings;
 pub struct CartService {
     settings: RustCommerceSettings,
     pricing_service: PricingService,
+    #[cfg(feature = "multi_currency")]
+    currency_service: Option<Arc<CurrencyService>>,
+    #[cfg(feature = "memberships")]
+    membership_service: Option<Arc<MembershipService>>,
 }
 
 /// Cart operation result
@@ -39,6 +50,10 @@ pub enum CartError {
     CartEmpty,
     MaxQuantityExceeded { max: i32 },
     ProductNotPurchasable,
+    /// Only members of one of these plans can buy the product
+    MembershipRequired { plan_ids: Vec<Uuid> },
+    /// The cart's currency cannot be priced
+    CurrencyUnavailable(String),
 }
 
 impl std::fmt::Display for CartError {
@@ -55,6 +70,8 @@ impl std::fmt::Display for CartError {
             Self::CartEmpty => write!(f, "Cart is empty"),
             Self::MaxQuantityExceeded { max } => write!(f, "Maximum quantity of {} exceeded", max),
             Self::ProductNotPurchasable => write!(f, "Product cannot be purchased"),
+            Self::MembershipRequired { .. } => write!(f, "Product can only be purchased by members"),
+            Self::CurrencyUnavailable(msg) => write!(f, "Currency unavailable: {}", msg),
         }
     }
 }
@@ -66,9 +83,27 @@ impl CartService {
         Self {
             settings,
             pricing_service,
+            #[cfg(feature = "multi_currency")]
+            currency_service: None,
+            #[cfg(feature = "memberships")]
+            membership_service: None,
         }
     }
 
+    /// Price items in the cart's currency when it is not the base currency
+    #[cfg(feature = "multi_currency")]
+    pub fn with_currency(mut self, currency_service: Arc<CurrencyService>) -> Self {
+        self.currency_service = Some(currency_service);
+        self
+    }
+
+    /// Enforce member-only purchase restrictions and apply member discounts
+    #[cfg(feature = "memberships")]
+    pub fn with_memberships(mut self, membership_service: Arc<MembershipService>) -> Self {
+        self.membership_service = Some(membership_service);
+        self
+    }
+
     /// Create a new empty cart
     pub fn create_cart(&self, customer_id: Option<Uuid>) -> Cart {
         Cart {
@@ -85,16 +120,20 @@ impl CartService {
             fees: Vec::new(),
             totals: CartTotals::default(),
             shipping_method_id: None,
+            currency: None,
+            currency_rate: None,
             shipping_address: None,
             billing_address: None,
             customer_note: None,
+            member: None,
             created_at: chrono::Utc::now(),
             updated_at: None,
             expires_at: Some(chrono::Utc::now() + chrono::Duration::days(7)),
         }
     }
 
-    /// Add item to cart
+    /// Add item to cart. Member-only products need the cart's member to
+    /// hold one of the plans that sells them.
     pub fn add_item(
         &self,
         cart: &mut Cart,
@@ -111,6 +150,10 @@ impl CartService {
             return Err(CartError::ProductNotPurchasable);
         }
 
+        // Check member-only purchase restrictions
+        #[cfg(feature = "memberships")]
+        self.check_member_purchase(cart, product)?;
+
         // Generate cart item key
         let key = self.generate_item_key(product.id, variation.map(|v| v.id));
 
@@ -131,11 +174,7 @@ impl CartService {
             }
 
             // Get price
-            let price = if let Some(var) = variation {
-                self.pricing_service.get_variation_price(product, var)
-            } else {
-                self.pricing_service.get_price(product)
-            }.unwrap_or(Decimal::ZERO);
+            let price = self.unit_price(cart, product, variation)?;
 
             let item = CartItem {
                 key: key.clone(),
@@ -162,6 +201,74 @@ impl CartService {
         Ok(())
     }
 
+    /// Switch the cart to another currency and recalculate its totals
+    #[cfg(feature = "multi_currency")]
+    pub fn set_currency(&self, cart: &mut Cart, code: &str) -> Result<(), CartError> {
+        let currency = self.currency_service.as_ref()
+            .ok_or_else(|| CartError::CurrencyUnavailable(code.to_string()))?;
+        currency.apply_to_cart(cart, code)
+            .map_err(|e| CartError::CurrencyUnavailable(e.to_string()))?;
+
+        self.calculate_totals(cart);
+        cart.update_hash();
+        cart.updated_at = Some(chrono::Utc::now());
+        Ok(())
+    }
+
+    /// Whether the cart's member may buy a product
+    #[cfg(feature = "memberships")]
+    pub fn check_member_purchase(&self, cart: &Cart, product: &Product) -> Result<(), CartError> {
+        let Some(memberships) = &self.membership_service else {
+            return Ok(());
+        };
+        let member = memberships.cart_context(cart, chrono::Utc::now());
+        let access = memberships.check_purchase(&member, product);
+        if access.has_access {
+            Ok(())
+        } else {
+            Err(CartError::MembershipRequired { plan_ids: access.required_plan_ids })
+        }
+    }
+
+    /// Unit price in the cart's currency: the fixed price for that currency
+    /// when set, otherwise the base price converted
+    #[cfg_attr(not(feature = "multi_currency"), allow(unused_variables))]
+    fn unit_price(
+        &self,
+        cart: &Cart,
+        product: &Product,
+        variation: Option<&ProductVariation>,
+    ) -> Result<Decimal, CartError> {
+        let base_price = match variation {
+            Some(var) => self.pricing_service.get_variation_price(product, var),
+            None => self.pricing_service.get_price(product),
+        };
+
+        #[cfg(feature = "multi_currency")]
+        if let (Some(currency), Some(code)) = (&self.currency_service, cart.currency.as_deref()) {
+            let price = match variation {
+                None => currency.product_price(product, code),
+                Some(var) => match currency.fixed_price(product.id, Some(var.id), code) {
+                    Some(fixed) => {
+                        let on_sale = base_price.is_some_and(|p| var.regular_price.is_some_and(|r| p < r));
+                        Ok(Some(match fixed.sale_price {
+                            Some(sale) if on_sale => sale,
+                            _ => fixed.regular_price,
+                        }))
+                    }
+                    None => base_price
+                        .map(|p| currency.convert(p, currency.base_currency_code(), code))
+                        .transpose(),
+                },
+            };
+            return price
+                .map(|p| p.unwrap_or(Decimal::ZERO))
+                .map_err(|e| CartError::CurrencyUnavailable(e.to_string()));
+        }
+
+        Ok(base_price.unwrap_or(Decimal::ZERO))
+    }
+
     /// Remove item from cart
     pub fn remove_item(&self, cart: &mut Cart, key: &str) -> Result<CartItem, CartError> {
         let index = cart.items.iter().position(|i| i.key == key)
@@ -227,6 +334,17 @@ impl CartService {
         // Calculate discount
         let discount = self.calculate_coupon_discount(cart, coupon);
 
+        // Coupons that would add nothing to the member discount are refused
+        #[cfg(feature = "memberships")]
+        if discount > Decimal::ZERO {
+            let coupon_discount = cart.coupons.iter().map(|c| c.discount).sum::<Decimal>() + discount;
+            if self.apply_member_discounts(cart, coupon_discount).is_zero() {
+                return Err(CartError::CouponNotValid(
+                    "Coupons cannot be combined with your member discount".to_string()
+                ));
+            }
+        }
+
         let applied = AppliedCoupon {
             code: coupon.code.clone(),
             coupon_id: coupon.id,
@@ -263,24 +381,37 @@ impl CartService {
         cart.updated_at = Some(chrono::Utc::now());
     }
 
-    /// Calculate cart totals
+    /// Calculate cart totals. Member discounts are refreshed from the
+    /// cart's member first and come off the line totals and their tax, so
+    /// members pay tax on what they pay.
     pub fn calculate_totals(&self, cart: &mut Cart) {
         let mut subtotal = Decimal::ZERO;
         let mut subtotal_tax = Decimal::ZERO;
+        let mut discount_total = Decimal::ZERO;
+        let mut discount_tax = Decimal::ZERO;
+
+        // Member discounts decide how much of the coupons' discount applies
+        let coupon_discount: Decimal = cart.coupons.iter().map(|c| c.discount).sum();
+        #[cfg(feature = "memberships")]
+        let coupon_discount = self.apply_member_discounts(cart, coupon_discount);
 
         // Calculate line totals
         for item in &mut cart.items {
             item.line_subtotal = item.unit_price * Decimal::from(item.quantity);
-            item.line_total = item.line_subtotal;
+            let (member_discount, member_discount_tax) = AppliedMemberDiscount::from_meta(&item.meta)
+                .map_or((Decimal::ZERO, Decimal::ZERO), |d| {
+                    (d.amount.min(item.line_subtotal), d.tax.min(item.line_subtotal_tax))
+                });
+            item.line_total = item.line_subtotal - member_discount;
+            item.line_tax = item.line_subtotal_tax - member_discount_tax;
             subtotal += item.line_subtotal;
             subtotal_tax += item.line_subtotal_tax;
+            discount_total += member_discount;
+            discount_tax += member_discount_tax;
         }
 
         // Calculate discount
-        let mut discount_total = Decimal::ZERO;
-        for coupon in &cart.coupons {
-            discount_total += coupon.discount;
-        }
+        discount_total += coupon_discount;
 
         // Calculate shipping
         let shipping_total = cart.totals.shipping_total;
@@ -295,7 +426,7 @@ impl CartService {
         }
 
         // Calculate tax
-        let tax_total = subtotal_tax + shipping_tax + fee_tax;
+        let tax_total = subtotal_tax - discount_tax + shipping_tax + fee_tax;
 
         // Calculate total
         let total = subtotal + shipping_total + fee_total + tax_total - discount_total;
@@ -306,7 +437,7 @@ impl CartService {
             shipping_total,
             shipping_tax,
             discount_total,
-            discount_tax: Decimal::ZERO,
+            discount_tax,
             fee_total,
             fee_tax,
             tax_total,
@@ -314,6 +445,22 @@ impl CartService {
         };
     }
 
+    /// Record the cart member's current discounts on the lines, returning
+    /// the part of `coupon_discount` the coupon stacking setting keeps.
+    /// Without a membership service no member discounts apply.
+    #[cfg(feature = "memberships")]
+    fn apply_member_discounts(&self, cart: &mut Cart, coupon_discount: Decimal) -> Decimal {
+        let Some(memberships) = &self.membership_service else {
+            for item in &mut cart.items {
+                item.meta.remove(AppliedMemberDiscount::META_KEY);
+            }
+            cart.meta.remove(MemberCartDiscount::META_KEY);
+            return coupon_discount;
+        };
+        memberships.refresh_cart(cart, chrono::Utc::now());
+        memberships.stack_coupons(cart, coupon_discount)
+    }
+
     /// Calculate discount from a coupon
     fn calculate_coupon_discount(&self, cart: &Cart, coupon: &Coupon) -> Decimal {
         use crate::models::coupon::DiscountType;
@@ -387,9 +534,10 @@ impl CartService {
         cart.items.iter().map(|i| i.line_subtotal).sum()
     }
 
-    /// Check if free shipping is available (via coupon)
+    /// Check if free shipping is available (via coupon or membership)
     pub fn has_free_shipping(&self, cart: &Cart) -> bool {
         cart.coupons.iter().any(|c| c.free_shipping)
+            || MemberCartDiscount::from_meta(&cart.meta).is_some_and(|d| d.free_shipping)
     }
 
     /// Validate cart items (check stock, prices, etc.)
@@ -480,4 +628,225 @@ mod tests {
         assert_eq!(service.get_item_count(&cart), 0);
         assert!(service.is_empty(&cart));
     }
+
+    #[cfg(feature = "memberships")]
+    fn product() -> Product {
+        use crate::models::product::{ProductStatus, ProductType, StockStatus};
+
+        Product {
+            id: Uuid::now_v7(),
+            site_id: None,
+            sku: None,
+            name: "Masterclass".to_string(),
+            slug: "masterclass".to_string(),
+            product_type: ProductType::Simple,
+            status: ProductStatus::Publish,
+            short_description: None,
+            description: None,
+            regular_price: Some(dec!(100)),
+            sale_price: None,
+            sale_price_from: None,
+            sale_price_to: None,
+            tax_status: Default::default(),
+            tax_class: String::new(),
+            tax_code: None,
+            manage_stock: false,
+            stock_quantity: 0,
+            stock_status: StockStatus::InStock,
+            backorders: Default::default(),
+            low_stock_amount: None,
+            sold_individually: false,
+            weight: None,
+            length: None,
+            width: None,
+            height: None,
+            shipping_class_id: None,
+            is_virtual: true,
+            is_downloadable: false,
+            download_limit: 0,
+            download_expiry: 0,
+            external_url: None,
+            button_text: None,
+            reviews_allowed: false,
+            average_rating: Decimal::ZERO,
+            rating_count: 0,
+            featured: false,
+            catalog_visibility: Default::default(),
+            parent_id: None,
+            menu_order: 0,
+            purchase_note: None,
+            total_sales: 0,
+            meta_title: None,
+            meta_description: None,
+            meta_keywords: None,
+            created_at: chrono::Utc::now(),
+            updated_at: None,
+            published_at: None,
+            categories: None,
+            tags: None,
+            images: None,
+            attributes: None,
+            variations: None,
+            downloads: None,
+        }
+    }
+
+    #[cfg(feature = "memberships")]
+    #[test]
+    fn test_member_only_products_need_membership() {
+        use crate::models::membership::{
+            AccessMethod, CartMember, ContentAccessType, ContentRestriction, MembershipPlan, MembershipSettings,
+            MembershipStatus, PlanStatus, RestrictedContentType, UserMembership,
+        };
+
+        let settings = RustCommerceSettings::default();
+        let masterclass = product();
+        let now = chrono::Utc::now();
+
+        let gold_id = Uuid::now_v7();
+        let plans = vec![MembershipPlan {
+            id: gold_id,
+            site_id: None,
+            name: "Gold".to_string(),
+            slug: "gold".to_string(),
+            description: None,
+            status: PlanStatus::Active,
+            access_method: AccessMethod::Unlimited,
+            access_length: None,
+            access_length_period: None,
+            access_start_date: None,
+            access_end_date: None,
+            product_ids: Vec::new(),
+            restricted_content: vec![ContentRestriction {
+                id: Uuid::now_v7(),
+                plan_id: gold_id,
+                content_type: RestrictedContentType::Product,
+                content_ids: vec![masterclass.id],
+                access_type: ContentAccessType::ViewOnly,
+                delay_days: None,
+            }],
+            member_discounts: Vec::new(),
+            trial_enabled: false,
+            trial_length: None,
+            trial_period: None,
+            meta: HashMap::new(),
+            created_at: now,
+            updated_at: None,
+        }];
+        let memberships = MembershipService::new(settings.clone(), MembershipSettings::default()).with_plans(plans);
+        let service = CartService::new(settings).with_memberships(Arc::new(memberships));
+
+        let mut cart = service.create_cart(None);
+        match service.add_item(&mut cart, &masterclass, None, 1) {
+            Err(CartError::MembershipRequired { plan_ids }) => assert_eq!(plan_ids, vec![gold_id]),
+            other => panic!("expected MembershipRequired, got {:?}", other),
+        }
+        assert!(service.is_empty(&cart));
+
+        let user_id = Uuid::now_v7();
+        let memberships = vec![UserMembership {
+            id: Uuid::now_v7(),
+            site_id: None,
+            plan_id: gold_id,
+            user_id,
+            customer_id: None,
+            order_id: None,
+            subscription_id: None,
+            status: MembershipStatus::Active,
+            start_date: now,
+            end_date: None,
+            paused_date: None,
+            cancelled_date: None,
+            in_trial: false,
+            trial_end_date: None,
+            meta: HashMap::new(),
+            created_at: now,
+            updated_at: None,
+        }];
+        cart.member = Some(CartMember { user_id, memberships });
+        service.add_item(&mut cart, &masterclass, None, 1).unwrap();
+        assert_eq!(service.get_item_count(&cart), 1);
+    }
+
+    #[cfg(feature = "memberships")]
+    #[test]
+    fn test_member_discount_stops_when_membership_lapses() {
+        use crate::models::cart::CartItem;
+        use crate::models::membership::{
+            AccessMethod, CartMember, DiscountAppliesTo, MemberDiscount, MemberDiscountType, MembershipPlan,
+            MembershipSettings, MembershipStatus, PlanStatus, UserMembership,
+        };
+
+        let settings = RustCommerceSettings::default();
+        let now = chrono::Utc::now();
+        let gold_id = Uuid::now_v7();
+        let plans = vec![MembershipPlan {
+            id: gold_id,
+            site_id: None,
+            name: "Gold".to_string(),
+            slug: "gold".to_string(),
+            description: None,
+            status: PlanStatus::Active,
+            access_method: AccessMethod::Unlimited,
+            access_length: None,
+            access_length_period: None,
+            access_start_date: None,
+            access_end_date: None,
+            product_ids: Vec::new(),
+            restricted_content: Vec::new(),
+            member_discounts: vec![MemberDiscount {
+                id: Uuid::now_v7(),
+                plan_id: gold_id,
+                discount_type: MemberDiscountType::Percentage,
+                amount: dec!(10),
+                applies_to: DiscountAppliesTo::AllProducts,
+                product_ids: None,
+                category_ids: None,
+                exclude_sale_items: false,
+            }],
+            trial_enabled: false,
+            trial_length: None,
+            trial_period: None,
+            meta: HashMap::new(),
+            created_at: now,
+            updated_at: None,
+        }];
+        let memberships = MembershipService::new(settings.clone(), MembershipSettings::default()).with_plans(plans);
+        let service = CartService::new(settings).with_memberships(Arc::new(memberships));
+
+        let user_id = Uuid::now_v7();
+        let mut cart = Cart::new(None, Some(Uuid::now_v7()));
+        cart.items = vec![CartItem::from_product(&product(), 1, None, HashMap::new())];
+        cart.member = Some(CartMember {
+            user_id,
+            memberships: vec![UserMembership {
+                id: Uuid::now_v7(),
+                site_id: None,
+                plan_id: gold_id,
+                user_id,
+                customer_id: None,
+                order_id: None,
+                subscription_id: None,
+                status: MembershipStatus::Active,
+                start_date: now - chrono::Duration::days(1),
+                end_date: None,
+                paused_date: None,
+                cancelled_date: None,
+                in_trial: false,
+                trial_end_date: None,
+                meta: HashMap::new(),
+                created_at: now,
+                updated_at: None,
+            }],
+        });
+        service.calculate_totals(&mut cart);
+        assert_eq!(cart.totals.discount_total, dec!(10));
+
+        // The discount recorded on the line goes with the membership
+        if let Some(member) = cart.member.as_mut() {
+            member.memberships[0].status = MembershipStatus::Paused;
+        }
+        service.calculate_totals(&mut cart);
+        assert_eq!(cart.totals.discount_total, Decimal::ZERO);
+    }
 }
@@ -6,16 +6,45 @@ use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use uuid::Uuid;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use crate::models::cart::Cart;
 use crate::models::order::{Order, OrderItem, OrderStatus, OrderAddress};
 use crate::models::payment::{PaymentRequest, PaymentResult};
-use crate::models::customer::Customer;
-use crate::settings::RustCommerceSettings;
+use crate::models::customer::{Address, Customer};
+use crate::models::pickup::{OrderPickup, PickupLocation, PickupSelection};
+use crate::models::tax::{AppliedTaxExemption, TaxExemption, TaxLocation, TaxRate};
+#[cfg(feature = "memberships")]
+use crate::models::product::Product;
+#[cfg(feature = "subscriptions")]
+use crate::models::subscription::{Subscription, SubscriptionProductSettings};
+use crate::address::local::LocalAddressVerifier;
+use crate::address::provider::{AddressChange, AddressVerification, AddressVerificationRegistry, VerificationStatus};
+use crate::vat::format::is_valid_vat_format;
+use crate::vat::local::LocalVatProvider;
+use crate::vat::provider::{VatNumberProviderRegistry, VatNumberValidation};
+use crate::geo;
+#[cfg(feature = "multi_currency")]
+use crate::services::currency::CurrencyService;
+use crate::services::pickup::PickupService;
+#[cfg(feature = "memberships")]
+use crate::services::membership::MembershipService;
+#[cfg(feature = "subscriptions")]
+use crate::services::subscription::SubscriptionService;
+use crate::services::tax::{CartTaxResult, TaxService};
+use crate::settings::{RustCommerceSettings, VatNumberField};
 
 /// Checkout service
 pub struct CheckoutService {
     settings: RustCommerceSettings,
+    address_verifiers: Arc<AddressVerificationRegistry>,
+    vat_providers: Arc<VatNumberProviderRegistry>,
+    #[cfg(feature = "multi_currency")]
+    currency_service: Option<Arc<CurrencyService>>,
+    #[cfg(feature = "subscriptions")]
+    subscription_service: Option<Arc<SubscriptionService>>,
+    #[cfg(feature = "memberships")]
+    membership_service: Option<Arc<MembershipService>>,
 }
 
 /// Checkout validation result
@@ -24,6 +53,19 @@ pub struct CheckoutValidation {
     pub is_valid: bool,
     pub errors: Vec<CheckoutError>,
     pub warnings: Vec<String>,
+    pub address_suggestions: Vec<AddressSuggestion>,
+    /// VAT number lookup, when the shopper entered one
+    pub vat_validation: Option<VatNumberValidation>,
+}
+
+/// Suggested correction to a checkout address
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AddressSuggestion {
+    /// "billing" or "shipping"
+    pub address_type: String,
+    pub suggested: Address,
+    pub changes: Vec<AddressChange>,
+    pub provider: String,
 }
 
 /// Checkout errors
@@ -33,10 +75,21 @@ pub enum CheckoutError {
     InvalidBillingAddress(String),
     InvalidShippingAddress(String),
     NoShippingMethod,
+    PickupSlotRequired,
+    PickupUnavailable(String),
+    CurrencyUnavailable(String),
+    AddressReviewRequired,
+    VatNumberRequired,
+    InvalidVatNumber(String),
     NoPaymentMethod,
     StockError { product_id: Uuid, message: String },
     CouponError(String),
     PaymentError(String),
+    SubscriptionError(String),
+    /// Only members of one of these plans can buy the product
+    MembershipRequired { product_id: Uuid, plan_ids: Vec<Uuid> },
+    /// The cart's member discounts no longer match the member's memberships
+    MemberDiscountChanged,
     CustomerRequired,
     TermsNotAccepted,
     InvalidEmail,
@@ -49,10 +102,19 @@ impl std::fmt::Display for CheckoutError {
             Self::InvalidBillingAddress(msg) => write!(f, "Invalid billing address: {}", msg),
             Self::InvalidShippingAddress(msg) => write!(f, "Invalid shipping address: {}", msg),
             Self::NoShippingMethod => write!(f, "Please select a shipping method"),
+            Self::PickupSlotRequired => write!(f, "Please choose a pickup location and time"),
+            Self::PickupUnavailable(msg) => write!(f, "Pickup unavailable: {}", msg),
+            Self::CurrencyUnavailable(msg) => write!(f, "Currency unavailable: {}", msg),
+            Self::AddressReviewRequired => write!(f, "Please review the suggested address"),
+            Self::VatNumberRequired => write!(f, "Please enter your VAT number"),
+            Self::InvalidVatNumber(msg) => write!(f, "Invalid VAT number: {}", msg),
             Self::NoPaymentMethod => write!(f, "Please select a payment method"),
             Self::StockError { message, .. } => write!(f, "Stock error: {}", message),
             Self::CouponError(msg) => write!(f, "Coupon error: {}", msg),
             Self::PaymentError(msg) => write!(f, "Payment error: {}", msg),
+            Self::SubscriptionError(msg) => write!(f, "Subscription error: {}", msg),
+            Self::MembershipRequired { .. } => write!(f, "Your cart has a product only members can purchase"),
+            Self::MemberDiscountChanged => write!(f, "Your member discount has changed, please review your cart"),
             Self::CustomerRequired => write!(f, "Customer information required"),
             Self::TermsNotAccepted => write!(f, "Please accept the terms and conditions"),
             Self::InvalidEmail => write!(f, "Please enter a valid email address"),
@@ -71,11 +133,21 @@ pub struct CheckoutRequest {
     pub billing_address: OrderAddress,
     pub shipping_address: Option<OrderAddress>,
     pub ship_to_different_address: bool,
+    /// Shopper has reviewed any suggested address corrections
+    pub address_confirmed: bool,
+    /// Address types ("billing", "shipping") whose suggested correction
+    /// the shopper accepted
+    pub accepted_address_suggestions: Vec<String>,
+    pub pickup: Option<PickupSelection>,
+    /// EU VAT identification number for reverse charge
+    pub vat_number: Option<String>,
     pub payment_method: String,
     pub payment_token: Option<Uuid>,
     pub customer_note: Option<String>,
     pub create_account: bool,
     pub accept_terms: bool,
+    /// Shopper's `Accept-Language` header or locale tag
+    pub locale: Option<String>,
 }
 
 /// Checkout result
@@ -91,14 +163,76 @@ pub struct CheckoutResult {
 impl CheckoutService {
     /// Create a new checkout service
     pub fn new(settings: RustCommerceSettings) -> Self {
-        Self { settings }
+        let mut address_verifiers = AddressVerificationRegistry::new();
+        address_verifiers.register(Arc::new(LocalAddressVerifier::new()));
+        let mut vat_providers = VatNumberProviderRegistry::new();
+        vat_providers.register(Arc::new(LocalVatProvider::new()));
+
+        Self {
+            settings,
+            address_verifiers: Arc::new(address_verifiers),
+            vat_providers: Arc::new(vat_providers),
+            #[cfg(feature = "multi_currency")]
+            currency_service: None,
+            #[cfg(feature = "subscriptions")]
+            subscription_service: None,
+            #[cfg(feature = "memberships")]
+            membership_service: None,
+        }
+    }
+
+    /// Place orders in the cart's currency
+    #[cfg(feature = "multi_currency")]
+    pub fn with_currency(mut self, currency_service: Arc<CurrencyService>) -> Self {
+        self.currency_service = Some(currency_service);
+        self
+    }
+
+    /// Start subscriptions for subscription products bought at checkout
+    #[cfg(feature = "subscriptions")]
+    pub fn with_subscriptions(mut self, subscription_service: Arc<SubscriptionService>) -> Self {
+        self.subscription_service = Some(subscription_service);
+        self
+    }
+
+    /// Enforce member-only purchase restrictions when the order is placed
+    #[cfg(feature = "memberships")]
+    pub fn with_memberships(mut self, membership_service: Arc<MembershipService>) -> Self {
+        self.membership_service = Some(membership_service);
+        self
+    }
+
+    /// Use the plugin's address verification providers
+    pub fn with_address_verifiers(mut self, registry: Arc<AddressVerificationRegistry>) -> Self {
+        self.address_verifiers = registry;
+        self
+    }
+
+    /// Use the plugin's VAT number providers
+    pub fn with_vat_providers(mut self, registry: Arc<VatNumberProviderRegistry>) -> Self {
+        self.vat_providers = registry;
+        self
     }
 
-    /// Validate checkout data
-    pub fn validate(&self, cart: &Cart, request: &CheckoutRequest) -> CheckoutValidation {
+    /// Validate checkout data.
+    ///
+    /// Address suggestions the shopper accepted are written back to the
+    /// request first, so the order is created with the corrected address.
+    pub async fn validate(&self, cart: &Cart, request: &mut CheckoutRequest) -> CheckoutValidation {
         let mut errors = Vec::new();
         let mut warnings = Vec::new();
 
+        // Suggest corrected addresses (state codes, postcode formatting)
+        let mut address_suggestions = if self.settings.checkout.suggest_address_corrections {
+            self.verify_addresses(request).await
+        } else {
+            vec![]
+        };
+        address_suggestions.retain(|suggestion| !Self::apply_address_suggestion(request, suggestion));
+        if !address_suggestions.is_empty() && !request.address_confirmed {
+            errors.push(CheckoutError::AddressReviewRequired);
+        }
+
         // Check cart is not empty
         if cart.items.is_empty() {
             errors.push(CheckoutError::CartEmpty);
@@ -127,11 +261,35 @@ impl CheckoutService {
             }
         }
 
+        // VAT number format and registry lookup
+        let vat_validation = match self.validate_vat_number(request).await {
+            Ok(validation) => validation,
+            Err(error) => {
+                errors.push(error);
+                None
+            }
+        };
+
         // Check shipping method (if cart needs shipping)
         if self.cart_needs_shipping(cart) && cart.shipping_method_id.is_none() {
             errors.push(CheckoutError::NoShippingMethod);
         }
 
+        // Local pickup needs a location and time slot
+        let is_pickup = cart.chosen_shipping_method.as_deref()
+            .is_some_and(|m| m.starts_with("local_pickup:"));
+        if is_pickup && request.pickup.is_none() {
+            errors.push(CheckoutError::PickupSlotRequired);
+        }
+
+        // The cart's currency must still be offered
+        #[cfg(feature = "multi_currency")]
+        if let (Some(currency), Some(code)) = (&self.currency_service, cart.currency.as_deref()) {
+            if let Err(error) = currency.get_active_currency(code) {
+                errors.push(CheckoutError::CurrencyUnavailable(error.to_string()));
+            }
+        }
+
         // Check payment method
         if request.payment_method.is_empty() {
             errors.push(CheckoutError::NoPaymentMethod);
@@ -146,11 +304,15 @@ impl CheckoutService {
             is_valid: errors.is_empty(),
             errors,
             warnings,
+            address_suggestions,
+            vat_validation,
         }
     }
 
-    /// Create order from cart
-    pub fn create_order(&self, cart: &Cart, request: &CheckoutRequest) -> Order {
+    /// Create order from cart, with the pickup from `validate_pickup`.
+    /// Carts with member discounts are checked with
+    /// `validate_member_purchases` first.
+    pub fn create_order(&self, cart: &Cart, request: &CheckoutRequest, pickup: Option<OrderPickup>) -> Order {
         let order_id = Uuid::now_v7();
         let order_number = self.generate_order_number();
 
@@ -180,6 +342,8 @@ impl CheckoutService {
 
         // Create order items
         let items: Vec<OrderItem> = cart.items.iter().map(|item| {
+            let mut meta = serde_json::json!(item.meta);
+            TaxService::order_item_tax_meta(item, &mut meta);
             OrderItem {
                 id: Uuid::now_v7(),
                 order_id,
@@ -194,17 +358,19 @@ impl CheckoutService {
                 total: item.line_total,
                 total_tax: item.line_tax,
                 sku: None,
-                meta: item.meta.clone(),
+                meta,
             }
         }).collect();
 
-        Order {
+        let mut order = Order {
             id: order_id,
             site_id: cart.site_id,
             order_number,
             customer_id: request.customer_id,
             status: OrderStatus::Pending,
-            currency: self.settings.general.currency.clone(),
+            currency: cart.currency.clone().unwrap_or_else(|| self.settings.general.currency.clone()),
+            base_currency_rate: cart.currency_rate,
+            locale: request.locale.as_deref().map(|l| crate::locale::negotiate_locale(l).tag.to_string()),
 
             // Pricing
             subtotal: cart.totals.subtotal,
@@ -231,6 +397,7 @@ impl CheckoutService {
             shipping_first_name,
             shipping_last_name,
             shipping_address,
+            pickup,
 
             // Items
             items,
@@ -257,16 +424,68 @@ impl CheckoutService {
             updated_at: None,
             paid_at: None,
             completed_at: None,
+        };
+
+        // Record the currency; the rate stays the one the cart was priced at
+        #[cfg(feature = "multi_currency")]
+        if let (Some(currency), Some(code)) = (&self.currency_service, cart.currency.as_deref()) {
+            if currency.apply_to_order(&mut order, code).is_ok() {
+                order.base_currency_rate = cart.currency_rate.or(order.base_currency_rate);
+            }
+        }
+
+        // Member discounts get an order line per plan
+        #[cfg(feature = "memberships")]
+        if let Some(memberships) = &self.membership_service {
+            memberships.apply_to_order(cart, &mut order);
         }
+
+        order
+    }
+
+    /// Location id of the chosen `local_pickup:{instance}:{location}` rate
+    fn chosen_pickup_location(cart: &Cart) -> Option<Uuid> {
+        let method = cart.chosen_shipping_method.as_deref()?.strip_prefix("local_pickup:")?;
+        method.rsplit(':').next().and_then(|id| Uuid::parse_str(id).ok())
+    }
+
+    /// Check the pickup selection against the chosen pickup rate, the
+    /// location's opening hours and slot capacity, returning the pickup to
+    /// store on the order
+    pub fn validate_pickup(
+        &self,
+        cart: &Cart,
+        request: &CheckoutRequest,
+        pickups: &PickupService,
+        locations: &[PickupLocation],
+        booked: &[OrderPickup],
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<OrderPickup>, CheckoutError> {
+        let is_pickup = cart.chosen_shipping_method.as_deref()
+            .is_some_and(|m| m.starts_with("local_pickup:"));
+        if !is_pickup {
+            return Ok(None);
+        }
+        let selection = request.pickup.as_ref().ok_or(CheckoutError::PickupSlotRequired)?;
+
+        if Self::chosen_pickup_location(cart) != Some(selection.location_id) {
+            return Err(CheckoutError::PickupUnavailable(
+                "The pickup location does not match the chosen shipping method".to_string()
+            ));
+        }
+        let location = locations.iter()
+            .find(|l| l.id == selection.location_id)
+            .ok_or_else(|| CheckoutError::PickupUnavailable("Unknown pickup location".to_string()))?;
+
+        let booked = pickups.booked_counts(location.id, booked);
+        let slot = pickups.validate_selection(location, selection, &booked, now)
+            .map_err(|e| CheckoutError::PickupUnavailable(e.to_string()))?;
+        Ok(Some(pickups.order_pickup(location, &slot)))
     }
 
     /// Generate unique order number
     fn generate_order_number(&self) -> String {
-        // Format: RC-YYYYMMDD-XXXX
-        let now = chrono::Utc::now();
-        let date_part = now.format("%Y%m%d").to_string();
-        let random_part: u16 = rand::random::<u16>() % 10000;
-        format!("RC-{}-{:04}", date_part, random_part)
+        Order::generate_number(chrono::Utc::now())
     }
 
     /// Validate email format
@@ -286,12 +505,194 @@ impl CheckoutService {
         if address.country.trim().is_empty() {
             return Err("Country is required".to_string());
         }
+        if !geo::is_valid_country(&address.country) {
+            return Err(format!("Unknown country: {}", address.country));
+        }
+        // Only countries whose address format needs a state check it against
+        // the subdivision list; elsewhere it's free text such as a county
+        if geo::requires_state(&address.country) {
+            if address.state.trim().is_empty() {
+                return Err("State / County is required".to_string());
+            }
+            if geo::country(&address.country).is_some_and(|c| c.has_subdivisions())
+                && geo::state_subdivision(&address.country, &address.state).is_none()
+            {
+                return Err(format!("Unknown state / county: {}", address.state));
+            }
+        }
         if address.postcode.trim().is_empty() {
-            return Err("Postal code is required".to_string());
+            if geo::requires_postcode(&address.country) {
+                return Err("Postal code is required".to_string());
+            }
+        } else if !geo::is_valid_postcode(&address.country, &address.postcode) {
+            return Err(match geo::postcode_example(&address.country) {
+                Some(example) => format!("Postal code {} is not valid (expected {})", address.postcode.trim(), example),
+                None => format!("Postal code {} is not valid", address.postcode.trim()),
+            });
         }
         Ok(())
     }
 
+    /// Address fields checked by verification providers
+    fn verification_address(address: &OrderAddress) -> Address {
+        Address {
+            address_1: address.address_1.clone(),
+            city: address.city.clone(),
+            state: address.state.clone(),
+            postcode: address.postcode.clone(),
+            country: address.country.clone(),
+            ..Address::default()
+        }
+    }
+
+    /// Addresses to verify, labelled billing / shipping
+    fn checkout_addresses(request: &CheckoutRequest) -> Vec<(&'static str, Address)> {
+        let mut addresses = vec![("billing", Self::verification_address(&request.billing_address))];
+        if request.ship_to_different_address {
+            if let Some(ref shipping) = request.shipping_address {
+                addresses.push(("shipping", Self::verification_address(shipping)));
+            }
+        }
+        addresses
+    }
+
+    /// Turn a verification result into a suggestion, if it corrects the address
+    fn suggestion_from(
+        address_type: &str,
+        original: &Address,
+        verification: &AddressVerification,
+    ) -> Option<AddressSuggestion> {
+        if verification.status != VerificationStatus::Corrected {
+            return None;
+        }
+
+        let changes = verification.changes(original);
+        if changes.is_empty() {
+            return None;
+        }
+
+        Some(AddressSuggestion {
+            address_type: address_type.to_string(),
+            suggested: verification.suggestion.clone()?,
+            changes,
+            provider: verification.provider.clone(),
+        })
+    }
+
+    /// Verify checkout addresses with the configured provider.
+    ///
+    /// Falls back to the offline "local" provider when the configured one is
+    /// unavailable, so a verification outage never blocks checkout.
+    pub async fn verify_addresses(&self, request: &CheckoutRequest) -> Vec<AddressSuggestion> {
+        let provider_id = &self.settings.checkout.address_verification_provider;
+        let mut suggestions = Vec::new();
+
+        for (address_type, address) in Self::checkout_addresses(request) {
+            let verification = match self.address_verifiers.verify(provider_id, &address).await {
+                Ok(verification) => verification,
+                Err(_) => match self.address_verifiers.verify("local", &address).await {
+                    Ok(verification) => verification,
+                    Err(_) => continue,
+                },
+            };
+            if let Some(suggestion) = Self::suggestion_from(address_type, &address, &verification) {
+                suggestions.push(suggestion);
+            }
+        }
+
+        suggestions
+    }
+
+    /// Write an accepted suggestion back to the checkout address.
+    ///
+    /// Returns false when the shopper has not accepted it.
+    fn apply_address_suggestion(request: &mut CheckoutRequest, suggestion: &AddressSuggestion) -> bool {
+        if !request.accepted_address_suggestions.contains(&suggestion.address_type) {
+            return false;
+        }
+
+        let address = match suggestion.address_type.as_str() {
+            "billing" => Some(&mut request.billing_address),
+            "shipping" => request.shipping_address.as_mut(),
+            _ => None,
+        };
+        let Some(address) = address else {
+            return false;
+        };
+
+        let suggested = &suggestion.suggested;
+        address.address_1 = suggested.address_1.clone();
+        address.city = suggested.city.clone();
+        address.state = suggested.state.clone();
+        address.postcode = suggested.postcode.clone();
+        address.country = suggested.country.clone();
+        true
+    }
+
+    /// Entered VAT number, if any
+    fn vat_number(request: &CheckoutRequest) -> Option<&str> {
+        request.vat_number.as_deref().map(str::trim).filter(|n| !n.is_empty())
+    }
+
+    /// Check the VAT number is present when required and well formed.
+    ///
+    /// The field is only shown to EU customers, so it is only required of them.
+    fn validate_vat_number_format(&self, request: &CheckoutRequest) -> Result<(), CheckoutError> {
+        let field = self.settings.tax.eu_vat.vat_number_field;
+        if field == VatNumberField::Hidden || !geo::is_eu_member(&request.billing_address.country) {
+            return Ok(());
+        }
+
+        match Self::vat_number(request) {
+            None if field == VatNumberField::Required => Err(CheckoutError::VatNumberRequired),
+            None => Ok(()),
+            Some(number) if !is_valid_vat_format(number, Some(&request.billing_address.country)) => {
+                Err(CheckoutError::InvalidVatNumber(format!("{} is not a valid VAT number", number)))
+            }
+            Some(_) => Ok(()),
+        }
+    }
+
+    /// Look the VAT number up with the configured provider.
+    ///
+    /// When the lookup service is down and outages are accepted, a
+    /// well-formed number passes on the offline check; the validation
+    /// records the "local" provider so it can be re-checked later.
+    pub async fn validate_vat_number(
+        &self,
+        request: &CheckoutRequest,
+    ) -> Result<Option<VatNumberValidation>, CheckoutError> {
+        self.validate_vat_number_format(request)?;
+
+        let field = self.settings.tax.eu_vat.vat_number_field;
+        let country = request.billing_address.country.as_str();
+        let Some(number) = Self::vat_number(request)
+            .filter(|_| field != VatNumberField::Hidden && geo::is_eu_member(country))
+        else {
+            return Ok(None);
+        };
+
+        let provider_id = &self.settings.tax.eu_vat.vat_validation_provider;
+        let registry = &self.vat_providers;
+
+        let validation = match registry.validate(provider_id, number, Some(country)).await {
+            Ok(validation) => validation,
+            Err(error) if error.is_outage() && self.settings.tax.eu_vat.accept_on_validation_outage => {
+                registry.validate("local", number, Some(country)).await
+                    .map_err(|_| CheckoutError::InvalidVatNumber(error.to_string()))?
+            }
+            Err(error) => return Err(CheckoutError::InvalidVatNumber(error.to_string())),
+        };
+
+        if !validation.valid {
+            return Err(CheckoutError::InvalidVatNumber(
+                format!("{} is not a registered VAT number", validation.vat_number),
+            ));
+        }
+
+        Ok(Some(validation))
+    }
+
     /// Check if cart needs shipping
     fn cart_needs_shipping(&self, cart: &Cart) -> bool {
         // In full implementation, check if items are virtual
@@ -304,6 +705,13 @@ impl CheckoutService {
         order: &mut Order,
         payment_request: PaymentRequest,
     ) -> Result<PaymentResult, CheckoutError> {
+        if !payment_request.currency.eq_ignore_ascii_case(&order.currency) {
+            return Err(CheckoutError::PaymentError(format!(
+                "Payment currency {} does not match order currency {}",
+                payment_request.currency, order.currency
+            )));
+        }
+
         // This would integrate with actual payment gateways
         // For now, return a mock success
         let result = PaymentResult::success(format!("txn_{}", Uuid::now_v7()));
@@ -317,23 +725,78 @@ impl CheckoutService {
         Ok(result)
     }
 
+    /// Start the subscriptions bought in an order once its first payment
+    /// has gone through. The payment's saved token is charged at renewal.
+    #[cfg(feature = "subscriptions")]
+    pub fn create_subscriptions(
+        &self,
+        order: &Order,
+        plans: &HashMap<Uuid, SubscriptionProductSettings>,
+        payment: &PaymentRequest,
+    ) -> Result<Vec<Subscription>, CheckoutError> {
+        let Some(ref subscriptions) = self.subscription_service else {
+            return Ok(Vec::new());
+        };
+        subscriptions.create_from_order(order, plans, payment.payment_token)
+            .map_err(|e| CheckoutError::SubscriptionError(e.to_string()))
+    }
+
+    /// Check the cart's member may still buy every product in it and
+    /// still gets the member discounts recorded on it. A membership can
+    /// lapse between adding a product and paying for it.
+    #[cfg(feature = "memberships")]
+    pub fn validate_member_purchases(&self, cart: &Cart, products: &[Product]) -> Result<(), CheckoutError> {
+        let Some(ref memberships) = self.membership_service else {
+            return Ok(());
+        };
+        let now = chrono::Utc::now();
+        let recorded = MembershipService::cart_discount(cart).map(|d| d.total);
+        let mut current = cart.clone();
+        memberships.refresh_cart(&mut current, now);
+        if MembershipService::cart_discount(&current).map(|d| d.total) != recorded {
+            return Err(CheckoutError::MemberDiscountChanged);
+        }
+
+        let member = memberships.cart_context(cart, now);
+        for item in &cart.items {
+            let Some(product) = products.iter().find(|p| p.id == item.product_id) else {
+                continue;
+            };
+            let access = memberships.check_purchase(&member, product);
+            if !access.has_access {
+                return Err(CheckoutError::MembershipRequired {
+                    product_id: product.id,
+                    plan_ids: access.required_plan_ids,
+                });
+            }
+        }
+        Ok(())
+    }
+
     /// Get checkout fields for a country
     pub fn get_checkout_fields(&self, country: &str) -> CheckoutFields {
-        // Return default fields - in full implementation, customize by country
+        let mut billing = vec![
+            CheckoutField::new("first_name", "First name", true),
+            CheckoutField::new("last_name", "Last name", true),
+            CheckoutField::new("company", "Company", false),
+            CheckoutField::new("address_1", "Street address", true),
+            CheckoutField::new("address_2", "Apartment, suite, etc.", false),
+            CheckoutField::new("city", "City", true),
+            self.state_field(country),
+            CheckoutField::new("postcode", "Postal code", true),
+            self.country_field(),
+            CheckoutField::new("phone", "Phone", false),
+            CheckoutField::new("email", "Email", true),
+        ];
+
+        // VAT number for EU business customers, after the company name
+        let vat_field = self.settings.tax.eu_vat.vat_number_field;
+        if vat_field != VatNumberField::Hidden && geo::is_eu_member(country) {
+            billing.insert(3, CheckoutField::new("vat_number", "VAT number", vat_field == VatNumberField::Required));
+        }
+
         CheckoutFields {
-            billing: vec![
-                CheckoutField::new("first_name", "First name", true),
-                CheckoutField::new("last_name", "Last name", true),
-                CheckoutField::new("company", "Company", false),
-                CheckoutField::new("address_1", "Street address", true),
-                CheckoutField::new("address_2", "Apartment, suite, etc.", false),
-                CheckoutField::new("city", "City", true),
-                CheckoutField::new("state", "State / Province", true),
-                CheckoutField::new("postcode", "Postal code", true),
-                CheckoutField::new("country", "Country", true),
-                CheckoutField::new("phone", "Phone", false),
-                CheckoutField::new("email", "Email", true),
-            ],
+            billing,
             shipping: vec![
                 CheckoutField::new("first_name", "First name", true),
                 CheckoutField::new("last_name", "Last name", true),
@@ -341,9 +804,9 @@ impl CheckoutService {
                 CheckoutField::new("address_1", "Street address", true),
                 CheckoutField::new("address_2", "Apartment, suite, etc.", false),
                 CheckoutField::new("city", "City", true),
-                CheckoutField::new("state", "State / Province", true),
+                self.state_field(country),
                 CheckoutField::new("postcode", "Postal code", true),
-                CheckoutField::new("country", "Country", true),
+                self.country_field(),
             ],
             order: vec![
                 CheckoutField::new("customer_note", "Order notes", false),
@@ -351,6 +814,22 @@ impl CheckoutService {
         }
     }
 
+    /// Country select field with all ISO 3166-1 countries
+    fn country_field(&self) -> CheckoutField {
+        CheckoutField::select("country", "Country", true, geo::country_options())
+    }
+
+    /// State field: a required select of the country's subdivisions where its
+    /// address format needs a state, otherwise optional free text
+    fn state_field(&self, country: &str) -> CheckoutField {
+        let options = geo::subdivision_options(country);
+        if geo::requires_state(country) && !options.is_empty() {
+            CheckoutField::select("state", "State / County", true, options)
+        } else {
+            CheckoutField::new("state", "State / County", geo::requires_state(country))
+        }
+    }
+
     /// Calculate order totals
     pub fn calculate_order_totals(&self, order: &mut Order) {
         let mut subtotal = Decimal::ZERO;
@@ -387,6 +866,50 @@ impl CheckoutService {
         order.discount_total = discount_total;
         order.total = subtotal + shipping_total + fee_total + order.total_tax - discount_total;
     }
+
+    /// Tax location for checkout addresses: shipping when shipping to a
+    /// different address, billing otherwise
+    fn tax_location(request: &CheckoutRequest) -> TaxLocation {
+        let address = match request.shipping_address {
+            Some(ref shipping) if request.ship_to_different_address => shipping,
+            _ => &request.billing_address,
+        };
+        TaxLocation::new(&address.country, &address.state, &address.postcode, &address.city)
+    }
+
+    /// Tax step: the cart's taxes for the checkout addresses, less the rates
+    /// waived by the customer's exemption for the tax location
+    pub async fn calculate_taxes(
+        &self,
+        taxes: &TaxService,
+        cart: &Cart,
+        request: &CheckoutRequest,
+        rates: &[TaxRate],
+        product_tax_classes: &HashMap<Uuid, String>,
+        exemptions: &[TaxExemption],
+    ) -> CartTaxResult {
+        let location = Self::tax_location(request);
+        taxes.calculate_exempt_cart_taxes(cart, &location, rates, product_tax_classes, exemptions).await
+    }
+
+    /// Tax step for the placed order: record the customer's exemption for
+    /// the order's tax location, zeroing the taxes of the rates it covers
+    pub fn apply_tax_exemption(
+        &self,
+        taxes: &TaxService,
+        order: &mut Order,
+        request: &CheckoutRequest,
+        rates: &[TaxRate],
+        exemptions: &[TaxExemption],
+    ) -> Option<AppliedTaxExemption> {
+        let customer_id = order.customer_id?;
+        let location = Self::tax_location(request);
+        let today = chrono::Utc::now().date_naive();
+        let (exemption, jurisdiction) = taxes.find_exemption(exemptions, customer_id, &location, today)?;
+
+        taxes.apply_exemption_to_order(order, exemption, jurisdiction, rates);
+        order.tax_exemption.clone()
+    }
 }
 
 /// Checkout fields configuration
@@ -419,6 +942,14 @@ impl CheckoutField {
             options: Vec::new(),
         }
     }
+
+    pub fn select(name: &str, label: &str, required: bool, options: Vec<(String, String)>) -> Self {
+        Self {
+            field_type: "select".to_string(),
+            options,
+            ..Self::new(name, label, required)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -445,4 +976,75 @@ mod tests {
         assert!(order_number.starts_with("RC-"));
         assert!(order_number.len() > 10);
     }
+
+    #[test]
+    fn test_checkout_fields_use_country_data() {
+        let settings = RustCommerceSettings::default();
+        let service = CheckoutService::new(settings);
+
+        let fields = service.get_checkout_fields("US");
+        let state = fields.billing.iter().find(|f| f.name == "state").unwrap();
+        assert_eq!(state.field_type, "select");
+        assert!(state.options.iter().any(|(code, _)| code == "CA"));
+        assert!(state.required);
+
+        // UK counties are optional free text
+        let fields = service.get_checkout_fields("GB");
+        let county = fields.billing.iter().find(|f| f.name == "state").unwrap();
+        assert_eq!(county.field_type, "text");
+        assert!(!county.required);
+
+        let country = fields.billing.iter().find(|f| f.name == "country").unwrap();
+        assert!(country.options.iter().any(|(code, _)| code == "PT"));
+    }
+
+    #[tokio::test]
+    async fn test_address_suggestion() {
+        let service = CheckoutService::new(RustCommerceSettings::default());
+        let address = Address {
+            address_1: "1 Main St".to_string(),
+            city: "Ottawa".to_string(),
+            state: "Ontario".to_string(),
+            postcode: "k1a0b1".to_string(),
+            country: "CA".to_string(),
+            ..Address::default()
+        };
+
+        let verification = service.address_verifiers.verify("local", &address).await.unwrap();
+        let suggestion = CheckoutService::suggestion_from("billing", &address, &verification).unwrap();
+        assert_eq!(suggestion.suggested.postcode, "K1A 0B1");
+        assert_eq!(suggestion.suggested.state, "ON");
+        assert_eq!(suggestion.changes.len(), 2);
+
+        let verification = service.address_verifiers.verify("local", &suggestion.suggested).await.unwrap();
+        assert!(CheckoutService::suggestion_from("billing", &suggestion.suggested, &verification).is_none());
+    }
+
+    #[test]
+    fn test_vat_number_field() {
+        let mut settings = RustCommerceSettings::default();
+        settings.tax.eu_vat.vat_number_field = VatNumberField::Required;
+        let service = CheckoutService::new(settings);
+
+        let fields = service.get_checkout_fields("AT");
+        let vat = fields.billing.iter().find(|f| f.name == "vat_number").unwrap();
+        assert!(vat.required);
+
+        // Only shown to EU customers
+        let fields = service.get_checkout_fields("US");
+        assert!(fields.billing.iter().all(|f| f.name != "vat_number"));
+    }
+
+    #[test]
+    fn test_chosen_pickup_location() {
+        let location_id = Uuid::now_v7();
+        let mut cart = Cart::new(None, None);
+        assert_eq!(CheckoutService::chosen_pickup_location(&cart), None);
+
+        cart.chosen_shipping_method = Some(format!("local_pickup:3:{}", location_id));
+        assert_eq!(CheckoutService::chosen_pickup_location(&cart), Some(location_id));
+
+        cart.chosen_shipping_method = Some("flat_rate:1".to_string());
+        assert_eq!(CheckoutService::chosen_pickup_location(&cart), None);
+    }
 }
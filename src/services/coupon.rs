@@ -402,9 +402,12 @@ mod tests {
                 ..Default::default()
             },
             shipping_method_id: None,
+            currency: None,
+            currency_rate: None,
             shipping_address: None,
             billing_address: None,
             customer_note: None,
+            member: None,
             created_at: Utc::now(),
             updated_at: None,
             expires_at: None,
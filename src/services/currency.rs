@@ -0,0 +1,590 @@
+//! Currency Service
+//!
+//! Resolves the shopper's currency, converts prices from the base currency
+//! (or uses fixed per-currency prices) and converts amounts back to the base
+//! currency for reporting.
+
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::models::cart::{Cart, CartTotals};
+use crate::models::currency::{
+    Currency, CurrencyDetection, CurrencyPrice, CurrencyPriceType, CurrencySettings, CurrencyZone,
+    ExchangeRate, PriceConversion,
+};
+use crate::models::order::Order;
+use crate::models::product::Product;
+
+/// Currency error
+#[derive(Debug, Clone)]
+pub enum CurrencyError {
+    UnknownCurrency(String),
+    InactiveCurrency(String),
+    InvalidRate(String),
+}
+
+impl std::fmt::Display for CurrencyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownCurrency(code) => write!(f, "Unknown currency: {}", code),
+            Self::InactiveCurrency(code) => write!(f, "Currency is not available: {}", code),
+            Self::InvalidRate(code) => write!(f, "No valid exchange rate for {}", code),
+        }
+    }
+}
+
+impl std::error::Error for CurrencyError {}
+
+/// What is known about the shopper when choosing a currency
+#[derive(Debug, Clone, Default)]
+pub struct CurrencyContext {
+    /// Picked in the currency switcher (session or cookie)
+    pub selected: Option<String>,
+    /// Saved on the customer's account
+    pub customer_preference: Option<String>,
+    /// Country from IP geolocation
+    pub geolocated_country: Option<String>,
+    pub shipping_country: Option<String>,
+    /// Raw `Accept-Language` header
+    pub accept_language: Option<String>,
+}
+
+/// Currency service
+#[derive(Debug, Clone)]
+pub struct CurrencyService {
+    settings: CurrencySettings,
+    currencies: Vec<Currency>,
+    zones: Vec<CurrencyZone>,
+    prices: Vec<CurrencyPrice>,
+}
+
+impl CurrencyService {
+    /// Create a currency service. The base currency must be among `currencies`.
+    pub fn new(settings: CurrencySettings, currencies: Vec<Currency>, zones: Vec<CurrencyZone>) -> Self {
+        Self { settings, currencies, zones, prices: Vec::new() }
+    }
+
+    /// Fixed per-currency product prices, used instead of converting the
+    /// base price
+    pub fn with_prices(mut self, prices: Vec<CurrencyPrice>) -> Self {
+        self.prices = prices;
+        self
+    }
+
+    /// Take refreshed exchange rates (from `ExchangeRateService`) for
+    /// conversions from now on
+    pub fn update_rates(&mut self, rates: &[ExchangeRate]) {
+        for rate in rates.iter().filter(|r| r.from_currency.eq_ignore_ascii_case(&self.settings.base_currency)) {
+            if let Some(currency) = self.currencies.iter_mut().find(|c| c.code.eq_ignore_ascii_case(&rate.to_currency)) {
+                currency.exchange_rate = rate.rate;
+                currency.updated_at = Some(rate.fetched_at);
+            }
+        }
+    }
+
+    /// Check if more than one currency can be used
+    pub fn is_enabled(&self) -> bool {
+        self.settings.enabled && self.active_currencies().len() > 1
+    }
+
+    /// Base (store) currency code
+    pub fn base_currency_code(&self) -> &str {
+        &self.settings.base_currency
+    }
+
+    /// Get the base currency
+    pub fn base_currency(&self) -> Option<&Currency> {
+        self.get_currency(&self.settings.base_currency)
+    }
+
+    /// Get a currency by code
+    pub fn get_currency(&self, code: &str) -> Option<&Currency> {
+        self.currencies.iter().find(|c| c.code.eq_ignore_ascii_case(code))
+    }
+
+    /// Get an active currency by code
+    pub fn get_active_currency(&self, code: &str) -> Result<&Currency, CurrencyError> {
+        let currency = self.get_currency(code)
+            .ok_or_else(|| CurrencyError::UnknownCurrency(code.to_string()))?;
+        if !currency.is_active && !currency.is_default {
+            return Err(CurrencyError::InactiveCurrency(currency.code.clone()));
+        }
+        Ok(currency)
+    }
+
+    /// Currencies shoppers can switch to
+    pub fn active_currencies(&self) -> Vec<&Currency> {
+        self.currencies.iter()
+            .filter(|c| c.is_active || c.code == self.settings.base_currency)
+            .collect()
+    }
+
+    /// Resolve the shopper's currency.
+    ///
+    /// A switcher selection wins, then the configured detection method,
+    /// then the base currency.
+    pub fn resolve_currency(&self, context: &CurrencyContext) -> String {
+        if !self.settings.enabled {
+            return self.settings.base_currency.clone();
+        }
+
+        let usable = |code: &Option<String>| {
+            code.as_deref()
+                .and_then(|c| self.get_active_currency(c).ok())
+                .map(|c| c.code.clone())
+        };
+
+        if let Some(code) = usable(&context.selected) {
+            return code;
+        }
+
+        if self.settings.auto_detect_currency {
+            let detected = match self.settings.detection_method {
+                CurrencyDetection::Geolocation => {
+                    context.geolocated_country.as_deref().and_then(|c| self.currency_for_country(c))
+                }
+                CurrencyDetection::ShippingCountry => {
+                    context.shipping_country.as_deref()
+                        .or(context.geolocated_country.as_deref())
+                        .and_then(|c| self.currency_for_country(c))
+                }
+                CurrencyDetection::BrowserLanguage => {
+                    context.accept_language.as_deref().and_then(|h| self.currency_for_accept_language(h))
+                }
+                CurrencyDetection::UserPreference => usable(&context.customer_preference),
+                CurrencyDetection::None => None,
+            };
+
+            if let Some(code) = detected {
+                return code;
+            }
+        }
+
+        self.settings.base_currency.clone()
+    }
+
+    /// Currency of the highest-priority auto-switch zone containing a country
+    pub fn currency_for_country(&self, country: &str) -> Option<String> {
+        let mut zones: Vec<&CurrencyZone> = self.zones.iter()
+            .filter(|z| z.is_active && z.auto_switch)
+            .filter(|z| z.countries.iter().any(|c| crate::geo::same_country(c, country)))
+            .collect();
+        zones.sort_by_key(|z| z.priority);
+
+        zones.into_iter()
+            .find_map(|z| self.get_active_currency(&z.currency_code).ok())
+            .map(|c| c.code.clone())
+    }
+
+    /// Currency from the first `Accept-Language` entry with a region, e.g.
+    /// "de-CH,de;q=0.9" resolves through the zone containing CH
+    pub fn currency_for_accept_language(&self, header: &str) -> Option<String> {
+        header.split(',')
+            .filter_map(|entry| entry.split(';').next())
+            .filter_map(|tag| tag.trim().split(['-', '_']).nth(1))
+            .filter(|region| region.len() == 2)
+            .find_map(|region| self.currency_for_country(region))
+    }
+
+    /// Exchange rate from the base currency to `code`, including markup
+    pub fn rate_with_markup(&self, code: &str) -> Result<Decimal, CurrencyError> {
+        let currency = self.get_active_currency(code)?;
+        if currency.code == self.settings.base_currency {
+            return Ok(Decimal::ONE);
+        }
+        if currency.exchange_rate <= Decimal::ZERO {
+            return Err(CurrencyError::InvalidRate(currency.code.clone()));
+        }
+        Ok(currency.exchange_rate * (Decimal::ONE + currency.rate_markup / Decimal::from(100)))
+    }
+
+    /// Convert an amount between currencies, applying markup and the
+    /// target currency's rounding
+    pub fn convert(&self, amount: Decimal, from: &str, to: &str) -> Result<Decimal, CurrencyError> {
+        if from.eq_ignore_ascii_case(to) {
+            return Ok(amount);
+        }
+
+        let from_rate = self.rate_with_markup(from)?;
+        let to_rate = self.rate_with_markup(to)?;
+        let target = self.get_active_currency(to)?;
+
+        Ok(self.round_converted(target, amount / from_rate * to_rate))
+    }
+
+    /// Convert an amount and describe the conversion, formatting both
+    /// amounts for `locale`
+    pub fn conversion(&self, amount: Decimal, from: &str, to: &str, locale: &str) -> Result<PriceConversion, CurrencyError> {
+        let source = self.get_active_currency(from)?;
+        let target = self.get_active_currency(to)?;
+        let converted = self.convert(amount, from, to)?;
+
+        let base_rate = |c: &Currency| if c.code == self.settings.base_currency { Decimal::ONE } else { c.exchange_rate };
+
+        Ok(PriceConversion {
+            original_amount: amount,
+            original_currency: source.code.clone(),
+            converted_amount: converted,
+            target_currency: target.code.clone(),
+            exchange_rate: base_rate(target) / base_rate(source),
+            rate_with_markup: self.rate_with_markup(to)? / self.rate_with_markup(from)?,
+            formatted_original: source.format_amount_for_locale(amount, locale),
+            formatted_converted: target.format_amount_for_locale(converted, locale),
+        })
+    }
+
+    /// Round a converted price: the currency's rounding method, then the
+    /// store-wide rounding increment
+    fn round_converted(&self, currency: &Currency, amount: Decimal) -> Decimal {
+        if !self.settings.round_converted_prices {
+            return amount.round_dp(currency.decimals.max(0) as u32);
+        }
+
+        let rounded = currency.round_amount(amount);
+        match self.settings.rounding_increment {
+            Some(increment) if increment > Decimal::ZERO => (rounded / increment).round() * increment,
+            _ => rounded,
+        }
+    }
+
+    /// Fixed price set for a product (or variation) in a currency
+    pub fn fixed_price(&self, product_id: Uuid, variation_id: Option<Uuid>, code: &str) -> Option<&CurrencyPrice> {
+        self.prices.iter().find(|p| {
+            p.price_type == CurrencyPriceType::Manual
+                && p.product_id == product_id
+                && p.variation_id == variation_id
+                && p.currency_code.eq_ignore_ascii_case(code)
+        })
+    }
+
+    /// A product's active price in a currency: the fixed price when set,
+    /// otherwise the converted base price
+    pub fn product_price(&self, product: &Product, code: &str) -> Result<Option<Decimal>, CurrencyError> {
+        if let Some(fixed) = self.fixed_price(product.id, None, code) {
+            // A base sale price that is scheduled but not running also holds back the fixed sale price
+            let on_sale = product.is_on_sale() || product.sale_price.is_none();
+            return Ok(Some(match fixed.sale_price {
+                Some(sale) if on_sale => sale,
+                _ => fixed.regular_price,
+            }));
+        }
+
+        match product.get_price() {
+            Some(price) => self.convert(price, &self.settings.base_currency, code).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Switch a cart to another currency.
+    ///
+    /// Item prices use fixed prices when set and are otherwise converted;
+    /// line amounts, fees and totals are reset so the cart service can
+    /// recalculate discounts, shipping and taxes in the new currency.
+    pub fn apply_to_cart(&self, cart: &mut Cart, code: &str) -> Result<(), CurrencyError> {
+        let target = self.get_active_currency(code)?.code.clone();
+        let current = cart.currency.clone().unwrap_or_else(|| self.settings.base_currency.clone());
+        if current == target {
+            return Ok(());
+        }
+
+        for item in &mut cart.items {
+            match self.fixed_price(item.product_id, item.variation_id, &target) {
+                Some(fixed) => {
+                    let on_sale = item.price < item.regular_price;
+                    item.regular_price = fixed.regular_price;
+                    item.price = match fixed.sale_price {
+                        Some(sale) if on_sale => sale,
+                        _ => fixed.regular_price,
+                    };
+                }
+                None => {
+                    // Convert the base prices, so switching between two other
+                    // currencies doesn't compound their rounding
+                    let base = &self.settings.base_currency;
+                    item.price = match item.base_price {
+                        Some(price) => self.convert(price, base, &target)?,
+                        None => self.convert(item.price, &current, &target)?,
+                    };
+                    item.regular_price = match item.base_regular_price {
+                        Some(price) => self.convert(price, base, &target)?,
+                        None => self.convert(item.regular_price, &current, &target)?,
+                    };
+                }
+            }
+
+            item.subtotal = item.price * Decimal::from(item.quantity);
+            item.total = item.subtotal;
+            item.subtotal_tax = Decimal::ZERO;
+            item.total_tax = Decimal::ZERO;
+        }
+
+        for fee in &mut cart.fees {
+            fee.amount = self.convert(fee.amount, &current, &target)?;
+            fee.total = fee.amount;
+            fee.total_tax = Decimal::ZERO;
+        }
+
+        cart.totals = CartTotals::default();
+        if target == self.settings.base_currency {
+            cart.currency = None;
+            cart.currency_rate = None;
+        } else {
+            cart.currency_rate = Some(self.rate_with_markup(&target)?);
+            cart.currency = Some(target);
+        }
+
+        Ok(())
+    }
+
+    /// Record the order's currency and the rate used to convert it
+    pub fn apply_to_order(&self, order: &mut Order, code: &str) -> Result<(), CurrencyError> {
+        let currency = self.get_active_currency(code)?;
+        order.currency = currency.code.clone();
+        order.currency_symbol = currency.symbol.clone();
+        order.base_currency_rate = if currency.code == self.settings.base_currency {
+            None
+        } else {
+            Some(self.rate_with_markup(&currency.code)?)
+        };
+        Ok(())
+    }
+
+    /// Format an amount in a currency, falling back to the code
+    pub fn format(&self, amount: Decimal, code: &str) -> String {
+        match self.get_currency(code) {
+            Some(currency) => currency.format_amount(amount),
+            None => format!("{} {}", amount, code),
+        }
+    }
+
+    /// Format an amount in a currency for a locale, falling back to the code
+    /// as the symbol
+    pub fn format_for_locale(&self, amount: Decimal, code: &str, locale: &str) -> String {
+        match self.get_currency(code) {
+            Some(currency) => currency.format_amount_for_locale(amount, locale),
+            None => crate::locale::locale_format(locale)
+                .format_currency(amount, crate::currency::currency_symbol(code), crate::currency::minor_units(code)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::cart::CartItem;
+    use crate::models::currency::{RoundingMethod, SymbolPosition};
+    use crate::models::product::{ProductStatus, ProductType, StockStatus};
+    use rust_decimal_macros::dec;
+
+    fn currency(code: &str, rate: Decimal, markup: Decimal, rounding: RoundingMethod) -> Currency {
+        Currency {
+            id: Uuid::now_v7(),
+            site_id: None,
+            code: code.to_string(),
+            name: code.to_string(),
+            symbol: code.to_string(),
+            symbol_position: SymbolPosition::RightSpace,
+            decimal_separator: ".".to_string(),
+            thousand_separator: ",".to_string(),
+            decimals: 2,
+            is_default: code == "USD",
+            is_active: true,
+            exchange_rate: rate,
+            auto_update_rate: false,
+            rate_markup: markup,
+            rounding,
+            min_amount: None,
+            max_amount: None,
+            created_at: chrono::Utc::now(),
+            updated_at: None,
+        }
+    }
+
+    fn zone(code: &str, countries: &[&str], priority: i32) -> CurrencyZone {
+        CurrencyZone {
+            id: Uuid::now_v7(),
+            site_id: None,
+            name: code.to_string(),
+            currency_code: code.to_string(),
+            countries: countries.iter().map(|c| c.to_string()).collect(),
+            auto_switch: true,
+            priority,
+            is_active: true,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    fn service(settings: CurrencySettings) -> CurrencyService {
+        let mut chf = currency("CHF", dec!(0.9), dec!(0), RoundingMethod::NearestQuarter);
+        chf.is_active = false;
+        CurrencyService::new(
+            settings,
+            vec![
+                currency("USD", dec!(1), dec!(0), RoundingMethod::Nearest),
+                currency("EUR", dec!(0.9), dec!(2), RoundingMethod::Nearest),
+                chf,
+            ],
+            vec![zone("EUR", &["DE", "FR", "AT"], 1), zone("CHF", &["CH"], 1)],
+        )
+    }
+
+    fn product(regular_price: Decimal) -> Product {
+        Product {
+            id: Uuid::now_v7(),
+            site_id: None,
+            sku: None,
+            name: "Poster".to_string(),
+            slug: "poster".to_string(),
+            product_type: ProductType::Simple,
+            status: ProductStatus::Publish,
+            short_description: None,
+            description: None,
+            regular_price: Some(regular_price),
+            sale_price: None,
+            sale_price_from: None,
+            sale_price_to: None,
+            tax_status: Default::default(),
+            tax_class: String::new(),
+            tax_code: None,
+            manage_stock: false,
+            stock_quantity: 0,
+            stock_status: StockStatus::InStock,
+            backorders: Default::default(),
+            low_stock_amount: None,
+            sold_individually: false,
+            weight: None,
+            length: None,
+            width: None,
+            height: None,
+            shipping_class_id: None,
+            is_virtual: false,
+            is_downloadable: false,
+            download_limit: 0,
+            download_expiry: 0,
+            external_url: None,
+            button_text: None,
+            reviews_allowed: false,
+            average_rating: Decimal::ZERO,
+            rating_count: 0,
+            featured: false,
+            catalog_visibility: Default::default(),
+            parent_id: None,
+            menu_order: 0,
+            purchase_note: None,
+            total_sales: 0,
+            meta_title: None,
+            meta_description: None,
+            meta_keywords: None,
+            created_at: chrono::Utc::now(),
+            updated_at: None,
+            published_at: None,
+            categories: None,
+            tags: None,
+            images: None,
+            attributes: None,
+            variations: None,
+            downloads: None,
+        }
+    }
+
+    #[test]
+    fn test_resolve_currency() {
+        let svc = service(CurrencySettings::default());
+
+        let context = CurrencyContext { geolocated_country: Some("DE".to_string()), ..Default::default() };
+        assert_eq!(svc.resolve_currency(&context), "EUR");
+
+        // Switcher selection wins; inactive currencies are ignored
+        let context = CurrencyContext { selected: Some("usd".to_string()), geolocated_country: Some("DE".to_string()), ..Default::default() };
+        assert_eq!(svc.resolve_currency(&context), "USD");
+        let context = CurrencyContext { geolocated_country: Some("CH".to_string()), ..Default::default() };
+        assert_eq!(svc.resolve_currency(&context), "USD");
+
+        let svc = service(CurrencySettings { detection_method: CurrencyDetection::BrowserLanguage, ..Default::default() });
+        let context = CurrencyContext { accept_language: Some("en, fr-FR;q=0.8".to_string()), ..Default::default() };
+        assert_eq!(svc.resolve_currency(&context), "EUR");
+    }
+
+    #[test]
+    fn test_convert_with_markup_and_rounding() {
+        let svc = service(CurrencySettings { rounding_increment: None, ..Default::default() });
+
+        // 10 USD * 0.9 * 1.02 = 9.18 EUR
+        assert_eq!(svc.convert(dec!(10), "USD", "EUR").unwrap(), dec!(9.18));
+        assert_eq!(svc.convert(dec!(9.18), "EUR", "USD").unwrap(), dec!(10));
+        assert!(matches!(svc.convert(dec!(10), "USD", "CHF"), Err(CurrencyError::InactiveCurrency(_))));
+        assert!(matches!(svc.convert(dec!(10), "USD", "JPY"), Err(CurrencyError::UnknownCurrency(_))));
+
+        let conversion = svc.conversion(dec!(10), "USD", "EUR", "de").unwrap();
+        assert_eq!(conversion.formatted_original, "10,00\u{a0}USD");
+        assert_eq!(conversion.formatted_converted, "9,18\u{a0}EUR");
+
+        let svc = service(CurrencySettings { rounding_increment: Some(dec!(0.5)), ..Default::default() });
+        assert_eq!(svc.convert(dec!(10), "USD", "EUR").unwrap(), dec!(9.0));
+    }
+
+    #[test]
+    fn test_update_rates() {
+        let mut svc = service(CurrencySettings { rounding_increment: None, ..Default::default() });
+        let rate = |from: &str, to: &str, rate: Decimal| ExchangeRate {
+            id: Uuid::now_v7(),
+            from_currency: from.to_string(),
+            to_currency: to.to_string(),
+            rate,
+            source: crate::models::currency::RateSource::Api,
+            fetched_at: chrono::Utc::now(),
+            valid_until: None,
+        };
+
+        // Only rates quoted against the base currency apply
+        svc.update_rates(&[rate("USD", "EUR", dec!(0.95)), rate("GBP", "CHF", dec!(1.1))]);
+        assert_eq!(svc.get_currency("EUR").unwrap().exchange_rate, dec!(0.95));
+        assert!(svc.get_currency("EUR").unwrap().updated_at.is_some());
+        assert_eq!(svc.get_currency("CHF").unwrap().exchange_rate, dec!(0.9));
+
+        // 10 USD * 0.95 * 1.02 = 9.69 EUR
+        assert_eq!(svc.convert(dec!(10), "USD", "EUR").unwrap(), dec!(9.69));
+    }
+
+    #[test]
+    fn test_apply_to_cart_reprices_from_base_prices() {
+        let poster = product(dec!(11));
+        let print = product(dec!(20));
+        let fixed = CurrencyPrice {
+            id: Uuid::now_v7(),
+            product_id: print.id,
+            variation_id: None,
+            currency_code: "GBP".to_string(),
+            regular_price: dec!(15),
+            sale_price: None,
+            price_type: CurrencyPriceType::Manual,
+            created_at: chrono::Utc::now(),
+            updated_at: None,
+        };
+        let mut svc = service(CurrencySettings { rounding_increment: Some(dec!(0.5)), ..Default::default() })
+            .with_prices(vec![fixed]);
+        svc.currencies.push(currency("GBP", dec!(0.8), dec!(0), RoundingMethod::Nearest));
+
+        let mut cart = Cart::new(None, None);
+        cart.items.push(CartItem::from_product(&poster, 2, None, Default::default()));
+        cart.items.push(CartItem::from_product(&print, 1, None, Default::default()));
+
+        // 11 USD * 0.918 = 10.098, rounded to 10.0 EUR
+        svc.apply_to_cart(&mut cart, "EUR").unwrap();
+        assert_eq!(cart.currency.as_deref(), Some("EUR"));
+        assert_eq!(cart.items[0].price, dec!(10.0));
+        assert_eq!(cart.items[0].subtotal, dec!(20.0));
+
+        // 11 USD * 0.8 = 8.8, rounded to 9.0 GBP; converting the rounded
+        // 10.0 EUR would give 8.5 GBP
+        svc.apply_to_cart(&mut cart, "GBP").unwrap();
+        assert_eq!(cart.items[0].price, dec!(9.0));
+        assert_eq!(cart.items[0].regular_price, dec!(9.0));
+        assert_eq!(cart.items[1].price, dec!(15));
+
+        svc.apply_to_cart(&mut cart, "usd").unwrap();
+        assert_eq!(cart.currency, None);
+        assert_eq!(cart.items[0].price, dec!(11));
+        assert_eq!(cart.items[1].price, dec!(20));
+    }
+}
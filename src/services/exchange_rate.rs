@@ -0,0 +1,299 @@
+//! Exchange Rate Service
+//!
+//! Refreshes currency exchange rates from a provider on a schedule, keeps
+//! their history and flags unusually large moves.
+
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::RwLock;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::currency::{ExchangeRateError, ExchangeRateProvider, ExchangeRates};
+use crate::models::currency::{Currency, CurrencySettings, ExchangeRate, ExchangeRateHistory, RateSource};
+
+/// A currency whose rate changed during a refresh
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateUpdate {
+    pub currency: String,
+    pub previous_rate: Decimal,
+    pub rate: Decimal,
+    /// Change relative to the previous rate, in percent
+    pub change_percent: Decimal,
+    /// Set when the change exceeds the alert threshold
+    pub alert: bool,
+}
+
+/// Result of applying a set of provider rates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateRefreshSummary {
+    pub provider: String,
+    pub published_at: DateTime<Utc>,
+    pub updated: Vec<RateUpdate>,
+    /// Rates to store, one per updated currency
+    pub rates: Vec<ExchangeRate>,
+    /// Auto-updated currencies the provider has no rate for
+    pub missing: Vec<String>,
+}
+
+impl RateRefreshSummary {
+    /// Updates that crossed the alert threshold
+    pub fn alerts(&self) -> impl Iterator<Item = &RateUpdate> {
+        self.updated.iter().filter(|u| u.alert)
+    }
+}
+
+/// Exchange rate service
+pub struct ExchangeRateService {
+    settings: CurrencySettings,
+    currencies: RwLock<Vec<Currency>>,
+    history: RwLock<Vec<ExchangeRateHistory>>,
+    last_refreshed: RwLock<Option<DateTime<Utc>>>,
+}
+
+impl ExchangeRateService {
+    /// Create a new exchange rate service
+    pub fn new(settings: CurrencySettings, currencies: Vec<Currency>) -> Self {
+        Self {
+            settings,
+            currencies: RwLock::new(currencies),
+            history: RwLock::new(Vec::new()),
+            last_refreshed: RwLock::new(None),
+        }
+    }
+
+    /// Current currencies with their latest rates
+    pub fn currencies(&self) -> Vec<Currency> {
+        self.currencies.read().clone()
+    }
+
+    /// Recorded rates for a currency, oldest first
+    pub fn history(&self, code: &str) -> Vec<ExchangeRateHistory> {
+        self.history.read().iter()
+            .filter(|h| h.to_currency.eq_ignore_ascii_case(code))
+            .cloned()
+            .collect()
+    }
+
+    /// When rates were last refreshed
+    pub fn last_refreshed(&self) -> Option<DateTime<Utc>> {
+        *self.last_refreshed.read()
+    }
+
+    /// Interval between scheduled refreshes
+    pub fn refresh_interval(&self) -> Duration {
+        Duration::hours(self.settings.rate_update_frequency_hours.max(1) as i64)
+    }
+
+    /// Check if rates are due for a refresh
+    pub fn is_refresh_due(&self, now: DateTime<Utc>) -> bool {
+        match self.last_refreshed() {
+            Some(last) => now - last >= self.refresh_interval(),
+            None => true,
+        }
+    }
+
+    /// Apply provider rates to every auto-updated currency.
+    ///
+    /// Stored rates are the provider's mid-market rate; each currency's
+    /// markup is added on top when prices are converted.
+    pub fn apply_rates(&self, rates: &ExchangeRates) -> RateRefreshSummary {
+        let base = self.settings.base_currency.clone();
+        let now = Utc::now();
+        let mut summary = RateRefreshSummary {
+            provider: rates.provider.clone(),
+            published_at: rates.published_at,
+            updated: Vec::new(),
+            rates: Vec::new(),
+            missing: Vec::new(),
+        };
+
+        let mut currencies = self.currencies.write();
+        let mut history = self.history.write();
+
+        for currency in currencies.iter_mut() {
+            if !currency.auto_update_rate || currency.code.eq_ignore_ascii_case(&base) {
+                continue;
+            }
+
+            let rate = match rates.cross_rate(&base, &currency.code) {
+                Ok(rate) => rate,
+                Err(_) => {
+                    summary.missing.push(currency.code.clone());
+                    continue;
+                }
+            };
+
+            if rate == currency.exchange_rate {
+                continue;
+            }
+
+            let change_percent = if currency.exchange_rate > Decimal::ZERO {
+                ((rate - currency.exchange_rate) / currency.exchange_rate * Decimal::from(100)).round_dp(2)
+            } else {
+                Decimal::ZERO
+            };
+            let alert = currency.exchange_rate > Decimal::ZERO
+                && self.settings.rate_alert_threshold.is_some_and(|t| change_percent.abs() > t);
+
+            summary.updated.push(RateUpdate {
+                currency: currency.code.clone(),
+                previous_rate: currency.exchange_rate,
+                rate,
+                change_percent,
+                alert,
+            });
+            summary.rates.push(ExchangeRate {
+                id: Uuid::now_v7(),
+                from_currency: base.clone(),
+                to_currency: currency.code.clone(),
+                rate,
+                source: RateSource::Api,
+                fetched_at: now,
+                valid_until: Some(now + self.refresh_interval()),
+            });
+            history.push(ExchangeRateHistory {
+                id: Uuid::now_v7(),
+                from_currency: base.clone(),
+                to_currency: currency.code.clone(),
+                rate,
+                recorded_at: now,
+            });
+
+            currency.exchange_rate = rate;
+            currency.updated_at = Some(now);
+        }
+
+        *self.last_refreshed.write() = Some(now);
+        summary
+    }
+
+    /// Fetch rates from a provider and apply them
+    pub async fn refresh(&self, provider: &dyn ExchangeRateProvider) -> Result<RateRefreshSummary, ExchangeRateError> {
+        let rates = provider.fetch_rates().await?;
+        Ok(self.apply_rates(&rates))
+    }
+
+    /// Refresh rates in the background every `rate_update_frequency_hours`,
+    /// starting immediately. `on_refresh` receives every result, e.g. to
+    /// persist rates and fire the alert hook.
+    pub fn spawn_scheduled_refresh<F>(
+        self: Arc<Self>,
+        provider: Arc<dyn ExchangeRateProvider>,
+        on_refresh: F,
+    ) -> tokio::task::JoinHandle<()>
+    where
+        F: Fn(Result<RateRefreshSummary, ExchangeRateError>) + Send + Sync + 'static,
+    {
+        let period = self.refresh_interval().to_std().unwrap_or(std::time::Duration::from_secs(3600));
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                on_refresh(self.refresh(provider.as_ref()).await);
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::currency::{RoundingMethod, SymbolPosition};
+    use async_trait::async_trait;
+    use rust_decimal_macros::dec;
+    use std::collections::HashMap;
+
+    fn currency(code: &str, rate: Decimal, auto_update: bool) -> Currency {
+        Currency {
+            id: Uuid::now_v7(),
+            site_id: None,
+            code: code.to_string(),
+            name: code.to_string(),
+            symbol: code.to_string(),
+            symbol_position: SymbolPosition::Left,
+            decimal_separator: ".".to_string(),
+            thousand_separator: ",".to_string(),
+            decimals: 2,
+            is_default: code == "USD",
+            is_active: true,
+            exchange_rate: rate,
+            auto_update_rate: auto_update,
+            rate_markup: dec!(0),
+            rounding: RoundingMethod::Nearest,
+            min_amount: None,
+            max_amount: None,
+            created_at: Utc::now(),
+            updated_at: None,
+        }
+    }
+
+    struct FixedRates(ExchangeRates);
+
+    #[async_trait]
+    impl ExchangeRateProvider for FixedRates {
+        fn id(&self) -> &str { "fixed" }
+        fn title(&self) -> &str { "Fixed" }
+        fn is_available(&self) -> bool { true }
+        async fn fetch_rates(&self) -> Result<ExchangeRates, ExchangeRateError> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn eur_feed() -> ExchangeRates {
+        ExchangeRates {
+            provider: "fixed".to_string(),
+            base: "EUR".to_string(),
+            rates: HashMap::from([
+                ("USD".to_string(), dec!(1.25)),
+                ("GBP".to_string(), dec!(0.85)),
+                ("JPY".to_string(), dec!(160)),
+            ]),
+            published_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_refresh_rates() {
+        let service = ExchangeRateService::new(
+            CurrencySettings::default(),
+            vec![
+                currency("USD", dec!(1), true),
+                currency("EUR", dec!(0.79), true),
+                currency("GBP", dec!(0.50), true),
+                currency("JPY", dec!(100), false),
+                currency("CHF", dec!(0.9), true),
+            ],
+        );
+        assert!(service.is_refresh_due(Utc::now()));
+
+        let summary = service.refresh(&FixedRates(eur_feed())).await.unwrap();
+
+        let eur = summary.updated.iter().find(|u| u.currency == "EUR").unwrap();
+        assert_eq!(eur.rate, dec!(0.8));
+        assert_eq!(eur.change_percent, dec!(1.27));
+        assert!(!eur.alert);
+
+        let gbp = summary.updated.iter().find(|u| u.currency == "GBP").unwrap();
+        assert_eq!(gbp.rate, dec!(0.68));
+        assert!(gbp.alert);
+
+        // Manually priced currencies are left alone; unknown ones are reported
+        assert!(summary.updated.iter().all(|u| u.currency != "JPY"));
+        assert_eq!(summary.missing, vec!["CHF".to_string()]);
+        assert_eq!(summary.alerts().count(), 1);
+
+        let currencies = service.currencies();
+        assert_eq!(currencies.iter().find(|c| c.code == "GBP").unwrap().exchange_rate, dec!(0.68));
+        assert_eq!(service.history("GBP").len(), 1);
+        assert!(!service.is_refresh_due(Utc::now()));
+
+        // Unchanged rates are not recorded again
+        let summary = service.refresh(&FixedRates(eur_feed())).await.unwrap();
+        assert!(summary.updated.is_empty());
+        assert_eq!(service.history("GBP").len(), 1);
+    }
+}
@@ -0,0 +1,625 @@
+//! Fulfillment Service
+//!
+//! Handles shipments, partial fulfillment and customer-facing tracking.
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+use std::collections::HashMap;
+
+use crate::models::order::{Order, OrderItem, OrderItemType};
+use crate::models::fulfillment::{
+    FulfillmentStatus, Shipment, ShipmentItem, ShipmentStatus, ShippingCarrier,
+    ShipmentTracking, ShipmentTrackingItem, OrderTracking, CreateShipmentRequest,
+    UpdateShipmentRequest, ShipmentLabel, LabelPackageRequest,
+};
+use crate::models::customer::Address;
+use crate::models::email_templates::SendEmailRequest;
+use crate::labels::provider::{LabelFormat, LabelItem, LabelPackage, LabelRequest, LabelResult};
+use crate::settings::RustCommerceSettings;
+
+/// Fulfillment service
+pub struct FulfillmentService {
+    settings: RustCommerceSettings,
+}
+
+/// Fulfillment errors
+#[derive(Debug, Clone)]
+pub enum FulfillmentError {
+    OrderNotShippable,
+    NoItems,
+    UnknownOrderItem(Uuid),
+    InvalidQuantity(Uuid),
+    QuantityExceedsRemaining { order_item_id: Uuid, remaining: i32 },
+    UnknownCarrier(String),
+    InvalidStatusTransition { from: ShipmentStatus, to: ShipmentStatus },
+    ItemNotInShipment(Uuid),
+}
+
+impl std::fmt::Display for FulfillmentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OrderNotShippable => write!(f, "Order cannot be shipped in its current status"),
+            Self::NoItems => write!(f, "A shipment must contain at least one item"),
+            Self::UnknownOrderItem(id) => write!(f, "Order item {} does not belong to this order", id),
+            Self::InvalidQuantity(id) => write!(f, "Invalid quantity for order item {}", id),
+            Self::QuantityExceedsRemaining { order_item_id, remaining } => write!(
+                f,
+                "Only {} unit(s) of order item {} remain to be shipped",
+                remaining, order_item_id
+            ),
+            Self::UnknownCarrier(slug) => write!(f, "Unknown carrier: {}", slug),
+            Self::InvalidStatusTransition { from, to } => {
+                write!(f, "Cannot change shipment from {:?} to {:?}", from, to)
+            }
+            Self::ItemNotInShipment(id) => write!(f, "Order item {} is not part of this shipment", id),
+        }
+    }
+}
+
+impl FulfillmentService {
+    /// Template key for the shipment notification email
+    pub const SHIPMENT_EMAIL_TEMPLATE: &'static str = "order_shipped";
+
+    /// Create a new fulfillment service
+    pub fn new(settings: RustCommerceSettings) -> Self {
+        Self { settings }
+    }
+
+    /// Built-in carriers with their tracking URL templates
+    pub fn builtin_carriers(&self) -> Vec<ShippingCarrier> {
+        [
+            ("ups", "UPS", "https://www.ups.com/track?tracknum={tracking_number}"),
+            ("usps", "USPS", "https://tools.usps.com/go/TrackConfirmAction?tLabels={tracking_number}"),
+            ("fedex", "FedEx", "https://www.fedex.com/fedextrack/?trknbr={tracking_number}"),
+            ("dhl", "DHL Express", "https://www.dhl.com/en/express/tracking.html?AWB={tracking_number}"),
+            ("royal_mail", "Royal Mail", "https://www.royalmail.com/track-your-item#/tracking-results/{tracking_number}"),
+            ("canada_post", "Canada Post", "https://www.canadapost-postescanada.ca/track-reperage/en#/search?searchFor={tracking_number}"),
+            ("australia_post", "Australia Post", "https://auspost.com.au/mypost/track/#/details/{tracking_number}"),
+            ("dpd", "DPD", "https://tracking.dpd.de/status/en_US/parcel/{tracking_number}"),
+            ("postnl", "PostNL", "https://postnl.nl/tracktrace/?B={tracking_number}"),
+            ("other", "Other", ""),
+        ]
+        .into_iter()
+        .map(|(slug, name, template)| ShippingCarrier {
+            id: Uuid::nil(),
+            site_id: None,
+            slug: slug.to_string(),
+            name: name.to_string(),
+            tracking_url_template: template.to_string(),
+            is_enabled: true,
+            created_at: Utc::now(),
+        })
+        .collect()
+    }
+
+    /// Find a carrier by slug among built-in and custom carriers
+    pub fn find_carrier(&self, slug: &str, custom: &[ShippingCarrier]) -> Option<ShippingCarrier> {
+        custom.iter()
+            .find(|c| c.slug == slug && c.is_enabled)
+            .cloned()
+            .or_else(|| self.builtin_carriers().into_iter().find(|c| c.slug == slug))
+    }
+
+    /// Line items of an order
+    fn line_items<'a>(&self, order: &'a Order) -> Vec<&'a OrderItem> {
+        order.line_items.as_deref()
+            .unwrap_or(&[])
+            .iter()
+            .filter(|i| i.item_type == OrderItemType::LineItem)
+            .collect()
+    }
+
+    /// Quantity already covered by shipments, per order item
+    pub fn fulfilled_quantities(&self, shipments: &[Shipment]) -> HashMap<Uuid, i32> {
+        let mut quantities = HashMap::new();
+        for shipment in shipments.iter().filter(|s| s.status.counts_as_fulfilled()) {
+            for item in &shipment.items {
+                *quantities.entry(item.order_item_id).or_insert(0) += item.quantity;
+            }
+        }
+        quantities
+    }
+
+    /// Quantity still to be shipped, per order item
+    pub fn remaining_quantities(&self, order: &Order, shipments: &[Shipment]) -> HashMap<Uuid, i32> {
+        let fulfilled = self.fulfilled_quantities(shipments);
+        self.line_items(order)
+            .into_iter()
+            .map(|item| {
+                let shipped = fulfilled.get(&item.id).copied().unwrap_or(0);
+                (item.id, (item.quantity - shipped).max(0))
+            })
+            .collect()
+    }
+
+    /// Compute the order fulfillment status from its shipments
+    pub fn fulfillment_status(&self, order: &Order, shipments: &[Shipment]) -> FulfillmentStatus {
+        let items = self.line_items(order);
+        let fulfilled = self.fulfilled_quantities(shipments);
+
+        let ordered: i32 = items.iter().map(|i| i.quantity).sum();
+        let shipped: i32 = items.iter()
+            .map(|i| fulfilled.get(&i.id).copied().unwrap_or(0).min(i.quantity))
+            .sum();
+
+        if shipped == 0 {
+            FulfillmentStatus::Unfulfilled
+        } else if shipped < ordered {
+            FulfillmentStatus::PartiallyFulfilled
+        } else {
+            FulfillmentStatus::Fulfilled
+        }
+    }
+
+    /// Create a shipment for some or all of an order's remaining items
+    pub fn create_shipment(
+        &self,
+        order: &Order,
+        existing: &[Shipment],
+        request: &CreateShipmentRequest,
+        custom_carriers: &[ShippingCarrier],
+    ) -> Result<Shipment, FulfillmentError> {
+        if !order.status.is_paid() {
+            return Err(FulfillmentError::OrderNotShippable);
+        }
+
+        if request.items.is_empty() {
+            return Err(FulfillmentError::NoItems);
+        }
+
+        let carrier = self.find_carrier(&request.carrier, custom_carriers)
+            .ok_or_else(|| FulfillmentError::UnknownCarrier(request.carrier.clone()))?;
+
+        // Validate quantities against what remains unshipped
+        let mut remaining = self.remaining_quantities(order, existing);
+        for item in &request.items {
+            if item.quantity <= 0 {
+                return Err(FulfillmentError::InvalidQuantity(item.order_item_id));
+            }
+            let left = remaining.get_mut(&item.order_item_id)
+                .ok_or(FulfillmentError::UnknownOrderItem(item.order_item_id))?;
+            if item.quantity > *left {
+                return Err(FulfillmentError::QuantityExceedsRemaining {
+                    order_item_id: item.order_item_id,
+                    remaining: *left,
+                });
+            }
+            *left -= item.quantity;
+        }
+
+        let shipment_id = Uuid::now_v7();
+        let now = Utc::now();
+        let tracking_number = request.tracking_number.clone()
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty());
+        let tracking_url = request.tracking_url.clone()
+            .or_else(|| tracking_number.as_deref().and_then(|n| carrier.tracking_url(n)));
+        let mark_shipped = request.mark_shipped.unwrap_or(true);
+
+        Ok(Shipment {
+            id: shipment_id,
+            order_id: order.id,
+            shipment_number: format!("{}-{}", order.order_number, existing.len() + 1),
+            status: if mark_shipped { ShipmentStatus::Shipped } else { ShipmentStatus::Pending },
+            carrier: carrier.slug.clone(),
+            carrier_name: carrier.name.clone(),
+            tracking_number,
+            tracking_url,
+            items: request.items.iter().map(|item| ShipmentItem {
+                id: Uuid::now_v7(),
+                shipment_id,
+                order_item_id: item.order_item_id,
+                quantity: item.quantity,
+            }).collect(),
+            label: None,
+            note: request.note.clone(),
+            notify_customer: request.notify_customer.unwrap_or(true),
+            shipped_at: if mark_shipped { Some(now) } else { None },
+            delivered_at: None,
+            meta: serde_json::json!({}),
+            created_at: now,
+            updated_at: None,
+        })
+    }
+
+    /// Apply an update to a shipment
+    pub fn update_shipment(
+        &self,
+        shipment: &mut Shipment,
+        request: &UpdateShipmentRequest,
+        custom_carriers: &[ShippingCarrier],
+    ) -> Result<(), FulfillmentError> {
+        if let Some(ref slug) = request.carrier {
+            let carrier = self.find_carrier(slug, custom_carriers)
+                .ok_or_else(|| FulfillmentError::UnknownCarrier(slug.clone()))?;
+            shipment.carrier = carrier.slug.clone();
+            shipment.carrier_name = carrier.name.clone();
+        }
+
+        if let Some(ref number) = request.tracking_number {
+            shipment.tracking_number = Some(number.trim().to_string()).filter(|n| !n.is_empty());
+        }
+
+        if request.tracking_url.is_some() {
+            shipment.tracking_url = request.tracking_url.clone();
+        } else if request.carrier.is_some() || request.tracking_number.is_some() {
+            shipment.tracking_url = shipment.tracking_number.as_deref().and_then(|n| {
+                self.find_carrier(&shipment.carrier, custom_carriers)
+                    .and_then(|c| c.tracking_url(n))
+            });
+        }
+
+        if request.note.is_some() {
+            shipment.note = request.note.clone();
+        }
+
+        if let Some(status) = request.status {
+            self.set_status(shipment, status, Utc::now())?;
+        }
+
+        shipment.updated_at = Some(Utc::now());
+        Ok(())
+    }
+
+    /// Change a shipment's status, stamping shipped/delivered dates
+    pub fn set_status(
+        &self,
+        shipment: &mut Shipment,
+        status: ShipmentStatus,
+        at: DateTime<Utc>,
+    ) -> Result<(), FulfillmentError> {
+        let from = shipment.status;
+        if from == status {
+            return Ok(());
+        }
+
+        let allowed = match from {
+            ShipmentStatus::Delivered => matches!(status, ShipmentStatus::Returned),
+            ShipmentStatus::Cancelled | ShipmentStatus::Returned => false,
+            _ => true,
+        };
+        if !allowed {
+            return Err(FulfillmentError::InvalidStatusTransition { from, to: status });
+        }
+
+        if status.is_shipped() && shipment.shipped_at.is_none() {
+            shipment.shipped_at = Some(at);
+        }
+        if status == ShipmentStatus::Delivered {
+            shipment.delivered_at = Some(at);
+        }
+
+        shipment.status = status;
+        shipment.updated_at = Some(at);
+        Ok(())
+    }
+
+    /// Mark a shipment as shipped
+    pub fn mark_shipped(&self, shipment: &mut Shipment) -> Result<(), FulfillmentError> {
+        self.set_status(shipment, ShipmentStatus::Shipped, Utc::now())
+    }
+
+    /// Mark a shipment as delivered
+    pub fn mark_delivered(&self, shipment: &mut Shipment) -> Result<(), FulfillmentError> {
+        self.set_status(shipment, ShipmentStatus::Delivered, Utc::now())
+    }
+
+    /// Store address used as the label sender
+    pub fn ship_from_address(&self) -> Address {
+        let general = &self.settings.general;
+        // The store country may carry a state, e.g. "US:CA"
+        let (country, state) = match general.store_country.split_once(':') {
+            Some((country, state)) => (country.to_string(), state.to_string()),
+            None => (general.store_country.clone(), general.store_state.clone()),
+        };
+
+        Address {
+            company: general.store_name.clone(),
+            address_1: general.store_address.clone(),
+            address_2: general.store_address_2.clone(),
+            city: general.store_city.clone(),
+            state,
+            postcode: general.store_postcode.clone(),
+            country,
+            ..Address::default()
+        }
+    }
+
+    /// Build a label request for a shipment.
+    ///
+    /// Without explicit packages, the whole shipment goes in a single package.
+    pub fn build_label_request(
+        &self,
+        order: &Order,
+        shipment: &Shipment,
+        packages: Option<&[LabelPackageRequest]>,
+        format: LabelFormat,
+        service_code: Option<String>,
+    ) -> Result<LabelRequest, FulfillmentError> {
+        let line_items: HashMap<Uuid, &OrderItem> = self.line_items(order)
+            .into_iter()
+            .map(|i| (i.id, i))
+            .collect();
+        let in_shipment: HashMap<Uuid, i32> = shipment.items.iter()
+            .map(|i| (i.order_item_id, i.quantity))
+            .collect();
+
+        let label_item = |order_item_id: Uuid, quantity: i32| -> Result<LabelItem, FulfillmentError> {
+            if !in_shipment.contains_key(&order_item_id) {
+                return Err(FulfillmentError::ItemNotInShipment(order_item_id));
+            }
+            if quantity <= 0 || quantity > in_shipment[&order_item_id] {
+                return Err(FulfillmentError::InvalidQuantity(order_item_id));
+            }
+            let item = line_items.get(&order_item_id)
+                .ok_or(FulfillmentError::UnknownOrderItem(order_item_id))?;
+            Ok(LabelItem {
+                name: item.product_name.clone().unwrap_or_else(|| item.name.clone()),
+                sku: item.sku.clone(),
+                quantity,
+            })
+        };
+
+        let packages = match packages {
+            Some(packages) if !packages.is_empty() => packages.iter()
+                .map(|package| {
+                    let items = match package.items {
+                        Some(ref items) => items.iter()
+                            .map(|i| label_item(i.order_item_id, i.quantity))
+                            .collect::<Result<Vec<_>, _>>()?,
+                        None => shipment.items.iter()
+                            .map(|i| label_item(i.order_item_id, i.quantity))
+                            .collect::<Result<Vec<_>, _>>()?,
+                    };
+                    Ok(LabelPackage {
+                        weight: package.weight,
+                        length: package.length,
+                        width: package.width,
+                        height: package.height,
+                        items,
+                    })
+                })
+                .collect::<Result<Vec<_>, FulfillmentError>>()?,
+            _ => vec![LabelPackage {
+                items: shipment.items.iter()
+                    .map(|i| label_item(i.order_item_id, i.quantity))
+                    .collect::<Result<Vec<_>, _>>()?,
+                ..LabelPackage::default()
+            }],
+        };
+
+        // Fall back to the billing address for orders without a shipping address
+        let ship_to = if order.shipping.address_1.is_empty() {
+            order.billing.clone()
+        } else {
+            order.shipping.clone()
+        };
+
+        Ok(LabelRequest {
+            order_number: order.order_number.clone(),
+            shipment_number: shipment.shipment_number.clone(),
+            store_name: self.settings.general.store_name.clone(),
+            ship_from: self.ship_from_address(),
+            ship_to,
+            packages,
+            format,
+            service_code,
+            note: shipment.note.clone(),
+        })
+    }
+
+    /// Attach a generated label to a shipment.
+    ///
+    /// Carrier-issued tracking numbers replace any number entered by hand.
+    pub fn attach_label(
+        &self,
+        shipment: &mut Shipment,
+        provider_id: &str,
+        result: LabelResult,
+        custom_carriers: &[ShippingCarrier],
+    ) {
+        if let Some(ref slug) = result.carrier {
+            if let Some(carrier) = self.find_carrier(slug, custom_carriers) {
+                shipment.carrier = carrier.slug.clone();
+                shipment.carrier_name = carrier.name.clone();
+            }
+        }
+
+        if let Some(ref number) = result.tracking_number {
+            shipment.tracking_number = Some(number.clone());
+            shipment.tracking_url = self.find_carrier(&shipment.carrier, custom_carriers)
+                .and_then(|c| c.tracking_url(number));
+        }
+
+        let now = Utc::now();
+        shipment.label = Some(ShipmentLabel {
+            id: Uuid::now_v7(),
+            shipment_id: shipment.id,
+            provider: provider_id.to_string(),
+            format: result.format,
+            file_name: format!("label-{}.{}", shipment.shipment_number, result.format.extension()),
+            content_type: result.format.content_type().to_string(),
+            document: result.document,
+            tracking_number: result.tracking_number,
+            cost: result.cost,
+            created_at: now,
+        });
+        shipment.updated_at = Some(now);
+    }
+
+    /// Build customer-facing tracking information for an order
+    pub fn order_tracking(&self, order: &Order, shipments: &[Shipment]) -> OrderTracking {
+        let names: HashMap<Uuid, String> = self.line_items(order)
+            .into_iter()
+            .map(|i| (i.id, i.product_name.clone().unwrap_or_else(|| i.name.clone())))
+            .collect();
+
+        OrderTracking {
+            order_number: order.order_number.clone(),
+            fulfillment_status: self.fulfillment_status(order, shipments),
+            shipments: shipments.iter()
+                .filter(|s| s.status != ShipmentStatus::Cancelled)
+                .map(|s| ShipmentTracking {
+                    shipment_number: s.shipment_number.clone(),
+                    status: s.status,
+                    status_label: s.status.display_name().to_string(),
+                    carrier_name: s.carrier_name.clone(),
+                    tracking_number: s.tracking_number.clone(),
+                    tracking_url: s.tracking_url.clone(),
+                    items: s.items.iter().map(|i| ShipmentTrackingItem {
+                        name: names.get(&i.order_item_id).cloned().unwrap_or_default(),
+                        quantity: i.quantity,
+                    }).collect(),
+                    shipped_at: s.shipped_at,
+                    delivered_at: s.delivered_at,
+                })
+                .collect(),
+        }
+    }
+
+    /// Check that a tracking lookup matches the order's billing email
+    pub fn can_view_tracking(&self, order: &Order, email: &str) -> bool {
+        !email.trim().is_empty() && order.billing.email.trim().eq_ignore_ascii_case(email.trim())
+    }
+
+    /// Build the shipment notification email, if the customer should be notified
+    pub fn shipment_email(&self, order: &Order, shipment: &Shipment) -> Option<SendEmailRequest> {
+        if !shipment.notify_customer || order.billing.email.is_empty() {
+            return None;
+        }
+
+        let tracking = self.order_tracking(order, std::slice::from_ref(shipment));
+        let mut variables = HashMap::new();
+        variables.insert("store_name".to_string(), serde_json::json!(self.settings.general.store_name));
+        variables.insert("customer_name".to_string(), serde_json::json!(order.get_customer_name()));
+        variables.insert("order_number".to_string(), serde_json::json!(order.order_number));
+        variables.insert("shipment_number".to_string(), serde_json::json!(shipment.shipment_number));
+        variables.insert("carrier_name".to_string(), serde_json::json!(shipment.carrier_name));
+        variables.insert("tracking_number".to_string(), serde_json::json!(shipment.tracking_number));
+        variables.insert("tracking_url".to_string(), serde_json::json!(shipment.tracking_url));
+        variables.insert("items".to_string(), serde_json::json!(
+            tracking.shipments.first().map(|s| s.items.clone()).unwrap_or_default()
+        ));
+        variables.insert("fulfillment_status".to_string(), serde_json::json!(tracking.fulfillment_status));
+
+        Some(SendEmailRequest {
+            template_id: None,
+            template_key: Some(Self::SHIPMENT_EMAIL_TEMPLATE.to_string()),
+            to_email: order.billing.email.clone(),
+            to_name: Some(order.get_customer_name()),
+            variables,
+            cc: None,
+            bcc: None,
+            attachments: None,
+            schedule_at: None,
+            subject_override: None,
+            from_name_override: None,
+            from_email_override: None,
+            reply_to_override: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shipment(status: ShipmentStatus, items: Vec<ShipmentItem>) -> Shipment {
+        Shipment {
+            id: Uuid::now_v7(),
+            order_id: Uuid::nil(),
+            shipment_number: "1-1".to_string(),
+            status,
+            carrier: "ups".to_string(),
+            carrier_name: "UPS".to_string(),
+            tracking_number: None,
+            tracking_url: None,
+            items,
+            label: None,
+            note: None,
+            notify_customer: false,
+            shipped_at: None,
+            delivered_at: None,
+            meta: serde_json::json!({}),
+            created_at: Utc::now(),
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn test_carrier_tracking_url() {
+        let service = FulfillmentService::new(RustCommerceSettings::default());
+
+        let ups = service.find_carrier("ups", &[]).unwrap();
+        assert_eq!(
+            ups.tracking_url("1Z 999").as_deref(),
+            Some("https://www.ups.com/track?tracknum=1Z%20999")
+        );
+
+        let other = service.find_carrier("other", &[]).unwrap();
+        assert!(other.tracking_url("123").is_none());
+        assert!(service.find_carrier("pigeon", &[]).is_none());
+    }
+
+    #[test]
+    fn test_fulfilled_quantities_skip_cancelled() {
+        let service = FulfillmentService::new(RustCommerceSettings::default());
+        let item_id = Uuid::now_v7();
+
+        let shipped = |status, quantity| shipment(status, vec![ShipmentItem {
+            id: Uuid::now_v7(),
+            shipment_id: Uuid::nil(),
+            order_item_id: item_id,
+            quantity,
+        }]);
+
+        let shipments = vec![
+            shipped(ShipmentStatus::Shipped, 2),
+            shipped(ShipmentStatus::Cancelled, 5),
+            shipped(ShipmentStatus::Delivered, 1),
+        ];
+
+        assert_eq!(service.fulfilled_quantities(&shipments).get(&item_id), Some(&3));
+    }
+
+    #[test]
+    fn test_shipment_status_transitions() {
+        let service = FulfillmentService::new(RustCommerceSettings::default());
+        let mut shipment = shipment(ShipmentStatus::Pending, vec![]);
+
+        service.mark_delivered(&mut shipment).unwrap();
+        assert!(shipment.shipped_at.is_some());
+        assert!(shipment.delivered_at.is_some());
+
+        assert!(service.set_status(&mut shipment, ShipmentStatus::InTransit, Utc::now()).is_err());
+        assert!(service.set_status(&mut shipment, ShipmentStatus::Returned, Utc::now()).is_ok());
+    }
+
+    #[test]
+    fn test_attach_label_sets_tracking() {
+        let service = FulfillmentService::new(RustCommerceSettings::default());
+        let mut shipment = Shipment {
+            shipment_number: "1001-1".to_string(),
+            carrier: "other".to_string(),
+            carrier_name: "Other".to_string(),
+            tracking_number: Some("manual".to_string()),
+            ..shipment(ShipmentStatus::Pending, vec![])
+        };
+
+        service.attach_label(&mut shipment, "ups_api", LabelResult {
+            format: LabelFormat::Zpl,
+            document: b"^XA^XZ".to_vec(),
+            tracking_number: Some("1Z999".to_string()),
+            carrier: Some("ups".to_string()),
+            cost: None,
+        }, &[]);
+
+        assert_eq!(shipment.carrier, "ups");
+        assert_eq!(shipment.tracking_number.as_deref(), Some("1Z999"));
+        assert!(shipment.tracking_url.is_some());
+
+        let label = shipment.label.unwrap();
+        assert_eq!(label.file_name, "label-1001-1.zpl");
+        assert_eq!(label.content_type, "application/x-zpl");
+    }
+}
@@ -420,6 +420,7 @@ mod tests {
             download_expiry: None,
             tax_status: crate::models::product::TaxStatus::Taxable,
             tax_class: None,
+            tax_code: None,
             low_stock_amount: None,
             created_at: chrono::Utc::now(),
             updated_at: None,
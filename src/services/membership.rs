@@ -0,0 +1,1104 @@
+//! Membership Service
+//!
+//! Grants memberships when qualifying products are bought and answers
+//! whether a member can view content or purchase products, including
+//! drip schedules that unlock content some days after joining. Current
+//! members also get their plans' discounts in listings, carts and orders.
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::models::cart::Cart;
+use crate::models::membership::{
+    AccessCheckResult, AccessDeniedReason, AccessMethod, AppliedMemberDiscount, CartMember, ContentRestriction,
+    CouponStacking, MemberCartDiscount, MemberDiscount, MemberDiscountType, MemberPlanDiscount, MembershipPlan, MembershipSettings,
+    MembershipStatus, PlanStatus, RestrictedContentType, UserMembership,
+};
+use crate::models::order::{Order, OrderCouponLine};
+use crate::models::product::Product;
+use crate::services::pricing::PricingService;
+use crate::services::product::ProductListing;
+use crate::settings::RustCommerceSettings;
+
+/// Order coupon line type for member discounts
+pub const MEMBER_DISCOUNT_TYPE: &str = "member_discount";
+
+/// The member an access check is for, with the store's plans and the
+/// member's memberships
+#[derive(Debug, Clone, Copy)]
+pub struct MemberContext<'a> {
+    /// `None` for guests
+    pub user_id: Option<Uuid>,
+    pub plans: &'a [MembershipPlan],
+    pub memberships: &'a [UserMembership],
+    pub now: DateTime<Utc>,
+}
+
+impl MemberContext<'_> {
+    fn memberships(&self) -> impl Iterator<Item = &UserMembership> {
+        let user_id = self.user_id;
+        self.memberships.iter().filter(move |m| Some(m.user_id) == user_id)
+    }
+}
+
+/// Memberships granted by an order
+#[derive(Debug, Clone, Default)]
+pub struct MembershipGrant {
+    pub created: Vec<UserMembership>,
+    /// Existing memberships renewed or extended by the purchase
+    pub extended: Vec<UserMembership>,
+}
+
+/// Membership service
+pub struct MembershipService {
+    settings: RustCommerceSettings,
+    membership_settings: MembershipSettings,
+    plans: Vec<MembershipPlan>,
+}
+
+impl MembershipService {
+    /// Create a new membership service
+    pub fn new(settings: RustCommerceSettings, membership_settings: MembershipSettings) -> Self {
+        Self { settings, membership_settings, plans: Vec::new() }
+    }
+
+    /// The store's membership plans, for checks made from a cart
+    pub fn with_plans(mut self, plans: Vec<MembershipPlan>) -> Self {
+        self.plans = plans;
+        self
+    }
+
+    /// The member a cart belongs to. Carts without a member loaded are
+    /// checked as a guest's.
+    pub fn cart_context<'a>(&'a self, cart: &'a Cart, now: DateTime<Utc>) -> MemberContext<'a> {
+        self.member_context(cart.member.as_ref(), now)
+    }
+
+    fn member_context<'a>(&'a self, member: Option<&'a CartMember>, now: DateTime<Utc>) -> MemberContext<'a> {
+        MemberContext {
+            user_id: member.map(|m| m.user_id),
+            plans: &self.plans,
+            memberships: member.map_or(&[], |m| &m.memberships),
+            now,
+        }
+    }
+
+    /// Whether a membership currently grants access. Delayed starts count
+    /// once their start date passes, before their status is refreshed.
+    pub fn is_current(membership: &UserMembership, now: DateTime<Utc>) -> bool {
+        let current_status = matches!(
+            membership.status,
+            MembershipStatus::Active
+                | MembershipStatus::FreeTrial
+                | MembershipStatus::Complimentary
+                | MembershipStatus::DelayedStart
+        );
+        current_status && membership.start_date <= now && membership.end_date.is_none_or(|end| end > now)
+    }
+
+    /// Grant or extend memberships for the plans an order's products
+    /// qualify for. Subscription plans are left open-ended; the linked
+    /// subscription ends them.
+    pub fn grant_from_order(
+        &self,
+        order: &Order,
+        user_id: Uuid,
+        plans: &[MembershipPlan],
+        existing: &[UserMembership],
+        now: DateTime<Utc>,
+    ) -> MembershipGrant {
+        let purchased: Vec<Uuid> = order.line_items.iter()
+            .flatten()
+            .flat_map(|item| [item.product_id, item.variation_id])
+            .flatten()
+            .collect();
+
+        let mut grant = MembershipGrant::default();
+        for plan in plans.iter().filter(|p| p.status == PlanStatus::Active) {
+            if !plan.product_ids.iter().any(|id| purchased.contains(id)) {
+                continue;
+            }
+            let current = existing.iter()
+                .filter(|m| m.user_id == user_id && m.plan_id == plan.id)
+                .find(|m| m.status != MembershipStatus::Cancelled);
+
+            match current {
+                Some(membership) => {
+                    if let Some(extended) = Self::extend(plan, membership, order, now) {
+                        grant.extended.push(extended);
+                    }
+                }
+                None => {
+                    if let Some(mut membership) = Self::new_membership(plan, user_id, now) {
+                        membership.customer_id = order.customer_id;
+                        membership.order_id = Some(order.id);
+                        grant.created.push(membership);
+                    }
+                }
+            }
+        }
+        grant
+    }
+
+    /// Dates and status for a new membership in a plan, or `None` once a
+    /// fixed-dates plan has ended
+    fn new_membership(plan: &MembershipPlan, user_id: Uuid, now: DateTime<Utc>) -> Option<UserMembership> {
+        let (start_date, end_date) = match plan.access_method {
+            AccessMethod::Unlimited | AccessMethod::Subscription => (now, None),
+            AccessMethod::FixedLength => (now, Self::access_length(plan).map(|length| now + length)),
+            AccessMethod::FixedDates => (plan.access_start_date.map_or(now, |start| start.max(now)), plan.access_end_date),
+        };
+        if end_date.is_some_and(|end| end <= start_date) {
+            return None;
+        }
+
+        let trial = match (plan.trial_enabled, plan.trial_length, plan.trial_period) {
+            (true, Some(length), Some(period)) if length > 0 => Some(start_date + period.to_duration(length)),
+            _ => None,
+        };
+        let status = match (start_date > now, trial) {
+            (true, _) => MembershipStatus::DelayedStart,
+            (false, Some(_)) => MembershipStatus::FreeTrial,
+            (false, None) => MembershipStatus::Active,
+        };
+
+        Some(UserMembership {
+            id: Uuid::now_v7(),
+            site_id: plan.site_id,
+            plan_id: plan.id,
+            user_id,
+            customer_id: None,
+            order_id: None,
+            subscription_id: None,
+            status,
+            start_date,
+            end_date,
+            paused_date: None,
+            cancelled_date: None,
+            in_trial: trial.is_some(),
+            trial_end_date: trial,
+            meta: HashMap::new(),
+            created_at: now,
+            updated_at: None,
+        })
+    }
+
+    fn access_length(plan: &MembershipPlan) -> Option<Duration> {
+        match (plan.access_length, plan.access_length_period) {
+            (Some(length), Some(period)) if length > 0 => Some(period.to_duration(length)),
+            _ => None,
+        }
+    }
+
+    /// Buying a plan's product again adds another term to a fixed-length
+    /// membership and renews an expired one
+    fn extend(
+        plan: &MembershipPlan,
+        membership: &UserMembership,
+        order: &Order,
+        now: DateTime<Utc>,
+    ) -> Option<UserMembership> {
+        let expired = membership.status == MembershipStatus::Expired;
+        let end_date = match (plan.access_method, Self::access_length(plan)) {
+            (AccessMethod::FixedLength, Some(length)) => Some(membership.end_date.map_or(now, |end| end.max(now)) + length),
+            _ if expired => Self::new_membership(plan, membership.user_id, now)?.end_date,
+            _ => return None,
+        };
+
+        let mut membership = membership.clone();
+        if expired {
+            membership.status = MembershipStatus::Active;
+            membership.start_date = now;
+            membership.in_trial = false;
+            membership.trial_end_date = None;
+        }
+        membership.end_date = end_date;
+        membership.order_id = Some(order.id);
+        membership.updated_at = Some(now);
+        Some(membership)
+    }
+
+    /// Move a membership along its dates: delayed starts begin, trials
+    /// convert, and memberships past their end date expire
+    pub fn refresh_status(membership: &mut UserMembership, now: DateTime<Utc>) -> bool {
+        let ended = membership.end_date.is_some_and(|end| end <= now);
+        let status = match membership.status {
+            MembershipStatus::Active | MembershipStatus::FreeTrial | MembershipStatus::Complimentary | MembershipStatus::DelayedStart
+                if ended => MembershipStatus::Expired,
+            MembershipStatus::DelayedStart if membership.start_date <= now => match membership.trial_end_date {
+                Some(trial_end) if trial_end > now => MembershipStatus::FreeTrial,
+                _ => MembershipStatus::Active,
+            },
+            MembershipStatus::FreeTrial if membership.trial_end_date.is_none_or(|end| end <= now) => MembershipStatus::Active,
+            status => status,
+        };
+        if status == membership.status {
+            return false;
+        }
+
+        if status == MembershipStatus::Active {
+            membership.in_trial = false;
+        }
+        membership.status = status;
+        membership.updated_at = Some(now);
+        true
+    }
+
+    /// Refresh every membership, returning those whose status changed
+    pub fn refresh_statuses(memberships: &mut [UserMembership], now: DateTime<Utc>) -> Vec<Uuid> {
+        memberships.iter_mut()
+            .filter_map(|m| Self::refresh_status(m, now).then_some(m.id))
+            .collect()
+    }
+
+    /// Whether the member can view a post, page or other content
+    pub fn check_access(
+        &self,
+        ctx: &MemberContext<'_>,
+        content_type: RestrictedContentType,
+        content_id: Uuid,
+    ) -> AccessCheckResult {
+        self.check(ctx, content_type, content_id, &[], false)
+    }
+
+    /// Whether the member can view a product
+    pub fn check_product_access(&self, ctx: &MemberContext<'_>, product: &Product) -> AccessCheckResult {
+        self.check(ctx, RestrictedContentType::Product, product.id, &Self::category_ids(product), false)
+    }
+
+    /// Whether the member can purchase a product
+    pub fn check_purchase(&self, ctx: &MemberContext<'_>, product: &Product) -> AccessCheckResult {
+        self.check(ctx, RestrictedContentType::Product, product.id, &Self::category_ids(product), true)
+    }
+
+    /// Products the member can see in listings
+    pub fn visible_products<'a>(&self, ctx: &MemberContext<'_>, products: &'a [Product]) -> Vec<&'a Product> {
+        products.iter()
+            .filter(|product| self.check_product_access(ctx, product).has_access)
+            .collect()
+    }
+
+    /// Mark a listed product as not purchasable when only members can buy it
+    pub fn restrict_listing(&self, ctx: &MemberContext<'_>, product: &Product, listing: &mut ProductListing) {
+        if !self.check_purchase(ctx, product).has_access {
+            listing.purchasable = false;
+        }
+    }
+
+    fn category_ids(product: &Product) -> Vec<Uuid> {
+        product.categories.iter().flatten().map(|c| c.id).collect()
+    }
+
+    fn check(
+        &self,
+        ctx: &MemberContext<'_>,
+        content_type: RestrictedContentType,
+        content_id: Uuid,
+        category_ids: &[Uuid],
+        purchase: bool,
+    ) -> AccessCheckResult {
+        let rules: Vec<(&MembershipPlan, &ContentRestriction)> = ctx.plans.iter()
+            .filter(|plan| plan.status == PlanStatus::Active)
+            .flat_map(|plan| plan.restricted_content.iter().map(move |rule| (plan, rule)))
+            .filter(|(_, rule)| rule.covers(content_type, content_id, category_ids))
+            .filter(|(_, rule)| match purchase {
+                true => rule.access_type.restricts_purchasing(),
+                false => rule.access_type.restricts_viewing(content_type),
+            })
+            .collect();
+
+        let mut result = AccessCheckResult {
+            has_access: rules.is_empty(),
+            reason: None,
+            membership_ids: Vec::new(),
+            available_after: None,
+            required_plan_ids: Vec::new(),
+        };
+        if result.has_access {
+            return result;
+        }
+        for (plan, _) in &rules {
+            if !result.required_plan_ids.contains(&plan.id) {
+                result.required_plan_ids.push(plan.id);
+            }
+        }
+
+        // Drip rules open `delay_days` after the membership starts
+        for (plan, rule) in &rules {
+            let delay = Duration::days(rule.delay_days.unwrap_or(0).max(0) as i64);
+            for membership in ctx.memberships().filter(|m| m.plan_id == plan.id) {
+                let opens = membership.start_date + delay;
+                let current = Self::is_current(membership, ctx.now);
+                if current && opens <= ctx.now {
+                    if !result.membership_ids.contains(&membership.id) {
+                        result.membership_ids.push(membership.id);
+                    }
+                } else if current || membership.status == MembershipStatus::DelayedStart {
+                    result.available_after = Some(result.available_after.map_or(opens, |after| after.min(opens)));
+                }
+            }
+        }
+
+        if !result.membership_ids.is_empty() {
+            result.has_access = true;
+            result.available_after = None;
+            return result;
+        }
+        result.reason = Some(match result.available_after {
+            Some(_) => AccessDeniedReason::DripContentLocked,
+            None => Self::denied_reason(ctx, &result.required_plan_ids),
+        });
+        result
+    }
+
+    fn denied_reason(ctx: &MemberContext<'_>, plan_ids: &[Uuid]) -> AccessDeniedReason {
+        let lapsed: Vec<&UserMembership> = ctx.memberships()
+            .filter(|m| plan_ids.contains(&m.plan_id))
+            .collect();
+
+        if lapsed.iter().any(|m| m.status == MembershipStatus::Paused) {
+            AccessDeniedReason::MembershipPaused
+        } else if lapsed.iter().any(|m| m.status == MembershipStatus::Expired && m.in_trial) {
+            AccessDeniedReason::TrialExpired
+        } else if !lapsed.is_empty() {
+            AccessDeniedReason::MembershipExpired
+        } else if ctx.memberships().any(|m| Self::is_current(m, ctx.now)) {
+            AccessDeniedReason::ContentNotIncluded
+        } else {
+            AccessDeniedReason::NoMembership
+        }
+    }
+
+    /// Discounts from the member's current memberships in active plans.
+    /// Paused, expired and not yet started memberships give none.
+    fn member_discounts<'a>(ctx: &MemberContext<'a>) -> Vec<(&'a MembershipPlan, &'a MemberDiscount)> {
+        let current: Vec<Uuid> = ctx.memberships()
+            .filter(|m| Self::is_current(m, ctx.now))
+            .map(|m| m.plan_id)
+            .collect();
+        ctx.plans.iter()
+            .filter(|plan| plan.status == PlanStatus::Active && current.contains(&plan.id))
+            .flat_map(|plan| plan.member_discounts.iter().map(move |discount| (plan, discount)))
+            .collect()
+    }
+
+    /// The largest discount off one unit. Discounts from different plans
+    /// don't add up.
+    fn best_discount<'a>(
+        discounts: &[(&'a MembershipPlan, &'a MemberDiscount)],
+        product_ids: &[Uuid],
+        category_ids: &[Uuid],
+        on_sale: bool,
+        price: Decimal,
+        rate: Decimal,
+    ) -> Option<(&'a MembershipPlan, &'a MemberDiscount, Decimal)> {
+        discounts.iter()
+            .filter(|(_, discount)| discount.applies_to_product(product_ids, category_ids, on_sale))
+            .map(|(plan, discount)| (*plan, *discount, discount.unit_discount(price, rate)))
+            .filter(|(_, _, amount)| *amount > Decimal::ZERO)
+            .max_by(|a, b| a.2.cmp(&b.2))
+    }
+
+    /// A product's price for the member, when one of their plans discounts it
+    pub fn member_price(&self, ctx: &MemberContext<'_>, product: &Product) -> Option<Decimal> {
+        let price = product.get_price()?;
+        let discounts = Self::member_discounts(ctx);
+        let category_ids = Self::category_ids(product);
+        let (_, _, discount) = Self::best_discount(
+            &discounts,
+            &[product.id],
+            &category_ids,
+            product.is_on_sale(),
+            price,
+            Decimal::ONE,
+        )?;
+        Some((price - discount).round_dp(self.settings.general.currency_decimals()))
+    }
+
+    /// Show the member's price in a product listing, with the usual price
+    /// struck through
+    pub fn apply_to_listing(
+        &self,
+        ctx: &MemberContext<'_>,
+        product: &Product,
+        listing: &mut ProductListing,
+        pricing: &PricingService,
+    ) {
+        let (Some(price), Some(member_price)) = (listing.price, self.member_price(ctx, product)) else {
+            return;
+        };
+        listing.price_html = format!(
+            "<del>{}</del> <ins>{}</ins>",
+            pricing.format_price(price),
+            pricing.format_price(member_price)
+        );
+        listing.price = Some(member_price);
+    }
+
+    /// Member discounts applied to a cart
+    pub fn cart_discount(cart: &Cart) -> Option<MemberCartDiscount> {
+        MemberCartDiscount::from_meta(&cart.meta)
+    }
+
+    /// Record the discounts of the cart's own member, replacing those
+    /// recorded before, so they stop once the membership lapses or is
+    /// paused. `CartService::calculate_totals` runs this on every
+    /// recalculation.
+    pub fn refresh_cart(&self, cart: &mut Cart, now: DateTime<Utc>) -> MemberCartDiscount {
+        let member = cart.member.clone();
+        self.apply_to_cart(&self.member_context(member.as_ref(), now), cart)
+    }
+
+    /// Record the member's discount on each cart line, replacing any member
+    /// discounts recorded before. `CartService::calculate_totals` takes the
+    /// discounts off the lines and their tax; recalculate the totals
+    /// afterwards.
+    pub fn apply_to_cart(&self, ctx: &MemberContext<'_>, cart: &mut Cart) -> MemberCartDiscount {
+        self.remove_from_cart(cart);
+
+        let discounts = Self::member_discounts(ctx);
+        let rate = cart.currency_rate.unwrap_or(Decimal::ONE);
+        let decimals = crate::currency::minor_units(cart.currency.as_deref().unwrap_or(&self.settings.general.currency));
+        let mut applied = MemberCartDiscount {
+            free_shipping: discounts.iter().any(|(_, d)| d.discount_type == MemberDiscountType::FreeShipping),
+            ..Default::default()
+        };
+
+        for item in &mut cart.items {
+            let product_ids: Vec<Uuid> = [Some(item.product_id), item.variation_id].into_iter().flatten().collect();
+            let Some((plan, discount, unit)) =
+                Self::best_discount(&discounts, &product_ids, &item.category_ids, item.is_on_sale(), item.price, rate)
+            else {
+                continue;
+            };
+            let amount = (unit * Decimal::from(item.quantity)).round_dp(decimals).min(item.subtotal);
+            if amount <= Decimal::ZERO {
+                continue;
+            }
+            // The line's tax falls in proportion to its discounted total
+            let tax = (item.subtotal_tax * amount / item.subtotal).round_dp(decimals);
+
+            let line = AppliedMemberDiscount { plan_id: plan.id, discount_id: discount.id, amount, tax };
+            item.meta.insert(AppliedMemberDiscount::META_KEY.to_string(), serde_json::to_value(line).unwrap_or_default());
+            match applied.plans.iter_mut().find(|p| p.plan_id == plan.id) {
+                Some(plan_discount) => {
+                    plan_discount.amount += amount;
+                    plan_discount.tax += tax;
+                }
+                None => applied.plans.push(MemberPlanDiscount {
+                    plan_id: plan.id,
+                    plan_name: plan.name.clone(),
+                    plan_slug: plan.slug.clone(),
+                    amount,
+                    tax,
+                }),
+            }
+            applied.total += amount;
+            applied.tax += tax;
+        }
+
+        if !applied.plans.is_empty() || applied.free_shipping {
+            cart.meta.insert(MemberCartDiscount::META_KEY.to_string(), serde_json::to_value(&applied).unwrap_or_default());
+        }
+        applied
+    }
+
+    /// Take member discounts back off a cart. Recalculate its totals
+    /// afterwards.
+    pub fn remove_from_cart(&self, cart: &mut Cart) {
+        for item in &mut cart.items {
+            item.meta.remove(AppliedMemberDiscount::META_KEY);
+        }
+        cart.meta.remove(MemberCartDiscount::META_KEY);
+    }
+
+    /// The coupon discount to keep alongside the cart's member discount.
+    /// When coupons save more under `BestDiscount`, the member discount
+    /// comes off the cart instead. Run after `refresh_cart`.
+    pub fn stack_coupons(&self, cart: &mut Cart, coupon_discount: Decimal) -> Decimal {
+        let Some(applied) = Self::cart_discount(cart).filter(|a| a.total > Decimal::ZERO) else {
+            return coupon_discount;
+        };
+
+        match self.membership_settings.coupon_stacking {
+            CouponStacking::Stack => coupon_discount,
+            CouponStacking::BestDiscount if coupon_discount > applied.total => {
+                self.remove_from_cart(cart);
+                if applied.free_shipping {
+                    let shipping_only = MemberCartDiscount { free_shipping: true, ..Default::default() };
+                    cart.meta.insert(MemberCartDiscount::META_KEY.to_string(), serde_json::to_value(shipping_only).unwrap_or_default());
+                }
+                coupon_discount
+            }
+            CouponStacking::BestDiscount | CouponStacking::MemberDiscountOnly => Decimal::ZERO,
+        }
+    }
+
+    /// Add the cart's member discounts to its order as their own lines,
+    /// one per plan. The order's totals and line items already include
+    /// them through the cart's; `CheckoutService::validate_member_purchases`
+    /// checks they are still current first.
+    pub fn apply_to_order(&self, cart: &Cart, order: &mut Order) {
+        let Some(applied) = Self::cart_discount(cart) else {
+            return;
+        };
+
+        let order_id = order.id;
+        let lines = order.coupon_lines.get_or_insert_with(Vec::new);
+        lines.extend(applied.plans.iter().map(|plan| OrderCouponLine {
+            id: Uuid::now_v7(),
+            order_id,
+            code: format!("member-{}", plan.plan_slug),
+            discount: plan.amount,
+            discount_tax: plan.tax,
+            discount_type: MEMBER_DISCOUNT_TYPE.to_string(),
+            coupon_id: None,
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::cart::CartItem;
+    use crate::models::customer::Address;
+    use crate::models::membership::{AccessPeriod, ContentAccessType, DiscountAppliesTo};
+    use crate::models::order::{OrderItem, OrderItemType, OrderStatus};
+    use crate::models::product::{ProductCategory, ProductStatus, ProductType, StockStatus};
+    use chrono::NaiveDate;
+    use rust_decimal_macros::dec;
+
+    fn service() -> MembershipService {
+        MembershipService::new(RustCommerceSettings::default(), MembershipSettings::default())
+    }
+
+    fn at(day: u32) -> DateTime<Utc> {
+        NaiveDate::from_ymd_opt(2030, 6, day).unwrap().and_hms_opt(12, 0, 0).unwrap().and_utc()
+    }
+
+    fn plan(access_method: AccessMethod, product_ids: Vec<Uuid>) -> MembershipPlan {
+        MembershipPlan {
+            id: Uuid::now_v7(),
+            site_id: None,
+            name: "Gold".to_string(),
+            slug: "gold".to_string(),
+            description: None,
+            status: PlanStatus::Active,
+            access_method,
+            access_length: None,
+            access_length_period: None,
+            access_start_date: None,
+            access_end_date: None,
+            product_ids,
+            restricted_content: Vec::new(),
+            member_discounts: Vec::new(),
+            trial_enabled: false,
+            trial_length: None,
+            trial_period: None,
+            meta: HashMap::new(),
+            created_at: at(1),
+            updated_at: None,
+        }
+    }
+
+    fn restriction(
+        plan: &MembershipPlan,
+        content_type: RestrictedContentType,
+        content_ids: Vec<Uuid>,
+        access_type: ContentAccessType,
+        delay_days: Option<i32>,
+    ) -> ContentRestriction {
+        ContentRestriction {
+            id: Uuid::now_v7(),
+            plan_id: plan.id,
+            content_type,
+            content_ids,
+            access_type,
+            delay_days,
+        }
+    }
+
+    fn discount(
+        plan: &MembershipPlan,
+        discount_type: MemberDiscountType,
+        amount: Decimal,
+        applies_to: DiscountAppliesTo,
+    ) -> MemberDiscount {
+        MemberDiscount {
+            id: Uuid::now_v7(),
+            plan_id: plan.id,
+            discount_type,
+            amount,
+            applies_to,
+            product_ids: None,
+            category_ids: None,
+            exclude_sale_items: false,
+        }
+    }
+
+    fn membership(plan: &MembershipPlan, user_id: Uuid, status: MembershipStatus, start: DateTime<Utc>) -> UserMembership {
+        UserMembership {
+            id: Uuid::now_v7(),
+            site_id: None,
+            plan_id: plan.id,
+            user_id,
+            customer_id: None,
+            order_id: None,
+            subscription_id: None,
+            status,
+            start_date: start,
+            end_date: None,
+            paused_date: None,
+            cancelled_date: None,
+            in_trial: false,
+            trial_end_date: None,
+            meta: HashMap::new(),
+            created_at: start,
+            updated_at: None,
+        }
+    }
+
+    fn order(product_ids: &[Uuid]) -> Order {
+        let id = Uuid::now_v7();
+        let items = product_ids.iter().map(|product_id| OrderItem {
+            id: Uuid::now_v7(),
+            order_id: id,
+            item_type: OrderItemType::LineItem,
+            name: "Gold Membership".to_string(),
+            quantity: 1,
+            subtotal: dec!(50),
+            subtotal_tax: Decimal::ZERO,
+            total: dec!(50),
+            total_tax: Decimal::ZERO,
+            product_id: Some(*product_id),
+            variation_id: None,
+            sku: None,
+            meta: serde_json::json!({}),
+            created_at: at(1),
+            product_name: None,
+            product_image: None,
+            variation_attributes: None,
+        }).collect();
+
+        Order {
+            id,
+            site_id: None,
+            order_number: "RC-20300601-0001".to_string(),
+            customer_id: Some(Uuid::now_v7()),
+            customer_ip_address: None,
+            customer_user_agent: None,
+            status: OrderStatus::Completed,
+            parent_id: None,
+            currency: "USD".to_string(),
+            currency_symbol: "$".to_string(),
+            base_currency_rate: None,
+            locale: None,
+            prices_include_tax: false,
+            discount_total: Decimal::ZERO,
+            discount_tax: Decimal::ZERO,
+            shipping_total: Decimal::ZERO,
+            shipping_tax: Decimal::ZERO,
+            cart_tax: Decimal::ZERO,
+            total: dec!(50),
+            total_tax: Decimal::ZERO,
+            billing: Address::default(),
+            shipping: Address::default(),
+            payment_method: None,
+            payment_method_title: None,
+            transaction_id: None,
+            shipping_method: None,
+            shipping_method_title: None,
+            fulfillment_status: Default::default(),
+            pickup: None,
+            vat: None,
+            tax_exemption: None,
+            customer_note: None,
+            date_paid: None,
+            date_completed: None,
+            cart_hash: None,
+            meta: serde_json::json!({}),
+            created_at: at(1),
+            updated_at: None,
+            line_items: Some(items),
+            shipping_lines: None,
+            tax_lines: None,
+            fee_lines: None,
+            coupon_lines: None,
+            notes: None,
+            refunds: None,
+            shipments: None,
+        }
+    }
+
+    fn product(category_ids: &[Uuid]) -> Product {
+        let categories = category_ids.iter().map(|id| ProductCategory {
+            id: *id,
+            name: "Members".to_string(),
+            slug: "members".to_string(),
+            description: None,
+            parent_id: None,
+            image_id: None,
+            display_type: Default::default(),
+            menu_order: 0,
+            count: 1,
+        }).collect();
+
+        Product {
+            id: Uuid::now_v7(),
+            site_id: None,
+            sku: None,
+            name: "Masterclass".to_string(),
+            slug: "masterclass".to_string(),
+            product_type: ProductType::Simple,
+            status: ProductStatus::Publish,
+            short_description: None,
+            description: None,
+            regular_price: Some(dec!(100)),
+            sale_price: None,
+            sale_price_from: None,
+            sale_price_to: None,
+            tax_status: Default::default(),
+            tax_class: String::new(),
+            tax_code: None,
+            manage_stock: false,
+            stock_quantity: 0,
+            stock_status: StockStatus::InStock,
+            backorders: Default::default(),
+            low_stock_amount: None,
+            sold_individually: false,
+            weight: None,
+            length: None,
+            width: None,
+            height: None,
+            shipping_class_id: None,
+            is_virtual: true,
+            is_downloadable: false,
+            download_limit: 0,
+            download_expiry: 0,
+            external_url: None,
+            button_text: None,
+            reviews_allowed: false,
+            average_rating: Decimal::ZERO,
+            rating_count: 0,
+            featured: false,
+            catalog_visibility: Default::default(),
+            parent_id: None,
+            menu_order: 0,
+            purchase_note: None,
+            total_sales: 0,
+            meta_title: None,
+            meta_description: None,
+            meta_keywords: None,
+            created_at: at(1),
+            updated_at: None,
+            published_at: None,
+            categories: Some(categories),
+            tags: None,
+            images: None,
+            attributes: None,
+            variations: None,
+            downloads: None,
+        }
+    }
+
+    fn listing(product: &Product) -> ProductListing {
+        ProductListing {
+            id: product.id,
+            name: product.name.clone(),
+            slug: product.slug.clone(),
+            permalink: format!("/product/{}", product.slug),
+            price: product.get_price(),
+            price_html: String::new(),
+            on_sale: false,
+            purchasable: product.is_purchasable(),
+            featured: false,
+            average_rating: Decimal::ZERO,
+            review_count: 0,
+        }
+    }
+
+    #[test]
+    fn test_grant_from_order() {
+        let service = service();
+        let (gold_product, silver_product) = (Uuid::now_v7(), Uuid::now_v7());
+        let mut yearly = plan(AccessMethod::FixedLength, vec![gold_product]);
+        yearly.access_length = Some(1);
+        yearly.access_length_period = Some(AccessPeriod::Year);
+        yearly.trial_enabled = true;
+        yearly.trial_length = Some(7);
+        yearly.trial_period = Some(AccessPeriod::Day);
+        let mut season = plan(AccessMethod::FixedDates, vec![gold_product]);
+        season.access_start_date = Some(at(20));
+        season.access_end_date = Some(at(30));
+        let unrelated = plan(AccessMethod::Unlimited, vec![silver_product]);
+        let plans = vec![yearly.clone(), season.clone(), unrelated];
+        let user_id = Uuid::now_v7();
+        let order = order(&[gold_product]);
+
+        let grant = service.grant_from_order(&order, user_id, &plans, &[], at(1));
+        assert_eq!(grant.created.len(), 2);
+        let (first, second) = (&grant.created[0], &grant.created[1]);
+        assert_eq!((first.plan_id, first.status), (yearly.id, MembershipStatus::FreeTrial));
+        assert_eq!(first.end_date, Some(at(1) + Duration::days(365)));
+        assert_eq!(first.trial_end_date, Some(at(8)));
+        assert_eq!(first.order_id, Some(order.id));
+        assert_eq!((second.plan_id, second.status), (season.id, MembershipStatus::DelayedStart));
+        assert_eq!((second.start_date, second.end_date), (at(20), Some(at(30))));
+
+        // Buying again adds a year to the existing membership
+        let regrant = service.grant_from_order(&order, user_id, &plans, &grant.created, at(5));
+        assert!(regrant.created.is_empty());
+        assert_eq!(regrant.extended.len(), 1);
+        assert_eq!(regrant.extended[0].end_date, Some(at(1) + Duration::days(730)));
+    }
+
+    #[test]
+    fn test_refresh_statuses() {
+        let gold = plan(AccessMethod::FixedLength, Vec::new());
+        let user_id = Uuid::now_v7();
+        let mut trial = membership(&gold, user_id, MembershipStatus::FreeTrial, at(1));
+        trial.in_trial = true;
+        trial.trial_end_date = Some(at(8));
+        let delayed = membership(&gold, user_id, MembershipStatus::DelayedStart, at(10));
+        let mut ending = membership(&gold, user_id, MembershipStatus::Active, at(1));
+        ending.end_date = Some(at(9));
+        let mut memberships = vec![trial, delayed, ending];
+
+        assert_eq!(MembershipService::refresh_statuses(&mut memberships, at(9)), vec![memberships[0].id, memberships[2].id]);
+        assert_eq!(memberships[0].status, MembershipStatus::Active);
+        assert!(!memberships[0].in_trial);
+        assert_eq!(memberships[2].status, MembershipStatus::Expired);
+        assert_eq!(MembershipService::refresh_statuses(&mut memberships, at(10)), vec![memberships[1].id]);
+        assert_eq!(memberships[1].status, MembershipStatus::Active);
+    }
+
+    #[test]
+    fn test_content_access_and_drip() {
+        let service = service();
+        let post = Uuid::now_v7();
+        let mut gold = plan(AccessMethod::Unlimited, Vec::new());
+        gold.restricted_content = vec![
+            restriction(&gold, RestrictedContentType::Post, vec![post], ContentAccessType::ViewOnly, Some(14)),
+        ];
+        let silver = plan(AccessMethod::Unlimited, Vec::new());
+        let plans = vec![gold.clone(), silver.clone()];
+        let user_id = Uuid::now_v7();
+        let ctx = |memberships, now| MemberContext { user_id: Some(user_id), plans: &plans, memberships, now };
+
+        // Unrestricted content is open to everyone
+        let open = service.check_access(&ctx(&[], at(1)), RestrictedContentType::Post, Uuid::now_v7());
+        assert!(open.has_access);
+
+        let result = service.check_access(&ctx(&[], at(1)), RestrictedContentType::Post, post);
+        assert_eq!(result.reason, Some(AccessDeniedReason::NoMembership));
+        assert_eq!(result.required_plan_ids, vec![gold.id]);
+
+        let member = [membership(&gold, user_id, MembershipStatus::Active, at(1))];
+        let locked = service.check_access(&ctx(&member, at(10)), RestrictedContentType::Post, post);
+        assert_eq!(locked.reason, Some(AccessDeniedReason::DripContentLocked));
+        assert_eq!(locked.available_after, Some(at(15)));
+
+        let unlocked = service.check_access(&ctx(&member, at(15)), RestrictedContentType::Post, post);
+        assert!(unlocked.has_access);
+        assert_eq!(unlocked.membership_ids, vec![member[0].id]);
+
+        let other_plan = [membership(&silver, user_id, MembershipStatus::Active, at(1))];
+        let result = service.check_access(&ctx(&other_plan, at(20)), RestrictedContentType::Post, post);
+        assert_eq!(result.reason, Some(AccessDeniedReason::ContentNotIncluded));
+
+        let paused = [membership(&gold, user_id, MembershipStatus::Paused, at(1))];
+        let result = service.check_access(&ctx(&paused, at(20)), RestrictedContentType::Post, post);
+        assert_eq!(result.reason, Some(AccessDeniedReason::MembershipPaused));
+
+        let mut expired = member.clone();
+        expired[0].end_date = Some(at(18));
+        let result = service.check_access(&ctx(&expired, at(20)), RestrictedContentType::Post, post);
+        assert_eq!(result.reason, Some(AccessDeniedReason::MembershipExpired));
+
+        // Another user's membership doesn't count
+        let guest = MemberContext { user_id: None, plans: &plans, memberships: &member, now: at(20) };
+        assert!(!service.check_access(&guest, RestrictedContentType::Post, post).has_access);
+    }
+
+    #[test]
+    fn test_member_only_products() {
+        let service = service();
+        let members_category = Uuid::now_v7();
+        let (buy_only, hidden, open) = (product(&[]), product(&[members_category]), product(&[]));
+        let mut gold = plan(AccessMethod::Unlimited, Vec::new());
+        gold.restricted_content = vec![
+            restriction(&gold, RestrictedContentType::Product, vec![buy_only.id], ContentAccessType::ViewOnly, None),
+            restriction(&gold, RestrictedContentType::ProductCategory, vec![members_category], ContentAccessType::FullAccess, None),
+        ];
+        let plans = vec![gold.clone()];
+        let user_id = Uuid::now_v7();
+        let products = vec![buy_only.clone(), hidden.clone(), open.clone()];
+
+        let guest = MemberContext { user_id: None, plans: &plans, memberships: &[], now: at(1) };
+        let visible: Vec<Uuid> = service.visible_products(&guest, &products).iter().map(|p| p.id).collect();
+        assert_eq!(visible, vec![buy_only.id, open.id]);
+        assert!(!service.check_purchase(&guest, &buy_only).has_access);
+        assert!(!service.check_purchase(&guest, &hidden).has_access);
+        assert!(service.check_purchase(&guest, &open).has_access);
+
+        let mut listing = listing(&buy_only);
+        assert!(listing.purchasable);
+        service.restrict_listing(&guest, &buy_only, &mut listing);
+        assert!(!listing.purchasable);
+
+        let memberships = [membership(&gold, user_id, MembershipStatus::Active, at(1))];
+        let member = MemberContext { user_id: Some(user_id), plans: &plans, memberships: &memberships, now: at(2) };
+        assert_eq!(service.visible_products(&member, &products).len(), 3);
+        assert!(service.check_purchase(&member, &buy_only).has_access);
+        assert!(service.check_purchase(&member, &hidden).has_access);
+    }
+
+    #[test]
+    fn test_member_prices() {
+        let service = service();
+        let regular = product(&[]);
+        let mut on_sale = product(&[]);
+        on_sale.sale_price = Some(dec!(80));
+        let mut gold = plan(AccessMethod::Unlimited, Vec::new());
+        let mut ten_percent = discount(&gold, MemberDiscountType::Percentage, dec!(10), DiscountAppliesTo::AllProducts);
+        ten_percent.exclude_sale_items = true;
+        gold.member_discounts = vec![ten_percent];
+        let plans = vec![gold.clone()];
+        let user_id = Uuid::now_v7();
+        let mut memberships = vec![membership(&gold, user_id, MembershipStatus::Active, at(1))];
+
+        let ctx = MemberContext { user_id: Some(user_id), plans: &plans, memberships: &memberships, now: at(2) };
+        assert_eq!(service.member_price(&ctx, &regular), Some(dec!(90)));
+        assert_eq!(service.member_price(&ctx, &on_sale), None);
+
+        let pricing = PricingService::new(RustCommerceSettings::default());
+        let mut listing = listing(&regular);
+        service.apply_to_listing(&ctx, &regular, &mut listing, &pricing);
+        assert_eq!(listing.price, Some(dec!(90)));
+        assert!(listing.price_html.starts_with("<del>"));
+
+        // Paused members pay the usual price
+        memberships[0].status = MembershipStatus::Paused;
+        let ctx = MemberContext { user_id: Some(user_id), plans: &plans, memberships: &memberships, now: at(2) };
+        assert_eq!(service.member_price(&ctx, &regular), None);
+    }
+
+    #[test]
+    fn test_cart_discounts_and_order_lines() {
+        let service = service();
+        let members_category = Uuid::now_v7();
+        let (book, course) = (product(&[]), product(&[members_category]));
+        let mut gold = plan(AccessMethod::Unlimited, Vec::new());
+        let mut fifteen_off = discount(&gold, MemberDiscountType::FixedAmount, dec!(15), DiscountAppliesTo::SpecificCategories);
+        fifteen_off.category_ids = Some(vec![members_category]);
+        gold.member_discounts = vec![
+            discount(&gold, MemberDiscountType::Percentage, dec!(10), DiscountAppliesTo::AllProducts),
+            fifteen_off,
+            discount(&gold, MemberDiscountType::FreeShipping, Decimal::ZERO, DiscountAppliesTo::AllProducts),
+        ];
+        let plans = vec![gold.clone()];
+        let user_id = Uuid::now_v7();
+        let mut memberships = vec![membership(&gold, user_id, MembershipStatus::Active, at(1))];
+        memberships[0].end_date = Some(at(10));
+        let mut cart = Cart::new(None, Some(Uuid::now_v7()));
+        cart.items = vec![
+            CartItem::from_product(&book, 2, None, HashMap::new()),
+            CartItem::from_product(&course, 1, None, HashMap::new()),
+        ];
+        cart.items[0].subtotal_tax = dec!(40);
+        cart.items[1].subtotal_tax = dec!(20);
+        let line = |item: &CartItem| AppliedMemberDiscount::from_meta(&item.meta).map(|d| (d.amount, d.tax));
+
+        // The larger discount wins on each line, and takes its share of the line's tax with it
+        let ctx = MemberContext { user_id: Some(user_id), plans: &plans, memberships: &memberships, now: at(2) };
+        let applied = service.apply_to_cart(&ctx, &mut cart);
+        assert_eq!((applied.total, applied.tax), (dec!(35), dec!(7)));
+        assert!(applied.free_shipping);
+        assert_eq!(line(&cart.items[0]), Some((dec!(20), dec!(4))));
+        assert_eq!(line(&cart.items[1]), Some((dec!(15), dec!(3))));
+        // Line totals are left to the cart's totals calculation
+        assert_eq!(cart.items[0].total, dec!(200));
+
+        // Applying again replaces the discount rather than adding to it
+        service.apply_to_cart(&ctx, &mut cart);
+        assert_eq!(MembershipService::cart_discount(&cart).unwrap().total, dec!(35));
+
+        let mut order = order(&[book.id]);
+        service.apply_to_order(&cart, &mut order);
+        let lines = order.coupon_lines.unwrap();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].code, "member-gold");
+        assert_eq!((lines[0].discount, lines[0].discount_tax), (dec!(35), dec!(7)));
+        assert_eq!(lines[0].discount_type, MEMBER_DISCOUNT_TYPE);
+
+        // Discounts stop once the membership expires
+        let expired = MemberContext { now: at(10), ..ctx };
+        let applied = service.apply_to_cart(&expired, &mut cart);
+        assert_eq!(applied.total, Decimal::ZERO);
+        assert_eq!(line(&cart.items[0]), None);
+        assert!(MembershipService::cart_discount(&cart).is_none());
+    }
+
+    #[test]
+    fn test_coupon_stacking() {
+        let mut gold = plan(AccessMethod::Unlimited, Vec::new());
+        gold.member_discounts = vec![discount(&gold, MemberDiscountType::Percentage, dec!(10), DiscountAppliesTo::AllProducts)];
+        let plans = vec![gold.clone()];
+        let user_id = Uuid::now_v7();
+        let memberships = vec![membership(&gold, user_id, MembershipStatus::Active, at(1))];
+        let ctx = MemberContext { user_id: Some(user_id), plans: &plans, memberships: &memberships, now: at(2) };
+        let book = product(&[]);
+        let with = |coupon_stacking| MembershipService::new(RustCommerceSettings::default(), MembershipSettings { coupon_stacking });
+        let discounted_cart = |service: &MembershipService| {
+            let mut cart = Cart::new(None, None);
+            cart.items = vec![CartItem::from_product(&book, 1, None, HashMap::new())];
+            service.apply_to_cart(&ctx, &mut cart);
+            cart
+        };
+        let member_discount = |cart: &Cart| MembershipService::cart_discount(cart).map(|d| d.total);
+
+        let stack = with(CouponStacking::Stack);
+        let mut cart = discounted_cart(&stack);
+        assert_eq!(stack.stack_coupons(&mut cart, dec!(20)), dec!(20));
+        assert_eq!(member_discount(&cart), Some(dec!(10)));
+
+        let best = with(CouponStacking::BestDiscount);
+        let mut cart = discounted_cart(&best);
+        assert_eq!(best.stack_coupons(&mut cart, dec!(5)), Decimal::ZERO);
+        assert_eq!(member_discount(&cart), Some(dec!(10)));
+        assert_eq!(best.stack_coupons(&mut cart, dec!(20)), dec!(20));
+        assert_eq!(member_discount(&cart), None);
+        assert!(AppliedMemberDiscount::from_meta(&cart.items[0].meta).is_none());
+
+        let member_only = with(CouponStacking::MemberDiscountOnly);
+        let mut cart = discounted_cart(&member_only);
+        assert_eq!(member_only.stack_coupons(&mut cart, dec!(20)), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_refresh_cart_drops_discounts_of_lapsed_memberships() {
+        let mut gold = plan(AccessMethod::Unlimited, Vec::new());
+        gold.member_discounts = vec![discount(&gold, MemberDiscountType::Percentage, dec!(10), DiscountAppliesTo::AllProducts)];
+        let service = service().with_plans(vec![gold.clone()]);
+        let user_id = Uuid::now_v7();
+
+        let mut cart = Cart::new(None, Some(Uuid::now_v7()));
+        cart.items = vec![CartItem::from_product(&product(&[]), 1, None, HashMap::new())];
+        cart.member = Some(CartMember {
+            user_id,
+            memberships: vec![membership(&gold, user_id, MembershipStatus::Active, at(1))],
+        });
+        assert_eq!(service.refresh_cart(&mut cart, at(2)).total, dec!(10));
+        assert!(AppliedMemberDiscount::from_meta(&cart.items[0].meta).is_some());
+
+        // The discount recorded while the membership was active doesn't outlive it
+        if let Some(member) = cart.member.as_mut() {
+            member.memberships[0].status = MembershipStatus::Paused;
+        }
+        assert_eq!(service.refresh_cart(&mut cart, at(3)).total, Decimal::ZERO);
+        assert!(AppliedMemberDiscount::from_meta(&cart.items[0].meta).is_none());
+        assert!(MembershipService::cart_discount(&cart).is_none());
+    }
+}
@@ -10,9 +10,24 @@ pub mod order;
 pub mod customer;
 pub mod shipping;
 pub mod tax;
+pub mod tax_import;
 pub mod coupon;
 pub mod product;
 pub mod report;
+pub mod fulfillment;
+pub mod pickup;
+#[cfg(feature = "multi_currency")]
+pub mod currency;
+#[cfg(feature = "multi_currency")]
+pub mod exchange_rate;
+#[cfg(feature = "subscriptions")]
+pub mod subscription;
+#[cfg(feature = "bookings")]
+pub mod booking;
+#[cfg(feature = "bookings")]
+pub mod booking_calendar;
+#[cfg(feature = "memberships")]
+pub mod membership;
 
 pub use pricing::PricingService;
 pub use inventory::InventoryService;
@@ -22,6 +37,21 @@ pub use order::OrderService;
 pub use customer::CustomerService;
 pub use shipping::ShippingService;
 pub use tax::TaxService;
+pub use tax_import::TaxImportService;
 pub use coupon::CouponService;
 pub use product::ProductService;
 pub use report::ReportService;
+pub use fulfillment::FulfillmentService;
+pub use pickup::PickupService;
+#[cfg(feature = "multi_currency")]
+pub use currency::CurrencyService;
+#[cfg(feature = "multi_currency")]
+pub use exchange_rate::ExchangeRateService;
+#[cfg(feature = "subscriptions")]
+pub use subscription::SubscriptionService;
+#[cfg(feature = "bookings")]
+pub use booking::BookingService;
+#[cfg(feature = "bookings")]
+pub use booking_calendar::BookingCalendarService;
+#[cfg(feature = "memberships")]
+pub use membership::MembershipService;
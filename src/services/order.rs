@@ -10,6 +10,8 @@ use crate::models::order::{
     Order, OrderItem, OrderStatus, OrderNote, OrderRefund,
     OrderShippingLine, OrderTaxLine, OrderFeeLine
 };
+#[cfg(feature = "multi_currency")]
+use crate::services::currency::CurrencyService;
 use crate::settings::RustCommerceSettings;
 
 /// Order service
@@ -60,6 +62,7 @@ pub enum OrderError {
     CannotRefund(String),
     InvalidAmount,
     OrderLocked,
+    CurrencyChange(String),
 }
 
 impl std::fmt::Display for OrderError {
@@ -73,6 +76,7 @@ impl std::fmt::Display for OrderError {
             Self::CannotRefund(msg) => write!(f, "Cannot refund: {}", msg),
             Self::InvalidAmount => write!(f, "Invalid amount"),
             Self::OrderLocked => write!(f, "Order is locked and cannot be modified"),
+            Self::CurrencyChange(msg) => write!(f, "Cannot change currency: {}", msg),
         }
     }
 }
@@ -93,11 +97,18 @@ impl OrderService {
                 OrderStatus::Failed,
             ],
             OrderStatus::Processing => vec![
+                OrderStatus::ReadyForPickup,
                 OrderStatus::Completed,
                 OrderStatus::OnHold,
                 OrderStatus::Cancelled,
                 OrderStatus::Refunded,
             ],
+            OrderStatus::ReadyForPickup => vec![
+                OrderStatus::Processing,
+                OrderStatus::Completed,
+                OrderStatus::Cancelled,
+                OrderStatus::Refunded,
+            ],
             OrderStatus::OnHold => vec![
                 OrderStatus::Pending,
                 OrderStatus::Processing,
@@ -313,6 +324,23 @@ impl OrderService {
         order.updated_at = Some(Utc::now());
     }
 
+    /// Set the currency of an order being created by hand. Amounts are not
+    /// converted, so the order must not have items yet.
+    #[cfg(feature = "multi_currency")]
+    pub fn set_currency(&self, order: &mut Order, currency: &CurrencyService, code: &str) -> Result<(), OrderError> {
+        if !self.is_editable(order) {
+            return Err(OrderError::OrderLocked);
+        }
+        if !order.items.is_empty() {
+            return Err(OrderError::CurrencyChange("remove the order's items first".to_string()));
+        }
+
+        currency.apply_to_order(order, code)
+            .map_err(|e| OrderError::CurrencyChange(e.to_string()))?;
+        order.updated_at = Some(Utc::now());
+        Ok(())
+    }
+
     /// Add item to order
     pub fn add_item(&self, order: &mut Order, item: OrderItem) -> Result<(), OrderError> {
         if !self.is_editable(order) {
@@ -389,6 +417,7 @@ impl OrderService {
             OrderStatus::Pending => "Pending payment",
             OrderStatus::Processing => "Processing",
             OrderStatus::OnHold => "On hold",
+            OrderStatus::ReadyForPickup => "Ready for pickup",
             OrderStatus::Completed => "Completed",
             OrderStatus::Cancelled => "Cancelled",
             OrderStatus::Refunded => "Refunded",
@@ -0,0 +1,342 @@
+//! Local Pickup Service
+//!
+//! Builds pickup slots from opening hours and closures, enforces slot
+//! capacity, and moves orders through the ready-for-pickup state.
+
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc, Datelike};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::models::order::{Order, OrderStatus};
+use crate::models::pickup::{OrderPickup, PickupLocation, PickupSelection, PickupSlot};
+use crate::models::email_templates::SendEmailRequest;
+use crate::settings::RustCommerceSettings;
+
+/// Local pickup service
+pub struct PickupService {
+    settings: RustCommerceSettings,
+}
+
+/// Pickup errors
+#[derive(Debug, Clone)]
+pub enum PickupError {
+    LocationUnavailable,
+    LocationClosed(NaiveDate),
+    SlotNotFound,
+    SlotFull,
+    NotAPickupOrder,
+    InvalidOrderStatus(OrderStatus),
+}
+
+impl std::fmt::Display for PickupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LocationUnavailable => write!(f, "This pickup location is not available"),
+            Self::LocationClosed(date) => write!(f, "The pickup location is closed on {}", date),
+            Self::SlotNotFound => write!(f, "The selected pickup time is not available"),
+            Self::SlotFull => write!(f, "The selected pickup time is fully booked"),
+            Self::NotAPickupOrder => write!(f, "This order is not a local pickup order"),
+            Self::InvalidOrderStatus(status) => {
+                write!(f, "Order cannot be updated while {}", status.display_name())
+            }
+        }
+    }
+}
+
+impl PickupService {
+    /// Template key for the ready-for-pickup notification email
+    pub const READY_EMAIL_TEMPLATE: &'static str = "order_ready_for_pickup";
+
+    /// Create a new pickup service
+    pub fn new(settings: RustCommerceSettings) -> Self {
+        Self { settings }
+    }
+
+    /// Today's date at the location
+    pub fn local_today(&self, location: &PickupLocation, now: DateTime<Utc>) -> NaiveDate {
+        now.with_timezone(&location.timezone).date_naive()
+    }
+
+    /// Count bookings per slot start for a location
+    pub fn booked_counts(&self, location_id: Uuid, pickups: &[OrderPickup]) -> HashMap<DateTime<Utc>, i32> {
+        let mut counts = HashMap::new();
+        for pickup in pickups.iter().filter(|p| p.location_id == location_id) {
+            *counts.entry(pickup.slot_start).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// All slots on a local date, including full ones and those inside the lead time
+    pub fn slots_on(
+        &self,
+        location: &PickupLocation,
+        date: NaiveDate,
+        booked: &HashMap<DateTime<Utc>, i32>,
+    ) -> Vec<PickupSlot> {
+        if !location.is_open_on(date) || location.slot_duration_minutes <= 0 {
+            return vec![];
+        }
+
+        let duration = Duration::minutes(location.slot_duration_minutes as i64);
+        let mut slots = Vec::new();
+
+        for hours in location.hours_on(date.weekday()) {
+            let closes = date.and_time(hours.closes);
+            let mut start = date.and_time(hours.opens);
+
+            while start + duration <= closes {
+                // Slots in a DST gap don't exist locally; on the repeated
+                // hour the first occurrence is used
+                let Some(starts_at) = location.timezone.from_local_datetime(&start).earliest() else {
+                    start += duration;
+                    continue;
+                };
+                let starts_at = starts_at.with_timezone(&Utc);
+                slots.push(PickupSlot {
+                    location_id: location.id,
+                    starts_at,
+                    ends_at: starts_at + duration,
+                    capacity: location.slot_capacity.max(0),
+                    booked: booked.get(&starts_at).copied().unwrap_or(0),
+                });
+                start += duration;
+            }
+        }
+
+        slots
+    }
+
+    /// Bookable slots from a local date, limited by lead time, booking horizon and capacity
+    pub fn available_slots(
+        &self,
+        location: &PickupLocation,
+        from: NaiveDate,
+        days: i32,
+        booked: &HashMap<DateTime<Utc>, i32>,
+        now: DateTime<Utc>,
+    ) -> Vec<PickupSlot> {
+        if !location.is_enabled {
+            return vec![];
+        }
+
+        let today = self.local_today(location, now);
+        let last_day = today + Duration::days(location.max_days_ahead.max(0) as i64);
+        let earliest = now + Duration::minutes(location.lead_time_minutes.max(0) as i64);
+        let first_day = from.max(today);
+
+        (0..days.max(0) as i64)
+            .map(|i| first_day + Duration::days(i))
+            .take_while(|date| *date <= last_day)
+            .flat_map(|date| self.slots_on(location, date, booked))
+            .filter(|slot| slot.starts_at >= earliest && slot.is_available())
+            .collect()
+    }
+
+    /// Validate a checkout pickup selection and return the chosen slot
+    pub fn validate_selection(
+        &self,
+        location: &PickupLocation,
+        selection: &PickupSelection,
+        booked: &HashMap<DateTime<Utc>, i32>,
+        now: DateTime<Utc>,
+    ) -> Result<PickupSlot, PickupError> {
+        if !location.is_enabled || location.id != selection.location_id {
+            return Err(PickupError::LocationUnavailable);
+        }
+
+        let date = selection.slot_start.with_timezone(&location.timezone).date_naive();
+        if !location.is_open_on(date) {
+            return Err(PickupError::LocationClosed(date));
+        }
+
+        let slot = self.slots_on(location, date, booked)
+            .into_iter()
+            .find(|s| s.starts_at == selection.slot_start)
+            .ok_or(PickupError::SlotNotFound)?;
+
+        let earliest = now + Duration::minutes(location.lead_time_minutes.max(0) as i64);
+        let last_day = self.local_today(location, now) + Duration::days(location.max_days_ahead.max(0) as i64);
+        if slot.starts_at < earliest || date > last_day {
+            return Err(PickupError::SlotNotFound);
+        }
+
+        if !slot.is_available() {
+            return Err(PickupError::SlotFull);
+        }
+
+        Ok(slot)
+    }
+
+    /// Pickup details to store on the order
+    pub fn order_pickup(&self, location: &PickupLocation, slot: &PickupSlot) -> OrderPickup {
+        OrderPickup {
+            location_id: location.id,
+            location_name: location.name.clone(),
+            slot_start: slot.starts_at,
+            slot_end: slot.ends_at,
+            ready_at: None,
+            picked_up_at: None,
+        }
+    }
+
+    /// Mark a pickup order as ready and build the customer notification
+    pub fn mark_ready(
+        &self,
+        order: &mut Order,
+        location: &PickupLocation,
+    ) -> Result<Option<SendEmailRequest>, PickupError> {
+        if order.status != OrderStatus::Processing {
+            return Err(PickupError::InvalidOrderStatus(order.status));
+        }
+
+        let now = Utc::now();
+        let pickup = order.pickup.as_mut().ok_or(PickupError::NotAPickupOrder)?;
+        pickup.ready_at = Some(now);
+        order.status = OrderStatus::ReadyForPickup;
+        order.updated_at = Some(now);
+
+        Ok(self.ready_email(order, location))
+    }
+
+    /// Record that the customer collected the order
+    pub fn mark_picked_up(&self, order: &mut Order) -> Result<(), PickupError> {
+        if !matches!(order.status, OrderStatus::ReadyForPickup | OrderStatus::Processing) {
+            return Err(PickupError::InvalidOrderStatus(order.status));
+        }
+
+        let now = Utc::now();
+        let pickup = order.pickup.as_mut().ok_or(PickupError::NotAPickupOrder)?;
+        pickup.picked_up_at = Some(now);
+        order.status = OrderStatus::Completed;
+        order.date_completed = Some(now);
+        order.updated_at = Some(now);
+        Ok(())
+    }
+
+    /// Build the ready-for-pickup email
+    pub fn ready_email(&self, order: &Order, location: &PickupLocation) -> Option<SendEmailRequest> {
+        let pickup = order.pickup.as_ref()?;
+        if order.billing.email.is_empty() {
+            return None;
+        }
+
+        let timezone = location.timezone;
+        let mut variables = HashMap::new();
+        variables.insert("store_name".to_string(), serde_json::json!(self.settings.general.store_name));
+        variables.insert("customer_name".to_string(), serde_json::json!(order.get_customer_name()));
+        variables.insert("order_number".to_string(), serde_json::json!(order.order_number));
+        variables.insert("location_name".to_string(), serde_json::json!(location.name));
+        variables.insert("location_address".to_string(), serde_json::json!(location.address.get_formatted()));
+        variables.insert("pickup_instructions".to_string(), serde_json::json!(location.instructions));
+        variables.insert("order_total".to_string(), serde_json::json!(order.format_amount(order.total)));
+        variables.insert("slot_date".to_string(), serde_json::json!(
+            pickup.slot_start.with_timezone(&timezone).format("%Y-%m-%d").to_string()
+        ));
+        variables.insert("slot_time".to_string(), serde_json::json!(format!(
+            "{} - {}",
+            pickup.slot_start.with_timezone(&timezone).format("%H:%M"),
+            pickup.slot_end.with_timezone(&timezone).format("%H:%M")
+        )));
+
+        Some(SendEmailRequest {
+            template_id: None,
+            template_key: Some(Self::READY_EMAIL_TEMPLATE.to_string()),
+            to_email: order.billing.email.clone(),
+            to_name: Some(order.get_customer_name()),
+            variables,
+            cc: None,
+            bcc: None,
+            attachments: None,
+            schedule_at: None,
+            subject_override: None,
+            from_name_override: None,
+            from_email_override: None,
+            reply_to_override: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveTime, Weekday};
+    use crate::models::customer::Address;
+    use crate::models::pickup::{OpeningHours, PickupClosure};
+
+    fn location() -> PickupLocation {
+        let hours = |weekday| OpeningHours {
+            weekday,
+            opens: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            closes: NaiveTime::from_hms_opt(12, 0, 0).unwrap(),
+        };
+
+        PickupLocation {
+            id: Uuid::now_v7(),
+            site_id: None,
+            name: "Downtown".to_string(),
+            address: Address::default(),
+            instructions: None,
+            phone: None,
+            email: None,
+            opening_hours: vec![hours(Weekday::Mon), hours(Weekday::Tue)],
+            closures: vec![PickupClosure {
+                start_date: NaiveDate::from_ymd_opt(2024, 12, 24).unwrap(),
+                end_date: NaiveDate::from_ymd_opt(2024, 12, 26).unwrap(),
+                reason: Some("Holidays".to_string()),
+            }],
+            timezone: chrono_tz::Europe::Berlin,
+            slot_duration_minutes: 60,
+            slot_capacity: 2,
+            lead_time_minutes: 120,
+            max_days_ahead: 14,
+            cost: None,
+            is_enabled: true,
+            sort_order: 0,
+            created_at: Utc::now(),
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn test_slots_follow_opening_hours_and_closures() {
+        let service = PickupService::new(RustCommerceSettings::default());
+        let location = location();
+        let booked = HashMap::new();
+
+        // Monday 2024-12-16: 09:00-12:00 local = three one-hour slots, first at 08:00 UTC
+        let monday = NaiveDate::from_ymd_opt(2024, 12, 16).unwrap();
+        let slots = service.slots_on(&location, monday, &booked);
+        assert_eq!(slots.len(), 3);
+        assert_eq!(slots[0].starts_at, Utc.with_ymd_and_hms(2024, 12, 16, 8, 0, 0).unwrap());
+
+        // Summer time moves the first slot to 07:00 UTC
+        let summer = service.slots_on(&location, NaiveDate::from_ymd_opt(2024, 7, 15).unwrap(), &booked);
+        assert_eq!(summer[0].starts_at, Utc.with_ymd_and_hms(2024, 7, 15, 7, 0, 0).unwrap());
+
+        // Wednesday has no hours, Tuesday 24th is a holiday
+        assert!(service.slots_on(&location, monday + Duration::days(2), &booked).is_empty());
+        assert!(service.slots_on(&location, NaiveDate::from_ymd_opt(2024, 12, 24).unwrap(), &booked).is_empty());
+    }
+
+    #[test]
+    fn test_available_slots_respect_lead_time_and_capacity() {
+        let service = PickupService::new(RustCommerceSettings::default());
+        let location = location();
+        let now = Utc.with_ymd_and_hms(2024, 12, 16, 7, 30, 0).unwrap();
+
+        // 08:30 local now, a two hour lead time leaves only the 11:00 slot today
+        let monday = NaiveDate::from_ymd_opt(2024, 12, 16).unwrap();
+        let today: Vec<_> = service.available_slots(&location, monday, 1, &HashMap::new(), now);
+        assert_eq!(today.len(), 1);
+
+        let mut booked = HashMap::new();
+        booked.insert(today[0].starts_at, 2);
+        assert!(service.available_slots(&location, monday, 1, &booked, now).is_empty());
+
+        let selection = PickupSelection { location_id: location.id, slot_start: today[0].starts_at };
+        assert!(matches!(
+            service.validate_selection(&location, &selection, &booked, now),
+            Err(PickupError::SlotFull)
+        ));
+    }
+}
@@ -4,18 +4,27 @@
 
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
+use crate::locale::{self, LocaleFormat};
 use crate::settings::{RustCommerceSettings, CurrencyPosition};
 use crate::models::product::{Product, ProductVariation};
 
 /// Pricing service
 pub struct PricingService {
     settings: RustCommerceSettings,
+    locale: Option<&'static LocaleFormat>,
 }
 
 impl PricingService {
     /// Create a new pricing service
     pub fn new(settings: RustCommerceSettings) -> Self {
-        Self { settings }
+        let locale = settings.general.locale.as_deref().map(locale::locale_format);
+        Self { settings, locale }
+    }
+
+    /// Format prices for a request's locale instead of the store's
+    pub fn with_locale(mut self, tag: &str) -> Self {
+        self.locale = Some(locale::locale_format(tag));
+        self
     }
 
     /// Get the effective price for a product
@@ -51,8 +60,12 @@ impl PricingService {
 
     /// Format a price for display
     pub fn format_price(&self, price: Decimal) -> String {
-        let formatted = self.format_decimal(price);
         let symbol = self.get_currency_symbol();
+        if let Some(locale) = self.locale {
+            return locale.format_currency(price, symbol, self.settings.general.currency_decimals());
+        }
+
+        let formatted = self.format_decimal(price);
 
         match self.settings.general.currency_position {
             CurrencyPosition::Left => format!("{}{}", symbol, formatted),
@@ -65,6 +78,10 @@ impl PricingService {
     /// Format a decimal number with proper separators
     pub fn format_decimal(&self, value: Decimal) -> String {
         let decimals = self.settings.general.number_of_decimals as u32;
+        if let Some(locale) = self.locale {
+            return locale.format_number(value, decimals);
+        }
+
         let rounded = value.round_dp(decimals);
 
         let (integer, decimal) = {
@@ -108,51 +125,7 @@ impl PricingService {
 
     /// Get currency symbol
     pub fn get_currency_symbol(&self) -> &str {
-        match self.settings.general.currency.as_str() {
-            "USD" => "$",
-            "EUR" => "€",
-            "GBP" => "£",
-            "JPY" => "¥",
-            "CNY" => "¥",
-            "INR" => "₹",
-            "AUD" => "A$",
-            "CAD" => "C$",
-            "CHF" => "CHF",
-            "KRW" => "₩",
-            "RUB" => "₽",
-            "BRL" => "R$",
-            "MXN" => "Mex$",
-            "PLN" => "zł",
-            "SEK" => "kr",
-            "NOK" => "kr",
-            "DKK" => "kr",
-            "HKD" => "HK$",
-            "SGD" => "S$",
-            "NZD" => "NZ$",
-            "ZAR" => "R",
-            "THB" => "฿",
-            "PHP" => "₱",
-            "IDR" => "Rp",
-            "MYR" => "RM",
-            "AED" => "د.إ",
-            "SAR" => "﷼",
-            "TRY" => "₺",
-            "ILS" => "₪",
-            "CZK" => "Kč",
-            "HUF" => "Ft",
-            "RON" => "lei",
-            "TWD" => "NT$",
-            "VND" => "₫",
-            "CLP" => "CLP$",
-            "COP" => "COP$",
-            "ARS" => "ARS$",
-            "PEN" => "S/",
-            "EGP" => "E£",
-            "NGN" => "₦",
-            "PKR" => "₨",
-            "BDT" => "৳",
-            _ => "$", // Default to USD
-        }
+        crate::currency::currency_symbol(&self.settings.general.currency)
     }
 
     /// Calculate sale percentage
@@ -241,6 +214,24 @@ mod tests {
         assert_eq!(service.format_price(dec!(0)), "$0.00");
     }
 
+    #[test]
+    fn test_format_price_for_locale() {
+        let mut settings = RustCommerceSettings::default();
+        settings.general.currency = "INR".to_string();
+        settings.general.locale = Some("en-IN".to_string());
+        let service = PricingService::new(settings);
+        assert_eq!(service.format_price(dec!(1234567)), "₹12,34,567.00");
+
+        let mut settings = RustCommerceSettings::default();
+        settings.general.currency = "JPY".to_string();
+        let service = PricingService::new(settings).with_locale("ja-JP");
+        assert_eq!(service.format_price(dec!(1234.5)), "¥1,235");
+
+        let service = PricingService::new(RustCommerceSettings::default()).with_locale("de-DE");
+        assert_eq!(service.format_price(dec!(-1234.5)), "-1.234,50\u{a0}$");
+        assert_eq!(service.format_price_range(dec!(5), dec!(10)), "5,00\u{a0}$ – 10,00\u{a0}$");
+    }
+
     #[test]
     fn test_sale_percentage() {
         let settings = RustCommerceSettings::default();
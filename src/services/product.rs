@@ -109,6 +109,7 @@ impl ProductService {
             download_expiry: None,
             tax_status: crate::models::product::TaxStatus::Taxable,
             tax_class: None,
+            tax_code: None,
             low_stock_amount: None,
             created_at: Utc::now(),
             updated_at: None,
@@ -1,16 +1,20 @@
 //! Report Service
 //!
-//! Handles sales reports, analytics, and dashboard statistics.
+//! Handles sales reports, tax reports, analytics, and dashboard statistics.
 
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use uuid::Uuid;
-use chrono::{DateTime, Utc, Duration, Datelike};
+use chrono::{DateTime, NaiveDate, Utc, Duration, Datelike};
 use std::collections::HashMap;
 
-use crate::models::order::{Order, OrderStatus};
+use crate::csv::csv_line;
+use crate::models::order::{Order, OrderStatus, OrderTaxLine};
+use crate::models::tax::{OrderVat, VatTreatment, TaxExemptionType, TaxExemptionReportFilter, TaxRate};
 use crate::models::product::Product;
 use crate::models::customer::Customer;
+#[cfg(feature = "subscriptions")]
+use crate::models::subscription::{RenewalOrder, RenewalStatus};
 use crate::settings::RustCommerceSettings;
 
 /// Report service
@@ -32,6 +36,33 @@ pub enum DateRange {
     Custom(DateTime<Utc>, DateTime<Utc>),
 }
 
+impl DateRange {
+    /// Parse a report period ("today", "week", "last_month", "year", ...),
+    /// or a custom range from `YYYY-MM-DD` dates
+    pub fn from_filter(period: Option<&str>, date_min: Option<&str>, date_max: Option<&str>) -> Option<Self> {
+        if let (Some(min), Some(max)) = (date_min, date_max) {
+            let start = NaiveDate::parse_from_str(min, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0)?;
+            let end = NaiveDate::parse_from_str(max, "%Y-%m-%d").ok()?.and_hms_opt(23, 59, 59)?;
+            return Some(Self::Custom(
+                DateTime::from_naive_utc_and_offset(start, Utc),
+                DateTime::from_naive_utc_and_offset(end, Utc),
+            ));
+        }
+
+        match period.unwrap_or("month") {
+            "today" => Some(Self::Today),
+            "yesterday" => Some(Self::Yesterday),
+            "week" | "this_week" => Some(Self::ThisWeek),
+            "last_week" => Some(Self::LastWeek),
+            "month" | "this_month" => Some(Self::ThisMonth),
+            "last_month" => Some(Self::LastMonth),
+            "year" | "this_year" => Some(Self::ThisYear),
+            "last_year" => Some(Self::LastYear),
+            _ => None,
+        }
+    }
+}
+
 /// Sales report
 #[derive(Debug, Clone)]
 pub struct SalesReport {
@@ -92,6 +123,119 @@ pub struct SalesByDate {
     pub refunds: Decimal,
 }
 
+/// EU One-Stop-Shop quarterly VAT return, in euros
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct OssReport {
+    /// e.g. "2026-Q3"
+    pub period: String,
+    /// Always "EUR", the currency OSS returns are filed in
+    pub currency: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    /// One line per member state and rate, sorted by member state
+    pub lines: Vec<OssReportLine>,
+    pub total_taxable_amount: Decimal,
+    pub total_vat_amount: Decimal,
+    pub order_count: i32,
+}
+
+/// OSS report line for one member state and rate
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct OssReportLine {
+    pub member_state: String,
+    pub rate: Decimal,
+    pub taxable_amount: Decimal,
+    pub vat_amount: Decimal,
+    pub order_count: i32,
+}
+
+/// Tax collected per rate over a period
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TaxReport {
+    pub period: String,
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    /// One line per tax rate, sorted by jurisdiction
+    pub lines: Vec<TaxReportLine>,
+    pub total_tax: Decimal,
+    pub total_shipping_tax: Decimal,
+    pub total_refunded_tax: Decimal,
+    pub net_tax: Decimal,
+    pub order_count: i32,
+}
+
+/// Tax report line for one tax rate
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TaxReportLine {
+    pub rate_id: Uuid,
+    pub rate_code: String,
+    pub label: String,
+    /// "US", "US:CA" or "US:CA 90210" for postcode-specific rates
+    pub jurisdiction: String,
+    pub rate_percent: Decimal,
+    pub order_count: i32,
+    /// Product tax collected
+    pub tax_amount: Decimal,
+    /// Shipping tax collected
+    pub shipping_tax_amount: Decimal,
+    /// Product tax refunded in the period
+    pub refunded_tax: Decimal,
+    /// Shipping tax refunded in the period
+    pub refunded_shipping_tax: Decimal,
+    /// Collected less refunded
+    pub net_tax: Decimal,
+}
+
+/// Tax movements of one order within a report period
+struct TaxReportEntry<'a> {
+    tax_lines: &'a [OrderTaxLine],
+    /// The order's base-to-order currency rate
+    base_currency_rate: Option<Decimal>,
+    /// Paid within the period
+    collected: bool,
+    /// Product and shipping tax refunded within the period
+    refunded_tax: Decimal,
+    refunded_shipping_tax: Decimal,
+}
+
+/// Exempted sales for one exemption certificate
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TaxExemptionReportLine {
+    pub exemption_id: Uuid,
+    pub certificate_id: String,
+    pub exemption_type: TaxExemptionType,
+    pub customer_id: Option<Uuid>,
+    /// Jurisdictions the exemption was applied in
+    pub jurisdictions: Vec<String>,
+    pub order_count: i32,
+    /// Sales excluding tax
+    pub net_sales: Decimal,
+    /// Tax not charged
+    pub exempt_tax: Decimal,
+}
+
+/// Failed subscription renewals and the revenue dunning recovered or lost
+#[cfg(feature = "subscriptions")]
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DunningReport {
+    pub period: String,
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    /// Renewals with at least one failed payment
+    pub failed_renewals: i32,
+    /// Paid on a retry
+    pub recovered_count: i32,
+    pub recovered_revenue: Decimal,
+    /// Ended after the last retry failed
+    pub lost_count: i32,
+    pub lost_revenue: Decimal,
+    /// Still waiting for a retry
+    pub retrying_count: i32,
+    pub retrying_revenue: Decimal,
+    /// Recovered as a percentage of renewals that are no longer retrying
+    pub recovery_rate: Decimal,
+}
+
 impl ReportService {
     /// Create a new report service
     pub fn new(settings: RustCommerceSettings) -> Self {
@@ -168,6 +312,22 @@ impl ReportService {
         }
     }
 
+    /// An order amount in the base currency, rounded to the store's decimals
+    fn base_amount(&self, order: &Order, amount: Decimal) -> Decimal {
+        self.base_amount_at(order.base_currency_rate, amount)
+    }
+
+    /// Convert an amount at an order's base-to-order currency rate
+    fn base_amount_at(&self, base_currency_rate: Option<Decimal>, amount: Decimal) -> Decimal {
+        to_base_currency(base_currency_rate, amount).round_dp(self.settings.general.currency_decimals())
+    }
+
+    /// Convert an amount at an order's base-to-order currency rate, then to
+    /// euros at `eur_rate` (euros per unit of the base currency)
+    fn eur_amount_at(&self, base_currency_rate: Option<Decimal>, eur_rate: Decimal, amount: Decimal) -> Decimal {
+        crate::currency::to_eur(to_base_currency(base_currency_rate, amount), &self.settings.general.currency, eur_rate)
+    }
+
     /// Filter orders by date range
     fn filter_orders_by_date<'a>(
         &self,
@@ -198,10 +358,11 @@ impl ReportService {
         let filtered = self.filter_orders_by_date(orders, start, end);
         let paid_orders = self.get_paid_orders(&filtered);
 
-        let total_sales: Decimal = paid_orders.iter().map(|o| o.total).sum();
-        let total_shipping: Decimal = paid_orders.iter().map(|o| o.shipping_total).sum();
-        let total_tax: Decimal = paid_orders.iter().map(|o| o.total_tax).sum();
-        let total_discount: Decimal = paid_orders.iter().map(|o| o.discount_total).sum();
+        // Amounts are reported in the base currency
+        let total_sales: Decimal = paid_orders.iter().map(|o| self.base_amount(o, o.total)).sum();
+        let total_shipping: Decimal = paid_orders.iter().map(|o| self.base_amount(o, o.shipping_total)).sum();
+        let total_tax: Decimal = paid_orders.iter().map(|o| self.base_amount(o, o.total_tax)).sum();
+        let total_discount: Decimal = paid_orders.iter().map(|o| self.base_amount(o, o.discount_total)).sum();
         let total_refunds = Decimal::ZERO; // Would sum from refunds
 
         let net_sales = total_sales - total_refunds;
@@ -306,7 +467,7 @@ impl ReportService {
             for item in &order.items {
                 let entry = product_sales.entry(item.product_id).or_insert((0, Decimal::ZERO));
                 entry.0 += item.quantity;
-                entry.1 += item.total;
+                entry.1 += self.base_amount(order, item.total);
             }
         }
 
@@ -359,9 +520,9 @@ impl ReportService {
                     ))
                     .collect();
 
-                let gross_sales: Decimal = paid.iter().map(|o| o.total).sum();
-                let shipping: Decimal = paid.iter().map(|o| o.shipping_total).sum();
-                let tax: Decimal = paid.iter().map(|o| o.total_tax).sum();
+                let gross_sales: Decimal = paid.iter().map(|o| self.base_amount(o, o.total)).sum();
+                let shipping: Decimal = paid.iter().map(|o| self.base_amount(o, o.shipping_total)).sum();
+                let tax: Decimal = paid.iter().map(|o| self.base_amount(o, o.total_tax)).sum();
                 let items: i32 = paid.iter()
                     .flat_map(|o| &o.items)
                     .map(|i| i.quantity)
@@ -455,6 +616,375 @@ impl ReportService {
             out_of_stock_products: out_of_stock.iter().map(|p| p.id).collect(),
         }
     }
+
+    /// First and last day of a calendar quarter (1-4)
+    pub fn quarter_dates(year: i32, quarter: u32) -> Option<(NaiveDate, NaiveDate)> {
+        if !(1..=4).contains(&quarter) {
+            return None;
+        }
+        let start = NaiveDate::from_ymd_opt(year, (quarter - 1) * 3 + 1, 1)?;
+        let next = if quarter == 4 {
+            NaiveDate::from_ymd_opt(year + 1, 1, 1)?
+        } else {
+            NaiveDate::from_ymd_opt(year, quarter * 3 + 1, 1)?
+        };
+        Some((start, next.pred_opt()?))
+    }
+
+    /// Generate the OSS report for a quarter: paid orders taxed at
+    /// destination-country rates, by payment date. Amounts are converted to
+    /// euros at `eur_rate` (euros per unit of the base currency), normally
+    /// the ECB rate on the last day of the quarter.
+    pub fn generate_oss_report(&self, orders: &[Order], year: i32, quarter: u32, eur_rate: Decimal) -> Option<OssReport> {
+        let (start, end) = Self::quarter_dates(year, quarter)?;
+
+        let entries = orders.iter()
+            .filter(|o| o.status.is_paid())
+            .filter(|o| {
+                let paid = o.date_paid.unwrap_or(o.created_at).date_naive();
+                paid >= start && paid <= end
+            })
+            .filter_map(|o| {
+                let vat = o.vat.as_ref().filter(|v| v.treatment == VatTreatment::Destination)?;
+                Some((vat, o.tax_lines.as_deref().unwrap_or(&[]), o.base_currency_rate))
+            });
+
+        Some(self.summarize_oss(year, quarter, start, end, eur_rate, entries))
+    }
+
+    /// Generate the tax report: tax collected on orders paid in the range,
+    /// less tax refunded in the range, per tax rate
+    pub fn generate_tax_report(&self, orders: &[Order], rates: &[TaxRate], range: DateRange) -> TaxReport {
+        let (start, end) = self.get_date_range(range);
+        let in_range = |date: &DateTime<Utc>| *date >= start && *date <= end;
+
+        let entries = orders.iter()
+            .filter(|o| o.tax_lines.as_ref().is_some_and(|t| !t.is_empty()))
+            .map(|o| {
+                let shipping_item_ids: Vec<Uuid> = o.shipping_lines.iter().flatten().map(|s| s.id).collect();
+                let mut refunded_tax = Decimal::ZERO;
+                let mut refunded_shipping_tax = Decimal::ZERO;
+
+                for item in o.refunds.iter().flatten()
+                    .filter(|r| in_range(&r.created_at))
+                    .flat_map(|r| r.items.iter().flatten())
+                {
+                    if shipping_item_ids.contains(&item.order_item_id) {
+                        refunded_shipping_tax += item.refund_tax.abs();
+                    } else {
+                        refunded_tax += item.refund_tax.abs();
+                    }
+                }
+
+                TaxReportEntry {
+                    tax_lines: o.tax_lines.as_deref().unwrap_or(&[]),
+                    base_currency_rate: o.base_currency_rate,
+                    collected: (o.status.is_paid() || o.status == OrderStatus::Refunded)
+                        && o.date_paid.as_ref().is_some_and(in_range),
+                    refunded_tax: self.base_amount(o, refunded_tax),
+                    refunded_shipping_tax: self.base_amount(o, refunded_shipping_tax),
+                }
+            });
+
+        self.summarize_tax(self.format_period(range), start, end, rates, entries)
+    }
+
+    /// Tax report as CSV
+    pub fn tax_report_csv(&self, report: &TaxReport) -> String {
+        let mut csv = csv_line([
+            "Jurisdiction", "Rate code", "Label", "Rate %", "Orders", "Tax", "Shipping tax",
+            "Refunded tax", "Refunded shipping tax", "Net tax",
+        ].iter().map(|h| h.to_string()));
+
+        for line in &report.lines {
+            csv.push_str(&csv_line([
+                line.jurisdiction.clone(),
+                line.rate_code.clone(),
+                line.label.clone(),
+                format!("{:.4}", line.rate_percent),
+                line.order_count.to_string(),
+                line.tax_amount.to_string(),
+                line.shipping_tax_amount.to_string(),
+                line.refunded_tax.to_string(),
+                line.refunded_shipping_tax.to_string(),
+                line.net_tax.to_string(),
+            ].into_iter()));
+        }
+
+        csv.push_str(&csv_line([
+            "Total".to_string(),
+            String::new(),
+            String::new(),
+            String::new(),
+            report.order_count.to_string(),
+            report.total_tax.to_string(),
+            report.total_shipping_tax.to_string(),
+            report.total_refunded_tax.to_string(),
+            String::new(),
+            report.net_tax.to_string(),
+        ].into_iter()));
+
+        csv
+    }
+
+    /// Group tax lines by rate, in the base currency. Refunded tax, which
+    /// refunds record per item rather than per rate, is spread over the
+    /// order's rates in proportion to the tax each collected.
+    fn summarize_tax<'a>(
+        &self,
+        period: String,
+        start_date: DateTime<Utc>,
+        end_date: DateTime<Utc>,
+        rates: &[TaxRate],
+        entries: impl Iterator<Item = TaxReportEntry<'a>>,
+    ) -> TaxReport {
+        let decimals = self.settings.general.currency_decimals();
+        let mut lines: HashMap<Uuid, TaxReportLine> = HashMap::new();
+        let mut order_count = 0;
+
+        for entry in entries {
+            if !entry.collected && entry.refunded_tax.is_zero() && entry.refunded_shipping_tax.is_zero() {
+                continue;
+            }
+            order_count += 1;
+
+            let base = |amount: Decimal| self.base_amount_at(entry.base_currency_rate, amount);
+            let product_refunds = allocate(entry.refunded_tax, entry.tax_lines.iter().map(|t| base(t.tax_total)), decimals);
+            let shipping_refunds = allocate(entry.refunded_shipping_tax, entry.tax_lines.iter().map(|t| base(t.shipping_tax_total)), decimals);
+
+            for ((tax_line, refunded), refunded_shipping) in entry.tax_lines.iter().zip(product_refunds).zip(shipping_refunds) {
+                let line = lines.entry(tax_line.rate_id)
+                    .or_insert_with(|| TaxReportLine {
+                        rate_id: tax_line.rate_id,
+                        rate_code: tax_line.rate_code.clone(),
+                        label: tax_line.label.clone(),
+                        jurisdiction: jurisdiction(tax_line, rates),
+                        rate_percent: tax_line.rate_percent,
+                        order_count: 0,
+                        tax_amount: Decimal::ZERO,
+                        shipping_tax_amount: Decimal::ZERO,
+                        refunded_tax: Decimal::ZERO,
+                        refunded_shipping_tax: Decimal::ZERO,
+                        net_tax: Decimal::ZERO,
+                    });
+
+                if entry.collected {
+                    line.tax_amount += base(tax_line.tax_total);
+                    line.shipping_tax_amount += base(tax_line.shipping_tax_total);
+                }
+                line.refunded_tax += refunded;
+                line.refunded_shipping_tax += refunded_shipping;
+                line.order_count += 1;
+            }
+        }
+
+        let mut lines: Vec<TaxReportLine> = lines.into_values()
+            .map(|mut l| {
+                l.net_tax = l.tax_amount + l.shipping_tax_amount - l.refunded_tax - l.refunded_shipping_tax;
+                l
+            })
+            .collect();
+        lines.sort_by(|a, b| {
+            a.jurisdiction.cmp(&b.jurisdiction)
+                .then(b.rate_percent.cmp(&a.rate_percent))
+                .then(a.rate_code.cmp(&b.rate_code))
+        });
+
+        TaxReport {
+            period,
+            start_date,
+            end_date,
+            total_tax: lines.iter().map(|l| l.tax_amount).sum(),
+            total_shipping_tax: lines.iter().map(|l| l.shipping_tax_amount).sum(),
+            total_refunded_tax: lines.iter().map(|l| l.refunded_tax + l.refunded_shipping_tax).sum(),
+            net_tax: lines.iter().map(|l| l.net_tax).sum(),
+            order_count,
+            lines,
+        }
+    }
+
+    /// Exempted sales by certificate for paid orders, largest exempt tax first
+    pub fn generate_tax_exemption_report(
+        &self,
+        orders: &[Order],
+        filter: &TaxExemptionReportFilter,
+    ) -> Vec<TaxExemptionReportLine> {
+        let mut lines: HashMap<Uuid, TaxExemptionReportLine> = HashMap::new();
+
+        for order in orders.iter().filter(|o| o.status.is_paid()) {
+            let Some(ref exemption) = order.tax_exemption else {
+                continue;
+            };
+
+            let paid = order.date_paid.unwrap_or(order.created_at).date_naive();
+            if filter.date_min.is_some_and(|min| paid < min)
+                || filter.date_max.is_some_and(|max| paid > max)
+                || filter.customer_id.is_some_and(|id| order.customer_id != Some(id))
+            {
+                continue;
+            }
+
+            let line = lines.entry(exemption.exemption_id)
+                .or_insert_with(|| TaxExemptionReportLine {
+                    exemption_id: exemption.exemption_id,
+                    certificate_id: exemption.certificate_id.clone(),
+                    exemption_type: exemption.exemption_type,
+                    customer_id: order.customer_id,
+                    jurisdictions: Vec::new(),
+                    order_count: 0,
+                    net_sales: Decimal::ZERO,
+                    exempt_tax: Decimal::ZERO,
+                });
+
+            if !line.jurisdictions.contains(&exemption.jurisdiction) {
+                line.jurisdictions.push(exemption.jurisdiction.clone());
+            }
+            line.order_count += 1;
+            line.net_sales += self.base_amount(order, order.total - order.total_tax);
+            line.exempt_tax += self.base_amount(order, exemption.exempt_amount);
+        }
+
+        let mut lines: Vec<TaxExemptionReportLine> = lines.into_values().collect();
+        lines.sort_by(|a, b| b.exempt_tax.cmp(&a.exempt_tax).then(a.certificate_id.cmp(&b.certificate_id)));
+        lines
+    }
+
+    /// Dunning outcomes for renewals due in the range
+    #[cfg(feature = "subscriptions")]
+    pub fn generate_dunning_report(&self, renewals: &[RenewalOrder], range: DateRange) -> DunningReport {
+        let (start, end) = self.get_date_range(range);
+        let mut report = DunningReport {
+            period: self.format_period(range),
+            start_date: start,
+            end_date: end,
+            failed_renewals: 0,
+            recovered_count: 0,
+            recovered_revenue: Decimal::ZERO,
+            lost_count: 0,
+            lost_revenue: Decimal::ZERO,
+            retrying_count: 0,
+            retrying_revenue: Decimal::ZERO,
+            recovery_rate: Decimal::ZERO,
+        };
+
+        for renewal in renewals.iter().filter(|r| r.renewal_date >= start && r.renewal_date <= end) {
+            let (count, revenue) = match renewal.status {
+                RenewalStatus::Completed if renewal.payment_attempts > 1 => {
+                    (&mut report.recovered_count, &mut report.recovered_revenue)
+                }
+                RenewalStatus::Cancelled if renewal.payment_attempts > 0 => {
+                    (&mut report.lost_count, &mut report.lost_revenue)
+                }
+                RenewalStatus::Failed => (&mut report.retrying_count, &mut report.retrying_revenue),
+                _ => continue,
+            };
+            *count += 1;
+            *revenue += renewal.amount;
+            report.failed_renewals += 1;
+        }
+
+        let resolved = report.recovered_count + report.lost_count;
+        if resolved > 0 {
+            report.recovery_rate = (Decimal::from(report.recovered_count) * dec!(100) / Decimal::from(resolved))
+                .round_dp(2);
+        }
+        report
+    }
+
+    /// Group destination-taxed tax lines by member state and rate, in euros
+    fn summarize_oss<'a>(
+        &self,
+        year: i32,
+        quarter: u32,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        eur_rate: Decimal,
+        entries: impl Iterator<Item = (&'a OrderVat, &'a [OrderTaxLine], Option<Decimal>)>,
+    ) -> OssReport {
+        let mut lines: HashMap<(String, Decimal), OssReportLine> = HashMap::new();
+        let mut order_count = 0;
+
+        for (vat, tax_lines, base_currency_rate) in entries {
+            order_count += 1;
+            for tax_line in tax_lines {
+                let eur = |amount: Decimal| self.eur_amount_at(base_currency_rate, eur_rate, amount);
+                let vat_amount = eur(tax_line.tax_total + tax_line.shipping_tax_total);
+                let rate = tax_line.rate_percent.normalize();
+                let line = lines.entry((vat.tax_country.clone(), rate))
+                    .or_insert_with(|| OssReportLine {
+                        member_state: vat.tax_country.clone(),
+                        rate,
+                        taxable_amount: Decimal::ZERO,
+                        vat_amount: Decimal::ZERO,
+                        order_count: 0,
+                    });
+                line.vat_amount += vat_amount;
+                line.taxable_amount += eur(tax_line.taxable_total);
+                line.order_count += 1;
+            }
+        }
+
+        let mut lines: Vec<OssReportLine> = lines.into_values().collect();
+        lines.sort_by(|a, b| a.member_state.cmp(&b.member_state).then(b.rate.cmp(&a.rate)));
+
+        OssReport {
+            period: format!("{}-Q{}", year, quarter),
+            currency: "EUR".to_string(),
+            start_date,
+            end_date,
+            total_taxable_amount: lines.iter().map(|l| l.taxable_amount).sum(),
+            total_vat_amount: lines.iter().map(|l| l.vat_amount).sum(),
+            order_count,
+            lines,
+        }
+    }
+}
+
+/// Convert an amount at an order's base-to-order currency rate, unrounded
+fn to_base_currency(base_currency_rate: Option<Decimal>, amount: Decimal) -> Decimal {
+    match base_currency_rate {
+        Some(rate) if rate > Decimal::ZERO => amount / rate,
+        _ => amount,
+    }
+}
+
+/// Jurisdiction of a tax line, from its rate when still on file
+fn jurisdiction(tax_line: &OrderTaxLine, rates: &[TaxRate]) -> String {
+    let Some(rate) = rates.iter().find(|r| r.id == tax_line.rate_id) else {
+        return tax_line.rate_code.split('_').next().unwrap_or_default().to_string();
+    };
+
+    let mut jurisdiction = if rate.country.is_empty() { "*".to_string() } else { rate.country.clone() };
+    if !rate.state.is_empty() {
+        jurisdiction = format!("{}:{}", jurisdiction, rate.state);
+    }
+    if !rate.postcode.is_empty() {
+        jurisdiction = format!("{} {}", jurisdiction, rate.postcode);
+    }
+    if !rate.city.is_empty() {
+        jurisdiction = format!("{} {}", jurisdiction, rate.city);
+    }
+    jurisdiction
+}
+
+/// Split an amount in proportion to weights; the rounding remainder goes
+/// to the largest share
+fn allocate(amount: Decimal, weights: impl Iterator<Item = Decimal>, decimals: u32) -> Vec<Decimal> {
+    let weights: Vec<Decimal> = weights.collect();
+    let total_weight: Decimal = weights.iter().sum();
+    if amount.is_zero() || total_weight.is_zero() {
+        return vec![Decimal::ZERO; weights.len()];
+    }
+
+    let mut shares: Vec<Decimal> = weights.iter()
+        .map(|w| (amount * w / total_weight).round_dp(decimals))
+        .collect();
+
+    let remainder = amount - shares.iter().sum::<Decimal>();
+    if let Some(largest) = (0..weights.len()).max_by(|&a, &b| weights[a].cmp(&weights[b])) {
+        shares[largest] += remainder;
+    }
+    shares
 }
 
 /// Customer statistics
@@ -477,6 +1007,7 @@ pub struct StockReport {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::order::{OrderRefund, OrderShippingLine, RefundItem};
 
     #[test]
     fn test_percentage_change() {
@@ -510,4 +1041,271 @@ mod tests {
         assert_eq!(service.format_period(DateRange::Today), "Today");
         assert_eq!(service.format_period(DateRange::ThisMonth), "This month");
     }
+
+    fn paid_order(status: OrderStatus, paid: &str) -> Order {
+        let paid_at: DateTime<Utc> = format!("{}T12:00:00Z", paid).parse().unwrap();
+        let mut order = Order::new("RC-20260601-0001".to_string(), None, "USD");
+        order.status = status;
+        order.date_paid = Some(paid_at);
+        order.created_at = paid_at;
+        order
+    }
+
+    fn tax_line(rate_id: Uuid, rate_code: &str, rate: Decimal, taxable: Decimal, tax: Decimal, shipping_tax: Decimal) -> OrderTaxLine {
+        OrderTaxLine {
+            id: Uuid::now_v7(),
+            order_id: Uuid::nil(),
+            rate_id,
+            rate_code: rate_code.to_string(),
+            label: rate_code.to_string(),
+            compound: false,
+            rate_percent: rate,
+            tax_total: tax,
+            shipping_tax_total: shipping_tax,
+            taxable_total: taxable,
+            exempt_amount: Decimal::ZERO,
+            exemption_id: None,
+        }
+    }
+
+    #[test]
+    fn test_oss_report_groups_by_member_state_and_rate() {
+        let service = ReportService::new(RustCommerceSettings::default());
+        assert!(service.generate_oss_report(&[], 2026, 5, dec!(0.5)).is_none());
+
+        let order = |country: &str, paid: &str, lines: Vec<OrderTaxLine>, base_rate: Option<Decimal>| {
+            let mut order = paid_order(OrderStatus::Completed, paid);
+            order.vat = Some(OrderVat {
+                vat_number: None,
+                validation: None,
+                treatment: VatTreatment::Destination,
+                tax_country: country.to_string(),
+                note: None,
+            });
+            order.base_currency_rate = base_rate;
+            order.tax_lines = Some(lines);
+            order
+        };
+        let vat = |rate: Decimal, taxable: Decimal, tax: Decimal, shipping_tax: Decimal| {
+            tax_line(Uuid::nil(), "VAT", rate, taxable, tax, shipping_tax)
+        };
+
+        let mut origin = order("FR", "2026-11-01", vec![vat(dec!(20), dec!(100), dec!(20), dec!(0))], None);
+        origin.vat.as_mut().unwrap().treatment = VatTreatment::Origin;
+        let mut unpaid = order("FR", "2026-11-01", vec![vat(dec!(20), dec!(100), dec!(20), dec!(0))], None);
+        unpaid.status = OrderStatus::Pending;
+
+        let orders = vec![
+            order("FR", "2026-10-01", vec![vat(dec!(20), dec!(110), dec!(20), dec!(2)), vat(dec!(5.5), dec!(10), dec!(0.55), dec!(0))], None),
+            order("FR", "2026-12-31", vec![vat(dec!(20.00), dec!(200), dec!(40), dec!(0))], None),
+            order("IE", "2026-11-15", vec![vat(dec!(0), dec!(30), dec!(0), dec!(0))], None),
+            // Paid in another currency at 2 per unit of the base currency
+            order("IT", "2026-11-20", vec![vat(dec!(22), dec!(200), dec!(44), dec!(0))], Some(dec!(2))),
+            order("FR", "2026-09-30", vec![vat(dec!(20), dec!(100), dec!(20), dec!(0))], None),
+            origin,
+            unpaid,
+        ];
+
+        // Base currency is USD, at half a euro per dollar
+        let report = service.generate_oss_report(&orders, 2026, 4, dec!(0.5)).unwrap();
+        assert_eq!(report.period, "2026-Q4");
+        assert_eq!(report.currency, "EUR");
+        assert_eq!(report.end_date, NaiveDate::from_ymd_opt(2026, 12, 31).unwrap());
+        assert_eq!(report.order_count, 4);
+
+        let summary: Vec<(&str, Decimal, Decimal, Decimal, i32)> = report.lines.iter()
+            .map(|l| (l.member_state.as_str(), l.rate, l.taxable_amount, l.vat_amount, l.order_count))
+            .collect();
+        assert_eq!(summary, vec![
+            ("FR", dec!(20), dec!(155), dec!(31), 2),
+            ("FR", dec!(5.5), dec!(5), dec!(0.28), 1),
+            ("IE", dec!(0), dec!(15), dec!(0), 1),
+            ("IT", dec!(22), dec!(50), dec!(11), 1),
+        ]);
+        assert_eq!(report.total_taxable_amount, dec!(225));
+        assert_eq!(report.total_vat_amount, dec!(42.28));
+    }
+
+    #[test]
+    fn test_tax_report_nets_refunds_by_rate() {
+        let service = ReportService::new(RustCommerceSettings::default());
+        let state_rate = Uuid::now_v7();
+        let county_rate = Uuid::now_v7();
+        let rates = vec![TaxRate {
+            id: state_rate,
+            site_id: None,
+            country: "US".to_string(),
+            state: "CA".to_string(),
+            postcode: String::new(),
+            city: String::new(),
+            rate: dec!(6),
+            name: "CA State".to_string(),
+            priority: 1,
+            compound: false,
+            shipping: true,
+            tax_order: 0,
+            tax_class: "standard".to_string(),
+            created_at: Utc::now(),
+        }];
+
+        let refund = |refunded: &str, lines: Vec<(Uuid, Decimal)>| {
+            let refund_id = Uuid::now_v7();
+            OrderRefund {
+                id: refund_id,
+                order_id: Uuid::nil(),
+                amount: Decimal::ZERO,
+                reason: None,
+                refunded_by: None,
+                refunded_payment: true,
+                created_at: format!("{}T12:00:00Z", refunded).parse().unwrap(),
+                items: Some(lines.into_iter().map(|(order_item_id, refund_tax)| RefundItem {
+                    id: Uuid::now_v7(),
+                    refund_id,
+                    order_item_id,
+                    quantity: 1,
+                    refund_total: Decimal::ZERO,
+                    refund_tax,
+                }).collect()),
+            }
+        };
+
+        let shipping_line_id = Uuid::now_v7();
+        let mut order_1 = paid_order(OrderStatus::Completed, "2026-06-05");
+        order_1.tax_lines = Some(vec![
+            tax_line(state_rate, "US_CA State", dec!(6), Decimal::ZERO, dec!(6), dec!(0.60)),
+            tax_line(county_rate, "US_LA County", dec!(3), Decimal::ZERO, dec!(3), dec!(0.30)),
+        ]);
+        order_1.shipping_lines = Some(vec![OrderShippingLine {
+            id: shipping_line_id,
+            order_id: order_1.id,
+            method_id: "flat_rate".to_string(),
+            method_title: "Flat rate".to_string(),
+            instance_id: None,
+            total: dec!(10),
+            total_tax: dec!(0.90),
+            taxes: Vec::new(),
+            meta: serde_json::json!({}),
+        }]);
+        order_1.refunds = Some(vec![
+            refund("2026-06-20", vec![(Uuid::now_v7(), dec!(-1.00)), (shipping_line_id, dec!(-0.90))]),
+            // Refunded after the period
+            refund("2026-07-02", vec![(Uuid::now_v7(), dec!(-2.00))]),
+        ]);
+
+        // Paid before the period in another currency, refunded during it
+        let mut order_2 = paid_order(OrderStatus::Refunded, "2026-05-28");
+        order_2.base_currency_rate = Some(dec!(2));
+        order_2.tax_lines = Some(vec![tax_line(state_rate, "US_CA State", dec!(6), Decimal::ZERO, dec!(24), dec!(0))]);
+        order_2.refunds = Some(vec![refund("2026-06-03", vec![(Uuid::now_v7(), dec!(-24))])]);
+
+        let range = DateRange::Custom(
+            "2026-06-01T00:00:00Z".parse().unwrap(),
+            "2026-06-30T23:59:59Z".parse().unwrap(),
+        );
+        let report = service.generate_tax_report(&[order_1, order_2], &rates, range);
+        assert_eq!(report.order_count, 2);
+
+        let summary: Vec<(&str, Decimal, Decimal, Decimal, Decimal, Decimal)> = report.lines.iter()
+            .map(|l| (l.jurisdiction.as_str(), l.tax_amount, l.shipping_tax_amount, l.refunded_tax, l.refunded_shipping_tax, l.net_tax))
+            .collect();
+        assert_eq!(summary, vec![
+            ("US", dec!(3), dec!(0.30), dec!(0.33), dec!(0.30), dec!(2.67)),
+            ("US:CA", dec!(6), dec!(0.60), dec!(12.67), dec!(0.60), dec!(-6.67)),
+        ]);
+        assert_eq!(report.net_tax, dec!(-4.00));
+
+        let csv = service.tax_report_csv(&report);
+        assert!(csv.starts_with("Jurisdiction,Rate code,Label,Rate %,Orders,Tax,"));
+        assert!(csv.contains("US:CA,US_CA State,US_CA State,6.0000,2,6,0.60,12.67,0.60,-6.67\r\n"));
+        assert!(csv.ends_with("Total,,,,2,9,0.90,13.90,,-4.00\r\n"));
+    }
+
+    #[test]
+    fn test_tax_exemption_report_groups_by_certificate() {
+        use crate::models::tax::AppliedTaxExemption;
+
+        let service = ReportService::new(RustCommerceSettings::default());
+        let customer_id = Uuid::now_v7();
+        let exemption_id = Uuid::now_v7();
+        let order = |status: OrderStatus, paid: &str, total: Decimal, exempt: Decimal, base_rate: Option<Decimal>| {
+            let mut order = paid_order(status, paid);
+            order.customer_id = Some(customer_id);
+            order.base_currency_rate = base_rate;
+            order.total = total;
+            order.tax_exemption = Some(AppliedTaxExemption {
+                exemption_id,
+                certificate_id: "TX-123".to_string(),
+                exemption_type: TaxExemptionType::Resale,
+                jurisdiction: "US:TX".to_string(),
+                exempt_amount: exempt,
+            });
+            order
+        };
+
+        let orders = vec![
+            order(OrderStatus::Completed, "2026-06-01", dec!(100), dec!(8.25), None),
+            // Paid in another currency at 2 per base unit
+            order(OrderStatus::Processing, "2026-06-10", dec!(200), dec!(16.50), Some(dec!(2))),
+            order(OrderStatus::Pending, "2026-06-12", dec!(500), dec!(41.25), None),
+            order(OrderStatus::Completed, "2026-07-01", dec!(100), dec!(8.25), None),
+        ];
+
+        let filter = TaxExemptionReportFilter {
+            date_min: NaiveDate::from_ymd_opt(2026, 6, 1),
+            date_max: NaiveDate::from_ymd_opt(2026, 6, 30),
+            customer_id: Some(customer_id),
+        };
+        let lines = service.generate_tax_exemption_report(&orders, &filter);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].order_count, 2);
+        assert_eq!(lines[0].net_sales, dec!(200));
+        assert_eq!(lines[0].exempt_tax, dec!(16.50));
+        assert_eq!(lines[0].jurisdictions, vec!["US:TX".to_string()]);
+
+        let other_customer = TaxExemptionReportFilter { customer_id: Some(Uuid::now_v7()), ..filter };
+        assert!(service.generate_tax_exemption_report(&orders, &other_customer).is_empty());
+    }
+
+    #[test]
+    fn test_date_range_from_filter() {
+        assert!(matches!(DateRange::from_filter(None, None, None), Some(DateRange::ThisMonth)));
+        assert!(matches!(DateRange::from_filter(Some("last_year"), None, None), Some(DateRange::LastYear)));
+        assert!(DateRange::from_filter(Some("decade"), None, None).is_none());
+        assert!(matches!(
+            DateRange::from_filter(None, Some("2026-01-01"), Some("2026-03-31")),
+            Some(DateRange::Custom(..))
+        ));
+    }
+
+    #[cfg(feature = "subscriptions")]
+    #[test]
+    fn test_dunning_report_splits_recovered_and_lost() {
+        let service = ReportService::new(RustCommerceSettings::default());
+        let renewal = |status, attempts, amount| RenewalOrder {
+            id: Uuid::now_v7(),
+            subscription_id: Uuid::now_v7(),
+            order_id: Uuid::now_v7(),
+            renewal_date: Utc::now(),
+            status,
+            amount,
+            payment_attempts: attempts,
+            last_attempt: None,
+            next_retry: None,
+        };
+        let renewals = vec![
+            renewal(RenewalStatus::Completed, 1, dec!(50)),
+            renewal(RenewalStatus::Completed, 3, dec!(20)),
+            renewal(RenewalStatus::Completed, 2, dec!(20)),
+            renewal(RenewalStatus::Cancelled, 4, dec!(35)),
+            renewal(RenewalStatus::Cancelled, 0, dec!(10)),
+            renewal(RenewalStatus::Failed, 2, dec!(15)),
+        ];
+
+        let report = service.generate_dunning_report(&renewals, DateRange::Today);
+        assert_eq!(report.failed_renewals, 4);
+        assert_eq!((report.recovered_count, report.recovered_revenue), (2, dec!(40)));
+        assert_eq!((report.lost_count, report.lost_revenue), (1, dec!(35)));
+        assert_eq!((report.retrying_count, report.retrying_revenue), (1, dec!(15)));
+        assert_eq!(report.recovery_rate, dec!(66.67));
+    }
 }
@@ -13,6 +13,9 @@ use crate::models::shipping::{
     LocationType, ShippingCalcType, ShippingTaxStatus,
 };
 use crate::models::cart::Cart;
+use crate::models::membership::MemberCartDiscount;
+use crate::models::pickup::PickupLocation;
+use crate::geo;
 use crate::settings::RustCommerceSettings;
 
 /// Shipping service
@@ -90,17 +93,18 @@ impl ShippingService {
         for location in &zone.locations {
             match location.location_type {
                 LocationType::Country => {
-                    if location.location_code == destination.country {
+                    if geo::same_country(&location.location_code, &destination.country) {
                         return true;
                     }
                 }
                 LocationType::State => {
                     // Format: "US:CA" for California, US
                     let parts: Vec<&str> = location.location_code.split(':').collect();
-                    if parts.len() == 2 {
-                        if parts[0] == destination.country && parts[1] == destination.state {
-                            return true;
-                        }
+                    if parts.len() == 2
+                        && geo::same_country(parts[0], &destination.country)
+                        && geo::same_subdivision(parts[0], parts[1], &destination.state)
+                    {
+                        return true;
                     }
                 }
                 LocationType::Postcode => {
@@ -161,17 +165,7 @@ impl ShippingService {
 
     /// Check if country is in continent
     fn country_in_continent(&self, country: &str, continent: &str) -> bool {
-        let continents: HashMap<&str, Vec<&str>> = HashMap::from([
-            ("NA", vec!["US", "CA", "MX"]),
-            ("EU", vec!["GB", "DE", "FR", "IT", "ES", "NL", "BE", "AT", "CH", "PL", "SE", "NO", "DK", "FI"]),
-            ("AS", vec!["CN", "JP", "KR", "IN", "SG", "HK", "TW", "TH", "MY", "ID", "PH", "VN"]),
-            ("OC", vec!["AU", "NZ"]),
-            ("SA", vec!["BR", "AR", "CL", "CO", "PE", "EC"]),
-            ("AF", vec!["ZA", "EG", "NG", "KE", "MA"]),
-        ]);
-
-        continents.get(continent)
-            .map_or(false, |countries| countries.contains(&country))
+        geo::country_in_continent(country, continent)
     }
 
     /// Calculate shipping rates for cart
@@ -296,23 +290,25 @@ impl ShippingService {
         Decimal::ZERO
     }
 
-    /// Calculate free shipping
+    /// Calculate free shipping. Members whose plan includes free shipping
+    /// get it without meeting the method's requirements.
     fn calculate_free_shipping(
         &self,
         method: &ShippingZoneMethod,
         cart: &Cart,
     ) -> Option<CalculatedShippingRate> {
         let settings = &method.settings;
+        let member_free_shipping = MemberCartDiscount::from_meta(&cart.meta).is_some_and(|d| d.free_shipping);
 
         // Check minimum amount
-        if let Some(min_amount) = settings.min_amount {
+        if let Some(min_amount) = settings.min_amount.filter(|_| !member_free_shipping) {
             if cart.totals.subtotal < min_amount {
                 return None;
             }
         }
 
         // Check if requires coupon
-        if settings.requires_coupon.unwrap_or(false) {
+        if settings.requires_coupon.unwrap_or(false) && !member_free_shipping {
             let has_free_shipping_coupon = cart.coupons.iter()
                 .any(|c| c.free_shipping);
             if !has_free_shipping_coupon {
@@ -356,6 +352,50 @@ impl ShippingService {
         })
     }
 
+    /// Replace each local pickup rate with one rate per pickup location it offers
+    pub fn expand_pickup_rates(
+        &self,
+        rates: Vec<CalculatedShippingRate>,
+        methods: &[ShippingZoneMethod],
+        locations: &[PickupLocation],
+    ) -> Vec<CalculatedShippingRate> {
+        let mut locations: Vec<&PickupLocation> = locations.iter()
+            .filter(|l| l.is_enabled)
+            .collect();
+        locations.sort_by_key(|l| l.sort_order);
+
+        let mut expanded = Vec::with_capacity(rates.len());
+        for rate in rates {
+            let method = methods.iter().find(|m| m.id.to_string() == rate.instance_id);
+            let allowed = match method {
+                Some(m) if rate.method_id == "local_pickup" && !locations.is_empty() => &m.settings.pickup_location_ids,
+                _ => {
+                    expanded.push(rate);
+                    continue;
+                }
+            };
+
+            for location in locations.iter().filter(|l| allowed.is_empty() || allowed.contains(&l.id)) {
+                let mut meta = rate.meta.clone();
+                meta.insert("pickup_location_id".to_string(), location.id.to_string());
+                meta.insert("pickup_address".to_string(), location.address.get_formatted());
+                if let Some(ref instructions) = location.instructions {
+                    meta.insert("pickup_details".to_string(), instructions.clone());
+                }
+
+                expanded.push(CalculatedShippingRate {
+                    id: format!("local_pickup:{}:{}", rate.instance_id, location.id),
+                    label: format!("{} ({})", rate.label, location.name),
+                    cost: location.cost.unwrap_or(rate.cost),
+                    meta,
+                    ..rate.clone()
+                });
+            }
+        }
+
+        expanded
+    }
+
     /// Create shipping packages from cart
     fn create_packages(&self, cart: &Cart, destination: &ShippingDestination) -> Vec<ShippingPackage> {
         // For simplicity, create single package
@@ -456,6 +496,34 @@ mod tests {
 
         assert!(service.country_in_continent("US", "NA"));
         assert!(service.country_in_continent("GB", "EU"));
+        assert!(service.country_in_continent("PT", "EU"));
+        assert!(service.country_in_continent("IE", "EU"));
+        assert!(service.country_in_continent("GR", "EU"));
         assert!(!service.country_in_continent("US", "EU"));
     }
+
+    #[test]
+    fn test_member_free_shipping() {
+        let service = ShippingService::new(RustCommerceSettings::default());
+        let method = ShippingZoneMethod {
+            id: Uuid::now_v7(),
+            zone_id: Uuid::now_v7(),
+            method_id: "free_shipping".to_string(),
+            method_order: 0,
+            is_enabled: true,
+            settings: ShippingMethodSettings {
+                min_amount: Some(dec!(50)),
+                requires_coupon: Some(true),
+                ..Default::default()
+            },
+        };
+
+        let mut cart = Cart::new(None, None);
+        cart.totals.subtotal = dec!(20);
+        assert!(service.calculate_free_shipping(&method, &cart).is_none());
+
+        let member = MemberCartDiscount { free_shipping: true, ..Default::default() };
+        cart.meta.insert(MemberCartDiscount::META_KEY.to_string(), serde_json::to_value(member).unwrap());
+        assert_eq!(service.calculate_free_shipping(&method, &cart).unwrap().cost, Decimal::ZERO);
+    }
 }
@@ -0,0 +1,2038 @@
+//! Subscription Service
+//!
+//! Creates subscriptions when subscription products are bought, generates
+//! renewal orders as they fall due, charges the saved payment method and
+//! advances the billing schedule. Subscriptions without a saved payment
+//! method are renewed manually through a pay link. Failed renewal payments
+//! are retried on the configured dunning schedule while the subscription
+//! is on hold. Customers can switch plans, with the unused part of the
+//! current cycle credited against the new plan, and manage their own
+//! subscriptions within what each product allows.
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::models::email_templates::SendEmailRequest;
+use crate::models::order::{Order, OrderItem, OrderItemType, OrderStatus};
+use crate::models::payment::{PaymentRequest, PaymentResult};
+use crate::models::product::{Product, ProductType};
+use crate::models::subscription::{
+    DunningFinalStatus, PlanSwitch, RenewalOrder, RenewalStatus, ScheduleChange, ScheduleChangeType,
+    Subscription, SubscriptionItem, SubscriptionProductSettings, SubscriptionSelfService,
+    SubscriptionSettings, SubscriptionStatus, SwitchCalculation, SwitchRequest,
+};
+use crate::payments::gateway::PaymentGatewayRegistry;
+use crate::services::tax::{ORDER_ITEM_TAX_CLASS_META, ORDER_ITEM_TAX_CODE_META};
+use crate::settings::RustCommerceSettings;
+
+/// Subscription errors
+#[derive(Debug, Clone)]
+pub enum SubscriptionError {
+    GuestCustomer,
+    NotActive(SubscriptionStatus),
+    NotDue,
+    RetryNotDue,
+    ItemNotFound,
+    SamePlan,
+    ScheduleConflict,
+    InvalidScheduleChange,
+    UnsupportedChange(ScheduleChangeType),
+    PlanNotFound,
+    NotAllowed(&'static str),
+    InvalidPauseLength(i32),
+    NotPaused,
+    InvalidDate,
+    InvalidQuantity { min: i32, max: Option<i32> },
+    GatewayUnavailable(String),
+}
+
+impl std::fmt::Display for SubscriptionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::GuestCustomer => write!(f, "Subscriptions require a customer account"),
+            Self::NotActive(status) => write!(f, "Subscription is not active ({:?})", status),
+            Self::NotDue => write!(f, "Subscription renewal is not due yet"),
+            Self::RetryNotDue => write!(f, "Renewal payment is not due for a retry"),
+            Self::ItemNotFound => write!(f, "No subscription item can be switched to this product"),
+            Self::SamePlan => write!(f, "The subscription is already on this plan"),
+            Self::ScheduleConflict => write!(f, "Other items on this subscription bill on a different schedule"),
+            Self::InvalidScheduleChange => write!(f, "Schedule change could not be read"),
+            Self::UnsupportedChange(change) => write!(f, "Schedule change {:?} cannot be applied", change),
+            Self::PlanNotFound => write!(f, "Subscription product settings not found"),
+            Self::NotAllowed(action) => write!(f, "This subscription does not allow {}", action),
+            Self::InvalidPauseLength(max) => write!(f, "Subscriptions can be paused for 1 to {} billing cycles", max),
+            Self::NotPaused => write!(f, "Subscription is not paused"),
+            Self::InvalidDate => write!(f, "The new payment date is not allowed"),
+            Self::InvalidQuantity { min, max: Some(max) } => write!(f, "Quantity must be between {} and {}", min, max),
+            Self::InvalidQuantity { min, max: None } => write!(f, "Quantity must be at least {}", min),
+            Self::GatewayUnavailable(id) => write!(f, "Payment method {} cannot be used for subscriptions", id),
+        }
+    }
+}
+
+impl std::error::Error for SubscriptionError {}
+
+/// A subscription due for renewal, with the order it was created from
+#[derive(Debug, Clone)]
+pub struct DueRenewal {
+    pub subscription: Subscription,
+    pub parent_order: Order,
+    /// Scheduled changes not yet applied, applied once effective
+    pub pending_changes: Vec<ScheduleChange>,
+}
+
+/// A failed renewal waiting for a payment retry
+#[derive(Debug, Clone)]
+pub struct DueRetry {
+    pub subscription: Subscription,
+    pub order: Order,
+    pub renewal: RenewalOrder,
+}
+
+/// Renewals and retries for the renewal job to process
+#[derive(Debug, Clone, Default)]
+pub struct DueWork {
+    pub renewals: Vec<DueRenewal>,
+    pub retries: Vec<DueRetry>,
+}
+
+/// What happened when a renewal was processed
+#[derive(Debug, Clone)]
+pub enum RenewalOutcome {
+    /// The saved payment method was charged
+    Paid { order: Order, renewal: RenewalOrder, payment: PaymentResult },
+    /// The charge failed: the renewal has a `next_retry` and the
+    /// subscription is on hold, or retries are used up and it has ended
+    Failed { order: Order, renewal: RenewalOrder, payment: PaymentResult, email: Option<SendEmailRequest> },
+    /// Manual renewal: the customer was sent a pay link
+    AwaitingPayment { order: Order, renewal: RenewalOrder, email: Option<SendEmailRequest> },
+    /// The subscription reached its end date
+    Expired,
+}
+
+/// Result of one renewal run for a subscription
+#[derive(Debug, Clone)]
+pub struct RenewalRun {
+    pub subscription: Subscription,
+    pub result: Result<RenewalOutcome, SubscriptionError>,
+}
+
+/// Name the renewal job is scheduled under
+pub const RENEWAL_JOB: &str = "rustcommerce_subscription_renewals";
+
+/// Subscription service
+pub struct SubscriptionService {
+    settings: RustCommerceSettings,
+    subscription_settings: SubscriptionSettings,
+    gateways: Arc<PaymentGatewayRegistry>,
+}
+
+impl SubscriptionService {
+    /// Subscription meta key holding switch credit for the next renewal
+    pub const SWITCH_CREDIT_META: &'static str = "switch_credit";
+    /// Subscription meta key holding when a pause ends
+    pub const PAUSED_UNTIL_META: &'static str = "paused_until";
+    /// Subscription meta key holding the next payment date before a pause
+    pub const PAUSED_NEXT_PAYMENT_META: &'static str = "paused_next_payment";
+
+    /// Template for the manual renewal pay link email
+    pub const RENEWAL_INVOICE_TEMPLATE: &'static str = "subscription_renewal_invoice";
+    /// Template for a failed renewal payment that will be retried
+    pub const PAYMENT_FAILED_TEMPLATE: &'static str = "subscription_payment_failed";
+    /// Template for a subscription ended after its last retry failed
+    pub const PAYMENT_FINAL_FAILURE_TEMPLATE: &'static str = "subscription_payment_final_failure";
+
+    /// Create a new subscription service
+    pub fn new(
+        settings: RustCommerceSettings,
+        subscription_settings: SubscriptionSettings,
+        gateways: Arc<PaymentGatewayRegistry>,
+    ) -> Self {
+        Self { settings, subscription_settings, gateways }
+    }
+
+    /// Subscription plans for the subscription products among `products`,
+    /// read from their product meta
+    pub fn subscription_plans(
+        products: &[Product],
+        product_meta: &HashMap<Uuid, serde_json::Value>,
+    ) -> HashMap<Uuid, SubscriptionProductSettings> {
+        products.iter()
+            .filter(|p| p.product_type == ProductType::Subscription)
+            .filter_map(|p| {
+                let plan = SubscriptionProductSettings::from_meta(product_meta.get(&p.id)?)?;
+                Some((p.id, plan))
+            })
+            .collect()
+    }
+
+    /// Create subscriptions for the subscription items of a checkout order.
+    /// Items sharing a billing schedule go on the same subscription.
+    pub fn create_from_order(
+        &self,
+        order: &Order,
+        plans: &HashMap<Uuid, SubscriptionProductSettings>,
+        payment_token_id: Option<Uuid>,
+    ) -> Result<Vec<Subscription>, SubscriptionError> {
+        let items: Vec<(&OrderItem, &SubscriptionProductSettings)> = order.line_items.iter()
+            .flatten()
+            .filter_map(|item| {
+                let plan = item.variation_id.and_then(|id| plans.get(&id))
+                    .or_else(|| item.product_id.and_then(|id| plans.get(&id)))?;
+                Some((item, plan))
+            })
+            .collect();
+        if items.is_empty() {
+            return Ok(Vec::new());
+        }
+        let customer_id = order.customer_id.ok_or(SubscriptionError::GuestCustomer)?;
+
+        let mut groups: Vec<Vec<(&OrderItem, &SubscriptionProductSettings)>> = Vec::new();
+        for (item, plan) in items {
+            match groups.iter_mut().find(|g| Self::same_schedule(g[0].1, plan)) {
+                Some(group) => group.push((item, plan)),
+                None => groups.push(vec![(item, plan)]),
+            }
+        }
+
+        let now = Utc::now();
+        let mut shipping_assigned = false;
+        let subscriptions = groups.into_iter().map(|group| {
+            let recurring_shipping = !shipping_assigned && group.iter().any(|(_, p)| !p.one_time_shipping);
+            shipping_assigned |= recurring_shipping;
+            self.build_subscription(order, customer_id, &group, recurring_shipping, payment_token_id, now)
+        }).collect();
+
+        Ok(subscriptions)
+    }
+
+    fn same_schedule(a: &SubscriptionProductSettings, b: &SubscriptionProductSettings) -> bool {
+        a.period == b.period
+            && a.interval == b.interval
+            && a.length == b.length
+            && a.trial_period == b.trial_period
+            && a.trial_length == b.trial_length
+    }
+
+    fn build_subscription(
+        &self,
+        order: &Order,
+        customer_id: Uuid,
+        group: &[(&OrderItem, &SubscriptionProductSettings)],
+        recurring_shipping: bool,
+        payment_token_id: Option<Uuid>,
+        now: DateTime<Utc>,
+    ) -> Subscription {
+        let id = Uuid::now_v7();
+        let plan = group[0].1;
+        let decimals = crate::currency::minor_units(&order.currency);
+
+        let items: Vec<SubscriptionItem> = group.iter().map(|(item, plan)| {
+            let total = plan.price * Decimal::from(item.quantity);
+            // Recurring tax at the rate charged on the checkout line
+            let tax = if item.total.is_zero() {
+                Decimal::ZERO
+            } else {
+                (item.total_tax * total / item.total).round_dp(decimals)
+            };
+            SubscriptionItem {
+                id: Uuid::now_v7(),
+                subscription_id: id,
+                product_id: item.product_id.unwrap_or_default(),
+                variation_id: item.variation_id,
+                name: item.name.clone(),
+                quantity: item.quantity,
+                subtotal: total,
+                total,
+                tax,
+                meta: HashMap::new(),
+            }
+        }).collect();
+
+        let subtotal: Decimal = items.iter().map(|i| i.total).sum();
+        let (shipping_total, shipping_tax) = if recurring_shipping {
+            (order.shipping_total, order.shipping_tax)
+        } else {
+            (Decimal::ZERO, Decimal::ZERO)
+        };
+        let tax_total = items.iter().map(|i| i.tax).sum::<Decimal>() + shipping_tax;
+
+        let trial_end_date = match (plan.trial_period, plan.trial_length) {
+            (Some(period), Some(length)) if length > 0 => Some(now + period.to_duration(length)),
+            _ => None,
+        };
+        let billing_start = trial_end_date.unwrap_or(now);
+        let end_date = plan.length
+            .filter(|length| *length > 0)
+            .map(|length| billing_start + plan.period.to_duration(length));
+
+        let payment_method = order.payment_method.clone().unwrap_or_default();
+        let paid = order.date_paid.is_some()
+            || matches!(order.status, OrderStatus::Processing | OrderStatus::Completed);
+
+        Subscription {
+            id,
+            site_id: order.site_id,
+            customer_id,
+            parent_order_id: order.id,
+            status: if paid { SubscriptionStatus::Active } else { SubscriptionStatus::Pending },
+            billing_period: plan.period,
+            billing_interval: plan.interval.max(1),
+            trial_period: trial_end_date.and(plan.trial_period),
+            trial_length: trial_end_date.and(plan.trial_length),
+            total: subtotal + shipping_total + tax_total,
+            subtotal,
+            tax_total,
+            shipping_total,
+            discount_total: Decimal::ZERO,
+            currency: order.currency.clone(),
+            requires_manual_renewal: payment_token_id.is_none() || !self.supports_automatic_renewal(&payment_method),
+            payment_method,
+            payment_token_id,
+            start_date: now,
+            trial_end_date,
+            next_payment_date: Some(trial_end_date.unwrap_or_else(|| now + plan.period.to_duration(plan.interval.max(1)))),
+            end_date,
+            cancelled_date: None,
+            items,
+            billing_address: serde_json::to_value(&order.billing).unwrap_or_default(),
+            shipping_address: serde_json::to_value(&order.shipping).unwrap_or_default(),
+            meta: HashMap::new(),
+            created_at: now,
+            updated_at: None,
+        }
+    }
+
+    /// Whether a gateway can charge saved payment methods off-session
+    fn supports_automatic_renewal(&self, gateway_id: &str) -> bool {
+        self.gateways.get(gateway_id)
+            .is_some_and(|g| g.supports_feature(crate::models::payment::GatewayFeature::Subscriptions))
+    }
+
+    /// Whether a subscription should be renewed at `now`
+    pub fn is_due(subscription: &Subscription, now: DateTime<Utc>) -> bool {
+        subscription.is_active() && subscription.next_payment_date.is_some_and(|date| date <= now)
+    }
+
+    /// End a subscription whose end date has passed: active subscriptions
+    /// expire, those set to cancel at the end of term are cancelled
+    pub fn expire_if_ended(subscription: &mut Subscription, now: DateTime<Utc>) -> bool {
+        let ended = subscription.end_date.is_some_and(|end| end <= now);
+        if !ended {
+            return false;
+        }
+        match subscription.status {
+            SubscriptionStatus::Active => subscription.status = SubscriptionStatus::Expired,
+            SubscriptionStatus::PendingCancel => {
+                subscription.status = SubscriptionStatus::Cancelled;
+                subscription.cancelled_date = Some(now);
+            }
+            _ => return false,
+        }
+        subscription.next_payment_date = None;
+        subscription.updated_at = Some(now);
+        true
+    }
+
+    /// Build the renewal order for a subscription's next payment, using the
+    /// parent order for anything the subscription doesn't record
+    pub fn create_renewal_order(
+        &self,
+        subscription: &Subscription,
+        parent: &Order,
+        now: DateTime<Utc>,
+    ) -> (Order, RenewalOrder) {
+        let order_id = Uuid::now_v7();
+        let mut order = parent.clone();
+
+        order.id = order_id;
+        order.order_number = Order::generate_number(now);
+        order.status = OrderStatus::Pending;
+        order.parent_id = Some(parent.id);
+        order.currency = subscription.currency.clone();
+        order.discount_total = subscription.discount_total;
+        order.discount_tax = Decimal::ZERO;
+        order.shipping_total = subscription.shipping_total;
+        order.shipping_tax = subscription.shipping_tax();
+        order.cart_tax = subscription.tax_total - order.shipping_tax;
+        order.total_tax = subscription.tax_total;
+        let credit = Self::switch_credit(subscription).min(subscription.total);
+        order.discount_total += credit;
+        order.total = subscription.total - credit;
+        order.billing = serde_json::from_value(subscription.billing_address.clone())
+            .unwrap_or_else(|_| parent.billing.clone());
+        order.shipping = serde_json::from_value(subscription.shipping_address.clone())
+            .unwrap_or_else(|_| parent.shipping.clone());
+        order.payment_method = Some(subscription.payment_method.clone());
+        order.transaction_id = None;
+        order.fulfillment_status = Default::default();
+        order.pickup = None;
+        order.customer_note = None;
+        order.date_paid = None;
+        order.date_completed = None;
+        order.cart_hash = None;
+        order.meta = serde_json::json!({ "subscription_id": subscription.id, "subscription_renewal": true });
+        order.created_at = now;
+        order.updated_at = None;
+        order.line_items = Some(subscription.items.iter().map(|item| OrderItem {
+            id: Uuid::now_v7(),
+            order_id,
+            item_type: OrderItemType::LineItem,
+            name: item.name.clone(),
+            quantity: item.quantity,
+            subtotal: item.subtotal,
+            subtotal_tax: item.tax,
+            total: item.total,
+            total_tax: item.tax,
+            product_id: Some(item.product_id),
+            variation_id: item.variation_id,
+            sku: None,
+            meta: Self::renewal_item_meta(parent, item.product_id),
+            created_at: now,
+            product_name: None,
+            product_image: None,
+            variation_attributes: None,
+        }).collect());
+        order.shipping_lines = None;
+        order.tax_lines = None;
+        order.fee_lines = None;
+        order.coupon_lines = None;
+        order.notes = None;
+        order.refunds = None;
+        order.shipments = None;
+
+        let renewal = RenewalOrder {
+            id: Uuid::now_v7(),
+            subscription_id: subscription.id,
+            order_id,
+            renewal_date: subscription.next_payment_date.unwrap_or(now),
+            status: RenewalStatus::Pending,
+            amount: order.total,
+            payment_attempts: 0,
+            last_attempt: None,
+            next_retry: None,
+        };
+
+        (order, renewal)
+    }
+
+    /// Carry the parent order item's tax class and code to the renewal
+    fn renewal_item_meta(parent: &Order, product_id: Uuid) -> serde_json::Value {
+        let mut meta = serde_json::Map::new();
+        let parent_item = parent.line_items.iter()
+            .flatten()
+            .find(|i| i.item_type == OrderItemType::LineItem && i.product_id == Some(product_id));
+        if let Some(parent_meta) = parent_item.and_then(|i| i.meta.as_object()) {
+            for key in [ORDER_ITEM_TAX_CLASS_META, ORDER_ITEM_TAX_CODE_META] {
+                if let Some(value) = parent_meta.get(key) {
+                    meta.insert(key.to_string(), value.clone());
+                }
+            }
+        }
+        serde_json::Value::Object(meta)
+    }
+
+    /// Renew a due subscription: create the renewal order and either charge
+    /// the saved payment method or email a pay link
+    pub async fn process_renewal(
+        &self,
+        subscription: &mut Subscription,
+        parent: &Order,
+        now: DateTime<Utc>,
+    ) -> Result<RenewalOutcome, SubscriptionError> {
+        Self::resume_if_pause_ended(subscription, now);
+        if Self::expire_if_ended(subscription, now) {
+            return Ok(RenewalOutcome::Expired);
+        }
+        if !subscription.is_active() {
+            return Err(SubscriptionError::NotActive(subscription.status));
+        }
+        if !Self::is_due(subscription, now) {
+            return Err(SubscriptionError::NotDue);
+        }
+
+        let (order, renewal) = self.create_renewal_order(subscription, parent, now);
+        subscription.meta.remove(Self::SWITCH_CREDIT_META);
+
+        if subscription.requires_manual_renewal || subscription.payment_token_id.is_none() {
+            subscription.status = SubscriptionStatus::OnHold;
+            subscription.updated_at = Some(now);
+            let email = self.renewal_invoice_email(&order);
+            return Ok(RenewalOutcome::AwaitingPayment { order, renewal, email });
+        }
+
+        Ok(self.attempt_payment(subscription, order, renewal, now).await)
+    }
+
+    /// Retry a failed renewal payment once its retry date has come
+    pub async fn retry_renewal(
+        &self,
+        subscription: &mut Subscription,
+        order: Order,
+        renewal: RenewalOrder,
+        now: DateTime<Utc>,
+    ) -> Result<RenewalOutcome, SubscriptionError> {
+        if subscription.status != SubscriptionStatus::OnHold {
+            return Err(SubscriptionError::NotActive(subscription.status));
+        }
+        let retry_due = renewal.status == RenewalStatus::Failed
+            && renewal.next_retry.is_some_and(|date| date <= now);
+        if !retry_due {
+            return Err(SubscriptionError::RetryNotDue);
+        }
+
+        Ok(self.attempt_payment(subscription, order, renewal, now).await)
+    }
+
+    async fn attempt_payment(
+        &self,
+        subscription: &mut Subscription,
+        mut order: Order,
+        mut renewal: RenewalOrder,
+        now: DateTime<Utc>,
+    ) -> RenewalOutcome {
+        let payment = self.charge(subscription, &order).await;
+        renewal.payment_attempts += 1;
+        renewal.last_attempt = Some(now);
+
+        if payment.success {
+            Self::mark_paid(subscription, &mut order, &mut renewal, payment.transaction_id.clone(), now);
+            RenewalOutcome::Paid { order, renewal, payment }
+        } else {
+            let email = self.record_failure(subscription, &mut order, &mut renewal, now);
+            RenewalOutcome::Failed { order, renewal, payment, email }
+        }
+    }
+
+    /// Schedule the next retry for a failed payment, or end the subscription
+    /// when the retries are used up
+    fn record_failure(
+        &self,
+        subscription: &mut Subscription,
+        order: &mut Order,
+        renewal: &mut RenewalOrder,
+        now: DateTime<Utc>,
+    ) -> Option<SendEmailRequest> {
+        order.status = OrderStatus::Failed;
+        order.updated_at = Some(now);
+        subscription.updated_at = Some(now);
+        renewal.next_retry = self.next_retry_date(renewal);
+
+        if renewal.next_retry.is_some() {
+            renewal.status = RenewalStatus::Failed;
+            subscription.status = SubscriptionStatus::OnHold;
+            return self.payment_failed_email(subscription, order, renewal);
+        }
+
+        renewal.status = RenewalStatus::Cancelled;
+        subscription.next_payment_date = None;
+        match self.subscription_settings.final_failure_status {
+            DunningFinalStatus::Cancelled => {
+                subscription.status = SubscriptionStatus::Cancelled;
+                subscription.cancelled_date = Some(now);
+            }
+            DunningFinalStatus::Expired => {
+                subscription.status = SubscriptionStatus::Expired;
+                subscription.end_date = Some(now);
+            }
+        }
+        self.final_failure_email(subscription, order)
+    }
+
+    /// When to retry a failed renewal, from the retry days counted from the
+    /// first failed attempt
+    pub fn next_retry_date(&self, renewal: &RenewalOrder) -> Option<DateTime<Utc>> {
+        let days = &self.subscription_settings.retry_days;
+        let retry = (renewal.payment_attempts as usize).checked_sub(1)?;
+        let day = *days.get(retry)?;
+        let previous = retry.checked_sub(1).map_or(0, |i| days[i]);
+        let wait = day - previous;
+
+        Some(renewal.last_attempt? + Duration::days(wait.max(0)))
+    }
+
+    /// Charge the subscription's saved payment method for a renewal order
+    async fn charge(&self, subscription: &Subscription, order: &Order) -> PaymentResult {
+        let Some(gateway) = self.gateways.get(&subscription.payment_method).filter(|g| g.is_available()) else {
+            return PaymentResult::failure(format!(
+                "Payment gateway {} is not available",
+                subscription.payment_method
+            ));
+        };
+
+        let mut request = PaymentRequest::for_order(order, gateway.id());
+        request.payment_token = subscription.payment_token_id;
+        request.metadata.insert("subscription_id".to_string(), subscription.id.to_string());
+
+        gateway.process_payment(request).await
+            .unwrap_or_else(|e| PaymentResult::failure(e.to_string()))
+    }
+
+    /// Record a manual renewal paid through its pay link
+    pub fn complete_manual_renewal(
+        &self,
+        subscription: &mut Subscription,
+        order: &mut Order,
+        renewal: &mut RenewalOrder,
+        transaction_id: Option<String>,
+        now: DateTime<Utc>,
+    ) {
+        renewal.payment_attempts += 1;
+        renewal.last_attempt = Some(now);
+        Self::mark_paid(subscription, order, renewal, transaction_id, now);
+    }
+
+    fn mark_paid(
+        subscription: &mut Subscription,
+        order: &mut Order,
+        renewal: &mut RenewalOrder,
+        transaction_id: Option<String>,
+        now: DateTime<Utc>,
+    ) {
+        order.status = OrderStatus::Processing;
+        order.transaction_id = transaction_id;
+        order.date_paid = Some(now);
+        order.updated_at = Some(now);
+        renewal.status = RenewalStatus::Completed;
+        renewal.next_retry = None;
+        subscription.status = SubscriptionStatus::Active;
+        Self::advance_schedule(subscription, now);
+    }
+
+    /// Move the next payment date on by one billing cycle. Cycles missed
+    /// while renewals weren't running are not billed retroactively.
+    pub fn advance_schedule(subscription: &mut Subscription, now: DateTime<Utc>) {
+        let cycle = subscription.billing_period.to_duration(subscription.billing_interval);
+        let mut next = subscription.calculate_next_payment();
+        if next <= now {
+            next = now + cycle;
+        }
+
+        subscription.next_payment_date = match subscription.end_date {
+            Some(end) if next >= end => None,
+            _ => Some(next),
+        };
+        subscription.updated_at = Some(now);
+    }
+
+    /// Credit from plan switches waiting to be taken off the next renewal
+    pub fn switch_credit(subscription: &Subscription) -> Decimal {
+        subscription.meta.get(Self::SWITCH_CREDIT_META)
+            .and_then(|credit| credit.parse().ok())
+            .unwrap_or(Decimal::ZERO)
+    }
+
+    /// The item a switch applies to: the item for the same product when
+    /// switching variations, otherwise the subscription's only item
+    fn switch_item<'a>(
+        subscription: &'a Subscription,
+        request: &SwitchRequest,
+    ) -> Result<&'a SubscriptionItem, SubscriptionError> {
+        let item = subscription.items.iter()
+            .find(|i| i.product_id == request.new_product_id)
+            .or(match subscription.items.as_slice() {
+                [only] => Some(only),
+                _ => None,
+            })
+            .ok_or(SubscriptionError::ItemNotFound)?;
+
+        if item.product_id == request.new_product_id && item.variation_id == request.new_variation_id {
+            return Err(SubscriptionError::SamePlan);
+        }
+        Ok(item)
+    }
+
+    /// Work out a plan switch without changing the subscription
+    pub fn calculate_switch(
+        &self,
+        subscription: &Subscription,
+        request: &SwitchRequest,
+        current_plan: &SubscriptionProductSettings,
+        new_plan: &SubscriptionProductSettings,
+        now: DateTime<Utc>,
+    ) -> Result<SwitchCalculation, SubscriptionError> {
+        self.switch_terms(subscription, request, current_plan, new_plan, now)
+            .map(|(calculation, _)| calculation)
+    }
+
+    /// Switch terms, plus any credit left over for the next renewal
+    fn switch_terms(
+        &self,
+        subscription: &Subscription,
+        request: &SwitchRequest,
+        current_plan: &SubscriptionProductSettings,
+        new_plan: &SubscriptionProductSettings,
+        now: DateTime<Utc>,
+    ) -> Result<(SwitchCalculation, Decimal), SubscriptionError> {
+        if !subscription.is_active() {
+            return Err(SubscriptionError::NotActive(subscription.status));
+        }
+        let item = Self::switch_item(subscription, request)?;
+        Self::check_schedule(subscription, new_plan)?;
+        let next_payment = subscription.next_payment_date.unwrap_or(now);
+        let decimals = crate::currency::minor_units(&subscription.currency);
+
+        // Item amounts including tax, at the rate already charged on the item
+        let tax_rate = if item.total.is_zero() { Decimal::ZERO } else { item.tax / item.total };
+        let gross = |net: Decimal| (net * (Decimal::ONE + tax_rate)).round_dp(decimals);
+        let current_amount = item.total + item.tax;
+        let new_amount = gross(new_plan.price * Decimal::from(item.quantity));
+        let new_recurring_amount = subscription.total - current_amount + new_amount;
+
+        // Sign-up fees: only the difference is charged when moving to a
+        // plan with a higher fee
+        let sign_up_fee = gross(
+            (new_plan.sign_up_fee.unwrap_or_default() - current_plan.sign_up_fee.unwrap_or_default())
+                .max(Decimal::ZERO),
+        );
+
+        let in_trial = subscription.trial_end_date.is_some_and(|end| end > now);
+        if !request.effective_immediately || in_trial {
+            // Nothing of the current cycle has been paid for during a trial;
+            // scheduled switches take effect on the next renewal
+            return Ok((SwitchCalculation {
+                credit_amount: Decimal::ZERO,
+                new_recurring_amount,
+                amount_due_now: sign_up_fee,
+                next_payment_date: next_payment,
+            }, Decimal::ZERO));
+        }
+
+        let cycle = subscription.billing_period.to_duration(subscription.billing_interval);
+        let remaining = Decimal::from((next_payment - now).num_seconds().clamp(0, cycle.num_seconds()))
+            / Decimal::from(cycle.num_seconds().max(1));
+        let credit_amount = if request.prorate {
+            (current_amount * remaining).round_dp(decimals)
+        } else {
+            Decimal::ZERO
+        };
+
+        // Same schedule: pay the new plan for what is left of the cycle.
+        // New schedule: a new cycle of the new plan starts now.
+        let same_schedule = new_plan.period == subscription.billing_period
+            && new_plan.interval.max(1) == subscription.billing_interval;
+        let (charge, next_payment_date) = if same_schedule {
+            let charge = if request.prorate { (new_amount * remaining).round_dp(decimals) } else { Decimal::ZERO };
+            (charge, next_payment)
+        } else {
+            (new_amount, now + new_plan.period.to_duration(new_plan.interval.max(1)))
+        };
+
+        Ok((SwitchCalculation {
+            credit_amount,
+            new_recurring_amount,
+            amount_due_now: (charge - credit_amount).max(Decimal::ZERO) + sign_up_fee,
+            next_payment_date,
+        }, (credit_amount - charge).max(Decimal::ZERO)))
+    }
+
+    /// Switch a subscription item to a new plan, now or at the next renewal.
+    /// Immediate switches are applied straight away and `amount_due_now`
+    /// should be charged; scheduled switches are applied by the renewal
+    /// worker from the returned change.
+    pub fn switch_plan(
+        &self,
+        subscription: &mut Subscription,
+        request: &SwitchRequest,
+        current_plan: &SubscriptionProductSettings,
+        new_plan: &SubscriptionProductSettings,
+        new_name: &str,
+        now: DateTime<Utc>,
+    ) -> Result<(SwitchCalculation, ScheduleChange), SubscriptionError> {
+        let (calculation, carried_credit) = self.switch_terms(subscription, request, current_plan, new_plan, now)?;
+        let item = Self::switch_item(subscription, request)?;
+
+        let switch = PlanSwitch {
+            item_id: item.id,
+            product_id: request.new_product_id,
+            variation_id: request.new_variation_id,
+            name: new_name.to_string(),
+            plan: new_plan.clone(),
+        };
+        let change = ScheduleChange {
+            id: Uuid::now_v7(),
+            subscription_id: subscription.id,
+            change_type: ScheduleChangeType::PlanChange,
+            effective_date: if request.effective_immediately { now } else { calculation.next_payment_date },
+            old_value: serde_json::json!({
+                "item": item,
+                "billing_period": subscription.billing_period,
+                "billing_interval": subscription.billing_interval,
+            }),
+            new_value: serde_json::to_value(&switch).unwrap_or_default(),
+            created_at: now,
+        };
+
+        if request.effective_immediately {
+            Self::apply_plan_switch(subscription, &switch, now);
+            subscription.next_payment_date = Some(calculation.next_payment_date);
+            if carried_credit > Decimal::ZERO {
+                let credit = Self::switch_credit(subscription) + carried_credit;
+                subscription.meta.insert(Self::SWITCH_CREDIT_META.to_string(), credit.to_string());
+            }
+        }
+
+        Ok((calculation, change))
+    }
+
+    /// Apply a scheduled change that has become effective
+    pub fn apply_schedule_change(
+        &self,
+        subscription: &mut Subscription,
+        change: &ScheduleChange,
+        now: DateTime<Utc>,
+    ) -> Result<(), SubscriptionError> {
+        match change.change_type {
+            ScheduleChangeType::PlanChange => {
+                let switch: PlanSwitch = serde_json::from_value(change.new_value.clone())
+                    .map_err(|_| SubscriptionError::InvalidScheduleChange)?;
+                if !subscription.items.iter().any(|i| i.id == switch.item_id) {
+                    return Err(SubscriptionError::ItemNotFound);
+                }
+                Self::check_schedule(subscription, &switch.plan)?;
+                Self::apply_plan_switch(subscription, &switch, now);
+                Ok(())
+            }
+            other => Err(SubscriptionError::UnsupportedChange(other)),
+        }
+    }
+
+    /// The billing schedule belongs to the whole subscription, so only a
+    /// single-item subscription can move to a plan with another schedule
+    fn check_schedule(subscription: &Subscription, plan: &SubscriptionProductSettings) -> Result<(), SubscriptionError> {
+        let same_schedule = plan.period == subscription.billing_period
+            && plan.interval.max(1) == subscription.billing_interval;
+        if same_schedule || subscription.items.len() <= 1 {
+            Ok(())
+        } else {
+            Err(SubscriptionError::ScheduleConflict)
+        }
+    }
+
+    /// Put an item on a new plan and recalculate the subscription totals
+    fn apply_plan_switch(subscription: &mut Subscription, switch: &PlanSwitch, now: DateTime<Utc>) {
+        let Some(item) = subscription.items.iter_mut().find(|i| i.id == switch.item_id) else {
+            return;
+        };
+        item.product_id = switch.product_id;
+        item.variation_id = switch.variation_id;
+        item.name = switch.name.clone();
+        let total = switch.plan.price * Decimal::from(item.quantity);
+
+        Self::reprice_item(subscription, switch.item_id, total);
+        subscription.billing_period = switch.plan.period;
+        subscription.billing_interval = switch.plan.interval.max(1);
+        subscription.updated_at = Some(now);
+    }
+
+    /// Set an item's recurring total at its current tax rate and update the
+    /// subscription totals
+    fn reprice_item(subscription: &mut Subscription, item_id: Uuid, total: Decimal) {
+        let decimals = crate::currency::minor_units(&subscription.currency);
+        let Some(item) = subscription.items.iter_mut().find(|i| i.id == item_id) else {
+            return;
+        };
+
+        let tax_rate = if item.total.is_zero() { Decimal::ZERO } else { item.tax / item.total };
+        let (old_total, old_tax) = (item.total, item.tax);
+        item.subtotal = total;
+        item.total = total;
+        item.tax = (total * tax_rate).round_dp(decimals);
+        let (new_total, new_tax) = (item.total, item.tax);
+
+        subscription.subtotal += new_total - old_total;
+        subscription.tax_total += new_tax - old_tax;
+        subscription.total += new_total + new_tax - old_total - old_tax;
+    }
+
+    /// Plan settings for the subscription's items
+    fn item_plans<'a>(
+        subscription: &Subscription,
+        plans: &'a HashMap<Uuid, SubscriptionProductSettings>,
+    ) -> Result<Vec<&'a SubscriptionProductSettings>, SubscriptionError> {
+        subscription.items.iter()
+            .map(|item| {
+                item.variation_id.and_then(|id| plans.get(&id))
+                    .or_else(|| plans.get(&item.product_id))
+                    .ok_or(SubscriptionError::PlanNotFound)
+            })
+            .collect()
+    }
+
+    /// Check that every item's product allows a customer change
+    fn require_allowed(
+        subscription: &Subscription,
+        plans: &HashMap<Uuid, SubscriptionProductSettings>,
+        action: &'static str,
+        allowed: impl Fn(&SubscriptionSelfService) -> bool,
+    ) -> Result<(), SubscriptionError> {
+        if Self::item_plans(subscription, plans)?.iter().all(|plan| allowed(&plan.self_service)) {
+            Ok(())
+        } else {
+            Err(SubscriptionError::NotAllowed(action))
+        }
+    }
+
+    fn require_active(subscription: &Subscription) -> Result<(), SubscriptionError> {
+        if subscription.is_active() {
+            Ok(())
+        } else {
+            Err(SubscriptionError::NotActive(subscription.status))
+        }
+    }
+
+    fn log_change(
+        subscription: &Subscription,
+        change_type: ScheduleChangeType,
+        effective_date: DateTime<Utc>,
+        old_value: serde_json::Value,
+        new_value: serde_json::Value,
+        now: DateTime<Utc>,
+    ) -> ScheduleChange {
+        ScheduleChange {
+            id: Uuid::now_v7(),
+            subscription_id: subscription.id,
+            change_type,
+            effective_date,
+            old_value,
+            new_value,
+            created_at: now,
+        }
+    }
+
+    fn meta_date(subscription: &Subscription, key: &str) -> Option<DateTime<Utc>> {
+        subscription.meta.get(key)
+            .and_then(|date| DateTime::parse_from_rfc3339(date).ok())
+            .map(|date| date.with_timezone(&Utc))
+    }
+
+    /// Pause billing for a number of cycles. The subscription is on hold
+    /// until the pause ends and billing picks up again.
+    pub fn pause(
+        &self,
+        subscription: &mut Subscription,
+        plans: &HashMap<Uuid, SubscriptionProductSettings>,
+        cycles: i32,
+        now: DateTime<Utc>,
+    ) -> Result<ScheduleChange, SubscriptionError> {
+        Self::require_active(subscription)?;
+        let max_cycles = Self::item_plans(subscription, plans)?.iter()
+            .map(|plan| plan.self_service.max_pause_cycles)
+            .min()
+            .unwrap_or(0);
+        if max_cycles <= 0 {
+            return Err(SubscriptionError::NotAllowed("pausing"));
+        }
+        if !(1..=max_cycles).contains(&cycles) {
+            return Err(SubscriptionError::InvalidPauseLength(max_cycles));
+        }
+
+        let next_payment = subscription.next_payment_date.unwrap_or(now);
+        let paused_until = next_payment + subscription.billing_period.to_duration(subscription.billing_interval * cycles);
+
+        subscription.status = SubscriptionStatus::OnHold;
+        subscription.next_payment_date = Some(paused_until);
+        subscription.meta.insert(Self::PAUSED_UNTIL_META.to_string(), paused_until.to_rfc3339());
+        subscription.meta.insert(Self::PAUSED_NEXT_PAYMENT_META.to_string(), next_payment.to_rfc3339());
+        subscription.updated_at = Some(now);
+
+        Ok(Self::log_change(
+            subscription,
+            ScheduleChangeType::Pause,
+            now,
+            serde_json::json!({ "next_payment_date": next_payment }),
+            serde_json::json!({ "next_payment_date": paused_until, "cycles": cycles }),
+            now,
+        ))
+    }
+
+    /// Resume a paused subscription early. Billing restarts on the payment
+    /// date the pause interrupted, or now if that has passed.
+    pub fn resume(
+        &self,
+        subscription: &mut Subscription,
+        now: DateTime<Utc>,
+    ) -> Result<ScheduleChange, SubscriptionError> {
+        if subscription.status != SubscriptionStatus::OnHold
+            || Self::meta_date(subscription, Self::PAUSED_UNTIL_META).is_none()
+        {
+            return Err(SubscriptionError::NotPaused);
+        }
+
+        let paused_until = subscription.next_payment_date;
+        let next_payment = Self::meta_date(subscription, Self::PAUSED_NEXT_PAYMENT_META)
+            .map_or(now, |date| date.max(now));
+        Self::end_pause(subscription, next_payment, now);
+
+        Ok(Self::log_change(
+            subscription,
+            ScheduleChangeType::Resume,
+            now,
+            serde_json::json!({ "next_payment_date": paused_until }),
+            serde_json::json!({ "next_payment_date": next_payment }),
+            now,
+        ))
+    }
+
+    /// Reactivate a subscription whose pause has run its course
+    pub fn resume_if_pause_ended(subscription: &mut Subscription, now: DateTime<Utc>) -> bool {
+        match Self::meta_date(subscription, Self::PAUSED_UNTIL_META) {
+            Some(until) if until <= now && subscription.status == SubscriptionStatus::OnHold => {
+                Self::end_pause(subscription, until, now);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn end_pause(subscription: &mut Subscription, next_payment: DateTime<Utc>, now: DateTime<Utc>) {
+        subscription.status = SubscriptionStatus::Active;
+        subscription.next_payment_date = Some(next_payment);
+        subscription.meta.remove(Self::PAUSED_UNTIL_META);
+        subscription.meta.remove(Self::PAUSED_NEXT_PAYMENT_META);
+        subscription.updated_at = Some(now);
+    }
+
+    /// Skip the next renewal; the one after it is billed as usual
+    pub fn skip_next(
+        &self,
+        subscription: &mut Subscription,
+        plans: &HashMap<Uuid, SubscriptionProductSettings>,
+        now: DateTime<Utc>,
+    ) -> Result<ScheduleChange, SubscriptionError> {
+        Self::require_active(subscription)?;
+        Self::require_allowed(subscription, plans, "skipping renewals", |s| s.allow_skip)?;
+
+        let skipped = subscription.next_payment_date.ok_or(SubscriptionError::InvalidDate)?;
+        let next_payment = skipped + subscription.billing_period.to_duration(subscription.billing_interval);
+        if subscription.end_date.is_some_and(|end| next_payment >= end) {
+            return Err(SubscriptionError::InvalidDate);
+        }
+
+        subscription.next_payment_date = Some(next_payment);
+        subscription.updated_at = Some(now);
+
+        Ok(Self::log_change(
+            subscription,
+            ScheduleChangeType::Skip,
+            skipped,
+            serde_json::json!({ "next_payment_date": skipped }),
+            serde_json::json!({ "next_payment_date": next_payment }),
+            now,
+        ))
+    }
+
+    /// Move the next payment date, to any time from now until one billing
+    /// cycle after the current date
+    pub fn change_payment_date(
+        &self,
+        subscription: &mut Subscription,
+        plans: &HashMap<Uuid, SubscriptionProductSettings>,
+        date: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<ScheduleChange, SubscriptionError> {
+        Self::require_active(subscription)?;
+        Self::require_allowed(subscription, plans, "changing the payment date", |s| s.allow_date_change)?;
+
+        let current = subscription.next_payment_date.ok_or(SubscriptionError::InvalidDate)?;
+        let latest = current + subscription.billing_period.to_duration(subscription.billing_interval);
+        if date <= now || date > latest || subscription.end_date.is_some_and(|end| date >= end) {
+            return Err(SubscriptionError::InvalidDate);
+        }
+
+        subscription.next_payment_date = Some(date);
+        subscription.updated_at = Some(now);
+
+        Ok(Self::log_change(
+            subscription,
+            ScheduleChangeType::DateChange,
+            now,
+            serde_json::json!({ "next_payment_date": current }),
+            serde_json::json!({ "next_payment_date": date }),
+            now,
+        ))
+    }
+
+    /// Change an item's quantity from the next renewal
+    pub fn change_quantity(
+        &self,
+        subscription: &mut Subscription,
+        plans: &HashMap<Uuid, SubscriptionProductSettings>,
+        item_id: Uuid,
+        quantity: i32,
+        now: DateTime<Utc>,
+    ) -> Result<ScheduleChange, SubscriptionError> {
+        Self::require_active(subscription)?;
+        let item = subscription.items.iter()
+            .find(|i| i.id == item_id)
+            .ok_or(SubscriptionError::ItemNotFound)?;
+        let plan = item.variation_id.and_then(|id| plans.get(&id))
+            .or_else(|| plans.get(&item.product_id))
+            .ok_or(SubscriptionError::PlanNotFound)?;
+
+        let (min, max) = (plan.self_service.min_quantity.max(1), plan.self_service.max_quantity);
+        if quantity < min || max.is_some_and(|max| quantity > max) {
+            return Err(SubscriptionError::InvalidQuantity { min, max });
+        }
+
+        let old_quantity = item.quantity;
+        let total = plan.price * Decimal::from(quantity);
+        if let Some(item) = subscription.items.iter_mut().find(|i| i.id == item_id) {
+            item.quantity = quantity;
+        }
+        Self::reprice_item(subscription, item_id, total);
+        subscription.updated_at = Some(now);
+
+        Ok(Self::log_change(
+            subscription,
+            ScheduleChangeType::QuantityChange,
+            subscription.next_payment_date.unwrap_or(now),
+            serde_json::json!({ "item_id": item_id, "quantity": old_quantity }),
+            serde_json::json!({ "item_id": item_id, "quantity": quantity }),
+            now,
+        ))
+    }
+
+    /// Charge renewals to a different saved payment method. The next
+    /// retry of a failed renewal uses the new method.
+    pub fn change_payment_method(
+        &self,
+        subscription: &mut Subscription,
+        gateway_id: &str,
+        payment_token_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<ScheduleChange, SubscriptionError> {
+        if !subscription.can_cancel() {
+            return Err(SubscriptionError::NotActive(subscription.status));
+        }
+        if !self.supports_automatic_renewal(gateway_id)
+            || !self.gateways.get(gateway_id).is_some_and(|g| g.is_available())
+        {
+            return Err(SubscriptionError::GatewayUnavailable(gateway_id.to_string()));
+        }
+
+        let old_value = serde_json::json!({
+            "payment_method": subscription.payment_method,
+            "payment_token_id": subscription.payment_token_id,
+        });
+        subscription.payment_method = gateway_id.to_string();
+        subscription.payment_token_id = Some(payment_token_id);
+        subscription.requires_manual_renewal = false;
+        subscription.updated_at = Some(now);
+
+        Ok(Self::log_change(
+            subscription,
+            ScheduleChangeType::PaymentMethodChange,
+            now,
+            old_value,
+            serde_json::json!({ "payment_method": gateway_id, "payment_token_id": payment_token_id }),
+            now,
+        ))
+    }
+
+    /// Cancel when the current paid period ends. Subscriptions with nothing
+    /// left to run out (on hold, pending, or without a next payment) are
+    /// cancelled straight away.
+    pub fn cancel_at_end_of_term(
+        &self,
+        subscription: &mut Subscription,
+        plans: &HashMap<Uuid, SubscriptionProductSettings>,
+        now: DateTime<Utc>,
+    ) -> Result<ScheduleChange, SubscriptionError> {
+        if !subscription.can_cancel() {
+            return Err(SubscriptionError::NotActive(subscription.status));
+        }
+        Self::require_allowed(subscription, plans, "cancellation", |s| s.allow_cancel)?;
+
+        let old_value = serde_json::json!({
+            "status": subscription.status,
+            "next_payment_date": subscription.next_payment_date,
+        });
+        let term_end = subscription.next_payment_date
+            .filter(|date| subscription.is_active() && *date > now);
+
+        match term_end {
+            Some(end) => {
+                subscription.status = SubscriptionStatus::PendingCancel;
+                subscription.end_date = Some(end);
+            }
+            None => {
+                subscription.status = SubscriptionStatus::Cancelled;
+                subscription.cancelled_date = Some(now);
+            }
+        }
+        subscription.next_payment_date = None;
+        subscription.meta.remove(Self::PAUSED_UNTIL_META);
+        subscription.meta.remove(Self::PAUSED_NEXT_PAYMENT_META);
+        subscription.updated_at = Some(now);
+
+        Ok(Self::log_change(
+            subscription,
+            ScheduleChangeType::Cancellation,
+            term_end.unwrap_or(now),
+            old_value,
+            serde_json::json!({ "status": subscription.status, "end_date": subscription.end_date }),
+            now,
+        ))
+    }
+
+    /// Pay link for a renewal order
+    pub fn pay_url(&self, order: &Order) -> String {
+        format!(
+            "{}/checkout/order-pay/{}",
+            self.subscription_settings.store_url.trim_end_matches('/'),
+            order.id
+        )
+    }
+
+    /// Link for the customer to update a subscription's payment method
+    pub fn update_payment_url(&self, subscription: &Subscription) -> String {
+        format!(
+            "{}/my-account/subscriptions/{}/payment-method",
+            self.subscription_settings.store_url.trim_end_matches('/'),
+            subscription.id
+        )
+    }
+
+    /// Build the manual renewal email with the order's pay link
+    pub fn renewal_invoice_email(&self, order: &Order) -> Option<SendEmailRequest> {
+        let mut variables = HashMap::new();
+        variables.insert("pay_url".to_string(), serde_json::json!(self.pay_url(order)));
+        self.order_email(Self::RENEWAL_INVOICE_TEMPLATE, order, variables)
+    }
+
+    /// Build the failed payment email with the retry date
+    pub fn payment_failed_email(
+        &self,
+        subscription: &Subscription,
+        order: &Order,
+        renewal: &RenewalOrder,
+    ) -> Option<SendEmailRequest> {
+        let mut variables = HashMap::new();
+        variables.insert("update_payment_url".to_string(), serde_json::json!(self.update_payment_url(subscription)));
+        variables.insert("attempt".to_string(), serde_json::json!(renewal.payment_attempts));
+        variables.insert("retry_date".to_string(), serde_json::json!(
+            renewal.next_retry.map(|date| date.format("%Y-%m-%d").to_string())
+        ));
+        self.order_email(Self::PAYMENT_FAILED_TEMPLATE, order, variables)
+    }
+
+    /// Build the email sent when the last retry fails
+    pub fn final_failure_email(&self, subscription: &Subscription, order: &Order) -> Option<SendEmailRequest> {
+        let mut variables = HashMap::new();
+        variables.insert("update_payment_url".to_string(), serde_json::json!(self.update_payment_url(subscription)));
+        variables.insert("subscription_status".to_string(), serde_json::json!(subscription.status));
+        self.order_email(Self::PAYMENT_FINAL_FAILURE_TEMPLATE, order, variables)
+    }
+
+    fn order_email(
+        &self,
+        template_key: &str,
+        order: &Order,
+        mut variables: HashMap<String, serde_json::Value>,
+    ) -> Option<SendEmailRequest> {
+        if order.billing.email.is_empty() {
+            return None;
+        }
+
+        variables.insert("store_name".to_string(), serde_json::json!(self.settings.general.store_name));
+        variables.insert("customer_name".to_string(), serde_json::json!(order.get_customer_name()));
+        variables.insert("order_number".to_string(), serde_json::json!(order.order_number));
+        variables.insert("order_total".to_string(), serde_json::json!(order.format_amount(order.total)));
+
+        Some(SendEmailRequest {
+            template_id: None,
+            template_key: Some(template_key.to_string()),
+            to_email: order.billing.email.clone(),
+            to_name: Some(order.get_customer_name()),
+            variables,
+            cc: None,
+            bcc: None,
+            attachments: None,
+            schedule_at: None,
+            subject_override: None,
+            from_name_override: None,
+            from_email_override: None,
+            reply_to_override: None,
+        })
+    }
+
+    /// Process every due renewal and payment retry
+    pub async fn run_due(&self, due: DueWork, now: DateTime<Utc>) -> Vec<RenewalRun> {
+        let mut runs = Vec::with_capacity(due.renewals.len() + due.retries.len());
+        for DueRenewal { mut subscription, parent_order, pending_changes } in due.renewals {
+            let applied = pending_changes.iter()
+                .filter(|change| change.effective_date <= now)
+                .try_for_each(|change| self.apply_schedule_change(&mut subscription, change, now));
+            let result = match applied {
+                Ok(()) => self.process_renewal(&mut subscription, &parent_order, now).await,
+                Err(e) => Err(e),
+            };
+            runs.push(RenewalRun { subscription, result });
+        }
+        for DueRetry { mut subscription, order, renewal } in due.retries {
+            let result = self.retry_renewal(&mut subscription, order, renewal, now).await;
+            runs.push(RenewalRun { subscription, result });
+        }
+        runs
+    }
+
+    /// How often the renewal job looks for due renewals and retries
+    pub fn renewal_interval(&self) -> std::time::Duration {
+        let minutes = self.subscription_settings.renewal_check_minutes.max(1) as u64;
+        std::time::Duration::from_secs(minutes * 60)
+    }
+
+    /// The renewal job, for scheduling every `renewal_interval`.
+    /// `load_due` returns the work due at the given time; `on_renewal`
+    /// receives each result for saving, hooks and emails.
+    pub fn renewal_job<L, F>(self: Arc<Self>, load_due: L, on_renewal: F) -> RenewalJob<L, F>
+    where
+        L: Fn(DateTime<Utc>) -> DueWork + Send + Sync + 'static,
+        F: Fn(RenewalRun) + Send + Sync + 'static,
+    {
+        RenewalJob { service: self, load_due, on_renewal }
+    }
+}
+
+/// Background job that processes due renewals and payment retries
+pub struct RenewalJob<L, F> {
+    service: Arc<SubscriptionService>,
+    load_due: L,
+    on_renewal: F,
+}
+
+#[async_trait::async_trait]
+impl<L, F> rustpress_jobs::Job for RenewalJob<L, F>
+where
+    L: Fn(DateTime<Utc>) -> DueWork + Send + Sync + 'static,
+    F: Fn(RenewalRun) + Send + Sync + 'static,
+{
+    fn name(&self) -> &str {
+        RENEWAL_JOB
+    }
+
+    async fn run(&self) -> rustpress_jobs::Result<()> {
+        let now = Utc::now();
+        for run in self.service.run_due((self.load_due)(now), now).await {
+            (self.on_renewal)(run);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::customer::Address;
+    use crate::models::payment::{GatewayFeature, RefundRequest, RefundResult};
+    use crate::models::subscription::{BillingPeriod, SubscriptionLimit};
+    use crate::payments::gateway::{GatewayError, GatewaySettingField, PaymentGateway};
+    use async_trait::async_trait;
+    use chrono::Duration;
+    use rust_decimal_macros::dec;
+
+    struct TestGateway {
+        decline: bool,
+    }
+
+    #[async_trait]
+    impl PaymentGateway for TestGateway {
+        fn id(&self) -> &str { "test" }
+        fn title(&self) -> &str { "Test" }
+        fn description(&self) -> &str { "Test gateway" }
+        fn supports(&self) -> Vec<GatewayFeature> { vec![GatewayFeature::Products, GatewayFeature::Subscriptions] }
+        fn is_available(&self) -> bool { true }
+        fn get_settings_fields(&self) -> Vec<GatewaySettingField> { Vec::new() }
+
+        async fn process_payment(&self, request: PaymentRequest) -> Result<PaymentResult, GatewayError> {
+            assert!(request.payment_token.is_some());
+            if self.decline {
+                Err(GatewayError::PaymentDeclined("insufficient funds".to_string()))
+            } else {
+                Ok(PaymentResult::success("txn_renewal".to_string()))
+            }
+        }
+
+        async fn process_refund(&self, _request: RefundRequest) -> Result<RefundResult, GatewayError> {
+            Err(GatewayError::UnsupportedFeature)
+        }
+    }
+
+    fn service(decline: bool) -> SubscriptionService {
+        let mut gateways = PaymentGatewayRegistry::new();
+        gateways.register(Arc::new(TestGateway { decline }));
+        let settings = SubscriptionSettings {
+            store_url: "https://shop.example/".to_string(),
+            ..Default::default()
+        };
+        SubscriptionService::new(RustCommerceSettings::default(), settings, Arc::new(gateways))
+    }
+
+    fn plan(price: Decimal, period: BillingPeriod) -> SubscriptionProductSettings {
+        SubscriptionProductSettings {
+            price,
+            period,
+            interval: 1,
+            length: None,
+            sign_up_fee: None,
+            trial_period: None,
+            trial_length: None,
+            one_time_shipping: false,
+            limit: SubscriptionLimit::NoLimit,
+            self_service: SubscriptionSelfService::default(),
+        }
+    }
+
+    fn item(order_id: Uuid, product_id: Uuid, total: Decimal, tax: Decimal) -> OrderItem {
+        OrderItem {
+            id: Uuid::now_v7(),
+            order_id,
+            item_type: OrderItemType::LineItem,
+            name: "Coffee Club".to_string(),
+            quantity: 1,
+            subtotal: total,
+            subtotal_tax: tax,
+            total,
+            total_tax: tax,
+            product_id: Some(product_id),
+            variation_id: None,
+            sku: None,
+            meta: serde_json::json!({}),
+            created_at: Utc::now(),
+            product_name: None,
+            product_image: None,
+            variation_attributes: None,
+        }
+    }
+
+    fn order(items: Vec<OrderItem>) -> Order {
+        let id = items.first().map_or_else(Uuid::now_v7, |i| i.order_id);
+        Order {
+            id,
+            site_id: None,
+            order_number: "RC-20240101-0001".to_string(),
+            customer_id: Some(Uuid::now_v7()),
+            customer_ip_address: None,
+            customer_user_agent: None,
+            status: OrderStatus::Processing,
+            parent_id: None,
+            currency: "USD".to_string(),
+            currency_symbol: "$".to_string(),
+            base_currency_rate: None,
+            locale: None,
+            prices_include_tax: false,
+            discount_total: Decimal::ZERO,
+            discount_tax: Decimal::ZERO,
+            shipping_total: dec!(5),
+            shipping_tax: Decimal::ZERO,
+            cart_tax: Decimal::ZERO,
+            total: dec!(27),
+            total_tax: dec!(2),
+            billing: Address {
+                first_name: "Ada".to_string(),
+                last_name: "Lovelace".to_string(),
+                email: "ada@example.com".to_string(),
+                ..Default::default()
+            },
+            shipping: Address::default(),
+            payment_method: Some("test".to_string()),
+            payment_method_title: None,
+            transaction_id: Some("txn_parent".to_string()),
+            shipping_method: None,
+            shipping_method_title: None,
+            fulfillment_status: Default::default(),
+            pickup: None,
+            vat: None,
+            tax_exemption: None,
+            customer_note: None,
+            date_paid: Some(Utc::now()),
+            date_completed: None,
+            cart_hash: None,
+            meta: serde_json::json!({}),
+            created_at: Utc::now(),
+            updated_at: None,
+            line_items: Some(items),
+            shipping_lines: None,
+            tax_lines: None,
+            fee_lines: None,
+            coupon_lines: None,
+            notes: None,
+            refunds: None,
+            shipments: None,
+        }
+    }
+
+    fn subscribed(service: &SubscriptionService, token: Option<Uuid>) -> (Subscription, Order) {
+        let product_id = Uuid::now_v7();
+        let parent = order(vec![item(Uuid::now_v7(), product_id, dec!(20), dec!(2))]);
+        let plans = HashMap::from([(product_id, plan(dec!(20), BillingPeriod::Month))]);
+        let subscription = service.create_from_order(&parent, &plans, token).unwrap().remove(0);
+        (subscription, parent)
+    }
+
+    #[test]
+    fn test_create_from_order_groups_by_schedule() {
+        let service = service(false);
+        let (monthly, yearly, plain) = (Uuid::now_v7(), Uuid::now_v7(), Uuid::now_v7());
+        let order_id = Uuid::now_v7();
+        let parent = order(vec![
+            item(order_id, monthly, dec!(20), dec!(2)),
+            item(order_id, yearly, dec!(100), dec!(10)),
+            item(order_id, plain, dec!(15), Decimal::ZERO),
+        ]);
+        let mut yearly_plan = plan(dec!(100), BillingPeriod::Year);
+        yearly_plan.one_time_shipping = true;
+        let plans = HashMap::from([
+            (monthly, plan(dec!(20), BillingPeriod::Month)),
+            (yearly, yearly_plan),
+        ]);
+
+        let subscriptions = service.create_from_order(&parent, &plans, Some(Uuid::now_v7())).unwrap();
+        assert_eq!(subscriptions.len(), 2);
+
+        let monthly_sub = &subscriptions[0];
+        assert_eq!(monthly_sub.status, SubscriptionStatus::Active);
+        assert_eq!(monthly_sub.items.len(), 1);
+        assert_eq!(monthly_sub.total, dec!(27));
+        assert!(!monthly_sub.requires_manual_renewal);
+
+        // Shipping is only billed once, on the first recurring subscription
+        assert_eq!(subscriptions[1].shipping_total, Decimal::ZERO);
+        assert_eq!(subscriptions[1].total, dec!(110));
+    }
+
+    #[test]
+    fn test_trial_and_length_set_schedule() {
+        let service = service(false);
+        let product_id = Uuid::now_v7();
+        let parent = order(vec![item(Uuid::now_v7(), product_id, Decimal::ZERO, Decimal::ZERO)]);
+        let mut trial_plan = plan(dec!(20), BillingPeriod::Month);
+        trial_plan.trial_period = Some(BillingPeriod::Week);
+        trial_plan.trial_length = Some(2);
+        trial_plan.length = Some(6);
+        let plans = HashMap::from([(product_id, trial_plan)]);
+
+        let subscription = service.create_from_order(&parent, &plans, None).unwrap().remove(0);
+        let trial_end = subscription.trial_end_date.unwrap();
+        assert_eq!(trial_end - subscription.start_date, Duration::weeks(2));
+        assert_eq!(subscription.next_payment_date, Some(trial_end));
+        assert_eq!(subscription.end_date, Some(trial_end + Duration::days(180)));
+        assert!(subscription.requires_manual_renewal);
+    }
+
+    #[test]
+    fn test_guest_orders_cannot_subscribe() {
+        let product_id = Uuid::now_v7();
+        let mut parent = order(vec![item(Uuid::now_v7(), product_id, dec!(20), dec!(2))]);
+        parent.customer_id = None;
+        let plans = HashMap::from([(product_id, plan(dec!(20), BillingPeriod::Month))]);
+
+        assert!(matches!(
+            service(false).create_from_order(&parent, &plans, None),
+            Err(SubscriptionError::GuestCustomer)
+        ));
+    }
+
+    #[test]
+    fn test_renewal_order_keeps_shipping_tax() {
+        let service = service(false);
+        let product_id = Uuid::now_v7();
+        let mut parent = order(vec![item(Uuid::now_v7(), product_id, dec!(20), dec!(2))]);
+        parent.shipping_tax = dec!(0.50);
+        let plans = HashMap::from([(product_id, plan(dec!(20), BillingPeriod::Month))]);
+        let subscription = service.create_from_order(&parent, &plans, None).unwrap().remove(0);
+        assert_eq!(subscription.tax_total, dec!(2.50));
+
+        let (order, _) = service.create_renewal_order(&subscription, &parent, subscription.next_payment_date.unwrap());
+        assert_eq!(order.cart_tax, dec!(2));
+        assert_eq!(order.shipping_tax, dec!(0.50));
+        assert_eq!(order.total_tax, dec!(2.50));
+    }
+
+    #[tokio::test]
+    async fn test_automatic_renewal_charges_saved_method() {
+        let service = service(false);
+        let (mut subscription, parent) = subscribed(&service, Some(Uuid::now_v7()));
+        let due = subscription.next_payment_date.unwrap();
+
+        assert!(matches!(
+            service.process_renewal(&mut subscription, &parent, due - Duration::hours(1)).await,
+            Err(SubscriptionError::NotDue)
+        ));
+
+        match service.process_renewal(&mut subscription, &parent, due).await.unwrap() {
+            RenewalOutcome::Paid { order, renewal, .. } => {
+                assert_eq!(order.parent_id, Some(parent.id));
+                assert_eq!(order.status, OrderStatus::Processing);
+                assert_eq!(order.total, dec!(27));
+                assert_eq!(order.transaction_id.as_deref(), Some("txn_renewal"));
+                assert_eq!(renewal.status, RenewalStatus::Completed);
+                assert_eq!(renewal.renewal_date, due);
+            }
+            other => panic!("unexpected outcome: {:?}", other),
+        }
+        assert_eq!(subscription.status, SubscriptionStatus::Active);
+        assert_eq!(subscription.next_payment_date, Some(due + Duration::days(30)));
+    }
+
+    #[tokio::test]
+    async fn test_renewal_job_reports_each_run() {
+        use rustpress_jobs::Job;
+
+        let service = Arc::new(service(false));
+        let (mut subscription, parent) = subscribed(&service, Some(Uuid::now_v7()));
+        subscription.next_payment_date = Some(Utc::now() - Duration::hours(1));
+        let due = DueRenewal { subscription, parent_order: parent, pending_changes: Vec::new() };
+
+        let runs = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let saved = runs.clone();
+        let job = service.clone().renewal_job(
+            move |_| DueWork { renewals: vec![due.clone()], ..Default::default() },
+            move |run| saved.lock().unwrap().push(run),
+        );
+        assert_eq!(job.name(), RENEWAL_JOB);
+        assert_eq!(service.renewal_interval(), std::time::Duration::from_secs(15 * 60));
+
+        job.run().await.unwrap();
+        let runs = runs.lock().unwrap();
+        assert_eq!(runs.len(), 1);
+        assert!(matches!(runs[0].result, Ok(RenewalOutcome::Paid { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_declined_renewal_puts_subscription_on_hold() {
+        let service = service(true);
+        let (mut subscription, parent) = subscribed(&service, Some(Uuid::now_v7()));
+        let due = subscription.next_payment_date.unwrap();
+
+        match service.process_renewal(&mut subscription, &parent, due).await.unwrap() {
+            RenewalOutcome::Failed { order, renewal, payment, email } => {
+                assert_eq!(order.status, OrderStatus::Failed);
+                assert_eq!(renewal.status, RenewalStatus::Failed);
+                assert_eq!(renewal.payment_attempts, 1);
+                assert_eq!(renewal.next_retry, Some(due + Duration::days(1)));
+                assert!(!payment.success);
+                assert_eq!(email.unwrap().template_key.as_deref(), Some(SubscriptionService::PAYMENT_FAILED_TEMPLATE));
+            }
+            other => panic!("unexpected outcome: {:?}", other),
+        }
+        assert_eq!(subscription.status, SubscriptionStatus::OnHold);
+        assert_eq!(subscription.next_payment_date, Some(due));
+    }
+
+    #[tokio::test]
+    async fn test_manual_renewal_emails_pay_link() {
+        let service = service(false);
+        let (mut subscription, parent) = subscribed(&service, None);
+        let due = subscription.next_payment_date.unwrap();
+
+        let RenewalOutcome::AwaitingPayment { mut order, mut renewal, email } =
+            service.process_renewal(&mut subscription, &parent, due).await.unwrap()
+        else {
+            panic!("expected a manual renewal");
+        };
+        let email = email.unwrap();
+        assert_eq!(email.to_email, "ada@example.com");
+        assert_eq!(
+            email.variables["pay_url"],
+            serde_json::json!(format!("https://shop.example/checkout/order-pay/{}", order.id))
+        );
+        assert_eq!(subscription.status, SubscriptionStatus::OnHold);
+
+        service.complete_manual_renewal(&mut subscription, &mut order, &mut renewal, None, due);
+        assert_eq!(subscription.status, SubscriptionStatus::Active);
+        assert_eq!(renewal.status, RenewalStatus::Completed);
+        assert_eq!(subscription.next_payment_date, Some(due + Duration::days(30)));
+    }
+
+    #[test]
+    fn test_schedule_stops_at_end_date() {
+        let service = service(false);
+        let (mut subscription, _) = subscribed(&service, None);
+        let due = subscription.next_payment_date.unwrap();
+        subscription.end_date = Some(due + Duration::days(10));
+
+        SubscriptionService::advance_schedule(&mut subscription, due);
+        assert_eq!(subscription.next_payment_date, None);
+
+        assert!(SubscriptionService::expire_if_ended(&mut subscription, due + Duration::days(10)));
+        assert_eq!(subscription.status, SubscriptionStatus::Expired);
+    }
+
+    #[test]
+    fn test_missed_cycles_are_not_backbilled() {
+        let service = service(false);
+        let (mut subscription, _) = subscribed(&service, None);
+        let late = subscription.next_payment_date.unwrap() + Duration::days(75);
+
+        SubscriptionService::advance_schedule(&mut subscription, late);
+        assert_eq!(subscription.next_payment_date, Some(late + Duration::days(30)));
+    }
+
+    #[tokio::test]
+    async fn test_retries_follow_schedule_then_cancel() {
+        let service = service(true);
+        let (mut subscription, parent) = subscribed(&service, Some(Uuid::now_v7()));
+        let due = subscription.next_payment_date.unwrap();
+
+        let RenewalOutcome::Failed { mut order, mut renewal, .. } =
+            service.process_renewal(&mut subscription, &parent, due).await.unwrap()
+        else {
+            panic!("expected a failed renewal");
+        };
+
+        assert!(matches!(
+            service.retry_renewal(&mut subscription, order.clone(), renewal.clone(), due).await,
+            Err(SubscriptionError::RetryNotDue)
+        ));
+
+        // Retries on day 1, 3 and 7 after the first failure
+        for day in [1, 3, 7] {
+            let retry_at = renewal.next_retry.unwrap();
+            assert_eq!(retry_at, due + Duration::days(day));
+            let outcome = service.retry_renewal(&mut subscription, order, renewal, retry_at).await.unwrap();
+            let RenewalOutcome::Failed { order: o, renewal: r, email, .. } = outcome else {
+                panic!("expected a failed retry");
+            };
+            (order, renewal) = (o, r);
+            if day < 7 {
+                assert_eq!(subscription.status, SubscriptionStatus::OnHold);
+                assert_eq!(email.unwrap().variables["update_payment_url"], serde_json::json!(format!(
+                    "https://shop.example/my-account/subscriptions/{}/payment-method",
+                    subscription.id
+                )));
+            } else {
+                assert_eq!(email.unwrap().template_key.as_deref(), Some(SubscriptionService::PAYMENT_FINAL_FAILURE_TEMPLATE));
+            }
+        }
+
+        assert_eq!(renewal.payment_attempts, 4);
+        assert_eq!(renewal.status, RenewalStatus::Cancelled);
+        assert_eq!(renewal.next_retry, None);
+        assert_eq!(subscription.status, SubscriptionStatus::Cancelled);
+        assert_eq!(subscription.next_payment_date, None);
+        assert!(subscription.cancelled_date.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_successful_retry_recovers_subscription() {
+        let (mut subscription, parent) = subscribed(&service(true), Some(Uuid::now_v7()));
+        let due = subscription.next_payment_date.unwrap();
+
+        let RenewalOutcome::Failed { order, renewal, .. } =
+            service(true).process_renewal(&mut subscription, &parent, due).await.unwrap()
+        else {
+            panic!("expected a failed renewal");
+        };
+
+        // The customer updated their card; the retry goes through
+        let retry_at = renewal.next_retry.unwrap();
+        match service(false).retry_renewal(&mut subscription, order, renewal, retry_at).await.unwrap() {
+            RenewalOutcome::Paid { order, renewal, .. } => {
+                assert_eq!(order.status, OrderStatus::Processing);
+                assert_eq!(renewal.status, RenewalStatus::Completed);
+                assert_eq!(renewal.payment_attempts, 2);
+            }
+            other => panic!("unexpected outcome: {:?}", other),
+        }
+        assert_eq!(subscription.status, SubscriptionStatus::Active);
+        assert_eq!(subscription.next_payment_date, Some(due + Duration::days(30)));
+    }
+
+    fn switch_request(product_id: Uuid, effective_immediately: bool) -> SwitchRequest {
+        SwitchRequest {
+            subscription_id: Uuid::now_v7(),
+            new_product_id: product_id,
+            new_variation_id: None,
+            prorate: true,
+            effective_immediately,
+        }
+    }
+
+    #[test]
+    fn test_upgrade_mid_cycle_is_prorated() {
+        let service = service(false);
+        let (mut subscription, _) = subscribed(&service, Some(Uuid::now_v7()));
+        let due = subscription.next_payment_date.unwrap();
+        let halfway = due - Duration::days(15);
+        let (current, premium) = (plan(dec!(20), BillingPeriod::Month), plan(dec!(40), BillingPeriod::Month));
+        let premium_id = Uuid::now_v7();
+
+        let (calculation, change) = service
+            .switch_plan(&mut subscription, &switch_request(premium_id, true), &current, &premium, "Premium", halfway)
+            .unwrap();
+
+        // Half of 22.00 credited, half of 44.00 charged
+        assert_eq!(calculation.credit_amount, dec!(11));
+        assert_eq!(calculation.amount_due_now, dec!(11));
+        assert_eq!(calculation.new_recurring_amount, dec!(49));
+        assert_eq!(calculation.next_payment_date, due);
+        assert_eq!(change.change_type, ScheduleChangeType::PlanChange);
+        assert_eq!(change.effective_date, halfway);
+
+        assert_eq!(subscription.items[0].product_id, premium_id);
+        assert_eq!(subscription.items[0].tax, dec!(4));
+        assert_eq!(subscription.total, dec!(49));
+    }
+
+    #[test]
+    fn test_downgrade_credit_carries_to_next_renewal() {
+        let service = service(false);
+        let (mut subscription, parent) = subscribed(&service, Some(Uuid::now_v7()));
+        let due = subscription.next_payment_date.unwrap();
+        let (current, basic) = (plan(dec!(20), BillingPeriod::Month), plan(dec!(10), BillingPeriod::Month));
+
+        let (calculation, _) = service
+            .switch_plan(&mut subscription, &switch_request(Uuid::now_v7(), true), &current, &basic, "Basic", due - Duration::days(15))
+            .unwrap();
+        assert_eq!(calculation.amount_due_now, Decimal::ZERO);
+        assert_eq!(SubscriptionService::switch_credit(&subscription), dec!(5.50));
+
+        let (order, renewal) = service.create_renewal_order(&subscription, &parent, due);
+        assert_eq!(order.total, dec!(16) - dec!(5.50));
+        assert_eq!(renewal.amount, order.total);
+    }
+
+    #[test]
+    fn test_switch_to_new_period_starts_new_cycle() {
+        let service = service(false);
+        let (subscription, _) = subscribed(&service, Some(Uuid::now_v7()));
+        let now = subscription.next_payment_date.unwrap() - Duration::days(15);
+        let (current, mut yearly) = (plan(dec!(20), BillingPeriod::Month), plan(dec!(200), BillingPeriod::Year));
+        yearly.sign_up_fee = Some(dec!(10));
+
+        let calculation = service
+            .calculate_switch(&subscription, &switch_request(Uuid::now_v7(), true), &current, &yearly, now)
+            .unwrap();
+        // 220.00 for the year, less 11.00 credit, plus the 11.00 sign-up fee
+        assert_eq!(calculation.amount_due_now, dec!(220));
+        assert_eq!(calculation.next_payment_date, now + Duration::days(365));
+    }
+
+    #[test]
+    fn test_switch_keeps_schedule_of_multi_item_subscription() {
+        let service = service(false);
+        let (mut subscription, _) = subscribed(&service, Some(Uuid::now_v7()));
+        let mut other = subscription.items[0].clone();
+        other.id = Uuid::now_v7();
+        other.product_id = Uuid::now_v7();
+        subscription.items.push(other);
+        let now = subscription.next_payment_date.unwrap() - Duration::days(15);
+
+        let mut request = switch_request(subscription.items[0].product_id, true);
+        request.new_variation_id = Some(Uuid::now_v7());
+        let current = plan(dec!(20), BillingPeriod::Month);
+        assert!(matches!(
+            service.switch_plan(&mut subscription, &request, &current, &plan(dec!(200), BillingPeriod::Year), "Yearly", now),
+            Err(SubscriptionError::ScheduleConflict)
+        ));
+        assert_eq!(subscription.billing_period, BillingPeriod::Month);
+
+        assert!(service.switch_plan(&mut subscription, &request, &current, &plan(dec!(30), BillingPeriod::Month), "Large", now).is_ok());
+    }
+
+    #[test]
+    fn test_switch_during_trial_keeps_trial() {
+        let service = service(false);
+        let product_id = Uuid::now_v7();
+        let parent = order(vec![item(Uuid::now_v7(), product_id, Decimal::ZERO, Decimal::ZERO)]);
+        let mut trial_plan = plan(dec!(20), BillingPeriod::Month);
+        trial_plan.trial_period = Some(BillingPeriod::Week);
+        trial_plan.trial_length = Some(2);
+        let plans = HashMap::from([(product_id, trial_plan.clone())]);
+        let mut subscription = service.create_from_order(&parent, &plans, None).unwrap().remove(0);
+        let trial_end = subscription.trial_end_date.unwrap();
+
+        let (calculation, _) = service
+            .switch_plan(&mut subscription, &switch_request(Uuid::now_v7(), true), &trial_plan, &plan(dec!(40), BillingPeriod::Month), "Premium", Utc::now())
+            .unwrap();
+        assert_eq!(calculation.credit_amount, Decimal::ZERO);
+        assert_eq!(calculation.amount_due_now, Decimal::ZERO);
+        assert_eq!(subscription.next_payment_date, Some(trial_end));
+        assert_eq!(subscription.total, dec!(45));
+    }
+
+    #[test]
+    fn test_scheduled_switch_applies_at_renewal() {
+        let service = service(false);
+        let (mut subscription, _) = subscribed(&service, Some(Uuid::now_v7()));
+        let due = subscription.next_payment_date.unwrap();
+        let premium_id = Uuid::now_v7();
+        let current = plan(dec!(20), BillingPeriod::Month);
+
+        let (calculation, change) = service
+            .switch_plan(&mut subscription, &switch_request(premium_id, false), &current, &plan(dec!(40), BillingPeriod::Month), "Premium", due - Duration::days(15))
+            .unwrap();
+        assert_eq!(calculation.amount_due_now, Decimal::ZERO);
+        assert_eq!(change.effective_date, due);
+        assert_ne!(subscription.items[0].product_id, premium_id);
+
+        service.apply_schedule_change(&mut subscription, &change, due).unwrap();
+        assert_eq!(subscription.items[0].product_id, premium_id);
+        assert_eq!(subscription.total, dec!(49));
+
+        assert!(matches!(
+            service.calculate_switch(&subscription, &switch_request(premium_id, true), &current, &current, due),
+            Err(SubscriptionError::SamePlan)
+        ));
+    }
+
+    fn plans_for(subscription: &Subscription, settings: SubscriptionProductSettings) -> HashMap<Uuid, SubscriptionProductSettings> {
+        subscription.items.iter().map(|i| (i.product_id, settings.clone())).collect()
+    }
+
+    #[tokio::test]
+    async fn test_pause_holds_billing_until_resumed() {
+        let service = service(false);
+        let (mut subscription, parent) = subscribed(&service, Some(Uuid::now_v7()));
+        let plans = plans_for(&subscription, plan(dec!(20), BillingPeriod::Month));
+        let next = subscription.next_payment_date.unwrap();
+        let now = next - Duration::days(5);
+
+        assert!(matches!(
+            service.pause(&mut subscription, &plans, 4, now),
+            Err(SubscriptionError::InvalidPauseLength(3))
+        ));
+        let change = service.pause(&mut subscription, &plans, 2, now).unwrap();
+        assert_eq!(change.change_type, ScheduleChangeType::Pause);
+        assert_eq!(subscription.status, SubscriptionStatus::OnHold);
+        let paused_until = next + Duration::days(60);
+        assert_eq!(subscription.next_payment_date, Some(paused_until));
+
+        // Nothing is billed while paused
+        assert!(matches!(
+            service.process_renewal(&mut subscription, &parent, next).await,
+            Err(SubscriptionError::NotActive(SubscriptionStatus::OnHold))
+        ));
+
+        // Once the pause ends the renewal goes through
+        match service.process_renewal(&mut subscription, &parent, paused_until).await.unwrap() {
+            RenewalOutcome::Paid { .. } => {}
+            other => panic!("expected paid renewal, got {:?}", other),
+        }
+        assert_eq!(subscription.status, SubscriptionStatus::Active);
+        assert!(!subscription.meta.contains_key(SubscriptionService::PAUSED_UNTIL_META));
+    }
+
+    #[test]
+    fn test_resume_restores_original_payment_date() {
+        let service = service(false);
+        let (mut subscription, _) = subscribed(&service, Some(Uuid::now_v7()));
+        let plans = plans_for(&subscription, plan(dec!(20), BillingPeriod::Month));
+        let next = subscription.next_payment_date.unwrap();
+        let now = next - Duration::days(5);
+
+        assert!(matches!(service.resume(&mut subscription, now), Err(SubscriptionError::NotPaused)));
+        service.pause(&mut subscription, &plans, 1, now).unwrap();
+        let change = service.resume(&mut subscription, now + Duration::days(1)).unwrap();
+        assert_eq!(change.change_type, ScheduleChangeType::Resume);
+        assert_eq!(subscription.status, SubscriptionStatus::Active);
+        assert_eq!(subscription.next_payment_date, Some(next));
+
+        let mut no_pause = plan(dec!(20), BillingPeriod::Month);
+        no_pause.self_service.max_pause_cycles = 0;
+        let plans = plans_for(&subscription, no_pause);
+        assert!(matches!(
+            service.pause(&mut subscription, &plans, 1, now),
+            Err(SubscriptionError::NotAllowed(_))
+        ));
+    }
+
+    #[test]
+    fn test_skip_and_change_payment_date() {
+        let service = service(false);
+        let (mut subscription, _) = subscribed(&service, Some(Uuid::now_v7()));
+        let plans = plans_for(&subscription, plan(dec!(20), BillingPeriod::Month));
+        let next = subscription.next_payment_date.unwrap();
+        let now = next - Duration::days(5);
+
+        let change = service.skip_next(&mut subscription, &plans, now).unwrap();
+        assert_eq!(change.effective_date, next);
+        assert_eq!(subscription.next_payment_date, Some(next + Duration::days(30)));
+
+        let skipped = subscription.next_payment_date.unwrap();
+        assert!(matches!(
+            service.change_payment_date(&mut subscription, &plans, skipped + Duration::days(31), now),
+            Err(SubscriptionError::InvalidDate)
+        ));
+        assert!(matches!(
+            service.change_payment_date(&mut subscription, &plans, now - Duration::days(1), now),
+            Err(SubscriptionError::InvalidDate)
+        ));
+        service.change_payment_date(&mut subscription, &plans, skipped + Duration::days(10), now).unwrap();
+        assert_eq!(subscription.next_payment_date, Some(skipped + Duration::days(10)));
+
+        let mut locked = plan(dec!(20), BillingPeriod::Month);
+        locked.self_service.allow_skip = false;
+        let plans = plans_for(&subscription, locked);
+        assert!(matches!(
+            service.skip_next(&mut subscription, &plans, now),
+            Err(SubscriptionError::NotAllowed(_))
+        ));
+    }
+
+    #[test]
+    fn test_change_quantity_reprices_within_limits() {
+        let service = service(false);
+        let (mut subscription, _) = subscribed(&service, Some(Uuid::now_v7()));
+        let mut settings = plan(dec!(20), BillingPeriod::Month);
+        settings.self_service.max_quantity = Some(3);
+        let plans = plans_for(&subscription, settings);
+        let item_id = subscription.items[0].id;
+        let now = Utc::now();
+
+        assert!(matches!(
+            service.change_quantity(&mut subscription, &plans, item_id, 4, now),
+            Err(SubscriptionError::InvalidQuantity { min: 1, max: Some(3) })
+        ));
+        assert!(matches!(
+            service.change_quantity(&mut subscription, &plans, item_id, 0, now),
+            Err(SubscriptionError::InvalidQuantity { .. })
+        ));
+
+        let change = service.change_quantity(&mut subscription, &plans, item_id, 2, now).unwrap();
+        assert_eq!(change.change_type, ScheduleChangeType::QuantityChange);
+        assert_eq!(subscription.items[0].quantity, 2);
+        assert_eq!(subscription.items[0].total, dec!(40));
+        assert_eq!(subscription.items[0].tax, dec!(4));
+        assert_eq!(subscription.total, dec!(49));
+    }
+
+    #[test]
+    fn test_change_payment_method_enables_automatic_renewal() {
+        let service = service(false);
+        let (mut subscription, _) = subscribed(&service, None);
+        assert!(subscription.requires_manual_renewal);
+
+        assert!(matches!(
+            service.change_payment_method(&mut subscription, "cod", Uuid::now_v7(), Utc::now()),
+            Err(SubscriptionError::GatewayUnavailable(_))
+        ));
+        let token = Uuid::now_v7();
+        service.change_payment_method(&mut subscription, "test", token, Utc::now()).unwrap();
+        assert_eq!(subscription.payment_token_id, Some(token));
+        assert!(!subscription.requires_manual_renewal);
+    }
+
+    #[test]
+    fn test_cancel_at_end_of_term() {
+        let service = service(false);
+        let (mut subscription, _) = subscribed(&service, Some(Uuid::now_v7()));
+        let plans = plans_for(&subscription, plan(dec!(20), BillingPeriod::Month));
+        let next = subscription.next_payment_date.unwrap();
+
+        let change = service.cancel_at_end_of_term(&mut subscription, &plans, Utc::now()).unwrap();
+        assert_eq!(change.effective_date, next);
+        assert_eq!(subscription.status, SubscriptionStatus::PendingCancel);
+        assert_eq!(subscription.end_date, Some(next));
+        assert!(subscription.next_payment_date.is_none());
+
+        assert!(SubscriptionService::expire_if_ended(&mut subscription, next));
+        assert_eq!(subscription.status, SubscriptionStatus::Cancelled);
+        assert_eq!(subscription.cancelled_date, Some(next));
+    }
+}
@@ -2,18 +2,41 @@
 //!
 //! Handles tax calculations, tax rates, and tax display.
 
-use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
 use rust_decimal_macros::dec;
+use chrono::{Datelike, NaiveDate};
 use uuid::Uuid;
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
 
-use crate::models::tax::{TaxRate, TaxClass, TaxLocation, CalculatedTax, TaxCalculationResult};
-use crate::models::cart::Cart;
-use crate::settings::RustCommerceSettings;
+use crate::models::tax::{
+    TaxRate, TaxClass, TaxLocation, CalculatedTax, TaxCalculationResult, OrderVat, VatTreatment,
+    TaxExemption, TaxJurisdiction, AppliedTaxExemption,
+};
+use crate::models::cart::{Cart, CartItem};
+use crate::models::customer::Address;
+use crate::models::invoice::Invoice;
+use crate::models::order::{Order, OrderItem, OrderItemType};
+use crate::geo;
+use crate::vat::provider::VatNumberValidation;
+use crate::settings::{RustCommerceSettings, TaxRoundingMode};
+use crate::tax::cache::TaxResultCache;
+use crate::tax::provider::{
+    TaxLineResult, TaxProvider, TaxProviderError, TaxProviderRegistry, TaxRequest, TaxRequestLine,
+    TaxResponse, TABLE_PROVIDER_ID,
+};
+
+/// Order item meta keys holding the product's tax class and tax code
+pub const ORDER_ITEM_TAX_CLASS_META: &str = "tax_class";
+pub const ORDER_ITEM_TAX_CODE_META: &str = "tax_code";
 
 /// Tax service
 pub struct TaxService {
     settings: RustCommerceSettings,
+    providers: Arc<TaxProviderRegistry>,
+    cache: Arc<TaxResultCache>,
 }
 
 /// Tax calculation mode
@@ -33,7 +56,19 @@ pub enum TaxDisplayMode {
 impl TaxService {
     /// Create a new tax service
     pub fn new(settings: RustCommerceSettings) -> Self {
-        Self { settings }
+        let cache = TaxResultCache::new(Duration::from_secs(settings.tax.tax_cache_ttl_seconds));
+        Self {
+            settings,
+            providers: Arc::new(TaxProviderRegistry::new()),
+            cache: Arc::new(cache),
+        }
+    }
+
+    /// Use the plugin's tax providers and result cache
+    pub fn with_providers(mut self, providers: Arc<TaxProviderRegistry>, cache: Arc<TaxResultCache>) -> Self {
+        self.providers = providers;
+        self.cache = cache;
+        self
     }
 
     /// Check if taxes are enabled
@@ -61,7 +96,7 @@ impl TaxService {
         applicable
     }
 
-    /// Calculate tax for a single amount
+    /// Calculate tax for a single amount in `currency`
     pub fn calculate_tax(
         &self,
         amount: Decimal,
@@ -69,6 +104,7 @@ impl TaxService {
         tax_class: &str,
         rates: &[TaxRate],
         prices_include_tax: bool,
+        currency: &str,
     ) -> TaxCalculationResult {
         if !self.taxes_enabled() {
             return TaxCalculationResult {
@@ -115,9 +151,9 @@ impl TaxService {
                 };
 
                 let tax_amount = if prices_include_tax {
-                    self.calculate_tax_from_inclusive(base_amount, rate.rate)
+                    self.calculate_tax_from_inclusive(base_amount, rate.rate, currency)
                 } else {
-                    self.calculate_tax_from_exclusive(base_amount, rate.rate)
+                    self.calculate_tax_from_exclusive(base_amount, rate.rate, currency)
                 };
 
                 taxes.push(CalculatedTax {
@@ -126,6 +162,7 @@ impl TaxService {
                     label: rate.name.clone(),
                     rate: rate.rate,
                     compound: rate.compound,
+                    taxable_amount: if prices_include_tax { base_amount - tax_amount } else { base_amount },
                     tax_amount,
                     shipping_tax_amount: Decimal::ZERO,
                 });
@@ -144,23 +181,147 @@ impl TaxService {
     }
 
     /// Calculate tax from tax-exclusive price
-    fn calculate_tax_from_exclusive(&self, amount: Decimal, rate: Decimal) -> Decimal {
-        (amount * rate / dec!(100)).round_dp(self.settings.general.number_of_decimals as u32)
+    fn calculate_tax_from_exclusive(&self, amount: Decimal, rate: Decimal, currency: &str) -> Decimal {
+        self.round_line_tax(amount * rate / dec!(100), currency)
     }
 
     /// Calculate tax from tax-inclusive price
-    fn calculate_tax_from_inclusive(&self, amount: Decimal, rate: Decimal) -> Decimal {
+    fn calculate_tax_from_inclusive(&self, amount: Decimal, rate: Decimal, currency: &str) -> Decimal {
         let divisor = dec!(1) + (rate / dec!(100));
         let pre_tax = amount / divisor;
-        (amount - pre_tax).round_dp(self.settings.general.number_of_decimals as u32)
+        self.round_line_tax(amount - pre_tax, currency)
+    }
+
+    /// Round a tax amount to the minor unit of `currency` (the cart or
+    /// order currency) using the rounding mode
+    pub fn round_tax(&self, amount: Decimal, currency: &str) -> Decimal {
+        let strategy = match self.settings.tax.tax_rounding_mode {
+            TaxRoundingMode::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+            TaxRoundingMode::HalfDown => RoundingStrategy::MidpointTowardZero,
+            TaxRoundingMode::HalfEven => RoundingStrategy::MidpointNearestEven,
+        };
+        amount.round_dp_with_strategy(crate::currency::minor_units(currency), strategy)
+    }
+
+    /// Round a line's tax, unless rounding happens per rate at subtotal.
+    /// Unrounded amounts are settled by `reconcile_line_taxes`.
+    fn round_line_tax(&self, amount: Decimal, currency: &str) -> Decimal {
+        if self.settings.tax.rounding_at_subtotal {
+            amount
+        } else {
+            self.round_tax(amount, currency)
+        }
+    }
+
+    /// Round amounts so that they add up exactly to `total`.
+    ///
+    /// Each amount is rounded on its own, then the remaining pennies are
+    /// given to (or taken from) the amounts whose rounding moved them
+    /// furthest the other way, largest amounts first on ties. No amount
+    /// moves by more than one minor unit; a larger difference is not a
+    /// rounding error and is returned as a mismatch.
+    pub fn distribute_rounding(&self, amounts: &[Decimal], total: Decimal, currency: &str) -> Result<Vec<Decimal>, TaxMismatch> {
+        let mut rounded: Vec<Decimal> = amounts.iter().map(|a| self.round_tax(*a, currency)).collect();
+        let expected = self.round_tax(total, currency);
+        let actual: Decimal = rounded.iter().sum();
+
+        let unit = Decimal::new(1, crate::currency::minor_units(currency));
+        let diff = expected - actual;
+        let steps = (diff / unit).round().abs().to_usize().unwrap_or(usize::MAX);
+        if steps == 0 {
+            return Ok(rounded);
+        }
+        if steps > rounded.len() {
+            return Err(TaxMismatch { expected, actual });
+        }
+
+        let step = if diff.is_sign_positive() { unit } else { -unit };
+        let mut order: Vec<usize> = (0..amounts.len()).collect();
+        order.sort_by(|&a, &b| {
+            let error_a = (amounts[a] - rounded[a]) * step;
+            let error_b = (amounts[b] - rounded[b]) * step;
+            error_b.cmp(&error_a).then(amounts[b].abs().cmp(&amounts[a].abs()))
+        });
+
+        for &index in order.iter().take(steps) {
+            rounded[index] += step;
+        }
+
+        Ok(rounded)
+    }
+
+    /// Settle line taxes after calculation.
+    ///
+    /// With `rounding_at_subtotal`, each rate's tax is rounded once over all
+    /// lines and the pennies are then spread across the lines, so that the
+    /// line taxes always add up to the rounded total.
+    pub fn reconcile_line_taxes(&self, lines: &mut [TaxLineResult], currency: &str) {
+        if self.settings.tax.rounding_at_subtotal {
+            let mut by_rate: HashMap<Uuid, Vec<(usize, usize)>> = HashMap::new();
+            for (line_index, line) in lines.iter().enumerate() {
+                for (tax_index, tax) in line.taxes.iter().enumerate() {
+                    by_rate.entry(tax.rate_id).or_default().push((line_index, tax_index));
+                }
+            }
+
+            for positions in by_rate.values() {
+                let amounts: Vec<Decimal> = positions.iter()
+                    .map(|&(l, t)| lines[l].taxes[t].tax_amount)
+                    .collect();
+                let total: Decimal = amounts.iter().sum();
+                // Rounding the total moves it by less than a unit per line, so this cannot fail
+                let rounded = self.distribute_rounding(&amounts, total, currency)
+                    .unwrap_or_else(|_| amounts.iter().map(|a| self.round_tax(*a, currency)).collect());
+
+                for (&(l, t), amount) in positions.iter().zip(rounded) {
+                    lines[l].taxes[t].tax_amount = amount;
+                }
+            }
+        }
+
+        for line in lines.iter_mut() {
+            line.tax = line.taxes.iter().map(|t| t.tax_amount).sum();
+        }
+    }
+
+    /// Make an order's line item taxes add up to its tax lines.
+    ///
+    /// Accounting exports expect the item taxes to sum to the order's cart
+    /// tax; any rounding difference is spread a penny at a time. A larger
+    /// difference means the taxes were edited or miscalculated, so the
+    /// items are left alone and the mismatch is returned for review.
+    pub fn reconcile_order_taxes(&self, order: &mut Order) -> Result<(), TaxMismatch> {
+        let target = match order.tax_lines {
+            Some(ref tax_lines) => tax_lines.iter().map(|t| t.tax_total).sum(),
+            None => order.cart_tax,
+        };
+
+        let Some(ref mut items) = order.line_items else {
+            return Ok(());
+        };
+
+        let items: Vec<&mut OrderItem> = items.iter_mut()
+            .filter(|i| i.item_type == OrderItemType::LineItem)
+            .collect();
+        let amounts: Vec<Decimal> = items.iter().map(|i| i.total_tax).collect();
+        if amounts.iter().sum::<Decimal>() == target {
+            return Ok(());
+        }
+
+        let rounded = self.distribute_rounding(&amounts, target, &order.currency)?;
+        for (item, amount) in items.into_iter().zip(rounded) {
+            item.total_tax = amount;
+        }
+        Ok(())
     }
 
-    /// Calculate shipping tax
+    /// Calculate shipping tax in `currency`
     pub fn calculate_shipping_tax(
         &self,
         shipping_cost: Decimal,
         location: &TaxLocation,
         rates: &[TaxRate],
+        currency: &str,
     ) -> Decimal {
         if !self.taxes_enabled() {
             return Decimal::ZERO;
@@ -176,7 +337,7 @@ impl TaxService {
 
         let mut total_tax = Decimal::ZERO;
         for rate in shipping_rates {
-            total_tax += self.calculate_tax_from_exclusive(shipping_cost, rate.rate);
+            total_tax += self.round_tax(shipping_cost * rate.rate / dec!(100), currency);
         }
 
         total_tax
@@ -208,8 +369,11 @@ impl TaxService {
         }
     }
 
-    /// Calculate cart taxes
-    pub fn calculate_cart_taxes(
+    /// Calculate cart taxes through the configured provider, or the rate
+    /// table when none is configured.
+    ///
+    /// `product_tax_classes` overrides the tax class recorded on cart items.
+    pub async fn calculate_cart_taxes(
         &self,
         cart: &Cart,
         location: &TaxLocation,
@@ -220,41 +384,21 @@ impl TaxService {
             return CartTaxResult::default();
         }
 
-        let prices_include_tax = self.settings.tax.prices_include_tax;
-        let mut item_taxes = HashMap::new();
-        let mut total_item_tax = Decimal::ZERO;
-
-        // Calculate tax for each item
-        for item in &cart.items {
-            let tax_class = product_tax_classes.get(&item.product_id)
-                .cloned()
-                .unwrap_or_else(|| "standard".to_string());
-
-            let result = self.calculate_tax(
-                item.line_subtotal,
-                location,
-                &tax_class,
-                rates,
-                prices_include_tax,
-            );
-
-            item_taxes.insert(item.key.clone(), result.total_tax);
-            total_item_tax += result.total_tax;
+        let destination = Address {
+            country: location.country.clone(),
+            state: location.state.clone(),
+            postcode: location.postcode.clone(),
+            city: location.city.clone(),
+            ..Address::default()
+        };
+        let mut request = self.build_tax_request(cart, &destination);
+        for line in &mut request.lines {
+            if let Some(tax_class) = line.product_id.and_then(|id| product_tax_classes.get(&id)) {
+                line.tax_class = tax_class.clone();
+            }
         }
 
-        // Calculate shipping tax
-        let shipping_tax = self.calculate_shipping_tax(
-            cart.totals.shipping_total,
-            location,
-            rates,
-        );
-
-        CartTaxResult {
-            item_taxes,
-            total_item_tax,
-            shipping_tax,
-            total_tax: total_item_tax + shipping_tax,
-        }
+        self.calculate_request(&request, rates).await
     }
 
     /// Get default tax class
@@ -280,6 +424,456 @@ impl TaxService {
         ]
     }
 
+    /// Store's country code, from the "CC" or "CC:STATE" store setting
+    fn store_country(&self) -> &str {
+        let country = &self.settings.general.store_country;
+        country.split(':').next().unwrap_or(country)
+    }
+
+    /// Store's own address as a tax location
+    pub fn store_location(&self) -> TaxLocation {
+        let general = &self.settings.general;
+        let state = general.store_country.split_once(':').map(|(_, s)| s).unwrap_or(&general.store_state);
+        TaxLocation::new(self.store_country(), state, &general.store_postcode, &general.store_city)
+    }
+
+    /// Net EU distance sales (excluding VAT) in euros for a calendar year:
+    /// paid orders to consumers in other member states, for the OSS
+    /// threshold. Each order is converted to the base currency at its own
+    /// rate, then to euros at `eur_rate` (euros per unit of the base currency).
+    pub fn eu_distance_sales(&self, orders: &[Order], year: i32, eur_rate: Decimal) -> Decimal {
+        let store_country = self.store_country();
+        let sales: Decimal = orders.iter()
+            .filter(|o| o.status.is_paid())
+            .filter(|o| o.date_paid.unwrap_or(o.created_at).year() == year)
+            .filter(|o| !o.vat.as_ref().is_some_and(|v| v.treatment.is_zero_rated()))
+            .filter(|o| {
+                let country = if o.shipping.country.is_empty() { &o.billing.country } else { &o.shipping.country };
+                geo::is_eu_member(country) && !geo::same_country(country, store_country)
+            })
+            .map(|o| o.to_base_currency(o.total - o.total_tax))
+            .sum();
+        crate::currency::to_eur(sales, &self.settings.general.currency, eur_rate)
+    }
+
+    /// Decide how VAT applies to a customer.
+    ///
+    /// `sales_this_year` and `sales_last_year` are net EU distance sales;
+    /// destination rates apply once either exceeds the threshold.
+    pub fn vat_treatment(
+        &self,
+        customer_country: &str,
+        validation: Option<&VatNumberValidation>,
+        sales_this_year: Decimal,
+        sales_last_year: Decimal,
+    ) -> VatTreatment {
+        let store_country = self.store_country();
+        let eu_vat = &self.settings.tax.eu_vat;
+
+        if !geo::is_eu_member(store_country) || geo::same_country(customer_country, store_country) {
+            return VatTreatment::Domestic;
+        }
+
+        if !geo::is_eu_member(customer_country) {
+            return VatTreatment::Export;
+        }
+
+        // B2B: the VAT number must be valid and registered in the customer's country
+        if validation.is_some_and(|v| v.valid && geo::same_country(&v.country_code, customer_country)) {
+            return VatTreatment::ReverseCharge;
+        }
+
+        let threshold = eu_vat.distance_sales_threshold;
+        if eu_vat.enable_oss
+            && (eu_vat.always_use_destination_rates || sales_this_year > threshold || sales_last_year > threshold)
+        {
+            VatTreatment::Destination
+        } else {
+            VatTreatment::Origin
+        }
+    }
+
+    /// Location whose rates apply under a VAT treatment, or None when the
+    /// sale is zero-rated
+    pub fn tax_location_for(&self, treatment: VatTreatment, customer: &TaxLocation) -> Option<TaxLocation> {
+        match treatment {
+            VatTreatment::Domestic | VatTreatment::Destination => Some(customer.clone()),
+            VatTreatment::Origin => Some(self.store_location()),
+            VatTreatment::ReverseCharge | VatTreatment::Export => None,
+        }
+    }
+
+    /// Calculate cart taxes for an EU store, applying the VAT treatment
+    pub async fn calculate_eu_cart_taxes(
+        &self,
+        cart: &Cart,
+        customer: &TaxLocation,
+        treatment: VatTreatment,
+        rates: &[TaxRate],
+        product_tax_classes: &HashMap<Uuid, String>,
+    ) -> CartTaxResult {
+        let mut result = match self.tax_location_for(treatment, customer) {
+            Some(location) => self.calculate_cart_taxes(cart, &location, rates, product_tax_classes).await,
+            None => CartTaxResult::default(),
+        };
+        result.vat_treatment = Some(treatment);
+        result.reverse_charge = treatment == VatTreatment::ReverseCharge;
+        result
+    }
+
+    /// VAT details to record on the order
+    pub fn order_vat(
+        &self,
+        treatment: VatTreatment,
+        customer_country: &str,
+        validation: Option<VatNumberValidation>,
+    ) -> OrderVat {
+        let tax_country = match treatment {
+            VatTreatment::Domestic | VatTreatment::Destination => {
+                geo::normalize_country(customer_country).unwrap_or(customer_country).to_string()
+            }
+            VatTreatment::Origin => self.store_country().to_string(),
+            VatTreatment::ReverseCharge | VatTreatment::Export => String::new(),
+        };
+
+        let note = match treatment {
+            VatTreatment::ReverseCharge => Some(self.settings.tax.eu_vat.reverse_charge_note.clone()),
+            _ => None,
+        };
+
+        OrderVat {
+            vat_number: validation.as_ref().map(|v| v.vat_number.clone()),
+            validation,
+            treatment,
+            tax_country,
+            note,
+        }
+    }
+
+    /// Add the buyer's VAT number and the order's VAT note to an invoice
+    pub fn apply_vat_to_invoice(&self, invoice: &mut Invoice, vat: &OrderVat) {
+        if let Some(ref number) = vat.vat_number {
+            invoice.buyer.vat_number = Some(number.clone());
+        }
+
+        if let Some(ref note) = vat.note {
+            invoice.notes = Some(match invoice.notes.take() {
+                Some(existing) if !existing.contains(note.as_str()) => format!("{}\n\n{}", existing, note),
+                Some(existing) => existing,
+                None => note.clone(),
+            });
+        }
+    }
+
+    /// Find the customer's exemption covering a location on a date
+    pub fn find_exemption<'a>(
+        &self,
+        exemptions: &'a [TaxExemption],
+        customer_id: Uuid,
+        location: &TaxLocation,
+        date: NaiveDate,
+    ) -> Option<(&'a TaxExemption, &'a TaxJurisdiction)> {
+        exemptions.iter()
+            .filter(|e| e.customer_id == customer_id && e.is_valid_on(date))
+            .find_map(|e| e.covering_jurisdiction(location).map(|j| (e, j)))
+    }
+
+    /// Calculate cart taxes, waiving the rates levied by the jurisdiction of
+    /// the customer's exemption for the tax location. Rates from other
+    /// jurisdictions are still charged. External providers receive the
+    /// customer and apply their own exemptions.
+    pub async fn calculate_exempt_cart_taxes(
+        &self,
+        cart: &Cart,
+        location: &TaxLocation,
+        rates: &[TaxRate],
+        product_tax_classes: &HashMap<Uuid, String>,
+        exemptions: &[TaxExemption],
+    ) -> CartTaxResult {
+        let mut result = self.calculate_cart_taxes(cart, location, rates, product_tax_classes).await;
+
+        let today = chrono::Utc::now().date_naive();
+        let exemption = cart.customer_id
+            .and_then(|customer_id| self.find_exemption(exemptions, customer_id, location, today));
+
+        let Some((exemption, jurisdiction)) = exemption else {
+            return result;
+        };
+
+        // Tax the cart again without the exempt rates
+        let charged_rates: Vec<TaxRate> = rates.iter()
+            .filter(|rate| !jurisdiction.covers_rate(rate))
+            .cloned()
+            .collect();
+        if charged_rates.len() == rates.len() {
+            return result;
+        }
+        let charged = self.calculate_cart_taxes(cart, location, &charged_rates, product_tax_classes).await;
+
+        let exempt_amount = result.total_tax - charged.total_tax;
+        if exempt_amount <= Decimal::ZERO {
+            return result;
+        }
+
+        result.item_taxes = charged.item_taxes;
+        result.total_item_tax = charged.total_item_tax;
+        result.shipping_tax = charged.shipping_tax;
+        result.total_tax = charged.total_tax;
+        result.exemption = Some(AppliedTaxExemption {
+            exemption_id: exemption.id,
+            certificate_id: exemption.certificate_id.clone(),
+            exemption_type: exemption.exemption_type,
+            jurisdiction: jurisdiction.to_string(),
+            exempt_amount,
+        });
+        result
+    }
+
+    /// Record an exemption on an order. The tax lines of rates levied by the
+    /// exemption's jurisdiction are zeroed and keep the amount that was not
+    /// charged; other rates are still charged. Item taxes, which are not
+    /// split by rate, give up the exempt share of the product tax.
+    pub fn apply_exemption_to_order(
+        &self,
+        order: &mut Order,
+        exemption: &TaxExemption,
+        jurisdiction: &TaxJurisdiction,
+        rates: &[TaxRate],
+    ) {
+        let covers = |rate_id: Uuid| rates.iter().any(|r| r.id == rate_id && jurisdiction.covers_rate(r));
+        let mut exempt_rate_ids = Vec::new();
+        let mut exempt_tax = Decimal::ZERO;
+        let mut exempt_shipping_tax = Decimal::ZERO;
+
+        for tax_line in order.tax_lines.iter_mut().flatten().filter(|t| covers(t.rate_id)) {
+            tax_line.exempt_amount = tax_line.tax_total + tax_line.shipping_tax_total;
+            tax_line.exemption_id = Some(exemption.id);
+            exempt_tax += tax_line.tax_total;
+            exempt_shipping_tax += tax_line.shipping_tax_total;
+            tax_line.tax_total = Decimal::ZERO;
+            tax_line.shipping_tax_total = Decimal::ZERO;
+            exempt_rate_ids.push(tax_line.rate_id);
+        }
+        if exempt_rate_ids.is_empty() {
+            return;
+        }
+
+        if order.cart_tax > Decimal::ZERO {
+            let share = exempt_tax / order.cart_tax;
+            let items: Vec<&mut OrderItem> = order.line_items.iter_mut().flatten().collect();
+            let exempt: Vec<Decimal> = items.iter().map(|i| i.total_tax * share).collect();
+            let exempt = self.distribute_rounding(&exempt, exempt_tax, &order.currency)
+                .unwrap_or_else(|_| exempt.iter().map(|a| self.round_tax(*a, &order.currency)).collect());
+            for (item, exempt) in items.into_iter().zip(exempt) {
+                item.subtotal_tax -= self.round_tax(item.subtotal_tax * share, &order.currency);
+                item.total_tax -= exempt;
+            }
+            order.discount_tax -= self.round_tax(order.discount_tax * share, &order.currency);
+        }
+
+        for shipping_line in order.shipping_lines.iter_mut().flatten() {
+            let exempt: Decimal = shipping_line.taxes.iter()
+                .filter(|t| exempt_rate_ids.contains(&t.rate_id))
+                .map(|t| t.total)
+                .sum();
+            shipping_line.taxes.retain(|t| !exempt_rate_ids.contains(&t.rate_id));
+            shipping_line.total_tax -= exempt;
+        }
+
+        let exempt_amount = exempt_tax + exempt_shipping_tax;
+        order.total -= exempt_amount;
+        order.cart_tax -= exempt_tax;
+        order.shipping_tax -= exempt_shipping_tax;
+        order.total_tax -= exempt_amount;
+        order.tax_exemption = Some(AppliedTaxExemption {
+            exemption_id: exemption.id,
+            certificate_id: exemption.certificate_id.clone(),
+            exemption_type: exemption.exemption_type,
+            jurisdiction: jurisdiction.to_string(),
+            exempt_amount,
+        });
+    }
+
+    /// Store's own address, used as the ship-from address for providers
+    pub fn store_address(&self) -> Address {
+        let general = &self.settings.general;
+        let location = self.store_location();
+        Address {
+            company: general.store_name.clone(),
+            address_1: general.store_address.clone(),
+            address_2: general.store_address_2.clone(),
+            city: location.city,
+            state: location.state,
+            postcode: location.postcode,
+            country: location.country,
+            ..Address::default()
+        }
+    }
+
+    /// Build a provider request for a cart
+    pub fn build_tax_request(&self, cart: &Cart, destination: &Address) -> TaxRequest {
+        let lines = cart.items.iter()
+            .map(|item| TaxRequestLine {
+                id: item.key.clone(),
+                product_id: Some(item.product_id),
+                sku: item.product_sku.clone(),
+                description: item.product_name.clone(),
+                quantity: item.quantity,
+                amount: item.total,
+                tax_class: self.tax_class_or_default(&item.tax_class),
+                tax_code: item.tax_code.clone(),
+            })
+            .collect();
+
+        TaxRequest {
+            cart_hash: cart.calculate_hash(),
+            customer_id: cart.customer_id,
+            currency: cart.currency.clone().unwrap_or_else(|| self.settings.general.currency.clone()),
+            origin: self.store_address(),
+            destination: destination.clone(),
+            lines,
+            shipping: cart.totals.shipping_total,
+            prices_include_tax: self.settings.tax.prices_include_tax,
+        }
+    }
+
+    /// Build a provider request for a placed order. Line items carry the
+    /// product's tax class and tax code in `meta` (see `order_item_tax_meta`).
+    pub fn build_order_tax_request(&self, order: &Order) -> TaxRequest {
+        let meta_str = |meta: &serde_json::Value, key: &str| {
+            meta.get(key).and_then(|v| v.as_str()).map(str::to_string)
+        };
+
+        let lines = order.line_items.iter()
+            .flatten()
+            .filter(|item| item.item_type == OrderItemType::LineItem)
+            .map(|item| TaxRequestLine {
+                id: item.id.to_string(),
+                product_id: item.product_id,
+                sku: item.sku.clone(),
+                description: item.name.clone(),
+                quantity: item.quantity,
+                amount: item.total,
+                tax_class: self.tax_class_or_default(&meta_str(&item.meta, ORDER_ITEM_TAX_CLASS_META).unwrap_or_default()),
+                tax_code: meta_str(&item.meta, ORDER_ITEM_TAX_CODE_META),
+            })
+            .collect();
+
+        let destination = if order.shipping.country.is_empty() { &order.billing } else { &order.shipping };
+
+        TaxRequest {
+            cart_hash: order.cart_hash.clone().unwrap_or_default(),
+            customer_id: order.customer_id,
+            currency: order.currency.clone(),
+            origin: self.store_address(),
+            destination: destination.clone(),
+            lines,
+            shipping: order.shipping_total,
+            prices_include_tax: order.prices_include_tax,
+        }
+    }
+
+    /// Record a cart item's tax class and tax code (copied from the product)
+    /// in its order item meta
+    pub fn order_item_tax_meta(item: &CartItem, meta: &mut serde_json::Value) {
+        if let Some(meta) = meta.as_object_mut() {
+            meta.insert(ORDER_ITEM_TAX_CLASS_META.to_string(), item.tax_class.clone().into());
+            if let Some(ref tax_code) = item.tax_code {
+                meta.insert(ORDER_ITEM_TAX_CODE_META.to_string(), tax_code.clone().into());
+            }
+        }
+    }
+
+    fn tax_class_or_default(&self, tax_class: &str) -> String {
+        if tax_class.is_empty() {
+            self.get_default_tax_class().to_string()
+        } else {
+            tax_class.to_string()
+        }
+    }
+
+    /// Calculate a provider request against the rate table
+    pub fn calculate_request_with_rates(&self, request: &TaxRequest, rates: &[TaxRate]) -> TaxResponse {
+        let destination = &request.destination;
+        let location = TaxLocation::new(&destination.country, &destination.state, &destination.postcode, &destination.city);
+
+        let mut lines: Vec<TaxLineResult> = request.lines.iter()
+            .map(|line| {
+                let result = self.calculate_tax(
+                    line.amount, &location, &line.tax_class, rates, request.prices_include_tax, &request.currency,
+                );
+                TaxLineResult {
+                    id: line.id.clone(),
+                    tax: result.total_tax,
+                    taxes: result.taxes,
+                }
+            })
+            .collect();
+        self.reconcile_line_taxes(&mut lines, &request.currency);
+
+        let shipping_tax = self.calculate_shipping_tax(request.shipping, &location, rates, &request.currency);
+        let total_tax = lines.iter().map(|l| l.tax).sum::<Decimal>() + shipping_tax;
+
+        TaxResponse {
+            provider: TABLE_PROVIDER_ID.to_string(),
+            lines,
+            shipping_tax,
+            total_tax,
+        }
+    }
+
+    /// The configured external provider, or None when the rate table is used
+    fn external_provider(&self) -> Option<Arc<dyn TaxProvider>> {
+        let provider_id = &self.settings.tax.tax_provider;
+        if provider_id.is_empty() || provider_id == TABLE_PROVIDER_ID {
+            return None;
+        }
+        self.providers.get(provider_id).filter(|p| p.is_available())
+    }
+
+    /// Calculate a request with the configured provider.
+    ///
+    /// Responses are cached per cart hash and request. If the provider
+    /// fails or does not answer within the configured timeout, the rate
+    /// table is used instead and the result is not cached.
+    async fn calculate_request(&self, request: &TaxRequest, rates: &[TaxRate]) -> CartTaxResult {
+        let Some(provider) = self.external_provider() else {
+            return CartTaxResult::from_response(self.calculate_request_with_rates(request, rates), false);
+        };
+
+        let key = request.cache_key(provider.id());
+        if let Some(response) = self.cache.get(&key) {
+            return CartTaxResult::from_response(response, false);
+        }
+
+        let timeout = Duration::from_millis(self.settings.tax.tax_provider_timeout_ms);
+        match tokio::time::timeout(timeout, provider.calculate(request)).await {
+            Ok(Ok(response)) => {
+                self.cache.insert(key, response.clone());
+                CartTaxResult::from_response(response, false)
+            }
+            Ok(Err(_)) | Err(_) => {
+                CartTaxResult::from_response(self.calculate_request_with_rates(request, rates), true)
+            }
+        }
+    }
+
+    /// Commit an order's taxes with the external provider once it is paid
+    pub async fn commit_order_taxes(&self, order: &Order) -> Result<(), TaxProviderError> {
+        match self.external_provider() {
+            Some(provider) => provider.commit(&order.order_number, &self.build_order_tax_request(order)).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Void an order's committed taxes when it is refunded
+    pub async fn void_order_taxes(&self, order: &Order) -> Result<(), TaxProviderError> {
+        match self.external_provider() {
+            Some(provider) => provider.void(&order.order_number).await,
+            None => Ok(()),
+        }
+    }
+
     /// Check if tax is applied based on shipping or billing address
     pub fn tax_based_on(&self) -> TaxBasedOn {
         // Could be configurable
@@ -294,8 +888,48 @@ pub struct CartTaxResult {
     pub total_item_tax: Decimal,
     pub shipping_tax: Decimal,
     pub total_tax: Decimal,
+    /// EU VAT treatment, when calculated for an EU store
+    pub vat_treatment: Option<VatTreatment>,
+    /// Zero-rated under reverse charge
+    pub reverse_charge: bool,
+    /// Provider that calculated the taxes
+    pub provider: Option<String>,
+    /// The provider failed and the rate table was used instead
+    pub used_fallback: bool,
+    /// Customer tax exemption that zeroed the taxes
+    pub exemption: Option<AppliedTaxExemption>,
+}
+
+impl CartTaxResult {
+    fn from_response(response: TaxResponse, used_fallback: bool) -> Self {
+        let total_item_tax = response.lines.iter().map(|l| l.tax).sum();
+        Self {
+            item_taxes: response.lines.into_iter().map(|l| (l.id, l.tax)).collect(),
+            total_item_tax,
+            shipping_tax: response.shipping_tax,
+            total_tax: response.total_tax,
+            provider: Some(response.provider),
+            used_fallback,
+            ..Self::default()
+        }
+    }
 }
 
+/// Line taxes that differ from their total by more than rounding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaxMismatch {
+    pub expected: Decimal,
+    pub actual: Decimal,
+}
+
+impl std::fmt::Display for TaxMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Line taxes add up to {} but the tax total is {}", self.actual, self.expected)
+    }
+}
+
+impl std::error::Error for TaxMismatch {}
+
 /// Tax class info
 #[derive(Debug, Clone)]
 pub struct TaxClassInfo {
@@ -348,6 +982,7 @@ mod tests {
             "standard",
             &rates,
             false,
+            "USD",
         );
 
         assert_eq!(result.total_tax, dec!(10));
@@ -367,9 +1002,438 @@ mod tests {
             "standard",
             &rates,
             true,
+            "USD",
         );
 
         // 110 / 1.1 = 100, tax = 10
         assert_eq!(result.total_tax, dec!(10));
     }
+
+    fn eu_service() -> TaxService {
+        let mut settings = RustCommerceSettings::default();
+        settings.general.store_country = "DE:BE".to_string();
+        settings.tax.eu_vat.enable_oss = true;
+        TaxService::new(settings)
+    }
+
+    fn validation(vat_number: &str, country_code: &str, valid: bool) -> VatNumberValidation {
+        VatNumberValidation {
+            vat_number: vat_number.to_string(),
+            country_code: country_code.to_string(),
+            valid,
+            name: None,
+            address: None,
+            request_identifier: None,
+            provider: "local".to_string(),
+            checked_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_vat_treatment() {
+        let service = eu_service();
+        let below = dec!(5000);
+        let above = dec!(12000);
+
+        assert_eq!(service.vat_treatment("DE", None, above, above), VatTreatment::Domestic);
+        assert_eq!(service.vat_treatment("US", None, above, above), VatTreatment::Export);
+        assert_eq!(service.vat_treatment("FR", None, below, below), VatTreatment::Origin);
+        assert_eq!(service.vat_treatment("FR", None, below, above), VatTreatment::Destination);
+
+        // Reverse charge needs a valid number from the customer's own country
+        let fr = validation("FR12345678901", "FR", true);
+        assert_eq!(service.vat_treatment("FRA", Some(&fr), above, above), VatTreatment::ReverseCharge);
+        assert_eq!(service.vat_treatment("IT", Some(&fr), above, above), VatTreatment::Destination);
+        let invalid = validation("FR12345678901", "FR", false);
+        assert_eq!(service.vat_treatment("FR", Some(&invalid), below, below), VatTreatment::Origin);
+
+        // Non-EU stores are unaffected
+        let us_store = TaxService::new(RustCommerceSettings::default());
+        assert_eq!(us_store.vat_treatment("FR", Some(&fr), above, above), VatTreatment::Domestic);
+    }
+
+    #[test]
+    fn test_tax_location_for_treatment() {
+        let service = eu_service();
+        let customer = TaxLocation::new("FR", "", "75001", "Paris");
+
+        assert_eq!(service.tax_location_for(VatTreatment::Destination, &customer).unwrap().country, "FR");
+        let origin = service.tax_location_for(VatTreatment::Origin, &customer).unwrap();
+        assert_eq!((origin.country.as_str(), origin.state.as_str()), ("DE", "BE"));
+        assert!(service.tax_location_for(VatTreatment::ReverseCharge, &customer).is_none());
+    }
+
+    #[test]
+    fn test_reverse_charge_order_vat() {
+        let service = eu_service();
+        let vat = service.order_vat(
+            VatTreatment::ReverseCharge,
+            "FR",
+            Some(validation("FR12345678901", "FR", true)),
+        );
+
+        assert!(vat.is_reverse_charge());
+        assert_eq!(vat.vat_number.as_deref(), Some("FR12345678901"));
+        assert!(vat.tax_country.is_empty());
+        assert!(vat.note.as_deref().unwrap().starts_with("Reverse charge"));
+
+        let vat = service.order_vat(VatTreatment::Destination, "fra", None);
+        assert_eq!(vat.tax_country, "FR");
+        assert!(vat.note.is_none());
+    }
+
+    struct MockTaxProvider {
+        fail: bool,
+        delay_ms: u64,
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl TaxProvider for MockTaxProvider {
+        fn id(&self) -> &str {
+            "mock"
+        }
+
+        fn title(&self) -> &str {
+            "Mock"
+        }
+
+        fn is_available(&self) -> bool {
+            true
+        }
+
+        async fn calculate(&self, _request: &TaxRequest) -> Result<TaxResponse, TaxProviderError> {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(self.delay_ms)).await;
+            if self.fail {
+                return Err(TaxProviderError::ProviderError("down".to_string()));
+            }
+            Ok(TaxResponse {
+                provider: "mock".to_string(),
+                lines: vec![],
+                shipping_tax: dec!(5),
+                total_tax: dec!(5),
+            })
+        }
+    }
+
+    fn provider_setup(fail: bool, delay_ms: u64) -> (TaxService, Arc<MockTaxProvider>) {
+        let mut settings = RustCommerceSettings::default();
+        settings.tax.tax_provider = "mock".to_string();
+        settings.tax.tax_provider_timeout_ms = 50;
+
+        let mock = Arc::new(MockTaxProvider { fail, delay_ms, calls: Default::default() });
+        let mut providers = TaxProviderRegistry::new();
+        providers.register(mock.clone());
+        let cache = TaxResultCache::new(std::time::Duration::from_secs(60));
+        let service = TaxService::new(settings).with_providers(Arc::new(providers), Arc::new(cache));
+        (service, mock)
+    }
+
+    fn shipped_cart() -> (Cart, TaxLocation) {
+        let mut cart = Cart::new(None, None);
+        cart.totals.shipping_total = dec!(10);
+        (cart, TaxLocation::new("US", "", "", ""))
+    }
+
+    #[tokio::test]
+    async fn test_provider_result_is_cached() {
+        let (service, mock) = provider_setup(false, 0);
+        let (cart, location) = shipped_cart();
+        let rates = vec![create_test_rate(dec!(10), "US", 1, false)];
+
+        for _ in 0..2 {
+            let result = service.calculate_cart_taxes(&cart, &location, &rates, &HashMap::new()).await;
+            assert_eq!(result.total_tax, dec!(5));
+            assert_eq!(result.provider.as_deref(), Some("mock"));
+            assert!(!result.used_fallback);
+        }
+        assert_eq!(mock.calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_provider_falls_back_to_table() {
+        let (cart, location) = shipped_cart();
+        let rates = vec![create_test_rate(dec!(10), "US", 1, false)];
+
+        for (fail, delay_ms) in [(true, 0), (false, 500)] {
+            let (service, _) = provider_setup(fail, delay_ms);
+            let result = service.calculate_cart_taxes(&cart, &location, &rates, &HashMap::new()).await;
+            assert_eq!(result.total_tax, dec!(1));
+            assert_eq!(result.provider.as_deref(), Some(TABLE_PROVIDER_ID));
+            assert!(result.used_fallback);
+        }
+    }
+
+    #[test]
+    fn test_rounding_modes() {
+        let mut settings = RustCommerceSettings::default();
+        let service = TaxService::new(settings.clone());
+        assert_eq!(service.round_tax(dec!(0.125), "USD"), dec!(0.13));
+        assert_eq!(service.round_tax(dec!(-0.125), "USD"), dec!(-0.13));
+
+        settings.tax.tax_rounding_mode = TaxRoundingMode::HalfEven;
+        let service = TaxService::new(settings.clone());
+        assert_eq!(service.round_tax(dec!(0.125), "USD"), dec!(0.12));
+        assert_eq!(service.round_tax(dec!(0.135), "USD"), dec!(0.14));
+
+        settings.tax.tax_rounding_mode = TaxRoundingMode::HalfDown;
+        let service = TaxService::new(settings);
+        assert_eq!(service.round_tax(dec!(0.125), "USD"), dec!(0.12));
+        assert_eq!(service.round_tax(dec!(0.1251), "USD"), dec!(0.13));
+
+        // Rounds to the document currency's minor unit
+        assert_eq!(service.round_tax(dec!(12.5), "JPY"), dec!(12));
+        assert_eq!(service.round_tax(dec!(0.12345), "KWD"), dec!(0.123));
+    }
+
+    #[test]
+    fn test_distribute_rounding() {
+        let service = TaxService::new(RustCommerceSettings::default());
+
+        // Three lines of 0.333..., total 1.00
+        let third = dec!(1) / dec!(3);
+        let rounded = service.distribute_rounding(&[third, third, third], dec!(1), "USD").unwrap();
+        assert_eq!(rounded.iter().sum::<Decimal>(), dec!(1.00));
+
+        // Rounding each up overshoots; the line rounded up the most gives a penny back
+        let rounded = service.distribute_rounding(&[dec!(0.005), dec!(0.005), dec!(0.006)], dec!(0.016), "USD").unwrap();
+        assert_eq!(rounded, vec![dec!(0.00), dec!(0.01), dec!(0.01)]);
+        assert_eq!(rounded.iter().sum::<Decimal>(), dec!(0.02));
+
+        // More than a penny per line is not rounding
+        let mismatch = service.distribute_rounding(&[dec!(1), dec!(2)], dec!(1000000), "USD").unwrap_err();
+        assert_eq!(mismatch, TaxMismatch { expected: dec!(1000000), actual: dec!(3) });
+        assert!(service.distribute_rounding(&[], dec!(0.01), "USD").is_err());
+
+        // Whole yen
+        let rounded = service.distribute_rounding(&[dec!(33.4), dec!(33.3), dec!(33.3)], dec!(100), "JPY").unwrap();
+        assert_eq!(rounded, vec![dec!(34), dec!(33), dec!(33)]);
+    }
+
+    #[test]
+    fn test_rounding_at_subtotal() {
+        let mut settings = RustCommerceSettings::default();
+        let rates = vec![create_test_rate(dec!(7.5), "US", 1, false)];
+        let request_lines: Vec<TaxRequestLine> = (0..3)
+            .map(|i| TaxRequestLine {
+                id: i.to_string(),
+                product_id: None,
+                sku: None,
+                description: String::new(),
+                quantity: 1,
+                amount: dec!(0.10),
+                tax_class: "standard".to_string(),
+                tax_code: None,
+            })
+            .collect();
+        let request = TaxRequest {
+            cart_hash: String::new(),
+            customer_id: None,
+            currency: "USD".to_string(),
+            origin: Address::default(),
+            destination: Address { country: "US".to_string(), ..Address::default() },
+            lines: request_lines,
+            shipping: Decimal::ZERO,
+            prices_include_tax: false,
+        };
+
+        // Per line: 0.0075 rounds to 0.01 three times
+        let per_line = TaxService::new(settings.clone()).calculate_request_with_rates(&request, &rates);
+        assert_eq!(per_line.total_tax, dec!(0.03));
+
+        // At subtotal: 0.0225 rounds to 0.02, spread over the lines
+        settings.tax.rounding_at_subtotal = true;
+        let at_subtotal = TaxService::new(settings).calculate_request_with_rates(&request, &rates);
+        assert_eq!(at_subtotal.total_tax, dec!(0.02));
+        assert_eq!(at_subtotal.lines.iter().map(|l| l.tax).sum::<Decimal>(), dec!(0.02));
+        assert!(at_subtotal.lines.iter().all(|l| l.taxes[0].tax_amount == l.tax));
+    }
+
+    fn exemption(
+        customer_id: Uuid,
+        status: crate::models::tax::TaxExemptionStatus,
+        expires_at: Option<NaiveDate>,
+        state: &str,
+    ) -> TaxExemption {
+        TaxExemption {
+            id: Uuid::now_v7(),
+            site_id: None,
+            customer_id,
+            certificate_id: "TX-123".to_string(),
+            exemption_type: crate::models::tax::TaxExemptionType::Resale,
+            jurisdictions: vec![TaxJurisdiction { country: "US".to_string(), state: state.to_string() }],
+            status,
+            document_url: None,
+            expires_at,
+            notes: None,
+            created_at: chrono::Utc::now(),
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn test_find_exemption() {
+        use crate::models::tax::TaxExemptionStatus;
+
+        let service = TaxService::new(RustCommerceSettings::default());
+        let customer_id = Uuid::now_v7();
+        let exemption = |status, expires_at, state: &str| exemption(customer_id, status, expires_at, state);
+
+        let date = NaiveDate::from_ymd_opt(2026, 6, 1).unwrap();
+        let texas = TaxLocation::new("US", "TX", "73301", "Austin");
+        let california = TaxLocation::new("US", "CA", "90210", "Beverly Hills");
+
+        let exemptions = vec![
+            exemption(TaxExemptionStatus::Pending, None, "TX"),
+            exemption(TaxExemptionStatus::Approved, NaiveDate::from_ymd_opt(2026, 1, 1), "TX"),
+        ];
+        assert!(service.find_exemption(&exemptions, customer_id, &texas, date).is_none());
+
+        let exemptions = vec![exemption(TaxExemptionStatus::Approved, NaiveDate::from_ymd_opt(2026, 6, 1), "tx")];
+        let (_, jurisdiction) = service.find_exemption(&exemptions, customer_id, &texas, date).unwrap();
+        assert_eq!(jurisdiction.to_string(), "US:tx");
+        assert!(service.find_exemption(&exemptions, customer_id, &california, date).is_none());
+        assert!(service.find_exemption(&exemptions, Uuid::now_v7(), &texas, date).is_none());
+
+        let exemptions = vec![exemption(TaxExemptionStatus::Approved, None, "")];
+        assert!(service.find_exemption(&exemptions, customer_id, &california, date).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_exempt_cart_taxes() {
+        use crate::models::tax::TaxExemptionStatus;
+
+        let service = TaxService::new(RustCommerceSettings::default());
+        let customer_id = Uuid::now_v7();
+        let state_rate = TaxRate { state: "TX".to_string(), ..create_test_rate(dec!(10), "US", 1, false) };
+        let country_rate = create_test_rate(dec!(2), "US", 2, false);
+        let rates = vec![state_rate, country_rate];
+        let texas = TaxLocation::new("US", "TX", "73301", "Austin");
+        let exemptions = vec![exemption(customer_id, TaxExemptionStatus::Approved, None, "TX")];
+
+        // Only the Texas rate is waived; the country-wide rate is still charged
+        let mut cart = Cart::new(None, Some(customer_id));
+        cart.totals.shipping_total = dec!(10);
+        let result = service.calculate_exempt_cart_taxes(&cart, &texas, &rates, &HashMap::new(), &exemptions).await;
+        assert_eq!(result.total_tax, dec!(0.20));
+        assert_eq!(result.shipping_tax, dec!(0.20));
+        let applied = result.exemption.unwrap();
+        assert_eq!(applied.exempt_amount, dec!(1));
+        assert_eq!(applied.jurisdiction, "US:TX");
+
+        // Guests and other customers pay tax
+        let mut guest = Cart::new(None, None);
+        guest.totals.shipping_total = dec!(10);
+        let result = service.calculate_exempt_cart_taxes(&guest, &texas, &rates, &HashMap::new(), &exemptions).await;
+        assert_eq!(result.total_tax, dec!(1.20));
+        assert!(result.exemption.is_none());
+    }
+
+    #[test]
+    fn test_apply_exemption_to_order() {
+        use crate::models::order::OrderTaxLine;
+        use crate::models::tax::TaxExemptionStatus;
+
+        let service = TaxService::new(RustCommerceSettings::default());
+        let customer_id = Uuid::now_v7();
+        let exemption = exemption(customer_id, TaxExemptionStatus::Approved, None, "TX");
+        let order_id = Uuid::now_v7();
+
+        let mut order: Order = serde_json::from_value(serde_json::json!({
+            "id": order_id,
+            "site_id": null,
+            "order_number": "RC-20260601-0001",
+            "customer_id": customer_id,
+            "customer_ip_address": null,
+            "customer_user_agent": null,
+            "status": "processing",
+            "parent_id": null,
+            "currency": "USD",
+            "currency_symbol": "$",
+            "prices_include_tax": false,
+            "discount_total": "0",
+            "discount_tax": "0",
+            "shipping_total": "10",
+            "shipping_tax": "1.03",
+            "cart_tax": "10.25",
+            "total": "121.28",
+            "total_tax": "11.28",
+            "billing": Address::default(),
+            "shipping": Address::default(),
+            "payment_method": null,
+            "payment_method_title": null,
+            "transaction_id": null,
+            "shipping_method": null,
+            "shipping_method_title": null,
+            "customer_note": null,
+            "date_paid": null,
+            "date_completed": null,
+            "cart_hash": null,
+            "meta": {},
+            "created_at": chrono::Utc::now(),
+            "updated_at": null,
+        })).unwrap();
+        order.line_items = Some(vec![OrderItem {
+            id: Uuid::now_v7(),
+            order_id,
+            item_type: OrderItemType::LineItem,
+            name: "Widget".to_string(),
+            quantity: 1,
+            subtotal: dec!(100),
+            subtotal_tax: dec!(10.25),
+            total: dec!(100),
+            total_tax: dec!(10.25),
+            product_id: None,
+            variation_id: None,
+            sku: None,
+            meta: serde_json::json!({}),
+            created_at: chrono::Utc::now(),
+            product_name: None,
+            product_image: None,
+            variation_attributes: None,
+        }]);
+        let state_rate = TaxRate { state: "TX".to_string(), ..create_test_rate(dec!(8.25), "US", 1, false) };
+        let country_rate = create_test_rate(dec!(2), "US", 2, false);
+        let tax_line = |rate: &TaxRate, tax: Decimal, shipping_tax: Decimal| OrderTaxLine {
+            id: Uuid::now_v7(),
+            order_id,
+            rate_id: rate.id,
+            rate_code: format!("US_{}", rate.name),
+            label: "Sales tax".to_string(),
+            compound: false,
+            rate_percent: rate.rate,
+            tax_total: tax,
+            shipping_tax_total: shipping_tax,
+            taxable_total: dec!(110),
+            exempt_amount: Decimal::ZERO,
+            exemption_id: None,
+        };
+        order.tax_lines = Some(vec![
+            tax_line(&state_rate, dec!(8.25), dec!(0.83)),
+            tax_line(&country_rate, dec!(2), dec!(0.20)),
+        ]);
+        let rates = vec![state_rate, country_rate];
+
+        let jurisdiction = &exemption.jurisdictions[0];
+        service.apply_exemption_to_order(&mut order, &exemption, jurisdiction, &rates);
+
+        // The Texas rate is waived; the country-wide rate is still charged
+        assert_eq!(order.total, dec!(112.20));
+        assert_eq!(order.total_tax, dec!(2.20));
+        assert_eq!(order.cart_tax, dec!(2));
+        assert_eq!(order.shipping_tax, dec!(0.20));
+        assert_eq!(order.line_items.as_ref().unwrap()[0].total_tax, dec!(2));
+
+        let tax_lines = order.tax_lines.as_ref().unwrap();
+        assert_eq!(tax_lines[0].tax_total, Decimal::ZERO);
+        assert_eq!(tax_lines[0].exempt_amount, dec!(9.08));
+        assert_eq!(tax_lines[0].exemption_id, Some(exemption.id));
+        assert_eq!(tax_lines[1].tax_total, dec!(2));
+        assert!(tax_lines[1].exemption_id.is_none());
+
+        let applied = order.tax_exemption.unwrap();
+        assert_eq!(applied.exempt_amount, dec!(9.08));
+        assert_eq!(applied.certificate_id, "TX-123");
+    }
 }
@@ -0,0 +1,708 @@
+//! Tax Rate Import Service
+//!
+//! Imports and exports tax rates as CSV using WooCommerce's column layout,
+//! so rate tables can be maintained in a spreadsheet.
+
+use rust_decimal::Decimal;
+use uuid::Uuid;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+use crate::csv::csv_line;
+use crate::geo;
+use crate::models::tax::{TaxRate, TaxRateImportError, TaxRateImportMode, TaxRateImportRequest, TaxRateImportSummary};
+use crate::settings::RustCommerceSettings;
+
+/// CSV columns, in WooCommerce's order
+pub const TAX_RATE_CSV_HEADERS: [&str; 10] = [
+    "Country code",
+    "State code",
+    "Postcode / ZIP",
+    "City",
+    "Rate %",
+    "Tax name",
+    "Priority",
+    "Compound",
+    "Shipping",
+    "Tax class",
+];
+
+/// Slug of the standard tax class (blank in the CSV)
+const STANDARD_CLASS: &str = "standard";
+
+/// Tax rate import service
+pub struct TaxImportService {
+    settings: RustCommerceSettings,
+}
+
+/// Tax rate import errors
+#[derive(Debug, Clone)]
+pub enum TaxImportError {
+    InvalidRows(usize),
+}
+
+impl std::fmt::Display for TaxImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidRows(count) => write!(f, "Import has {} invalid row(s); no rates were changed", count),
+        }
+    }
+}
+
+impl std::error::Error for TaxImportError {}
+
+/// A validated CSV row
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaxRateRow {
+    pub line: usize,
+    pub country: String,
+    pub state: String,
+    pub postcode: String,
+    pub city: String,
+    pub rate: Decimal,
+    pub name: String,
+    pub priority: i32,
+    pub compound: bool,
+    pub shipping: bool,
+    pub tax_class: String,
+}
+
+impl TaxRateRow {
+    /// Identity used to match rows against existing rates
+    fn key(&self) -> RateKey {
+        RateKey::new(&self.country, &self.state, &self.postcode, &self.city, &self.tax_class, self.priority, &self.name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct RateKey(String, String, String, String, String, i32, String);
+
+impl RateKey {
+    fn new(country: &str, state: &str, postcode: &str, city: &str, tax_class: &str, priority: i32, name: &str) -> Self {
+        Self(
+            country.to_uppercase(),
+            state.to_uppercase(),
+            postcode.to_uppercase(),
+            city.to_lowercase(),
+            tax_class.to_lowercase(),
+            priority,
+            name.trim().to_lowercase(),
+        )
+    }
+
+    fn of(rate: &TaxRate) -> Self {
+        Self::new(&rate.country, &rate.state, &rate.postcode, &rate.city, &rate.tax_class, rate.priority, &rate.name)
+    }
+}
+
+/// Result of parsing a CSV file
+#[derive(Debug, Clone, Default)]
+pub struct ParsedTaxRates {
+    /// Non-blank data rows in the file
+    pub records: usize,
+    pub rows: Vec<TaxRateRow>,
+    pub errors: Vec<TaxRateImportError>,
+}
+
+/// Changes an import would make
+#[derive(Debug, Clone, Default)]
+pub struct TaxRateImportPlan {
+    pub mode: TaxRateImportMode,
+    pub rows: usize,
+    pub create: Vec<TaxRate>,
+    pub update: Vec<TaxRate>,
+    pub delete: Vec<Uuid>,
+    pub unchanged: usize,
+    pub errors: Vec<TaxRateImportError>,
+}
+
+impl TaxRateImportPlan {
+    /// Check if the import can be applied
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Summary for the API response
+    pub fn summary(&self, dry_run: bool) -> TaxRateImportSummary {
+        TaxRateImportSummary {
+            dry_run,
+            mode: self.mode,
+            rows: self.rows,
+            created: self.create.len(),
+            updated: self.update.len(),
+            deleted: self.delete.len(),
+            unchanged: self.unchanged,
+            errors: self.errors.clone(),
+        }
+    }
+}
+
+impl TaxImportService {
+    /// Create a new tax import service
+    pub fn new(settings: RustCommerceSettings) -> Self {
+        Self { settings }
+    }
+
+    /// Tax class slugs rates can be imported into
+    pub fn tax_classes(&self) -> Vec<String> {
+        let mut classes = vec![STANDARD_CLASS.to_string()];
+        classes.extend(self.settings.tax.additional_tax_classes.iter().map(|c| slugify(c)));
+        classes
+    }
+
+    /// Export rates as CSV, optionally for a single tax class
+    pub fn export_csv(&self, rates: &[TaxRate], tax_class: Option<&str>) -> String {
+        let mut rates: Vec<&TaxRate> = rates.iter()
+            .filter(|r| tax_class.is_none_or(|c| r.tax_class.eq_ignore_ascii_case(c)))
+            .collect();
+        rates.sort_by(|a, b| a.tax_class.cmp(&b.tax_class).then(a.tax_order.cmp(&b.tax_order)));
+
+        let mut csv = csv_line(TAX_RATE_CSV_HEADERS.iter().map(|h| h.to_string()));
+        for rate in rates {
+            let any = |value: &str| if value.is_empty() { "*".to_string() } else { value.to_string() };
+            let flag = |value: bool| if value { "1" } else { "0" }.to_string();
+            let class = if rate.tax_class == STANDARD_CLASS { String::new() } else { rate.tax_class.clone() };
+
+            csv.push_str(&csv_line([
+                any(&rate.country),
+                any(&rate.state),
+                any(&rate.postcode),
+                any(&rate.city),
+                format!("{:.4}", rate.rate),
+                rate.name.clone(),
+                rate.priority.to_string(),
+                flag(rate.compound),
+                flag(rate.shipping),
+                class,
+            ].into_iter()));
+        }
+        csv
+    }
+
+    /// File name for an export, with the tax class slugified so that it is
+    /// safe in a `Content-Disposition` header
+    pub fn export_file_name(&self, tax_class: Option<&str>) -> String {
+        match tax_class.map(slugify).filter(|class| !class.is_empty()) {
+            Some(class) => format!("tax_rates_{}.csv", class),
+            None => "tax_rates.csv".to_string(),
+        }
+    }
+
+    /// Parse and validate CSV rows
+    pub fn parse_csv(&self, csv: &str, tax_class: Option<&str>) -> ParsedTaxRates {
+        let mut parsed = ParsedTaxRates::default();
+
+        let records = match read_csv(csv) {
+            Ok(records) => records,
+            Err(error) => {
+                parsed.errors.push(error);
+                return parsed;
+            }
+        };
+
+        let mut records = records.into_iter()
+            .filter(|(_, record)| record.iter().any(|v| !v.trim().is_empty()));
+        let Some((header_line, header)) = records.next() else {
+            parsed.errors.push(row_error(1, None, "File is empty"));
+            return parsed;
+        };
+
+        // Columns are located by name so they may appear in any order
+        let columns: Vec<(&str, usize)> = TAX_RATE_CSV_HEADERS.iter()
+            .filter_map(|name| {
+                header.iter()
+                    .position(|h| h.trim().eq_ignore_ascii_case(name))
+                    .map(|i| (*name, i))
+            })
+            .collect();
+        for required in ["Country code", "Rate %"] {
+            if !columns.iter().any(|(name, _)| *name == required) {
+                parsed.errors.push(row_error(header_line, Some(required), "Missing column"));
+            }
+        }
+        if !parsed.errors.is_empty() {
+            return parsed;
+        }
+
+        let classes = self.tax_classes();
+        let target_class = tax_class.map(slugify);
+        let mut seen: HashMap<RateKey, usize> = HashMap::new();
+
+        for (line, record) in records {
+            parsed.records += 1;
+            let values: HashMap<&str, &str> = columns.iter()
+                .map(|(name, i)| (*name, record.get(*i).map_or("", |v| v.trim())))
+                .collect();
+
+            match self.parse_row(line, &values, target_class.as_deref(), &classes) {
+                Ok(row) => {
+                    if let Some(first) = seen.insert(row.key(), line) {
+                        parsed.errors.push(row_error(line, None, &format!("Duplicate of line {}", first)));
+                    } else {
+                        parsed.rows.push(row);
+                    }
+                }
+                Err(mut errors) => parsed.errors.append(&mut errors),
+            }
+        }
+
+        parsed
+    }
+
+    /// Validate a single row
+    fn parse_row(
+        &self,
+        line: usize,
+        values: &HashMap<&str, &str>,
+        target_class: Option<&str>,
+        classes: &[String],
+    ) -> Result<TaxRateRow, Vec<TaxRateImportError>> {
+        let mut errors = Vec::new();
+        let field = |column: &str| values.get(column).copied().unwrap_or("");
+        let any = |value: &str| if value == "*" { String::new() } else { value.to_string() };
+
+        // Country
+        let country = match any(field("Country code")).as_str() {
+            "" => String::new(),
+            code => match geo::normalize_country(code) {
+                Some(code) => code.to_string(),
+                None => {
+                    errors.push(row_error(line, Some("Country code"), &format!("Unknown country code {}", code)));
+                    String::new()
+                }
+            },
+        };
+
+        // State
+        let state = match any(field("State code")).as_str() {
+            "" => String::new(),
+            _ if country.is_empty() => {
+                errors.push(row_error(line, Some("State code"), "State code requires a country code"));
+                String::new()
+            }
+            state => match geo::normalize_subdivision(&country, state) {
+                Some(code) => code.to_string(),
+                None if geo::country(&country).is_some_and(|c| c.has_subdivisions()) => {
+                    errors.push(row_error(line, Some("State code"), &format!("Unknown state code {} for {}", state, country)));
+                    String::new()
+                }
+                None => state.to_uppercase(),
+            },
+        };
+
+        // Postcodes: ";"-separated, with wildcards and ranges
+        let postcodes: Vec<String> = any(field("Postcode / ZIP")).split(';')
+            .map(|p| p.trim().to_uppercase())
+            .filter(|p| !p.is_empty())
+            .collect();
+        for postcode in &postcodes {
+            if let Some((start, end)) = postcode.split_once("...") {
+                if start.is_empty() || end.is_empty() {
+                    errors.push(row_error(line, Some("Postcode / ZIP"), &format!("Incomplete postcode range {}", postcode)));
+                }
+            }
+        }
+
+        let cities: Vec<&str> = field("City").split(';')
+            .map(str::trim)
+            .filter(|c| !c.is_empty() && *c != "*")
+            .collect();
+
+        // Rate
+        let rate = match Decimal::from_str(field("Rate %")) {
+            Ok(rate) if rate >= Decimal::ZERO && rate <= Decimal::from(100) => rate,
+            Ok(_) => {
+                errors.push(row_error(line, Some("Rate %"), "Rate must be between 0 and 100"));
+                Decimal::ZERO
+            }
+            Err(_) => {
+                errors.push(row_error(line, Some("Rate %"), &format!("Invalid rate {}", field("Rate %"))));
+                Decimal::ZERO
+            }
+        };
+
+        let name = match field("Tax name") {
+            "" => "Tax".to_string(),
+            name => name.to_string(),
+        };
+
+        let priority = match field("Priority") {
+            "" => 1,
+            value => match value.parse::<i32>() {
+                Ok(priority) if priority >= 1 => priority,
+                _ => {
+                    errors.push(row_error(line, Some("Priority"), &format!("Invalid priority {}", value)));
+                    1
+                }
+            },
+        };
+
+        let mut flag = |column: &str, default: bool| match parse_flag(field(column)) {
+            Some(value) => value.unwrap_or(default),
+            None => {
+                errors.push(row_error(line, Some(column), &format!("Expected 1 or 0, got {}", field(column))));
+                default
+            }
+        };
+        let compound = flag("Compound", false);
+        let shipping = flag("Shipping", true);
+
+        // Tax class: blank is the standard class, or the class being imported into
+        let tax_class = match (slugify(field("Tax class")), target_class) {
+            (class, Some(target)) if class.is_empty() || class == target => target.to_string(),
+            (class, Some(target)) => {
+                errors.push(row_error(line, Some("Tax class"), &format!("Row is for {}, not {}", class, target)));
+                target.to_string()
+            }
+            (class, None) if class.is_empty() => STANDARD_CLASS.to_string(),
+            (class, None) => class,
+        };
+        if !classes.contains(&tax_class) {
+            errors.push(row_error(line, Some("Tax class"), &format!("Unknown tax class {}", tax_class)));
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(TaxRateRow {
+            line,
+            country,
+            state,
+            postcode: postcodes.join(";"),
+            city: cities.join(";"),
+            rate,
+            name,
+            priority,
+            compound,
+            shipping,
+            tax_class,
+        })
+    }
+
+    /// Work out the changes an import makes to the existing rates.
+    ///
+    /// Rows are matched to existing rates on location, class, priority and
+    /// name, so matched rates keep their IDs. In replace mode, unmatched
+    /// rates of the imported classes are deleted and the file order becomes
+    /// the rate order.
+    pub fn plan_import(
+        &self,
+        existing: &[TaxRate],
+        request: &TaxRateImportRequest,
+        site_id: Option<Uuid>,
+    ) -> TaxRateImportPlan {
+        let ParsedTaxRates { records, rows, errors } = self.parse_csv(&request.csv, request.tax_class.as_deref());
+        let mut plan = TaxRateImportPlan {
+            mode: request.mode,
+            rows: records,
+            errors,
+            ..TaxRateImportPlan::default()
+        };
+
+        let by_key: HashMap<RateKey, &TaxRate> = existing.iter().map(|r| (RateKey::of(r), r)).collect();
+        let mut next_order: HashMap<String, i32> = HashMap::new();
+        for rate in existing {
+            let order = next_order.entry(rate.tax_class.clone()).or_insert(0);
+            *order = (*order).max(rate.tax_order + 1);
+        }
+
+        let replace = request.mode == TaxRateImportMode::Replace;
+        let mut positions: HashMap<String, i32> = HashMap::new();
+        let mut matched: HashSet<Uuid> = HashSet::new();
+
+        for row in &rows {
+            let position = positions.entry(row.tax_class.clone()).or_insert(0);
+            let file_order = *position;
+            *position += 1;
+
+            match by_key.get(&row.key()) {
+                Some(current) => {
+                    matched.insert(current.id);
+                    let tax_order = if replace { file_order } else { current.tax_order };
+                    if current.rate == row.rate
+                        && current.compound == row.compound
+                        && current.shipping == row.shipping
+                        && current.tax_order == tax_order
+                    {
+                        plan.unchanged += 1;
+                    } else {
+                        plan.update.push(TaxRate {
+                            rate: row.rate,
+                            compound: row.compound,
+                            shipping: row.shipping,
+                            tax_order,
+                            ..(*current).clone()
+                        });
+                    }
+                }
+                None => {
+                    let tax_order = if replace {
+                        file_order
+                    } else {
+                        let next = next_order.entry(row.tax_class.clone()).or_insert(0);
+                        *next += 1;
+                        *next - 1
+                    };
+                    plan.create.push(TaxRate {
+                        id: Uuid::now_v7(),
+                        site_id,
+                        country: row.country.clone(),
+                        state: row.state.clone(),
+                        postcode: row.postcode.clone(),
+                        city: row.city.clone(),
+                        rate: row.rate,
+                        name: row.name.clone(),
+                        priority: row.priority,
+                        compound: row.compound,
+                        shipping: row.shipping,
+                        tax_order,
+                        tax_class: row.tax_class.clone(),
+                        created_at: chrono::Utc::now(),
+                    });
+                }
+            }
+        }
+
+        if replace {
+            let classes: HashSet<String> = match request.tax_class {
+                Some(ref class) => HashSet::from([slugify(class)]),
+                None => rows.iter().map(|r| r.tax_class.clone()).collect(),
+            };
+            plan.delete = existing.iter()
+                .filter(|r| classes.contains(&r.tax_class.to_lowercase()) && !matched.contains(&r.id))
+                .map(|r| r.id)
+                .collect();
+        }
+
+        plan
+    }
+
+    /// Apply a plan to the existing rates, returning the new rate table
+    pub fn apply_plan(&self, existing: &[TaxRate], plan: &TaxRateImportPlan) -> Result<Vec<TaxRate>, TaxImportError> {
+        if !plan.is_valid() {
+            return Err(TaxImportError::InvalidRows(plan.errors.len()));
+        }
+
+        let updates: HashMap<Uuid, &TaxRate> = plan.update.iter().map(|r| (r.id, r)).collect();
+        let mut rates: Vec<TaxRate> = existing.iter()
+            .filter(|r| !plan.delete.contains(&r.id))
+            .map(|r| updates.get(&r.id).map_or_else(|| r.clone(), |u| (*u).clone()))
+            .collect();
+        rates.extend(plan.create.iter().cloned());
+        Ok(rates)
+    }
+}
+
+fn row_error(line: usize, column: Option<&str>, message: &str) -> TaxRateImportError {
+    TaxRateImportError {
+        line,
+        column: column.map(str::to_string),
+        message: message.to_string(),
+    }
+}
+
+/// Parse a 1/0 style flag; Some(None) when blank
+fn parse_flag(value: &str) -> Option<Option<bool>> {
+    match value.to_lowercase().as_str() {
+        "" => Some(None),
+        "1" | "yes" | "true" => Some(Some(true)),
+        "0" | "no" | "false" => Some(Some(false)),
+        _ => None,
+    }
+}
+
+/// Tax class slug, e.g. "Reduced rate" -> "reduced-rate"
+fn slugify(s: &str) -> String {
+    s.trim()
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Read CSV records with the line number each starts on
+fn read_csv(input: &str) -> Result<Vec<(usize, Vec<String>)>, TaxRateImportError> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes => {
+                if chars.peek() == Some(&'"') {
+                    field.push('"');
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            }
+            '"' if field.is_empty() => in_quotes = true,
+            '\n' if in_quotes => {
+                field.push(c);
+                line += 1;
+            }
+            ',' if !in_quotes => record.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                records.push((record_line, std::mem::take(&mut record)));
+                line += 1;
+                record_line = line;
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(row_error(record_line, None, "Unterminated quoted field"));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push((record_line, record));
+    }
+
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn rate(country: &str, state: &str, rate: Decimal, name: &str, tax_class: &str) -> TaxRate {
+        TaxRate {
+            id: Uuid::now_v7(),
+            site_id: None,
+            country: country.to_string(),
+            state: state.to_string(),
+            postcode: String::new(),
+            city: String::new(),
+            rate,
+            name: name.to_string(),
+            priority: 1,
+            compound: false,
+            shipping: true,
+            tax_order: 0,
+            tax_class: tax_class.to_string(),
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    fn request(csv: &str, mode: TaxRateImportMode) -> TaxRateImportRequest {
+        TaxRateImportRequest {
+            csv: csv.to_string(),
+            mode,
+            dry_run: true,
+            tax_class: None,
+        }
+    }
+
+    const HEADER: &str = "Country code,State code,Postcode / ZIP,City,Rate %,Tax name,Priority,Compound,Shipping,Tax class\n";
+
+    #[test]
+    fn test_export_round_trip() {
+        let service = TaxImportService::new(RustCommerceSettings::default());
+        let mut ca = rate("US", "CA", dec!(7.25), "CA, state", "standard");
+        ca.postcode = "90210;902*".to_string();
+        let rates = vec![ca, rate("GB", "", dec!(5), "VAT", "reduced-rate")];
+
+        let csv = service.export_csv(&rates, None);
+        assert!(csv.starts_with("Country code,State code,Postcode / ZIP,City,Rate %"));
+        assert!(csv.contains("GB,*,*,*,5.0000,VAT,1,0,1,reduced-rate\r\n"));
+        assert!(csv.contains("US,CA,90210;902*,*,7.2500,\"CA, state\",1,0,1,\r\n"));
+
+        let parsed = service.parse_csv(&csv, None);
+        assert!(parsed.errors.is_empty(), "{:?}", parsed.errors);
+        assert_eq!(parsed.rows.len(), 2);
+        assert_eq!(parsed.rows[1].name, "CA, state");
+        assert_eq!(parsed.rows[1].postcode, "90210;902*");
+        assert_eq!(parsed.rows[1].tax_class, "standard");
+
+        // Unchanged on re-import
+        let plan = service.plan_import(&rates, &request(&csv, TaxRateImportMode::Merge), None);
+        assert_eq!((plan.create.len(), plan.update.len(), plan.unchanged), (0, 0, 2));
+
+        // Rate names can't run as spreadsheet formulas
+        let formula = rate("GB", "", dec!(5), "=HYPERLINK(\"http://x\")", "standard");
+        assert!(service.export_csv(&[formula], None).contains(",\"'=HYPERLINK(\"\"http://x\"\")\","));
+
+        assert_eq!(service.export_file_name(Some("Reduced rate\"; x=\"")), "tax_rates_reduced-rate-x.csv");
+        assert_eq!(service.export_file_name(Some("../")), "tax_rates.csv");
+        assert_eq!(service.export_file_name(None), "tax_rates.csv");
+    }
+
+    #[test]
+    fn test_dry_run_reports_bad_rows() {
+        let service = TaxImportService::new(RustCommerceSettings::default());
+        let csv = format!(
+            "{}{}{}{}{}",
+            HEADER,
+            "US,CA,,,7.25,CA Tax,1,0,1,\n",
+            "XX,,,,5,Bad country,1,0,1,\n",
+            "US,ZZ,,,abc,Bad,0,maybe,1,luxury\n",
+            "usa,california,,,8,CA Tax,1,0,1,\n",
+        );
+
+        let plan = service.plan_import(&[], &request(&csv, TaxRateImportMode::Merge), None);
+        assert_eq!(plan.rows, 4);
+        assert_eq!(plan.create.len(), 1);
+        assert!(!plan.is_valid());
+
+        let problems: Vec<(usize, Option<&str>)> = plan.errors.iter()
+            .map(|e| (e.line, e.column.as_deref()))
+            .collect();
+        assert_eq!(problems, vec![
+            (3, Some("Country code")),
+            (4, Some("State code")),
+            (4, Some("Rate %")),
+            (4, Some("Priority")),
+            (4, Some("Compound")),
+            (4, Some("Tax class")),
+            (5, None), // duplicate of line 2 once normalised
+        ]);
+
+        assert!(service.apply_plan(&[], &plan).is_err());
+    }
+
+    #[test]
+    fn test_merge_and_replace() {
+        let service = TaxImportService::new(RustCommerceSettings::default());
+        let existing = vec![
+            rate("DE", "", dec!(19), "MwSt", "standard"),
+            rate("FR", "", dec!(20), "TVA", "standard"),
+            rate("DE", "", dec!(7), "MwSt", "reduced-rate"),
+        ];
+        let csv = format!("{}{}{}", HEADER, "DE,,,,19.5,MwSt,1,0,1,\n", "AT,,,,20,USt,1,0,1,\n");
+
+        let plan = service.plan_import(&existing, &request(&csv, TaxRateImportMode::Merge), None);
+        assert_eq!((plan.create.len(), plan.update.len(), plan.delete.len()), (1, 1, 0));
+        assert_eq!(plan.update[0].id, existing[0].id);
+
+        let rates = service.apply_plan(&existing, &plan).unwrap();
+        assert_eq!(rates.len(), 4);
+        assert_eq!(rates[0].rate, dec!(19.5));
+
+        // Replace only touches the standard class
+        let plan = service.plan_import(&existing, &request(&csv, TaxRateImportMode::Replace), None);
+        assert_eq!(plan.delete, vec![existing[1].id]);
+        let rates = service.apply_plan(&existing, &plan).unwrap();
+        let names: Vec<(&str, &str)> = rates.iter().map(|r| (r.name.as_str(), r.tax_class.as_str())).collect();
+        assert_eq!(names, vec![("MwSt", "standard"), ("MwSt", "reduced-rate"), ("USt", "standard")]);
+        assert_eq!(rates[2].tax_order, 1);
+    }
+
+    #[test]
+    fn test_read_csv_quotes() {
+        let records = read_csv("\u{feff}a,\"b,c\",\"say \"\"hi\"\"\"\r\n\"multi\nline\",x\n").unwrap();
+        assert_eq!(records[0], (1, vec!["a".to_string(), "b,c".to_string(), "say \"hi\"".to_string()]));
+        assert_eq!(records[1], (2, vec!["multi\nline".to_string(), "x".to_string()]));
+        assert!(read_csv("\"open").is_err());
+    }
+}
@@ -46,6 +46,10 @@ pub struct GeneralSettings {
     pub thousand_separator: String,
     pub decimal_separator: String,
     pub number_of_decimals: u8,
+    /// Store locale for price formatting (e.g. `de-CH`); when unset the
+    /// separators and currency position above are used
+    #[serde(default)]
+    pub locale: Option<String>,
 }
 
 impl Default for GeneralSettings {
@@ -63,10 +67,18 @@ impl Default for GeneralSettings {
             thousand_separator: ",".to_string(),
             decimal_separator: ".".to_string(),
             number_of_decimals: 2,
+            locale: None,
         }
     }
 }
 
+impl GeneralSettings {
+    /// ISO 4217 minor units of the store currency, used for rounding money
+    pub fn currency_decimals(&self) -> u32 {
+        crate::currency::minor_units(&self.currency)
+    }
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum CurrencyPosition {
@@ -176,6 +188,10 @@ pub struct CheckoutSettings {
     pub enable_order_notes: bool,
     pub require_phone: bool,
     pub require_company: bool,
+    /// Suggest corrected addresses before the order is placed
+    pub suggest_address_corrections: bool,
+    /// Address verification provider ID
+    pub address_verification_provider: String,
 }
 
 impl Default for CheckoutSettings {
@@ -191,6 +207,8 @@ impl Default for CheckoutSettings {
             enable_order_notes: true,
             require_phone: false,
             require_company: false,
+            suggest_address_corrections: true,
+            address_verification_provider: "local".to_string(),
         }
     }
 }
@@ -233,12 +251,23 @@ pub struct TaxSettings {
     pub prices_include_tax: bool,
     pub calculate_tax_based_on: TaxBasedOn,
     pub shipping_tax_class: String,
+    /// Round tax per rate at subtotal instead of per line
     pub rounding_at_subtotal: bool,
+    /// How half-cent tax amounts are rounded
+    pub tax_rounding_mode: TaxRoundingMode,
     pub additional_tax_classes: Vec<String>,
     pub display_prices_in_shop: TaxDisplay,
     pub display_prices_in_cart: TaxDisplay,
     pub price_display_suffix: String,
     pub display_tax_totals: TaxTotalsDisplay,
+    /// Tax calculation provider ID ("table" uses the rate table)
+    pub tax_provider: String,
+    /// Provider timeout before falling back to the rate table
+    pub tax_provider_timeout_ms: u64,
+    /// How long provider results are cached per cart
+    pub tax_cache_ttl_seconds: u64,
+    /// EU VAT One-Stop-Shop and reverse charge
+    pub eu_vat: EuVatSettings,
 }
 
 impl Default for TaxSettings {
@@ -249,15 +278,62 @@ impl Default for TaxSettings {
             calculate_tax_based_on: TaxBasedOn::ShippingAddress,
             shipping_tax_class: String::new(),
             rounding_at_subtotal: false,
+            tax_rounding_mode: TaxRoundingMode::HalfUp,
             additional_tax_classes: vec!["reduced-rate".to_string(), "zero-rate".to_string()],
             display_prices_in_shop: TaxDisplay::ExcludingTax,
             display_prices_in_cart: TaxDisplay::ExcludingTax,
             price_display_suffix: String::new(),
             display_tax_totals: TaxTotalsDisplay::Itemized,
+            tax_provider: "table".to_string(),
+            tax_provider_timeout_ms: 3000,
+            tax_cache_ttl_seconds: 900,
+            eu_vat: EuVatSettings::default(),
+        }
+    }
+}
+
+/// EU VAT settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EuVatSettings {
+    /// Charge destination-country VAT on EU distance sales (OSS)
+    pub enable_oss: bool,
+    /// EU-wide distance sales threshold in EUR; below it origin VAT applies
+    pub distance_sales_threshold: Decimal,
+    /// Use destination rates even below the threshold
+    pub always_use_destination_rates: bool,
+    /// VAT number field on the checkout form
+    pub vat_number_field: VatNumberField,
+    /// VAT number provider ID ("local", "vies")
+    pub vat_validation_provider: String,
+    /// Accept well-formed numbers when the lookup service is down
+    pub accept_on_validation_outage: bool,
+    /// Note printed on reverse charge invoices
+    pub reverse_charge_note: String,
+}
+
+impl Default for EuVatSettings {
+    fn default() -> Self {
+        Self {
+            enable_oss: false,
+            distance_sales_threshold: Decimal::from(10_000),
+            always_use_destination_rates: false,
+            vat_number_field: VatNumberField::Optional,
+            vat_validation_provider: "local".to_string(),
+            accept_on_validation_outage: true,
+            reverse_charge_note: "Reverse charge: VAT to be accounted for by the recipient (Article 196, Directive 2006/112/EC)".to_string(),
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum VatNumberField {
+    Hidden,
+    #[default]
+    Optional,
+    Required,
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum TaxBasedOn {
@@ -283,6 +359,19 @@ pub enum TaxTotalsDisplay {
     Single,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TaxRoundingMode {
+    /// 0.005 rounds away from zero
+    #[default]
+    HalfUp,
+    /// 0.005 rounds towards zero
+    HalfDown,
+    /// 0.005 rounds to the even digit (banker's rounding)
+    #[serde(alias = "bankers")]
+    HalfEven,
+}
+
 /// Payment settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PaymentSettings {
@@ -2,9 +2,13 @@
 //!
 //! Shortcodes for embedding e-commerce content in pages and posts.
 
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::services::pricing::PricingService;
+use crate::settings::RustCommerceSettings;
+
 /// Shortcode definition
 #[derive(Debug, Clone)]
 pub struct Shortcode {
@@ -291,6 +295,25 @@ pub fn get_shortcodes() -> Vec<Shortcode> {
             ],
             example: r#"[rc_add_to_cart_url id="123"]"#.to_string(),
         },
+        Shortcode {
+            tag: "rc_price".to_string(),
+            description: "Display an amount formatted for a locale".to_string(),
+            attributes: vec![
+                ShortcodeAttribute {
+                    name: "amount".to_string(),
+                    description: "Amount in the store currency".to_string(),
+                    default: None,
+                    required: true,
+                },
+                ShortcodeAttribute {
+                    name: "locale".to_string(),
+                    description: "Locale to format for, e.g. de-CH (defaults to the store locale)".to_string(),
+                    default: None,
+                    required: false,
+                },
+            ],
+            example: r#"[rc_price amount="1299.90" locale="en-IN"]"#.to_string(),
+        },
     ]
 }
 
@@ -300,6 +323,7 @@ pub fn render_shortcode(tag: &str, attributes: &HashMap<String, String>) -> Stri
         "rc_cart" => render_cart(),
         "rc_checkout" => render_checkout(),
         "rc_my_account" => render_my_account(),
+        "rc_price" => render_price(attributes),
         _ => format!("<!-- Unknown shortcode: {} -->", tag),
     }
 }
@@ -315,3 +339,17 @@ fn render_checkout() -> String {
 fn render_my_account() -> String {
     r#"<div class="rc-my-account" data-rc-my-account></div>"#.to_string()
 }
+
+fn render_price(attributes: &HashMap<String, String>) -> String {
+    let Some(amount) = attributes.get("amount").and_then(|a| a.trim().parse::<Decimal>().ok()) else {
+        return "<!-- rc_price: invalid amount -->".to_string();
+    };
+
+    // Would load from database
+    let mut pricing = PricingService::new(RustCommerceSettings::default());
+    if let Some(locale) = attributes.get("locale") {
+        pricing = pricing.with_locale(locale);
+    }
+
+    format!(r#"<span class="rc-price">{}</span>"#, pricing.format_price(amount))
+}
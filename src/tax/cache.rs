@@ -0,0 +1,83 @@
+//! Tax Result Cache
+//!
+//! Keeps provider responses per cart so that external services are not
+//! called again until the cart, addresses or provider change.
+
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use super::provider::TaxResponse;
+
+/// In-memory cache of tax responses keyed by `TaxRequest::cache_key`
+pub struct TaxResultCache {
+    entries: RwLock<HashMap<String, (Instant, TaxResponse)>>,
+    ttl: Duration,
+}
+
+impl TaxResultCache {
+    /// Create a cache whose entries expire after `ttl`
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            entries: RwLock::new(HashMap::new()),
+            ttl,
+        }
+    }
+
+    /// Get a cached response if it has not expired
+    pub fn get(&self, key: &str) -> Option<TaxResponse> {
+        self.entries.read()
+            .get(key)
+            .filter(|(stored, _)| stored.elapsed() < self.ttl)
+            .map(|(_, response)| response.clone())
+    }
+
+    /// Store a response
+    pub fn insert(&self, key: String, response: TaxResponse) {
+        let mut entries = self.entries.write();
+        entries.retain(|_, (stored, _)| stored.elapsed() < self.ttl);
+        entries.insert(key, (Instant::now(), response));
+    }
+
+    /// Drop every entry for a cart
+    pub fn invalidate_cart(&self, cart_hash: &str) {
+        let prefix = format!("{}:", cart_hash);
+        self.entries.write().retain(|key, _| !key.starts_with(&prefix));
+    }
+
+    /// Drop all entries
+    pub fn clear(&self) {
+        self.entries.write().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+
+    fn response() -> TaxResponse {
+        TaxResponse {
+            provider: "table".to_string(),
+            lines: vec![],
+            shipping_tax: Decimal::ZERO,
+            total_tax: Decimal::ONE,
+        }
+    }
+
+    #[test]
+    fn test_cache_expiry_and_invalidation() {
+        let cache = TaxResultCache::new(Duration::from_secs(60));
+        cache.insert("abc:1".to_string(), response());
+        cache.insert("def:1".to_string(), response());
+        assert!(cache.get("abc:1").is_some());
+
+        cache.invalidate_cart("abc");
+        assert!(cache.get("abc:1").is_none());
+        assert!(cache.get("def:1").is_some());
+
+        let expired = TaxResultCache::new(Duration::ZERO);
+        expired.insert("abc:1".to_string(), response());
+        assert!(expired.get("abc:1").is_none());
+    }
+}
@@ -0,0 +1,12 @@
+//! RustCommerce Tax Providers
+//!
+//! Pluggable tax calculation. The built-in rate table is the default
+//! provider and the fallback when an external service times out or fails.
+
+pub mod provider;
+pub mod table;
+pub mod cache;
+
+pub use provider::{TaxProvider, TaxProviderError, TaxProviderRegistry, TaxRequest, TaxResponse};
+pub use table::TableTaxProvider;
+pub use cache::TaxResultCache;
@@ -0,0 +1,165 @@
+//! Tax Provider Base
+//!
+//! Defines the tax calculation provider trait and registry.
+
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::models::customer::Address;
+use crate::models::tax::CalculatedTax;
+
+/// ID of the built-in rate table provider
+pub const TABLE_PROVIDER_ID: &str = "table";
+
+/// Tax provider error
+#[derive(Debug, Clone)]
+pub enum TaxProviderError {
+    NotConfigured,
+    Timeout,
+    InvalidAddress(String),
+    NetworkError(String),
+    ProviderError(String),
+}
+
+impl std::fmt::Display for TaxProviderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotConfigured => write!(f, "Tax provider not configured"),
+            Self::Timeout => write!(f, "Tax provider did not respond in time"),
+            Self::InvalidAddress(msg) => write!(f, "Invalid address: {}", msg),
+            Self::NetworkError(msg) => write!(f, "Network error: {}", msg),
+            Self::ProviderError(msg) => write!(f, "Tax provider error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for TaxProviderError {}
+
+/// Taxable line sent to a provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxRequestLine {
+    /// Cart item key or order item ID
+    pub id: String,
+    pub product_id: Option<Uuid>,
+    pub sku: Option<String>,
+    pub description: String,
+    pub quantity: i32,
+    /// Line amount after discounts
+    pub amount: Decimal,
+    pub tax_class: String,
+    /// Provider-specific product tax code
+    pub tax_code: Option<String>,
+}
+
+/// Tax calculation request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxRequest {
+    pub cart_hash: String,
+    pub customer_id: Option<Uuid>,
+    pub currency: String,
+    /// Ship-from address (the store)
+    pub origin: Address,
+    /// Ship-to address (or billing address for virtual carts)
+    pub destination: Address,
+    pub lines: Vec<TaxRequestLine>,
+    pub shipping: Decimal,
+    pub prices_include_tax: bool,
+}
+
+impl TaxRequest {
+    /// Cache key: the cart hash plus everything else that affects the result
+    pub fn cache_key(&self, provider_id: &str) -> String {
+        use sha2::{Sha256, Digest};
+
+        let mut hasher = Sha256::new();
+        hasher.update(provider_id);
+        hasher.update(serde_json::to_vec(self).unwrap_or_default());
+        format!("{}:{}", self.cart_hash, hex::encode(&hasher.finalize()[..16]))
+    }
+}
+
+/// Tax for one request line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxLineResult {
+    pub id: String,
+    pub tax: Decimal,
+    /// Breakdown by jurisdiction / rate
+    pub taxes: Vec<CalculatedTax>,
+}
+
+/// Tax calculation response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxResponse {
+    pub provider: String,
+    pub lines: Vec<TaxLineResult>,
+    pub shipping_tax: Decimal,
+    pub total_tax: Decimal,
+}
+
+/// Tax provider trait
+#[async_trait]
+pub trait TaxProvider: Send + Sync {
+    /// Get provider ID
+    fn id(&self) -> &str;
+
+    /// Get provider title
+    fn title(&self) -> &str;
+
+    /// Check if provider is available
+    fn is_available(&self) -> bool;
+
+    /// Calculate taxes for a cart or order
+    async fn calculate(&self, request: &TaxRequest) -> Result<TaxResponse, TaxProviderError>;
+
+    /// Record the order's taxes as final once it is paid
+    async fn commit(&self, _order_number: &str, _request: &TaxRequest) -> Result<(), TaxProviderError> {
+        Ok(())
+    }
+
+    /// Cancel a committed transaction when the order is refunded
+    async fn void(&self, _order_number: &str) -> Result<(), TaxProviderError> {
+        Ok(())
+    }
+}
+
+/// Tax provider registry
+pub struct TaxProviderRegistry {
+    providers: HashMap<String, Arc<dyn TaxProvider>>,
+}
+
+impl TaxProviderRegistry {
+    /// Create a new provider registry
+    pub fn new() -> Self {
+        Self {
+            providers: HashMap::new(),
+        }
+    }
+
+    /// Register a tax provider
+    pub fn register(&mut self, provider: Arc<dyn TaxProvider>) {
+        self.providers.insert(provider.id().to_string(), provider);
+    }
+
+    /// Get a provider by ID
+    pub fn get(&self, id: &str) -> Option<Arc<dyn TaxProvider>> {
+        self.providers.get(id).cloned()
+    }
+
+    /// Get available providers
+    pub fn get_available(&self) -> Vec<Arc<dyn TaxProvider>> {
+        self.providers.values()
+            .filter(|p| p.is_available())
+            .cloned()
+            .collect()
+    }
+}
+
+impl Default for TaxProviderRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,57 @@
+//! Rate Table Tax Provider
+//!
+//! Default provider: calculates taxes from the store's own tax rate table.
+//! Also used as the fallback when an external provider fails.
+
+use async_trait::async_trait;
+use parking_lot::RwLock;
+
+use super::provider::{TaxProvider, TaxProviderError, TaxRequest, TaxResponse, TABLE_PROVIDER_ID};
+use crate::models::tax::TaxRate;
+use crate::services::tax::TaxService;
+use crate::settings::RustCommerceSettings;
+
+/// Rate table tax provider
+pub struct TableTaxProvider {
+    service: TaxService,
+    rates: RwLock<Vec<TaxRate>>,
+}
+
+impl TableTaxProvider {
+    /// Create a table provider with the given rates
+    pub fn new(settings: RustCommerceSettings, rates: Vec<TaxRate>) -> Self {
+        Self {
+            service: TaxService::new(settings),
+            rates: RwLock::new(rates),
+        }
+    }
+
+    /// Replace the rates, e.g. after an import
+    pub fn set_rates(&self, rates: Vec<TaxRate>) {
+        *self.rates.write() = rates;
+    }
+
+    /// Calculate without going through the async trait
+    pub fn calculate_now(&self, request: &TaxRequest) -> TaxResponse {
+        self.service.calculate_request_with_rates(request, &self.rates.read())
+    }
+}
+
+#[async_trait]
+impl TaxProvider for TableTaxProvider {
+    fn id(&self) -> &str {
+        TABLE_PROVIDER_ID
+    }
+
+    fn title(&self) -> &str {
+        "Tax rate table"
+    }
+
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    async fn calculate(&self, request: &TaxRequest) -> Result<TaxResponse, TaxProviderError> {
+        Ok(self.calculate_now(request))
+    }
+}
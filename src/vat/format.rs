@@ -0,0 +1,166 @@
+//! VAT Number Formats
+//!
+//! Format rules for EU VAT identification numbers (and Northern Ireland's
+//! XI numbers). In a pattern `#` is a digit, `@` a letter and `?` a letter
+//! or digit; anything else is literal.
+
+use serde::{Deserialize, Serialize};
+
+use crate::geo;
+
+/// VAT number patterns by VAT prefix, excluding the prefix itself
+static VAT_FORMATS: &[(&str, &[&str])] = &[
+    ("AT", &["U########"]),
+    ("BE", &["0#########", "1#########"]),
+    ("BG", &["#########", "##########"]),
+    ("CY", &["########@"]),
+    ("CZ", &["########", "#########", "##########"]),
+    ("DE", &["#########"]),
+    ("DK", &["########"]),
+    ("EE", &["#########"]),
+    ("EL", &["#########"]),
+    ("ES", &["?#######?"]),
+    ("FI", &["########"]),
+    ("FR", &["??#########"]),
+    ("HR", &["###########"]),
+    ("HU", &["########"]),
+    ("IE", &["#?#####@", "#######@", "#######@@"]),
+    ("IT", &["###########"]),
+    ("LT", &["#########", "############"]),
+    ("LU", &["########"]),
+    ("LV", &["###########"]),
+    ("MT", &["########"]),
+    ("NL", &["#########B##"]),
+    ("PL", &["##########"]),
+    ("PT", &["#########"]),
+    ("RO", &["##", "###", "####", "#####", "######", "#######", "########", "#########", "##########"]),
+    ("SE", &["##########01"]),
+    ("SI", &["########"]),
+    ("SK", &["##########"]),
+    ("XI", &["#########", "############", "GD###", "HA###"]),
+];
+
+/// Parsed VAT identification number
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VatNumber {
+    /// VAT prefix, e.g. "DE", "EL" for Greece, "XI" for Northern Ireland
+    pub prefix: String,
+    /// Number without the prefix
+    pub number: String,
+}
+
+impl VatNumber {
+    /// ISO country code the number belongs to
+    pub fn country_code(&self) -> &str {
+        match self.prefix.as_str() {
+            "EL" => "GR",
+            "XI" => "GB",
+            prefix => prefix,
+        }
+    }
+
+    /// Full number including the prefix, e.g. "DE123456789"
+    pub fn full(&self) -> String {
+        format!("{}{}", self.prefix, self.number)
+    }
+}
+
+impl std::fmt::Display for VatNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.prefix, self.number)
+    }
+}
+
+/// VAT prefix for a country (Greece uses "EL")
+pub fn vat_prefix(country_code: &str) -> Option<&'static str> {
+    let code = geo::normalize_country(country_code)?;
+    match code {
+        "GR" => Some("EL"),
+        code if geo::is_eu_member(code) => Some(code),
+        _ => None,
+    }
+}
+
+fn formats(prefix: &str) -> Option<&'static [&'static str]> {
+    VAT_FORMATS.iter().find(|(p, _)| *p == prefix).map(|(_, f)| *f)
+}
+
+fn matches(pattern: &str, value: &str) -> bool {
+    pattern.len() == value.len()
+        && pattern.chars().zip(value.chars()).all(|(p, c)| match p {
+            '#' => c.is_ascii_digit(),
+            '@' => c.is_ascii_alphabetic(),
+            '?' => c.is_ascii_alphanumeric(),
+            literal => c == literal,
+        })
+}
+
+/// Parse and format-check a VAT number.
+///
+/// Spaces, dots and hyphens are ignored. Without a prefix, the customer's
+/// country is used to supply one.
+pub fn parse_vat_number(input: &str, country_code: Option<&str>) -> Option<VatNumber> {
+    let cleaned: String = input.chars()
+        .filter(|c| !matches!(c, ' ' | '.' | '-'))
+        .collect::<String>()
+        .to_uppercase();
+
+    if cleaned.len() < 3 {
+        return None;
+    }
+
+    let (prefix, number) = match cleaned.get(..2) {
+        Some(p) if p.chars().all(|c| c.is_ascii_alphabetic()) && formats(p).is_some() => {
+            (p.to_string(), cleaned[2..].to_string())
+        }
+        _ => {
+            let prefix = match country_code.map(|c| c.trim().to_uppercase()) {
+                Some(ref c) if c == "XI" => "XI",
+                Some(ref c) => vat_prefix(c)?,
+                None => return None,
+            };
+            (prefix.to_string(), cleaned)
+        }
+    };
+
+    if formats(&prefix)?.iter().any(|pattern| matches(pattern, &number)) {
+        Some(VatNumber { prefix, number })
+    } else {
+        None
+    }
+}
+
+/// Check a VAT number's format
+pub fn is_valid_vat_format(input: &str, country_code: Option<&str>) -> bool {
+    parse_vat_number(input, country_code).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vat_number() {
+        let number = parse_vat_number("de 123.456.789", None).unwrap();
+        assert_eq!(number.full(), "DE123456789");
+        assert_eq!(number.country_code(), "DE");
+
+        // Prefix supplied from the billing country
+        assert_eq!(parse_vat_number("123456789", Some("DE")).unwrap().full(), "DE123456789");
+        assert_eq!(parse_vat_number("094014298", Some("GR")).unwrap().full(), "EL094014298");
+        assert_eq!(parse_vat_number("NL123456789B01", None).unwrap().country_code(), "NL");
+        assert_eq!(parse_vat_number("ATU12345678", None).unwrap().number, "U12345678");
+        assert_eq!(parse_vat_number("ESX1234567X", None).unwrap().country_code(), "ES");
+        assert_eq!(parse_vat_number("XI123456789", None).unwrap().country_code(), "GB");
+    }
+
+    #[test]
+    fn test_rejects_bad_formats() {
+        assert!(parse_vat_number("DE12345678", None).is_none());
+        assert!(parse_vat_number("NL123456789A01", None).is_none());
+        assert!(parse_vat_number("US123456789", None).is_none());
+        assert!(parse_vat_number("123456789", None).is_none());
+        assert!(parse_vat_number("123456789", Some("US")).is_none());
+        assert!(parse_vat_number("", Some("DE")).is_none());
+    }
+}
@@ -0,0 +1,95 @@
+//! Local VAT Number Provider
+//!
+//! Offline stub for development and for stores that do not check numbers
+//! against VIES. Well-formed numbers are accepted unless they appear on the
+//! block list; numbers on the allow list are always accepted.
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use super::format::VatNumber;
+use super::provider::{VatNumberProvider, VatNumberValidation, VatValidationError};
+
+/// Offline VAT number provider
+#[derive(Debug, Clone, Default)]
+pub struct LocalVatProvider {
+    /// Full numbers (with prefix) that are always accepted
+    pub allowed: Vec<String>,
+    /// Full numbers (with prefix) that are always rejected
+    pub blocked: Vec<String>,
+    /// Accept any well-formed number not on either list
+    pub accept_well_formed: bool,
+}
+
+impl LocalVatProvider {
+    /// Create a provider that accepts any well-formed number
+    pub fn new() -> Self {
+        Self {
+            allowed: Vec::new(),
+            blocked: Vec::new(),
+            accept_well_formed: true,
+        }
+    }
+
+    /// Check a number without any network calls
+    pub fn check(&self, number: &VatNumber) -> VatNumberValidation {
+        let full = number.full();
+        let listed = |list: &[String]| list.iter().any(|n| n.replace(' ', "").eq_ignore_ascii_case(&full));
+
+        let valid = if listed(&self.blocked) {
+            false
+        } else {
+            listed(&self.allowed) || self.accept_well_formed
+        };
+
+        VatNumberValidation {
+            vat_number: full,
+            country_code: number.country_code().to_string(),
+            valid,
+            name: None,
+            address: None,
+            request_identifier: None,
+            provider: self.id().to_string(),
+            checked_at: Utc::now(),
+        }
+    }
+}
+
+#[async_trait]
+impl VatNumberProvider for LocalVatProvider {
+    fn id(&self) -> &str {
+        "local"
+    }
+
+    fn title(&self) -> &str {
+        "Format check (offline)"
+    }
+
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    async fn validate(&self, number: &VatNumber) -> Result<VatNumberValidation, VatValidationError> {
+        Ok(self.check(number))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vat::format::parse_vat_number;
+
+    #[test]
+    fn test_local_lists() {
+        let mut provider = LocalVatProvider::new();
+        provider.blocked.push("DE999999999".to_string());
+
+        assert!(provider.check(&parse_vat_number("DE123456789", None).unwrap()).valid);
+        assert!(!provider.check(&parse_vat_number("DE999999999", None).unwrap()).valid);
+
+        provider.accept_well_formed = false;
+        provider.allowed.push("FR 12 345678901".to_string());
+        assert!(provider.check(&parse_vat_number("FR12345678901", None).unwrap()).valid);
+        assert!(!provider.check(&parse_vat_number("DE123456789", None).unwrap()).valid);
+    }
+}
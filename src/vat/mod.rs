@@ -0,0 +1,12 @@
+//! RustCommerce EU VAT
+//!
+//! VAT identification number formats and lookup providers used for EU
+//! One-Stop-Shop (OSS) taxation and reverse charge.
+
+pub mod format;
+pub mod provider;
+pub mod vies;
+pub mod local;
+
+pub use format::{parse_vat_number, is_valid_vat_format, VatNumber};
+pub use provider::{VatNumberProvider, VatNumberProviderRegistry, VatNumberValidation, VatValidationError};
@@ -0,0 +1,139 @@
+//! VAT Number Provider Base
+//!
+//! Defines the VAT number validation provider trait and registry.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::format::{parse_vat_number, VatNumber};
+
+/// VAT number validation error
+#[derive(Debug, Clone)]
+pub enum VatValidationError {
+    NotConfigured,
+    InvalidFormat(String),
+    UnsupportedCountry(String),
+    ServiceUnavailable(String),
+    NetworkError(String),
+    ProviderError(String),
+}
+
+impl VatValidationError {
+    /// Whether the error means the lookup service could not be reached,
+    /// rather than the number being wrong
+    pub fn is_outage(&self) -> bool {
+        matches!(self, Self::ServiceUnavailable(_) | Self::NetworkError(_))
+    }
+}
+
+impl std::fmt::Display for VatValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotConfigured => write!(f, "VAT number provider not configured"),
+            Self::InvalidFormat(number) => write!(f, "{} is not a valid VAT number", number),
+            Self::UnsupportedCountry(country) => write!(f, "VAT numbers from {} cannot be validated", country),
+            Self::ServiceUnavailable(msg) => write!(f, "VAT number service unavailable: {}", msg),
+            Self::NetworkError(msg) => write!(f, "Network error: {}", msg),
+            Self::ProviderError(msg) => write!(f, "VAT number validation error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for VatValidationError {}
+
+/// Result of a VAT number lookup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VatNumberValidation {
+    /// Full number including prefix, e.g. "DE123456789"
+    pub vat_number: String,
+    /// ISO country code of the registration
+    pub country_code: String,
+    /// Whether the number is registered and active
+    pub valid: bool,
+    /// Registered trader name, when disclosed
+    pub name: Option<String>,
+    /// Registered trader address, when disclosed
+    pub address: Option<String>,
+    /// Consultation number to keep as proof of the check
+    pub request_identifier: Option<String>,
+    pub provider: String,
+    pub checked_at: DateTime<Utc>,
+}
+
+/// VAT number validation provider trait
+#[async_trait]
+pub trait VatNumberProvider: Send + Sync {
+    /// Get provider ID
+    fn id(&self) -> &str;
+
+    /// Get provider title
+    fn title(&self) -> &str;
+
+    /// Check if provider is available
+    fn is_available(&self) -> bool;
+
+    /// Look up a well-formed VAT number
+    async fn validate(&self, number: &VatNumber) -> Result<VatNumberValidation, VatValidationError>;
+}
+
+/// VAT number provider registry
+pub struct VatNumberProviderRegistry {
+    providers: HashMap<String, Arc<dyn VatNumberProvider>>,
+}
+
+impl VatNumberProviderRegistry {
+    /// Create a new provider registry
+    pub fn new() -> Self {
+        Self {
+            providers: HashMap::new(),
+        }
+    }
+
+    /// Register a VAT number provider
+    pub fn register(&mut self, provider: Arc<dyn VatNumberProvider>) {
+        self.providers.insert(provider.id().to_string(), provider);
+    }
+
+    /// Get a provider by ID
+    pub fn get(&self, id: &str) -> Option<Arc<dyn VatNumberProvider>> {
+        self.providers.get(id).cloned()
+    }
+
+    /// Get available providers
+    pub fn get_available(&self) -> Vec<Arc<dyn VatNumberProvider>> {
+        self.providers.values()
+            .filter(|p| p.is_available())
+            .cloned()
+            .collect()
+    }
+
+    /// Check the format of a VAT number, then look it up with the
+    /// specified provider
+    pub async fn validate(
+        &self,
+        provider_id: &str,
+        vat_number: &str,
+        country_code: Option<&str>,
+    ) -> Result<VatNumberValidation, VatValidationError> {
+        let number = parse_vat_number(vat_number, country_code)
+            .ok_or_else(|| VatValidationError::InvalidFormat(vat_number.trim().to_string()))?;
+
+        let provider = self.get(provider_id)
+            .ok_or(VatValidationError::NotConfigured)?;
+
+        if !provider.is_available() {
+            return Err(VatValidationError::NotConfigured);
+        }
+
+        provider.validate(&number).await
+    }
+}
+
+impl Default for VatNumberProviderRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
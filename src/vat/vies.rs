@@ -0,0 +1,200 @@
+//! VIES VAT Number Provider
+//!
+//! Looks VAT numbers up in the European Commission's VIES service.
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use super::format::{parse_vat_number, VatNumber};
+use super::provider::{VatNumberProvider, VatNumberValidation, VatValidationError};
+
+/// VIES REST endpoint
+pub const VIES_ENDPOINT: &str = "https://ec.europa.eu/taxation_customs/vies/rest-api/check-vat-number";
+
+/// VIES provider configuration
+#[derive(Debug, Clone)]
+pub struct ViesConfig {
+    pub endpoint: String,
+    /// Store's own VAT number; when set, VIES returns a consultation
+    /// number that serves as proof of the check
+    pub requester_vat_number: Option<String>,
+    pub timeout_seconds: u64,
+}
+
+impl Default for ViesConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: VIES_ENDPOINT.to_string(),
+            requester_vat_number: None,
+            timeout_seconds: 10,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ViesRequest<'a> {
+    country_code: &'a str,
+    vat_number: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    requester_member_state_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    requester_number: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ViesResponse {
+    #[serde(default)]
+    valid: bool,
+    name: Option<String>,
+    address: Option<String>,
+    request_identifier: Option<String>,
+    #[serde(default)]
+    error_wrappers: Vec<ViesError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ViesError {
+    error: String,
+    message: Option<String>,
+}
+
+/// VIES VAT number provider
+pub struct ViesProvider {
+    config: ViesConfig,
+    enabled: bool,
+}
+
+impl ViesProvider {
+    /// Create a new VIES provider
+    pub fn new(config: ViesConfig) -> Self {
+        Self {
+            config,
+            enabled: true,
+        }
+    }
+
+    /// Set enabled state
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// VIES reports undisclosed fields as "---"
+    fn disclosed(value: Option<String>) -> Option<String> {
+        value
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty() && v != "---")
+    }
+
+    fn parse_response(
+        &self,
+        number: &VatNumber,
+        body: &str,
+    ) -> Result<VatNumberValidation, VatValidationError> {
+        let response: ViesResponse = serde_json::from_str(body)
+            .map_err(|e| VatValidationError::ProviderError(e.to_string()))?;
+
+        if let Some(error) = response.error_wrappers.into_iter().next() {
+            let detail = error.message.unwrap_or_else(|| error.error.clone());
+            return Err(match error.error.as_str() {
+                "INVALID_INPUT" => VatValidationError::InvalidFormat(number.full()),
+                "MS_UNAVAILABLE" | "SERVICE_UNAVAILABLE" | "TIMEOUT" | "MS_MAX_CONCURRENT_REQ"
+                | "GLOBAL_MAX_CONCURRENT_REQ" => VatValidationError::ServiceUnavailable(detail),
+                _ => VatValidationError::ProviderError(detail),
+            });
+        }
+
+        Ok(VatNumberValidation {
+            vat_number: number.full(),
+            country_code: number.country_code().to_string(),
+            valid: response.valid,
+            name: Self::disclosed(response.name),
+            address: Self::disclosed(response.address),
+            request_identifier: Self::disclosed(response.request_identifier),
+            provider: self.id().to_string(),
+            checked_at: Utc::now(),
+        })
+    }
+}
+
+#[async_trait]
+impl VatNumberProvider for ViesProvider {
+    fn id(&self) -> &str {
+        "vies"
+    }
+
+    fn title(&self) -> &str {
+        "VIES (European Commission)"
+    }
+
+    fn is_available(&self) -> bool {
+        self.enabled && !self.config.endpoint.is_empty()
+    }
+
+    async fn validate(&self, number: &VatNumber) -> Result<VatNumberValidation, VatValidationError> {
+        let requester = self.config.requester_vat_number.as_deref()
+            .and_then(|n| parse_vat_number(n, None));
+
+        let request = ViesRequest {
+            country_code: &number.prefix,
+            vat_number: &number.number,
+            requester_member_state_code: requester.as_ref().map(|r| r.prefix.clone()),
+            requester_number: requester.as_ref().map(|r| r.number.clone()),
+        };
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(self.config.timeout_seconds))
+            .build()
+            .map_err(|e| VatValidationError::NetworkError(e.to_string()))?;
+
+        let response = client.post(&self.config.endpoint)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| {
+                if e.is_timeout() {
+                    VatValidationError::ServiceUnavailable("VIES did not respond in time".to_string())
+                } else {
+                    VatValidationError::NetworkError(e.to_string())
+                }
+            })?;
+
+        if response.status().is_server_error() {
+            return Err(VatValidationError::ServiceUnavailable(
+                format!("VIES returned {}", response.status()),
+            ));
+        }
+
+        let body = response.text()
+            .await
+            .map_err(|e| VatValidationError::NetworkError(e.to_string()))?;
+
+        self.parse_response(number, &body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_response() {
+        let provider = ViesProvider::new(ViesConfig::default());
+        let number = parse_vat_number("DE123456789", None).unwrap();
+
+        let body = r#"{"countryCode":"DE","vatNumber":"123456789","valid":true,
+            "name":"---","address":"---","requestIdentifier":"WAPIAAAAYxyz"}"#;
+        let result = provider.parse_response(&number, body).unwrap();
+        assert!(result.valid);
+        assert_eq!(result.vat_number, "DE123456789");
+        assert!(result.name.is_none());
+        assert_eq!(result.request_identifier.as_deref(), Some("WAPIAAAAYxyz"));
+
+        let body = r#"{"errorWrappers":[{"error":"MS_UNAVAILABLE"}]}"#;
+        let error = provider.parse_response(&number, body).unwrap_err();
+        assert!(error.is_outage());
+    }
+}